@@ -1,13 +1,65 @@
 //! EchoBehaviour for demonstrating the system operation
 
-use libp2p::PeerId;
-use libp2p::swarm::{ConnectionDenied, NetworkBehaviour, ToSwarm};
+use libp2p::core::upgrade::{DeniedUpgrade, ReadyUpgrade};
+use libp2p::swarm::handler::ConnectionEvent;
+use libp2p::swarm::{
+    ConnectionDenied, ConnectionHandler, ConnectionHandlerEvent, NetworkBehaviour,
+    SubstreamProtocol, ToSwarm,
+};
+use libp2p::{PeerId, StreamProtocol};
 use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::task::{Context, Poll};
 
 use super::event::EchoEvent;
 
+/// Protocol advertised by EchoBehaviour
+pub const ECHO_PROTOCOL: StreamProtocol = StreamProtocol::new("/echo/1.0.0");
+
+/// Connection handler that advertises the echo protocol
+///
+/// Inbound echo streams are accepted and dropped, messages are exchanged locally
+#[derive(Default)]
+pub struct EchoConnectionHandler;
+
+impl ConnectionHandler for EchoConnectionHandler {
+    type FromBehaviour = Infallible;
+    type ToBehaviour = Infallible;
+    type InboundProtocol = ReadyUpgrade<StreamProtocol>;
+    type OutboundProtocol = DeniedUpgrade;
+    type InboundOpenInfo = ();
+    type OutboundOpenInfo = Infallible;
+
+    fn listen_protocol(&self) -> SubstreamProtocol<Self::InboundProtocol, Self::InboundOpenInfo> {
+        SubstreamProtocol::new(ReadyUpgrade::new(ECHO_PROTOCOL), ())
+    }
+
+    fn on_behaviour_event(&mut self, event: Self::FromBehaviour) {
+        match event {}
+    }
+
+    fn poll(
+        &mut self,
+        _: &mut Context<'_>,
+    ) -> Poll<
+        ConnectionHandlerEvent<Self::OutboundProtocol, Self::OutboundOpenInfo, Self::ToBehaviour>,
+    > {
+        Poll::Pending
+    }
+
+    fn on_connection_event(
+        &mut self,
+        _: ConnectionEvent<
+            '_,
+            Self::InboundProtocol,
+            Self::OutboundProtocol,
+            Self::InboundOpenInfo,
+            Self::OutboundOpenInfo,
+        >,
+    ) {
+    }
+}
+
 /// Simple behaviour for demonstrating the system operation
 #[derive(Default)]
 pub struct EchoBehaviour {
@@ -49,7 +101,7 @@ impl EchoBehaviour {
 }
 
 impl NetworkBehaviour for EchoBehaviour {
-    type ConnectionHandler = libp2p::swarm::dummy::ConnectionHandler;
+    type ConnectionHandler = EchoConnectionHandler;
     type ToSwarm = EchoEvent;
 
     fn handle_established_inbound_connection(
@@ -59,7 +111,7 @@ impl NetworkBehaviour for EchoBehaviour {
         _: &libp2p::Multiaddr,
         _: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, ConnectionDenied> {
-        Ok(libp2p::swarm::dummy::ConnectionHandler)
+        Ok(EchoConnectionHandler)
     }
 
     fn handle_established_outbound_connection(
@@ -70,7 +122,7 @@ impl NetworkBehaviour for EchoBehaviour {
         _: libp2p::core::Endpoint,
         _: libp2p::core::transport::PortUse,
     ) -> Result<libp2p::swarm::THandler<Self>, ConnectionDenied> {
-        Ok(libp2p::swarm::dummy::ConnectionHandler)
+        Ok(EchoConnectionHandler)
     }
 
     fn on_swarm_event(&mut self, _: libp2p::swarm::FromSwarm) {}
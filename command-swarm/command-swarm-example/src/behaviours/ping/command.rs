@@ -1,7 +0,0 @@
-//! Commands for PingBehaviour
-command_swarm::swarm_commands! {
-    PingCommand {
-        DummyTest() -> (),
-    }
-
-}
@@ -1,7 +1,5 @@
 //! Ping behaviour for libp2p
+//!
+//! Uses the reusable ping handler from command-swarm.
 
-pub use command::PingCommand;
-pub use handler::PingBehaviourHandler;
-
-mod command;
-mod handler;
+pub use command_swarm::ping::{PingBehaviourHandler, PingCommand};
@@ -22,6 +22,21 @@ pub enum SwarmLevelCommand {
         addr: Multiaddr,
         response: tokio::sync::oneshot::Sender<Result<libp2p::Multiaddr, Box<dyn std::error::Error + Send + Sync>>>
     },
+
+    /// Get protocols supported by the combined behaviour
+    GetProtocols {
+        response: tokio::sync::oneshot::Sender<Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>>>
+    },
+
+    /// Get dial attempts seen by the on_dialing hook
+    GetDialAttempts {
+        response: tokio::sync::oneshot::Sender<Result<Vec<(Option<PeerId>, libp2p::swarm::ConnectionId)>, Box<dyn std::error::Error + Send + Sync>>>
+    },
+
+    /// Get number of commands and events seen by the before_command/after_event hooks
+    GetHookCounts {
+        response: tokio::sync::oneshot::Sender<Result<(usize, usize), Box<dyn std::error::Error + Send + Sync>>>
+    },
 }
 
 impl command_swarm::SwarmCommand for SwarmLevelCommand {
@@ -43,7 +58,14 @@ make_command_swarm! {
 }
 
 #[derive(Default)]
-struct MySwarmHandler;
+struct MySwarmHandler {
+    /// Dial attempts recorded by on_dialing
+    dial_attempts: Vec<(Option<PeerId>, libp2p::swarm::ConnectionId)>,
+    /// Commands counted by before_command
+    commands_seen: usize,
+    /// Events counted by after_event
+    events_seen: usize,
+}
 
 #[async_trait::async_trait]
 impl SwarmHandler<MyBehaviour> for MySwarmHandler {
@@ -66,9 +88,36 @@ impl SwarmHandler<MyBehaviour> for MySwarmHandler {
                 // For now, just acknowledge the command
                 let _ = response.send(Ok(()));
             }
+            SwarmLevelCommand::GetProtocols { response } => {
+                let protocols = command_swarm::supported_protocols(swarm);
+                println!("📋 [SwarmHandler] Supported protocols: {:?}", protocols);
+                let _ = response.send(Ok(protocols));
+            }
+            SwarmLevelCommand::GetDialAttempts { response } => {
+                let _ = response.send(Ok(self.dial_attempts.clone()));
+            }
+            SwarmLevelCommand::GetHookCounts { response } => {
+                let _ = response.send(Ok((self.commands_seen, self.events_seen)));
+            }
         }
     }
 
+    fn before_command(&mut self, _cmd: &Self::Command) {
+        self.commands_seen += 1;
+    }
+
+    fn after_event(
+        &mut self,
+        _event: &libp2p::swarm::SwarmEvent<<MyBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm>,
+    ) {
+        self.events_seen += 1;
+    }
+
+    async fn on_dialing(&mut self, peer_id: Option<PeerId>, connection_id: libp2p::swarm::ConnectionId) {
+        println!("📞 [SwarmHandler] Dialing - Peer: {:?}, Connection: {:?}", peer_id, connection_id);
+        self.dial_attempts.push((peer_id, connection_id));
+    }
+
     async fn handle_event(
         &mut self,
         _swarm: &mut Swarm<MyBehaviour>,
@@ -90,21 +139,9 @@ impl SwarmHandler<MyBehaviour> for MySwarmHandler {
     }
 }
 
-#[tokio::main]
-async fn main() {
-    // Initialize tracing subscriber
-    let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .with_thread_ids(true)
-        .with_thread_names(true)
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set global subscriber");
-
-    println!("🚀 Application started");
-    // Create Swarm using SwarmBuilder (correct pattern from examples)
-    let swarm = libp2p::SwarmBuilder::with_new_identity()
+/// Create Swarm using SwarmBuilder (correct pattern from examples)
+fn build_swarm() -> Swarm<MyBehaviour> {
+    libp2p::SwarmBuilder::with_new_identity()
         .with_tokio()
         .with_tcp(
             tcp::Config::default(),
@@ -117,8 +154,23 @@ async fn main() {
             ping: libp2p::ping::Behaviour::default(),
         })
         .unwrap()
-        .build();
+        .build()
+}
 
+#[tokio::main]
+async fn main() {
+    // Initialize tracing subscriber
+    let subscriber = tracing_subscriber::FmtSubscriber::builder()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_thread_ids(true)
+        .with_thread_names(true)
+        .finish();
+
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Failed to set global subscriber");
+
+    println!("🚀 Application started");
+    let swarm = build_swarm();
 
     let local_peer_id = swarm.local_peer_id().clone();
     println!("🆔 [Main] Local PeerId: {:?}", swarm.local_peer_id());
@@ -176,10 +228,11 @@ async fn main() {
         Err(_) => println!("❌ [Main] Echo response channel closed"),
     }
 
-    println!("📤 [Main] Sending Ping command with response...");
+    println!("📤 [Main] Sending Ping GetRtt command with response...");
     let (ping_response_tx, ping_response_rx) = tokio::sync::oneshot::channel();
     command_tx
-        .send(MyCommands::ping(PingCommand::DummyTest {
+        .send(MyCommands::ping(PingCommand::GetRtt {
+            peer_id: local_peer_id.clone(),
             response: ping_response_tx,
         }))
         .await
@@ -187,7 +240,7 @@ async fn main() {
 
     // Wait for ping command result
     match ping_response_rx.await {
-        Ok(Ok(())) => println!("✅ [Main] Ping command completed successfully"),
+        Ok(Ok(rtt)) => println!("✅ [Main] Ping RTT for local peer: {:?}", rtt),
         Ok(Err(e)) => println!("❌ [Main] Ping command failed: {}", e),
         Err(_) => println!("❌ [Main] Ping response channel closed"),
     }
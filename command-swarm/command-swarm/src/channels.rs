@@ -0,0 +1,134 @@
+//! Named command channels drained by SwarmLoop in a weighted order
+
+use std::future::poll_fn;
+use std::task::{Context, Poll};
+
+use tokio::sync::mpsc;
+
+/// Settings for one named command channel
+#[derive(Debug, Clone)]
+pub struct NamedChannelConfig {
+    pub name: String,
+    pub capacity: usize,
+    /// Commands taken from this channel in a row before moving to the next one
+    pub weight: usize,
+}
+
+struct NamedChannel<C> {
+    rx: mpsc::Receiver<C>,
+    weight: usize,
+}
+
+/// Receivers of all named channels, polled in configured order
+///
+/// The current channel is served up to `weight` times before the next one
+/// gets a turn. Empty channels are skipped, so a lower-priority channel is
+/// never starved while the ones before it are idle.
+pub(crate) struct NamedChannels<C> {
+    channels: Vec<NamedChannel<C>>,
+    cursor: usize,
+    credit: usize,
+}
+
+impl<C> NamedChannels<C> {
+    /// Creates the channels, returning senders in the same order as `configs`
+    pub(crate) fn new(configs: &[NamedChannelConfig]) -> (Self, Vec<mpsc::Sender<C>>) {
+        let mut channels = Vec::with_capacity(configs.len());
+        let mut senders = Vec::with_capacity(configs.len());
+        for config in configs {
+            let (tx, rx) = mpsc::channel(config.capacity);
+            channels.push(NamedChannel {
+                rx,
+                weight: config.weight.max(1),
+            });
+            senders.push(tx);
+        }
+        let credit = channels.first().map(|c| c.weight).unwrap_or(0);
+        (
+            Self {
+                channels,
+                cursor: 0,
+                credit,
+            },
+            senders,
+        )
+    }
+
+    /// Waits for the next command; never resolves if there are no channels
+    pub(crate) async fn recv(&mut self) -> Option<C> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<C>> {
+        let count = self.channels.len();
+        for offset in 0..count {
+            let index = (self.cursor + offset) % count;
+            if let Poll::Ready(Some(cmd)) = self.channels[index].rx.poll_recv(cx) {
+                if index != self.cursor {
+                    self.cursor = index;
+                    self.credit = self.channels[index].weight;
+                }
+                self.credit -= 1;
+                if self.credit == 0 {
+                    self.cursor = (index + 1) % count;
+                    self.credit = self.channels[self.cursor].weight;
+                }
+                return Poll::Ready(Some(cmd));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, weight: usize) -> NamedChannelConfig {
+        NamedChannelConfig {
+            name: name.to_string(),
+            capacity: 16,
+            weight,
+        }
+    }
+
+    #[tokio::test]
+    async fn control_channel_is_serviced_preferentially() {
+        let (mut channels, senders) =
+            NamedChannels::new(&[config("control", 3), config("data", 1)]);
+        let (control, data) = (&senders[0], &senders[1]);
+
+        // Data is queued first, control should still be drained ahead of it
+        for i in 0..4 {
+            data.send(format!("data-{i}")).await.unwrap();
+        }
+        for i in 0..4 {
+            control.send(format!("control-{i}")).await.unwrap();
+        }
+
+        let mut order = Vec::new();
+        for _ in 0..8 {
+            order.push(channels.recv().await.unwrap());
+        }
+
+        assert_eq!(
+            order,
+            vec![
+                "control-0", "control-1", "control-2", "data-0", "control-3", "data-1",
+                "data-2", "data-3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn idle_channel_does_not_block_others() {
+        let (mut channels, senders) =
+            NamedChannels::new(&[config("control", 3), config("data", 1)]);
+
+        senders[1].send(1u32).await.unwrap();
+        senders[1].send(2u32).await.unwrap();
+
+        assert_eq!(channels.recv().await, Some(1));
+        assert_eq!(channels.recv().await, Some(2));
+    }
+}
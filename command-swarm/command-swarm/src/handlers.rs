@@ -1,8 +1,8 @@
 //! Handler traits and structures for processing commands and events
 
 use async_trait::async_trait;
-use libp2p::Swarm;
-use libp2p::swarm::{NetworkBehaviour, SwarmEvent};
+use libp2p::{PeerId, Swarm};
+use libp2p::swarm::{ConnectionId, NetworkBehaviour, SwarmEvent};
 
 /// Trait for handling commands and events of a specific behaviour
 #[async_trait::async_trait]
@@ -37,4 +37,86 @@ where
 
     /// Handle swarm event
     async fn handle_event(&mut self, swarm: &mut Swarm<B>, event: &SwarmEvent<B::ToSwarm>);
+
+    /// Called by the loop on `SwarmEvent::Dialing`, before the event itself is handled.
+    /// Default implementation does nothing.
+    async fn on_dialing(&mut self, _peer_id: Option<PeerId>, _connection_id: ConnectionId) {}
+
+    /// Called by the loop before every swarm-level command is dispatched.
+    /// Synchronous so commands don't have to be `Sync`. Default implementation does nothing.
+    fn before_command(&mut self, _cmd: &Self::Command) {}
+
+    /// Called by the loop after `handle_event` for every swarm event.
+    /// Default implementation does nothing.
+    fn after_event(&mut self, _event: &SwarmEvent<B::ToSwarm>) {}
+}
+
+/// Swarm-level command of a `CompositeSwarmHandler`, routed to one of its two handlers
+#[derive(Debug)]
+pub enum CompositeCommand<A, B> {
+    /// Command for the first handler
+    First(A),
+    /// Command for the second handler
+    Second(B),
+}
+
+/// Two `SwarmHandler`s behind one swarm-level command type
+///
+/// Commands go to the handler their `CompositeCommand` variant names, every
+/// event and hook call goes to both, first handler first. Nest composites or
+/// use `composite_swarm_handler!` for more than two handlers.
+#[derive(Debug, Default)]
+pub struct CompositeSwarmHandler<H1, H2> {
+    /// First handler
+    pub first: H1,
+    /// Second handler
+    pub second: H2,
+}
+
+impl<H1, H2> CompositeSwarmHandler<H1, H2> {
+    /// Compose two handlers
+    pub fn new(first: H1, second: H2) -> Self {
+        Self { first, second }
+    }
+}
+
+#[async_trait]
+impl<B, H1, H2> SwarmHandler<B> for CompositeSwarmHandler<H1, H2>
+where
+    B: NetworkBehaviour,
+    Swarm<B>: Send,
+    SwarmEvent<B::ToSwarm>: Sync,
+    H1: SwarmHandler<B>,
+    H2: SwarmHandler<B>,
+{
+    type Command = CompositeCommand<H1::Command, H2::Command>;
+
+    async fn handle_command(&mut self, swarm: &mut Swarm<B>, cmd: Self::Command) {
+        match cmd {
+            CompositeCommand::First(cmd) => self.first.handle_command(swarm, cmd).await,
+            CompositeCommand::Second(cmd) => self.second.handle_command(swarm, cmd).await,
+        }
+    }
+
+    async fn handle_event(&mut self, swarm: &mut Swarm<B>, event: &SwarmEvent<B::ToSwarm>) {
+        self.first.handle_event(swarm, event).await;
+        self.second.handle_event(swarm, event).await;
+    }
+
+    async fn on_dialing(&mut self, peer_id: Option<PeerId>, connection_id: ConnectionId) {
+        self.first.on_dialing(peer_id, connection_id).await;
+        self.second.on_dialing(peer_id, connection_id).await;
+    }
+
+    fn before_command(&mut self, cmd: &Self::Command) {
+        match cmd {
+            CompositeCommand::First(cmd) => self.first.before_command(cmd),
+            CompositeCommand::Second(cmd) => self.second.before_command(cmd),
+        }
+    }
+
+    fn after_event(&mut self, event: &SwarmEvent<B::ToSwarm>) {
+        self.first.after_event(event);
+        self.second.after_event(event);
+    }
 }
@@ -3,14 +3,26 @@
 //! This library provides traits and utilities for managing libp2p swarms through
 //! command-based interfaces, making it easier to build complex p2p applications.
 
+pub mod channels;
 pub mod command;
 pub mod handlers;
 pub mod macros;
+pub mod ping;
+pub mod protocols;
 pub mod swarm_loop;
 
+#[cfg(test)]
+#[allow(non_camel_case_types)]
+mod tests;
+
+pub use channels::NamedChannelConfig;
 pub use command::SwarmCommand;
-pub use handlers::{BehaviourHandler, SwarmHandler};
-pub use swarm_loop::{BehaviourHandlerDispatcherTrait, SwarmLoop, SwarmLoopBuilder, SwarmLoopStopper};
+pub use handlers::{BehaviourHandler, CompositeCommand, CompositeSwarmHandler, SwarmHandler};
+pub use protocols::supported_protocols;
+pub use swarm_loop::{
+    BehaviourHandlerDispatcherTrait, CommandId, ShutdownHook, SwarmLoop, SwarmLoopBuilder,
+    SwarmLoopStopper, current_command_id,
+};
 
 /// Re-export commonly used libp2p types for convenience
 pub use libp2p::{
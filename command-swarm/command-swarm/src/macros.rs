@@ -53,6 +53,26 @@ macro_rules! swarm_commands {
     };
 }
 
+/// Type of a `CompositeSwarmHandler` composing any number of swarm handlers
+///
+/// Expands to nested composites: `composite_swarm_handler!(A, B, C)` is
+/// `CompositeSwarmHandler<A, CompositeSwarmHandler<B, C>>`, so a command for `C`
+/// is `CompositeCommand::Second(CompositeCommand::Second(cmd))`.
+///
+/// # Example
+/// ```ignore
+/// type AppHandler = command_swarm::composite_swarm_handler!(ConnHandler, MetricsHandler, AdminHandler);
+/// ```
+#[macro_export]
+macro_rules! composite_swarm_handler {
+    ($handler:ty $(,)?) => {
+        $handler
+    };
+    ($first:ty, $($rest:ty),+ $(,)?) => {
+        $crate::handlers::CompositeSwarmHandler<$first, $crate::composite_swarm_handler!($($rest),+)>
+    };
+}
+
 /// Macro for creating complete Command-Swarm infrastructure
 #[macro_export]
 macro_rules! make_command_swarm {
@@ -169,6 +189,7 @@ macro_rules! make_command_swarm {
                     use $crate::handlers::SwarmHandler;
                     // Pass ALL events entirely to swarm_handler cause later swarm_handle can pass event
                     self.swarm_handler.handle_event(swarm, &event).await;
+                    self.swarm_handler.after_event(&event);
 
                     // Pprocess Behaviour events
                     if let libp2p::swarm::SwarmEvent::Behaviour(behaviour_event) = event {
@@ -178,6 +199,20 @@ macro_rules! make_command_swarm {
 
                 }
 
+                /// Forward swarm-level commands to the swarm_handler hook
+                fn before_command(&mut self, command: &$commands_name) {
+                    use $crate::handlers::SwarmHandler;
+                    if let $commands_name::SwarmLevel(inner_cmd) = command {
+                        self.swarm_handler.before_command(inner_cmd);
+                    }
+                }
+
+                /// Forward dial attempts to swarm_handler
+                async fn on_dialing(&mut self, peer_id: Option<libp2p::PeerId>, connection_id: libp2p::swarm::ConnectionId) {
+                    use $crate::handlers::SwarmHandler;
+                    self.swarm_handler.on_dialing(peer_id, connection_id).await;
+                }
+
                 /// Handle behaviour events
                 async fn handle_events(&mut self, swarm: &mut libp2p::Swarm<$behaviour_name>, event: <$behaviour_name as libp2p::swarm::NetworkBehaviour>::ToSwarm) {
                     tracing::debug!("{}Dispatcher: Processing behaviour event", stringify!($behaviour_name));
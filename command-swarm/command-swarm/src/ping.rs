@@ -0,0 +1,63 @@
+//! Reusable handler for the libp2p ping behaviour that records RTTs per peer
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use libp2p::{PeerId, ping};
+use tracing::debug;
+
+use crate::handlers::BehaviourHandler;
+
+crate::swarm_commands! {
+    PingCommand {
+        GetRtt(peer_id: PeerId) -> Option<Duration>,
+        GetAllRtts() -> HashMap<PeerId, Duration>,
+    }
+}
+
+/// Handler for `ping::Behaviour`
+///
+/// Keeps the last successful round-trip time for every peer.
+/// Failed pings are logged and do not overwrite the recorded value.
+#[derive(Default)]
+pub struct PingBehaviourHandler {
+    rtts: HashMap<PeerId, Duration>,
+}
+
+impl PingBehaviourHandler {
+    /// Last recorded RTT for the peer
+    pub fn rtt(&self, peer_id: &PeerId) -> Option<Duration> {
+        self.rtts.get(peer_id).copied()
+    }
+}
+
+#[async_trait]
+impl BehaviourHandler for PingBehaviourHandler {
+    type Behaviour = ping::Behaviour;
+    type Event = ping::Event;
+    type Command = PingCommand;
+
+    async fn handle_cmd(&mut self, _behaviour: &mut Self::Behaviour, cmd: Self::Command) {
+        match cmd {
+            PingCommand::GetRtt { peer_id, response } => {
+                let _ = response.send(Ok(self.rtt(&peer_id)));
+            }
+            PingCommand::GetAllRtts { response } => {
+                let _ = response.send(Ok(self.rtts.clone()));
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, _behaviour: &mut Self::Behaviour, event: &Self::Event) {
+        match &event.result {
+            Ok(rtt) => {
+                debug!("Ping to {} succeeded, rtt: {:?}", event.peer, rtt);
+                self.rtts.insert(event.peer, *rtt);
+            }
+            Err(e) => {
+                debug!("Ping to {} failed: {}", event.peer, e);
+            }
+        }
+    }
+}
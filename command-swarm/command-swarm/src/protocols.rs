@@ -0,0 +1,66 @@
+//! Protocol introspection for the combined behaviour
+
+use libp2p::core::ConnectedPoint;
+use libp2p::swarm::behaviour::{ConnectionClosed, ConnectionEstablished};
+use libp2p::swarm::handler::UpgradeInfoSend;
+use libp2p::swarm::{ConnectionHandler, ConnectionId, FromSwarm, NetworkBehaviour};
+use libp2p::{Multiaddr, Swarm};
+
+/// Collect the inbound protocols currently supported by the swarm's behaviour
+///
+/// A probe connection handler is created on the live behaviour through
+/// `handle_established_inbound_connection` and its listen protocols are read.
+/// The probe connection is then reported as established and closed right away,
+/// so behaviours release whatever they reserved for it, such as connection
+/// limit slots. Behaviours that deny the probe connection contribute nothing.
+/// The returned list is sorted and deduplicated.
+pub fn supported_protocols<B>(swarm: &mut Swarm<B>) -> Vec<String>
+where
+    B: NetworkBehaviour,
+{
+    let local_peer_id = *swarm.local_peer_id();
+    let connection_id = ConnectionId::new_unchecked(usize::MAX);
+    let probe_addr = Multiaddr::empty();
+    let behaviour = swarm.behaviour_mut();
+
+    let handler = match behaviour.handle_established_inbound_connection(
+        connection_id,
+        local_peer_id,
+        &probe_addr,
+        &probe_addr,
+    ) {
+        Ok(handler) => handler,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut protocols: Vec<String> = handler
+        .listen_protocol()
+        .upgrade()
+        .protocol_info()
+        .map(|protocol| protocol.as_ref().to_string())
+        .collect();
+    drop(handler);
+
+    let endpoint = ConnectedPoint::Listener {
+        local_addr: probe_addr.clone(),
+        send_back_addr: probe_addr,
+    };
+    behaviour.on_swarm_event(FromSwarm::ConnectionEstablished(ConnectionEstablished {
+        peer_id: local_peer_id,
+        connection_id,
+        endpoint: &endpoint,
+        failed_addresses: &[],
+        other_established: 0,
+    }));
+    behaviour.on_swarm_event(FromSwarm::ConnectionClosed(ConnectionClosed {
+        peer_id: local_peer_id,
+        connection_id,
+        endpoint: &endpoint,
+        cause: None,
+        remaining_established: 0,
+    }));
+
+    protocols.sort();
+    protocols.dedup();
+    protocols
+}
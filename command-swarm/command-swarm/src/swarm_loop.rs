@@ -1,12 +1,15 @@
 //! SwarmLoop - main event and command processing loop using MyBehaviourHandler
 
 use futures::StreamExt;
-use libp2p::Swarm;
-use libp2p::swarm::{NetworkBehaviour, SwarmEvent};
+use libp2p::{PeerId, Swarm};
+use libp2p::swarm::{ConnectionId, NetworkBehaviour, SwarmEvent};
+use std::collections::HashMap;
 use std::error::Error;
 use tokio::sync::{mpsc, watch};
 use tracing::{debug, info, instrument};
 
+use crate::channels::{NamedChannelConfig, NamedChannels};
+
 /// Trait for BehaviourHandlerDispatcher that defines processing methods
 #[async_trait::async_trait]
 pub trait BehaviourHandlerDispatcherTrait<B, C>
@@ -17,8 +20,39 @@ where
     async fn handle_commands(&mut self, swarm: &mut Swarm<B>, command: C);
     async fn handle_swarm_event(&mut self, swarm: &mut Swarm<B>, event: SwarmEvent<B::ToSwarm>);
     async fn handle_events(&mut self, swarm: &mut Swarm<B>, event: B::ToSwarm);
+
+    /// Hook for outgoing dial attempts, no-op by default
+    async fn on_dialing(&mut self, _peer_id: Option<PeerId>, _connection_id: ConnectionId) {}
+
+    /// Hook run before each command is dispatched, no-op by default
+    fn before_command(&mut self, _command: &C) {}
+}
+
+/// Id the SwarmLoop hands to every command it receives, ids start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CommandId(pub u64);
+
+impl std::fmt::Display for CommandId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_COMMAND: CommandId;
 }
 
+/// Id of the command the SwarmLoop is handling right now
+///
+/// Set only while the handlers process a command on the loop task; swarm
+/// events and spawned tasks see None, even if the command caused them.
+pub fn current_command_id() -> Option<CommandId> {
+    CURRENT_COMMAND.try_with(|command_id| *command_id).ok()
+}
+
+/// Cleanup hook run once with the swarm when the loop is stopping
+pub type ShutdownHook<B> = Box<dyn FnOnce(&mut Swarm<B>) + Send>;
+
 /// Cloneable stopper for SwarmLoop
 #[derive(Clone)]
 pub struct SwarmLoopStopper {
@@ -42,8 +76,13 @@ where
 {
     pub swarm: Swarm<B>,
     command_rx: mpsc::Receiver<C>,
+    named_channels: NamedChannels<C>,
+    named_senders: HashMap<String, mpsc::Sender<C>>,
     shutdown_rx: watch::Receiver<bool>,
     behaviour_handler: H,
+    shutdown_hook: Option<ShutdownHook<B>>,
+    /// Id handed to the next received command
+    next_command_id: u64,
 }
 
 impl<B, H, C> SwarmLoop<B, H, C>
@@ -53,15 +92,36 @@ where
     C: Send + 'static,
     H: BehaviourHandlerDispatcherTrait<B, C>,
 {
+    /// Number of commands waiting in the main command channel
+    pub fn command_queue_depth(&self) -> usize {
+        self.command_rx.len()
+    }
+
+    /// Sender of the named channel added with `SwarmLoopBuilder::with_named_channel`
+    pub fn named_sender(&self, name: &str) -> Option<mpsc::Sender<C>> {
+        self.named_senders.get(name).cloned()
+    }
+
     /// Start the main loop
+    ///
+    /// Sources are polled in a fixed order: the main command channel, the named
+    /// channels, swarm events, then the shutdown signal. A steady stream of
+    /// commands therefore delays swarm events until the channels run empty.
     #[instrument(name = "swarm_loop", skip(self))]
     pub async fn run(mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
         info!("Main loop started");
         loop {
             tokio::select! {
+                biased;
                 Some(cmd) = self.command_rx.recv() => {
                     debug!("Received command from channel");
-                    self.handle_command(cmd).await;
+                    let command_id = self.next_command_id();
+                    self.handle_command(command_id, cmd).await;
+                }
+                Some(cmd) = self.named_channels.recv() => {
+                    debug!("Received command from named channel");
+                    let command_id = self.next_command_id();
+                    self.handle_command(command_id, cmd).await;
                 }
                 event = self.swarm.select_next_some() => {
                     debug!("Received event from Swarm");
@@ -75,20 +135,41 @@ where
                 }
             }
         }
+        if let Some(hook) = self.shutdown_hook.take() {
+            info!("Running shutdown hook");
+            hook(&mut self.swarm);
+        }
         info!("Main loop finished gracefully");
         Ok(())
     }
 
-    #[instrument(name = "handle_command", skip(self, cmd))]
-    async fn handle_command(&mut self, cmd: C) {
+    fn next_command_id(&mut self) -> CommandId {
+        let command_id = CommandId(self.next_command_id);
+        self.next_command_id += 1;
+        command_id
+    }
+
+    /// Logs emitted while the handlers process the command are recorded inside
+    /// a span carrying `command_id`, the handlers read it with `current_command_id`
+    ///
+    /// Only the synchronous handling is covered: swarm events the command causes,
+    /// such as a dial result, arrive later in their own `handle_swarm_event` span
+    /// and do not carry the id.
+    #[instrument(name = "handle_command", skip(self, command_id, cmd), fields(command_id = command_id.0))]
+    async fn handle_command(&mut self, command_id: CommandId, cmd: C) {
         debug!(
             command_type = std::any::type_name::<C>(),
             "Received command"
         );
 
+        self.behaviour_handler.before_command(&cmd);
+
         // Pass command to behaviour_handler
-        self.behaviour_handler
-            .handle_commands(&mut self.swarm, cmd)
+        CURRENT_COMMAND
+            .scope(
+                command_id,
+                self.behaviour_handler.handle_commands(&mut self.swarm, cmd),
+            )
             .await;
     }
 
@@ -96,6 +177,12 @@ where
     async fn handle_swarm_event(&mut self, event: SwarmEvent<B::ToSwarm>) {
         debug!("Received Swarm event");
 
+        if let SwarmEvent::Dialing { peer_id, connection_id } = &event {
+            self.behaviour_handler
+                .on_dialing(*peer_id, *connection_id)
+                .await;
+        }
+
         // Pass event to behaviour_handler
         self.behaviour_handler
             .handle_swarm_event(&mut self.swarm, event)
@@ -113,6 +200,8 @@ where
     swarm: Option<Swarm<B>>,
     behaviour_handler: Option<H>,
     channel_size: usize,
+    named_channels: Vec<NamedChannelConfig>,
+    shutdown_hook: Option<ShutdownHook<B>>,
     _phantom: std::marker::PhantomData<C>,
 }
 
@@ -127,6 +216,8 @@ where
             swarm: None,
             behaviour_handler: None,
             channel_size: 32, // default channel size
+            named_channels: Vec::new(),
+            shutdown_hook: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -146,12 +237,54 @@ where
         self
     }
 
+    /// Adds a named command channel with its own capacity
+    ///
+    /// Named channels are drained in the order they were added; each one is
+    /// served up to `weight` commands in a row before the next gets a turn.
+    pub fn with_named_channel(
+        mut self,
+        name: impl Into<String>,
+        capacity: usize,
+        weight: usize,
+    ) -> Self {
+        self.named_channels.push(NamedChannelConfig {
+            name: name.into(),
+            capacity,
+            weight,
+        });
+        self
+    }
+
+    /// Sets a hook that runs once with the swarm before the loop exits
+    pub fn with_shutdown_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(&mut Swarm<B>) + Send + 'static,
+    {
+        self.shutdown_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Named channel senders are available through `SwarmLoop::named_sender`
     pub fn build(self) -> Result<(mpsc::Sender<C>, SwarmLoopStopper, SwarmLoop<B, H, C>), String> {
         let swarm = self.swarm.ok_or("Swarm not set")?;
         let behaviour_handler = self.behaviour_handler.ok_or("Behaviour handler not set")?;
 
+        let mut names = std::collections::HashSet::new();
+        if let Some(config) = self.named_channels.iter().find(|c| !names.insert(&c.name)) {
+            return Err(format!("Duplicate named channel: {}", config.name));
+        }
+
         // Create command channel
         let (command_tx, command_rx) = mpsc::channel(self.channel_size);
+
+        // Create named command channels
+        let (named_channels, senders) = NamedChannels::new(&self.named_channels);
+        let named_senders = self
+            .named_channels
+            .into_iter()
+            .map(|config| config.name)
+            .zip(senders)
+            .collect();
         
         // Create shutdown channel
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
@@ -159,8 +292,12 @@ where
         let swarm_loop = SwarmLoop {
             swarm,
             command_rx,
+            named_channels,
+            named_senders,
             shutdown_rx,
             behaviour_handler,
+            shutdown_hook: self.shutdown_hook,
+            next_command_id: 1,
         };
 
         let stopper = SwarmLoopStopper { shutdown_tx };
@@ -0,0 +1,505 @@
+//! Tests of the SwarmLoop, its hooks and the swarm-level helpers on a real swarm
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use libp2p::{Multiaddr, PeerId, Swarm, identify, noise, ping, tcp, yamux};
+use tokio::sync::oneshot;
+
+use crate::ping::{PingBehaviourHandler, PingCommand};
+use crate::{
+    BehaviourHandler, BehaviourHandlerDispatcherTrait, SwarmHandler, SwarmLoopBuilder,
+    SwarmLoopStopper,
+};
+
+type Response<T> = oneshot::Sender<Result<T, Box<dyn std::error::Error + Send + Sync>>>;
+
+crate::swarm_commands! {
+    IdentifyCommand {}
+}
+
+/// Handler for `identify::Behaviour`, present to give the combined behaviour a second protocol set
+#[derive(Default)]
+struct IdentifyBehaviourHandler;
+
+#[async_trait]
+impl BehaviourHandler for IdentifyBehaviourHandler {
+    type Behaviour = identify::Behaviour;
+    type Event = identify::Event;
+    type Command = IdentifyCommand;
+
+    async fn handle_cmd(&mut self, _behaviour: &mut Self::Behaviour, cmd: Self::Command) {
+        match cmd {}
+    }
+
+    async fn handle_event(&mut self, _behaviour: &mut Self::Behaviour, _event: &Self::Event) {}
+}
+
+/// Swarm-level commands of the test swarm
+#[derive(Debug)]
+enum TestSwarmCommand {
+    Dial { peer_id: PeerId, addr: Multiaddr, response: Response<()> },
+    GetProtocols { response: Response<Vec<String>> },
+    GetDialAttempts { response: Response<Vec<(Option<PeerId>, libp2p::swarm::ConnectionId)>> },
+    GetHookCounts { response: Response<(usize, usize)> },
+}
+
+crate::make_command_swarm! {
+    behaviour_name: TestBehaviour,
+    behaviours_handlers: {
+        ping: PingBehaviourHandler,
+        identify: IdentifyBehaviourHandler
+    },
+    commands: {
+        name: TestCommands,
+        swarm_level: TestSwarmCommand
+    },
+    swarm_handler: TestSwarmHandler
+}
+
+#[derive(Default)]
+struct TestSwarmHandler {
+    /// Dial attempts recorded by on_dialing
+    dial_attempts: Vec<(Option<PeerId>, libp2p::swarm::ConnectionId)>,
+    /// Commands counted by before_command
+    commands_seen: usize,
+    /// Events counted by after_event
+    events_seen: usize,
+}
+
+#[async_trait]
+impl SwarmHandler<TestBehaviour> for TestSwarmHandler {
+    type Command = TestSwarmCommand;
+
+    async fn handle_command(&mut self, swarm: &mut Swarm<TestBehaviour>, cmd: Self::Command) {
+        match cmd {
+            TestSwarmCommand::Dial { peer_id, addr, response } => {
+                let opts = libp2p::swarm::dial_opts::DialOpts::peer_id(peer_id)
+                    .addresses(vec![addr])
+                    .build();
+                let _ = response.send(swarm.dial(opts).map_err(|e| e.into()));
+            }
+            TestSwarmCommand::GetProtocols { response } => {
+                let _ = response.send(Ok(crate::supported_protocols(swarm)));
+            }
+            TestSwarmCommand::GetDialAttempts { response } => {
+                let _ = response.send(Ok(self.dial_attempts.clone()));
+            }
+            TestSwarmCommand::GetHookCounts { response } => {
+                let _ = response.send(Ok((self.commands_seen, self.events_seen)));
+            }
+        }
+    }
+
+    async fn handle_event(
+        &mut self,
+        _swarm: &mut Swarm<TestBehaviour>,
+        _event: &SwarmEvent<<TestBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm>,
+    ) {
+    }
+
+    async fn on_dialing(&mut self, peer_id: Option<PeerId>, connection_id: libp2p::swarm::ConnectionId) {
+        self.dial_attempts.push((peer_id, connection_id));
+    }
+
+    fn before_command(&mut self, _cmd: &Self::Command) {
+        self.commands_seen += 1;
+    }
+
+    fn after_event(
+        &mut self,
+        _event: &SwarmEvent<<TestBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm>,
+    ) {
+        self.events_seen += 1;
+    }
+}
+
+fn build_swarm() -> Swarm<TestBehaviour> {
+    libp2p::SwarmBuilder::with_new_identity()
+        .with_tokio()
+        .with_tcp(tcp::Config::default(), noise::Config::new, yamux::Config::default)
+        .unwrap()
+        .with_behaviour(|key| TestBehaviour {
+            ping: ping::Behaviour::default(),
+            identify: identify::Behaviour::new(identify::Config::new(
+                "/command-swarm-test/1.0.0".to_string(),
+                key.public(),
+            )),
+        })
+        .unwrap()
+        // Ping alone does not keep the connection alive between rounds
+        .with_swarm_config(|config| config.with_idle_connection_timeout(Duration::from_secs(30)))
+        .build()
+}
+
+fn dispatcher() -> TestBehaviourHandlerDispatcher {
+    TestBehaviourHandlerDispatcher {
+        ping: PingBehaviourHandler::default(),
+        identify: IdentifyBehaviourHandler,
+        swarm_handler: TestSwarmHandler::default(),
+    }
+}
+
+fn start_loop(
+    swarm: Swarm<TestBehaviour>,
+) -> (
+    tokio::sync::mpsc::Sender<TestCommands>,
+    SwarmLoopStopper,
+    tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+) {
+    let (command_tx, stopper, swarm_loop) =
+        SwarmLoopBuilder::<TestBehaviour, TestBehaviourHandlerDispatcher, TestCommands>::new()
+            .with_behaviour_handler(dispatcher())
+            .with_swarm(swarm)
+            .build()
+            .unwrap();
+    let handle = tokio::spawn(async move { swarm_loop.run().await });
+    (command_tx, stopper, handle)
+}
+
+/// Swarm listening on a local TCP port, with the address it listens on
+async fn listening_swarm() -> (Swarm<TestBehaviour>, Multiaddr) {
+    // Listen before handing the swarm to its loop to learn the real address
+    let mut swarm = build_swarm();
+    swarm.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap()).unwrap();
+    let addr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+            break address;
+        }
+    };
+    (swarm, addr)
+}
+
+async fn get_protocols(command_tx: &tokio::sync::mpsc::Sender<TestCommands>) -> Vec<String> {
+    let (response_tx, response_rx) = oneshot::channel();
+    command_tx
+        .send(TestCommands::SwarmLevel(TestSwarmCommand::GetProtocols { response: response_tx }))
+        .await
+        .unwrap();
+    response_rx.await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn test_supported_protocols_aggregates_all_behaviours() {
+    let (command_tx, stopper, handle) = start_loop(build_swarm());
+
+    let protocols = get_protocols(&command_tx).await;
+    for expected in [ping::PROTOCOL_NAME, identify::PROTOCOL_NAME, identify::PUSH_PROTOCOL_NAME] {
+        assert!(
+            protocols.contains(&expected.to_string()),
+            "{} missing: {:?}",
+            expected,
+            protocols
+        );
+    }
+    assert_eq!(
+        get_protocols(&command_tx).await,
+        protocols,
+        "probing the live behaviour again must give the same answer"
+    );
+
+    stopper.stop();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_build_serves_named_channels() {
+    let (_command_tx, stopper, swarm_loop) =
+        SwarmLoopBuilder::<TestBehaviour, TestBehaviourHandlerDispatcher, TestCommands>::new()
+            .with_behaviour_handler(dispatcher())
+            .with_swarm(build_swarm())
+            .with_named_channel("control", 4, 1)
+            .build()
+            .unwrap();
+    let control = swarm_loop.named_sender("control").expect("control channel missing");
+    assert!(swarm_loop.named_sender("data").is_none(), "data channel was never added");
+    let handle = tokio::spawn(async move { swarm_loop.run().await });
+
+    let protocols = get_protocols(&control).await;
+    assert!(!protocols.is_empty(), "command sent on the named channel was not handled");
+
+    stopper.stop();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_ping_handler_reports_rtt_between_loops() {
+    let (swarm_a, addr_a) = listening_swarm().await;
+    let peer_a = *swarm_a.local_peer_id();
+
+    let (_command_a, stopper_a, handle_a) = start_loop(swarm_a);
+    let (command_b, stopper_b, handle_b) = start_loop(build_swarm());
+
+    let (dial_tx, dial_rx) = oneshot::channel();
+    command_b
+        .send(TestCommands::SwarmLevel(TestSwarmCommand::Dial {
+            peer_id: peer_a,
+            addr: addr_a,
+            response: dial_tx,
+        }))
+        .await
+        .unwrap();
+    dial_rx.await.unwrap().expect("dial failed");
+
+    let rtt = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let (response_tx, response_rx) = oneshot::channel();
+            command_b
+                .send(TestCommands::ping(PingCommand::GetRtt {
+                    peer_id: peer_a,
+                    response: response_tx,
+                }))
+                .await
+                .unwrap();
+            if let Some(rtt) = response_rx.await.unwrap().unwrap() {
+                break rtt;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("no RTT reported for peer A");
+    assert!(rtt > Duration::ZERO, "RTT should be positive: {:?}", rtt);
+
+    let (response_tx, response_rx) = oneshot::channel();
+    command_b
+        .send(TestCommands::ping(PingCommand::GetAllRtts { response: response_tx }))
+        .await
+        .unwrap();
+    let all = response_rx.await.unwrap().unwrap();
+    assert_eq!(all.get(&peer_a), Some(&rtt), "GetAllRtts should include peer A");
+
+    stopper_a.stop();
+    stopper_b.stop();
+    handle_a.await.unwrap().unwrap();
+    handle_b.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_on_dialing_hook_sees_dial_attempt() {
+    let (swarm_a, addr_a) = listening_swarm().await;
+    let peer_a = *swarm_a.local_peer_id();
+
+    let (_command_a, stopper_a, handle_a) = start_loop(swarm_a);
+    let (command_b, stopper_b, handle_b) = start_loop(build_swarm());
+
+    let get_attempts = || async {
+        let (response_tx, response_rx) = oneshot::channel();
+        command_b
+            .send(TestCommands::SwarmLevel(TestSwarmCommand::GetDialAttempts {
+                response: response_tx,
+            }))
+            .await
+            .unwrap();
+        response_rx.await.unwrap().unwrap()
+    };
+    assert!(get_attempts().await.is_empty(), "no dial attempts expected before Dial");
+
+    let (dial_tx, dial_rx) = oneshot::channel();
+    command_b
+        .send(TestCommands::SwarmLevel(TestSwarmCommand::Dial {
+            peer_id: peer_a,
+            addr: addr_a,
+            response: dial_tx,
+        }))
+        .await
+        .unwrap();
+    dial_rx.await.unwrap().expect("dial failed");
+
+    let attempts = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let attempts = get_attempts().await;
+            if !attempts.is_empty() {
+                break attempts;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("on_dialing was not called");
+    assert_eq!(attempts.len(), 1, "exactly one dial attempt expected: {:?}", attempts);
+    assert_eq!(attempts[0].0, Some(peer_a), "dial attempt should carry the peer id");
+
+    stopper_a.stop();
+    stopper_b.stop();
+    handle_a.await.unwrap().unwrap();
+    handle_b.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_before_command_hook_fires_once_per_command() {
+    let (command_tx, stopper, handle) = start_loop(build_swarm());
+
+    for _ in 0..3 {
+        get_protocols(&command_tx).await;
+    }
+
+    let (response_tx, response_rx) = oneshot::channel();
+    command_tx
+        .send(TestCommands::SwarmLevel(TestSwarmCommand::GetHookCounts { response: response_tx }))
+        .await
+        .unwrap();
+    let (commands_seen, _events_seen) = response_rx.await.unwrap().unwrap();
+    // Three GetProtocols plus GetHookCounts itself, counted before dispatch
+    assert_eq!(commands_seen, 4, "before_command must fire once per command");
+
+    stopper.stop();
+    handle.await.unwrap().unwrap();
+}
+
+/// Collects command_id of every handle_command span
+struct CommandSpans(std::sync::Arc<std::sync::Mutex<Vec<u64>>>);
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CommandSpans {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct CommandId(Option<u64>);
+        impl tracing::field::Visit for CommandId {
+            fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+                if field.name() == "command_id" {
+                    self.0 = Some(value);
+                }
+            }
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "command_id" {
+                    self.0 = format!("{:?}", value).parse().ok();
+                }
+            }
+        }
+
+        if attrs.metadata().name() != "handle_command" {
+            return;
+        }
+        let mut command_id = CommandId(None);
+        attrs.record(&mut command_id);
+        if let Some(id) = command_id.0 {
+            self.0.lock().unwrap().push(id);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_command_span_carries_command_id() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let ids = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let subscriber = tracing_subscriber::registry().with(CommandSpans(ids.clone()));
+    // Current-thread runtime: the spawned loop sees the thread-local subscriber
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let (command_tx, stopper, handle) = start_loop(build_swarm());
+    for _ in 0..3 {
+        get_protocols(&command_tx).await;
+    }
+
+    stopper.stop();
+    handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        *ids.lock().unwrap(),
+        vec![1, 2, 3],
+        "each command must get its own span with a fresh command_id"
+    );
+}
+
+#[tokio::test]
+async fn test_shutdown_hook_runs_on_stop() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let swarm = build_swarm();
+    let local_peer_id = *swarm.local_peer_id();
+    let hook_called = Arc::new(AtomicBool::new(false));
+    let hook_peer_id = Arc::new(Mutex::new(None));
+
+    let called = hook_called.clone();
+    let seen_peer_id = hook_peer_id.clone();
+    let (_command_tx, stopper, swarm_loop) =
+        SwarmLoopBuilder::<TestBehaviour, TestBehaviourHandlerDispatcher, TestCommands>::new()
+            .with_behaviour_handler(dispatcher())
+            .with_swarm(swarm)
+            .with_shutdown_hook(move |swarm| {
+                *seen_peer_id.lock().unwrap() = Some(*swarm.local_peer_id());
+                called.store(true, Ordering::SeqCst);
+            })
+            .build()
+            .unwrap();
+    let handle = tokio::spawn(async move { swarm_loop.run().await });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!hook_called.load(Ordering::SeqCst), "hook must not run before stop");
+
+    stopper.stop();
+    handle.await.unwrap().unwrap();
+
+    assert!(hook_called.load(Ordering::SeqCst), "shutdown hook was not called");
+    assert_eq!(
+        *hook_peer_id.lock().unwrap(),
+        Some(local_peer_id),
+        "hook should receive the loop's swarm"
+    );
+}
+
+/// Handler recording the events and commands it observes
+#[derive(Default)]
+struct RecordingHandler {
+    events: Vec<String>,
+    commands: usize,
+}
+
+#[derive(Debug)]
+struct Mark;
+
+#[async_trait]
+impl SwarmHandler<TestBehaviour> for RecordingHandler {
+    type Command = Mark;
+
+    async fn handle_command(&mut self, _swarm: &mut Swarm<TestBehaviour>, _cmd: Self::Command) {
+        self.commands += 1;
+    }
+
+    async fn handle_event(
+        &mut self,
+        _swarm: &mut Swarm<TestBehaviour>,
+        event: &SwarmEvent<<TestBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm>,
+    ) {
+        self.events.push(format!("{:?}", event));
+    }
+}
+
+#[tokio::test]
+async fn test_composite_handler_dispatches_events_to_all() {
+    use crate::{CompositeCommand, CompositeSwarmHandler};
+
+    let mut swarm = build_swarm();
+    let mut composite = CompositeSwarmHandler::new(RecordingHandler::default(), RecordingHandler::default());
+
+    let event = SwarmEvent::NewListenAddr {
+        listener_id: libp2p::core::transport::ListenerId::next(),
+        address: "/ip4/127.0.0.1/tcp/4001".parse().unwrap(),
+    };
+    composite.handle_event(&mut swarm, &event).await;
+
+    assert_eq!(composite.first.events.len(), 1, "first handler must observe the event");
+    assert_eq!(
+        composite.first.events, composite.second.events,
+        "both handlers must observe the same event"
+    );
+
+    composite.handle_command(&mut swarm, CompositeCommand::Second(Mark)).await;
+    assert_eq!(composite.first.commands, 0, "command must not reach the first handler");
+    assert_eq!(composite.second.commands, 1, "command must reach the second handler");
+}
+
+#[test]
+fn test_composite_handler_macro_nests_handlers() {
+    type Three = crate::composite_swarm_handler!(RecordingHandler, RecordingHandler, RecordingHandler);
+    let handler = Three::default();
+    assert!(handler.second.second.events.is_empty(), "third handler is nested in the second slot");
+}
@@ -1,5 +1,6 @@
 use libp2p::{
     core::{Endpoint, Multiaddr},
+    identity::Keypair,
     request_response::{self, ResponseChannel},
     swarm::{
         derive_prelude::*, ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, ToSwarm,
@@ -16,10 +17,11 @@ use super::definitions::DirectionalAuthState;
 
 // Import the ProofOfRepresentation from the por module
 use super::{
+    challenge::{new_challenge, ChallengeSignature, CHALLENGE_REQUIRED},
     connection_data::ConnectionData,
     definitions::{
         AuthDirection, AuthResult, CombinedAuthState, PendingVerification, PorAuthRequest,
-        PorAuthResponse, AUTH_TIMEOUT, PROTOCOL_ID,
+        PorAuthResponse, RejectCode, AUTH_TIMEOUT, PROTOCOL_ID,
     },
     events::PorAuthEvent,
     por::por::ProofOfRepresentation,
@@ -47,6 +49,19 @@ pub struct PorAuthBehaviour {
 
     // Storage for pending PoR verifications using ConnectionId
     pending_verifications: HashMap<ConnectionId, PendingVerification>,
+
+    // Authentication timeouts per direction (default: AUTH_TIMEOUT)
+    inbound_auth_timeout: Duration,
+    outbound_auth_timeout: Duration,
+
+    // Node key used to answer challenges from remote verifiers
+    challenge_keypair: Option<Keypair>,
+
+    // Challenge remote provers before verifying their PoR
+    require_challenge: bool,
+
+    // Connection each outbound auth request was sent for; libp2p may carry it over another one
+    outbound_requests: HashMap<request_response::OutboundRequestId, ConnectionId>,
 }
 
 impl PorAuthBehaviour {
@@ -70,9 +85,43 @@ impl PorAuthBehaviour {
             por,
             metadata,
             pending_verifications: HashMap::new(),
+            inbound_auth_timeout: AUTH_TIMEOUT,
+            outbound_auth_timeout: AUTH_TIMEOUT,
+            challenge_keypair: None,
+            require_challenge: false,
+            outbound_requests: HashMap::new(),
         }
     }
 
+    // Use the same authentication timeout for both directions
+    pub fn with_auth_timeout(self, timeout: Duration) -> Self {
+        self.with_auth_timeouts(timeout, timeout)
+    }
+
+    // Use separate timeouts for inbound and outbound authentication
+    pub fn with_auth_timeouts(mut self, inbound: Duration, outbound: Duration) -> Self {
+        self.inbound_auth_timeout = inbound;
+        self.outbound_auth_timeout = outbound;
+        self
+    }
+
+    // Answer remote challenges with the node key; challenge remote provers too if `require`
+    pub fn with_challenge(mut self, keypair: Keypair, require: bool) -> Self {
+        self.challenge_keypair = Some(keypair);
+        self.require_challenge = require;
+        self
+    }
+
+    // Whether remote provers must sign a fresh nonce before their PoR is verified
+    pub fn requires_challenge(&self) -> bool {
+        self.require_challenge
+    }
+
+    // Current (inbound, outbound) authentication timeouts
+    pub fn auth_timeouts(&self) -> (Duration, Duration) {
+        (self.inbound_auth_timeout, self.outbound_auth_timeout)
+    }
+
     // Update the PoR data used for authentication
     pub fn update_por(&mut self, por: ProofOfRepresentation) {
         self.por = por;
@@ -96,8 +145,43 @@ impl PorAuthBehaviour {
         if let Some(conn) = self.connections.get_mut(&connection_id) {
             conn.touch();
 
+            // Connection whose inbound auth this request completes
+            let mut connection_id = connection_id;
+            match request.challenge_signature.as_ref() {
+                Some(signature) => {
+                    // Ответ может прийти по другому соединению с тем же пиром
+                    match self.take_issued_challenge(peer_id, connection_id, signature, &request.por) {
+                        Ok(issued_on) => connection_id = issued_on,
+                        Err(reason) => {
+                            self.reject_auth_request(connection_id, channel, reason);
+                            return;
+                        }
+                    }
+                }
+                None if self.require_challenge => {
+                    // PoR проверяется только после подписи свежего nonce
+                    let challenge = new_challenge();
+                    let target = self.challenge_target(peer_id, connection_id);
+                    if let Some(conn) = self.connections.get_mut(&target) {
+                        conn.challenge = Some(challenge.clone());
+                    }
+                    let _ = self.request_response.send_response(
+                        channel,
+                        PorAuthResponse {
+                            result: AuthResult::Error(CHALLENGE_REQUIRED.to_string()),
+                            challenge: Some(challenge),
+                        },
+                    );
+                    return;
+                }
+                None => {}
+            }
+
             // Get address for event
-            let address = conn.address.clone();
+            let Some(address) = self.connections.get(&connection_id).map(|conn| conn.address.clone())
+            else {
+                return;
+            };
 
             // Store the verification request with connection_id
             let verification = PendingVerification {
@@ -124,6 +208,64 @@ impl PorAuthBehaviour {
         }
     }
 
+    // Connection a new nonce is issued for: the one the request arrived on unless it
+    // already has a nonce or verification outstanding, otherwise another free one to the peer
+    fn challenge_target(&self, peer_id: PeerId, connection_id: ConnectionId) -> ConnectionId {
+        let is_free = |id: &ConnectionId| {
+            self.connections.get(id).is_some_and(|conn| {
+                conn.challenge.is_none()
+                    && !matches!(conn.inbound_auth, DirectionalAuthState::Successful(_))
+            }) && !self.pending_verifications.contains_key(id)
+        };
+        if is_free(&connection_id) {
+            return connection_id;
+        }
+        self.peer_connections
+            .get(&peer_id)
+            .and_then(|ids| ids.iter().copied().find(|id| is_free(id)))
+            .unwrap_or(connection_id)
+    }
+
+    // Consume the nonce issued to the peer that `signature` answers, returning the connection
+    // it was issued for. Nonces are single use; an invalid signature burns all of the peer's nonces.
+    fn take_issued_challenge(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        signature: &ChallengeSignature,
+        por: &ProofOfRepresentation,
+    ) -> Result<ConnectionId, String> {
+        let mut candidates = vec![connection_id];
+        if let Some(ids) = self.peer_connections.get(&peer_id) {
+            candidates.extend(ids.iter().copied().filter(|id| *id != connection_id));
+        }
+
+        let mut error = None;
+        for id in &candidates {
+            let Some(challenge) = self.connections.get(id).and_then(|conn| conn.challenge.as_ref())
+            else {
+                continue;
+            };
+            match signature.verify(challenge, por) {
+                Ok(()) => {
+                    if let Some(conn) = self.connections.get_mut(id) {
+                        conn.challenge = None;
+                    }
+                    return Ok(*id);
+                }
+                Err(reason) => error = Some(reason),
+            }
+        }
+
+        for id in &candidates {
+            if let Some(conn) = self.connections.get_mut(id) {
+                conn.challenge = None;
+            }
+        }
+        // Подпись без выданного нами nonce - повтор перехваченного запроса
+        Err(error.unwrap_or_else(|| "Challenge signature without an issued challenge".to_string()))
+    }
+
     // Process the authentication response from the remote peer
     fn handle_auth_response(
         &mut self,
@@ -139,7 +281,46 @@ impl PorAuthBehaviour {
         if let Some(conn) = self.connections.get_mut(&connection_id) {
             conn.touch();
 
-            match response.result {
+            // The verifier wants proof that we hold the key of our PoR node first
+            if let Some(challenge) = &response.challenge {
+                if !matches!(response.result, AuthResult::Ok(_)) && !conn.challenge_answered {
+                    if let Some(keypair) = &self.challenge_keypair {
+                        match ChallengeSignature::sign(keypair, challenge, &self.por) {
+                            Ok(signature) => {
+                                conn.challenge_answered = true;
+                                let request_id = self.request_response.send_request(
+                                    &peer_id,
+                                    PorAuthRequest {
+                                        por: self.por.clone(),
+                                        metadata: self.metadata.clone(),
+                                        challenge_signature: Some(signature),
+                                    },
+                                );
+                                self.outbound_requests.insert(request_id, connection_id);
+                                return;
+                            }
+                            Err(e) => {
+                                // Без подписи верификатор не примет PoR, ответ о вызове - не отказ
+                                let reason = format!("Failed to answer auth challenge: {}", e);
+                                conn.set_outbound_auth_failed(reason.clone());
+                                self.pending_events.push_back(ToSwarm::GenerateEvent(
+                                    PorAuthEvent::InboundAuthFailure {
+                                        peer_id,
+                                        connection_id,
+                                        address: conn.address.clone(),
+                                        reason,
+                                        code: None,
+                                    },
+                                ));
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            let result = response.result;
+
+            match result {
                 AuthResult::Ok(metadata) => {
                     // Update connection state
                     conn.set_outbound_auth_success(metadata.clone());
@@ -178,7 +359,9 @@ impl PorAuthBehaviour {
                                 conn.get_combined_state(), peer_id, connection_id);
                     }
                 }
-                AuthResult::Error(reason) => {
+                failure => {
+                    let (reason, code) = failure.failure().unwrap_or_default();
+
                     // Update state
                     conn.set_outbound_auth_failed(reason.clone());
 
@@ -189,6 +372,7 @@ impl PorAuthBehaviour {
                             connection_id,
                             address: conn.address.clone(),
                             reason,
+                            code,
                         },
                     ));
                 }
@@ -202,13 +386,17 @@ impl PorAuthBehaviour {
             // Update state
             conn.start_outbound_auth();
 
-            // Send authentication request
+            conn.challenge_answered = false;
+
+            // Send authentication request, the signature comes once the verifier issues a nonce
             let request = PorAuthRequest {
                 por: self.por.clone(),
                 metadata: self.metadata.clone(),
+                challenge_signature: None,
             };
 
-            self.request_response.send_request(&peer_id, request);
+            let request_id = self.request_response.send_request(&peer_id, request);
+            self.outbound_requests.insert(request_id, connection_id);
         }
     }
 
@@ -243,6 +431,7 @@ impl PorAuthBehaviour {
             verification.response_channel,
             PorAuthResponse {
                 result: result.clone(),
+                challenge: None,
             },
         ) {
             return Err(format!("Failed to send auth response: {:?}", e));
@@ -302,7 +491,8 @@ impl PorAuthBehaviour {
                                 conn.get_combined_state(), peer_id, connection_id);
                     }
                 }
-                AuthResult::Error(reason) => {
+                failure => {
+                    let (reason, code) = failure.failure().unwrap_or_default();
                     conn.set_inbound_auth_failed(reason.clone());
                     need_outbound_auth = false;
 
@@ -313,6 +503,7 @@ impl PorAuthBehaviour {
                             connection_id,
                             address: address.clone(),
                             reason,
+                            code,
                         },
                     ));
                 }
@@ -367,11 +558,12 @@ impl PorAuthBehaviour {
     // Check for authentication timeouts
     fn check_timeouts(&mut self) {
         // Find connections with timeouts
+        let (inbound_timeout, outbound_timeout) = self.auth_timeouts();
         let timed_out_connections: Vec<(ConnectionId, PeerId, AuthDirection, Multiaddr)> = self
             .connections
             .iter()
             .filter_map(|(conn_id, conn)| {
-                conn.check_timeout(AUTH_TIMEOUT)
+                conn.check_timeout_directional(inbound_timeout, outbound_timeout)
                     .map(|direction| (*conn_id, conn.peer_id, direction, conn.address.clone()))
             })
             .collect();
@@ -461,12 +653,42 @@ impl PorAuthBehaviour {
                     verification.response_channel,
                     PorAuthResponse {
                         result: AuthResult::Error("Verification timed out".to_string()),
+                        challenge: None,
                     },
                 );
             }
         }
     }
 
+    // Reject a request whose challenge answer is missing or invalid, without asking the application
+    fn reject_auth_request(
+        &mut self,
+        connection_id: ConnectionId,
+        channel: ResponseChannel<PorAuthResponse>,
+        reason: String,
+    ) {
+        let _ = self.request_response.send_response(
+            channel,
+            PorAuthResponse {
+                result: AuthResult::reject(RejectCode::InvalidSignature, reason.clone()),
+                challenge: None,
+            },
+        );
+
+        if let Some(conn) = self.connections.get_mut(&connection_id) {
+            conn.set_inbound_auth_failed(reason.clone());
+            self.pending_events.push_back(ToSwarm::GenerateEvent(
+                PorAuthEvent::OutboundAuthFailure {
+                    peer_id: conn.peer_id,
+                    connection_id,
+                    address: conn.address.clone(),
+                    reason,
+                    code: Some(RejectCode::InvalidSignature),
+                },
+            ));
+        }
+    }
+
     // Get current authentication state for a peer
     pub fn is_peer_authenticated(&self, peer_id: &PeerId) -> bool {
         // Check if any connection for this peer is fully authenticated
@@ -621,6 +843,8 @@ impl NetworkBehaviour for PorAuthBehaviour {
                     // Remove any pending verification for this connection
                     self.pending_verifications
                         .remove(&connection_closed.connection_id);
+                    self.outbound_requests
+                        .retain(|_, id| *id != connection_closed.connection_id);
                 }
             }
             _ => {}
@@ -651,21 +875,30 @@ impl NetworkBehaviour for PorAuthBehaviour {
                     return Poll::Pending;
                 }
                 ToSwarm::GenerateEvent(request_response::Event::Message {
-                    message: request_response::Message::Response { response, .. },
+                    message: request_response::Message::Response { request_id, response },
                     peer,
                     connection_id, // Fixed field name
                     ..
                 }) => {
-                    // Handle auth response with connection ID
+                    // The response belongs to the connection the request was sent for
+                    let connection_id = self
+                        .outbound_requests
+                        .remove(&request_id)
+                        .unwrap_or(connection_id);
                     self.handle_auth_response(peer, connection_id, response);
                     return Poll::Pending;
                 }
                 ToSwarm::GenerateEvent(request_response::Event::OutboundFailure {
                     peer,
                     connection_id, // Fixed field name
+                    request_id,
                     error,
                     ..
                 }) => {
+                    let connection_id = self
+                        .outbound_requests
+                        .remove(&request_id)
+                        .unwrap_or(connection_id);
                     // Mark connection as failed if it exists
                     // Mark connection as failed if it exists
                     if let Some(conn) = self.connections.get_mut(&connection_id) {
@@ -681,6 +914,7 @@ impl NetworkBehaviour for PorAuthBehaviour {
                                 connection_id,
                                 address,
                                 reason: format!("Outbound request failed: {:?}", error),
+                                code: None,
                             },
                         ));
                     }
@@ -706,6 +940,7 @@ impl NetworkBehaviour for PorAuthBehaviour {
                                 connection_id,
                                 address,
                                 reason: format!("Inbound request failed: {:?}", error),
+                                code: None,
                             },
                         ));
                     }
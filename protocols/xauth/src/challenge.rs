@@ -0,0 +1,74 @@
+use libp2p::identity::{Keypair, PublicKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::por::por::ProofOfRepresentation;
+
+// Domain separation tag for challenge signatures
+const CHALLENGE_DOMAIN: &[u8] = b"/por-auth/challenge/2.0.0";
+
+// Length of the random nonce issued by the verifier
+pub const CHALLENGE_LEN: usize = 32;
+
+// Error message of a response that carries a challenge instead of a verdict
+pub const CHALLENGE_REQUIRED: &str = "Challenge required";
+
+// Prover's signature over the verifier's nonce and its PoR, made with the key of `por.peer_id`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeSignature {
+    // Protobuf-encoded node public key
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl ChallengeSignature {
+    // Sign a challenge issued by the verifier, binding it to the PoR we present
+    pub fn sign(
+        keypair: &Keypair,
+        challenge: &[u8],
+        por: &ProofOfRepresentation,
+    ) -> Result<Self, String> {
+        let signature = keypair
+            .sign(&Self::message(challenge, por))
+            .map_err(|e| format!("Challenge signing error: {}", e))?;
+
+        Ok(Self {
+            public_key: keypair.public().encode_protobuf(),
+            signature,
+        })
+    }
+
+    // Verify that the node named in `por` signed exactly `challenge` for this PoR
+    pub fn verify(&self, challenge: &[u8], por: &ProofOfRepresentation) -> Result<(), String> {
+        let public_key = PublicKey::try_decode_protobuf(&self.public_key)
+            .map_err(|e| format!("Invalid challenge public key: {}", e))?;
+
+        if public_key.to_peer_id() != por.peer_id {
+            return Err("Challenge signed by a peer other than the PoR node".to_string());
+        }
+
+        if public_key.verify(&Self::message(challenge, por), &self.signature) {
+            Ok(())
+        } else {
+            Err("Invalid challenge signature".to_string())
+        }
+    }
+
+    // domain || nonce || H(PoR)
+    fn message(challenge: &[u8], por: &ProofOfRepresentation) -> Vec<u8> {
+        let digest = por.digest();
+        let mut message =
+            Vec::with_capacity(CHALLENGE_DOMAIN.len() + challenge.len() + digest.len());
+        message.extend_from_slice(CHALLENGE_DOMAIN);
+        message.extend_from_slice(challenge);
+        message.extend_from_slice(&digest);
+        message
+    }
+}
+
+// Generate a fresh random challenge nonce
+pub fn new_challenge() -> Vec<u8> {
+    let mut nonce = vec![0u8; CHALLENGE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    nonce
+}
@@ -23,6 +23,10 @@ pub struct ConnectionData {
     // Timeout flags to make timeout events idempotent
     pub outbound_timed_out: bool,
     pub inbound_timed_out: bool,
+    // Nonce we issued to the remote prover, consumed by its next request
+    pub challenge: Option<Vec<u8>>,
+    // Whether we already answered the remote verifier's challenge
+    pub challenge_answered: bool,
 }
 
 impl ConnectionData {
@@ -39,6 +43,8 @@ impl ConnectionData {
             outbound_auth: DirectionalAuthState::NotStarted,
             outbound_timed_out: false,
             inbound_timed_out: false,
+            challenge: None,
+            challenge_answered: false,
         }
     }
 
@@ -112,6 +118,15 @@ impl ConnectionData {
 
     // Check for authentication timeouts
     pub fn check_timeout(&self, auth_timeout: Duration) -> Option<AuthDirection> {
+        self.check_timeout_directional(auth_timeout, auth_timeout)
+    }
+
+    // Check for authentication timeouts with separate limits per direction
+    pub fn check_timeout_directional(
+        &self,
+        inbound_timeout_limit: Duration,
+        outbound_timeout_limit: Duration,
+    ) -> Option<AuthDirection> {
         let now = Instant::now();
 
         // Ignore timeout if both directions are NotStarted
@@ -124,7 +139,7 @@ impl ConnectionData {
         // Check each authentication direction for timeout
         let inbound_timeout = match &self.inbound_auth {
             DirectionalAuthState::InProgress { started }
-                if now.duration_since(*started) > auth_timeout =>
+                if now.duration_since(*started) > inbound_timeout_limit =>
             {
                 true
             }
@@ -133,7 +148,7 @@ impl ConnectionData {
 
         let outbound_timeout = match &self.outbound_auth {
             DirectionalAuthState::InProgress { started }
-                if now.duration_since(*started) > auth_timeout =>
+                if now.duration_since(*started) > outbound_timeout_limit =>
             {
                 true
             }
@@ -141,6 +156,7 @@ impl ConnectionData {
         };
 
         // Check for overall connection inactivity
+        let auth_timeout = inbound_timeout_limit.max(outbound_timeout_limit);
         let conn_timeout = now.duration_since(self.last_activity) > auth_timeout * 1;
 
         // Return appropriate timeout direction
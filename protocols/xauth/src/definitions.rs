@@ -6,7 +6,7 @@ use std::{
 use libp2p::{request_response::ResponseChannel, Multiaddr, PeerId};
 use serde::{Deserialize, Serialize};
 
-use super::por::por::ProofOfRepresentation;
+use super::{challenge::ChallengeSignature, por::por::ProofOfRepresentation};
 
 // Protocol identifier
 pub const PROTOCOL_ID: &str = "/por-auth/1.0.0";
@@ -27,17 +27,65 @@ pub struct PendingVerification {
 pub struct PorAuthRequest {
     pub por: ProofOfRepresentation,
     pub metadata: HashMap<String, String>,
+    // Answer to the nonce the verifier issued on this connection (absent on the first attempt)
+    #[serde(default)]
+    pub challenge_signature: Option<ChallengeSignature>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PorAuthResponse {
     pub result: AuthResult,
+    // Fresh nonce the prover must sign before the PoR is verified (absent for old peers)
+    #[serde(default)]
+    pub challenge: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AuthResult {
     Ok(HashMap<String, String>),
     Error(String),
+    // Rejection with a machine-readable reason
+    Reject { code: RejectCode, message: String },
+}
+
+impl AuthResult {
+    pub fn reject(code: RejectCode, message: impl Into<String>) -> Self {
+        AuthResult::Reject {
+            code,
+            message: message.into(),
+        }
+    }
+
+    // Failure reason and optional code, None for a successful result
+    pub fn failure(&self) -> Option<(String, Option<RejectCode>)> {
+        match self {
+            AuthResult::Ok(_) => None,
+            AuthResult::Error(reason) => Some((reason.clone(), None)),
+            AuthResult::Reject { code, message } => Some((message.clone(), Some(*code))),
+        }
+    }
+}
+
+// Typed reasons for rejecting a PoR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RejectCode {
+    Expired,
+    InvalidSignature,
+    UntrustedOwner,
+    PolicyDenied,
+}
+
+impl RejectCode {
+    // Classify a PoR that fails local validation, None if it is valid
+    pub fn for_por(por: &ProofOfRepresentation) -> Option<RejectCode> {
+        if por.validate().is_ok() {
+            None
+        } else if por.is_expired().unwrap_or(false) {
+            Some(RejectCode::Expired)
+        } else {
+            Some(RejectCode::InvalidSignature)
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use libp2p::{swarm::ConnectionId, Multiaddr, PeerId};
 
-use super::{definitions::AuthDirection, por::por::ProofOfRepresentation};
+use super::{
+    definitions::{AuthDirection, RejectCode},
+    por::por::ProofOfRepresentation,
+};
 
 // Events emitted by the behaviour
 #[derive(Debug, Clone)]
@@ -33,6 +36,7 @@ pub enum PorAuthEvent {
         connection_id: ConnectionId,
         address: Multiaddr,
         reason: String,
+        code: Option<RejectCode>,
     },
     // Remote peer rejected our authentication
     InboundAuthFailure {
@@ -40,6 +44,7 @@ pub enum PorAuthEvent {
         connection_id: ConnectionId,
         address: Multiaddr,
         reason: String,
+        code: Option<RejectCode>,
     },
     // Authentication timeout
     AuthTimeout {
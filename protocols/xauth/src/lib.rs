@@ -1,5 +1,6 @@
 #![allow(warnings)]
 pub mod behaviours;          // src/utils.rs  
+pub mod challenge;
 pub mod connection_data;      
 pub mod definitions;      
 pub mod events;      
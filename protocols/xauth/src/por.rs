@@ -3,6 +3,7 @@ use libp2p::{
     PeerId,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // Create a module with serialization/deserialization logic for PublicKey
@@ -229,6 +230,19 @@ pub mod por {
             message
         }
 
+        /// SHA-256 digest of the signed POR contents and the owner's signature
+        pub fn digest(&self) -> [u8; 32] {
+            let mut hasher = Sha256::new();
+            hasher.update(Self::prepare_message_for_signing(
+                &self.owner_public_key,
+                &self.peer_id,
+                self.issued_at,
+                self.expires_at,
+            ));
+            hasher.update(&self.signature);
+            hasher.finalize().into()
+        }
+
         /// Check if POR has expired
         pub fn is_expired(&self) -> Result<bool, String> {
             let now = SystemTime::now()
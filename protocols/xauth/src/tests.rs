@@ -314,4 +314,144 @@ mod tests {
         assert!(!conn.inbound_timed_out);
         assert!(!conn.outbound_timed_out);
     }
+
+    #[test]
+    fn test_directional_timeouts_are_independent() {
+        use crate::connection_data::ConnectionData;
+        use crate::definitions::AuthDirection;
+
+        let peer_id = PeerId::random();
+        let connection_id = ConnectionId::new_unchecked(1);
+        let address = Multiaddr::empty();
+
+        let mut conn = ConnectionData::new(peer_id, connection_id, address);
+        conn.start_outbound_auth();
+        std::thread::sleep(Duration::from_millis(20));
+
+        // Only the outbound limit is exceeded
+        let result = conn.check_timeout_directional(Duration::from_secs(10), Duration::from_millis(5));
+        assert_eq!(result, Some(AuthDirection::Outbound));
+
+        // A long outbound limit must not fire
+        let result = conn.check_timeout_directional(Duration::from_millis(5), Duration::from_secs(10));
+        assert!(result.is_none(), "Inbound limit must not apply to outbound auth");
+    }
+
+    #[test]
+    fn test_reject_code_for_por() {
+        use crate::definitions::{AuthResult, RejectCode};
+
+        let owner_keypair = PorUtils::generate_owner_keypair();
+        let node_keypair = PorUtils::generate_owner_keypair();
+        let node_peer_id = PorUtils::peer_id_from_keypair(&node_keypair);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // Valid PoR has no reject code
+        let valid = ProofOfRepresentation::create(&owner_keypair, node_peer_id, Duration::from_secs(3600))
+            .expect("Failed to create POR");
+        assert_eq!(RejectCode::for_por(&valid), None);
+
+        // Expired PoR is classified as Expired
+        let expired = ProofOfRepresentation::create_with_times(&owner_keypair, node_peer_id, now - 7200, now - 3600)
+            .expect("Failed to create POR");
+        assert_eq!(RejectCode::for_por(&expired), Some(RejectCode::Expired));
+
+        // Tampered PoR is classified as InvalidSignature
+        let mut tampered = valid.clone();
+        tampered.expires_at += 1;
+        assert_eq!(RejectCode::for_por(&tampered), Some(RejectCode::InvalidSignature));
+
+        // Reject carries its code, Error stays untyped
+        let rejected = AuthResult::reject(RejectCode::Expired, "POR has expired");
+        assert_eq!(
+            rejected.failure(),
+            Some(("POR has expired".to_string(), Some(RejectCode::Expired)))
+        );
+        let error = AuthResult::Error("denied".to_string());
+        assert_eq!(error.failure(), Some(("denied".to_string(), None)));
+        assert_eq!(AuthResult::Ok(Default::default()).failure(), None);
+    }
+
+    #[test]
+    fn test_challenge_fresh_handshake() {
+        use crate::challenge::{new_challenge, ChallengeSignature};
+
+        let owner_keypair = PorUtils::generate_owner_keypair();
+        let node_keypair = PorUtils::generate_owner_keypair();
+        let node_peer_id = PorUtils::peer_id_from_keypair(&node_keypair);
+        let por = ProofOfRepresentation::create(&owner_keypair, node_peer_id, Duration::from_secs(3600))
+            .expect("Failed to create POR");
+
+        // Prover signs the verifier's nonce together with its PoR
+        let challenge = new_challenge();
+        let signature = ChallengeSignature::sign(&node_keypair, &challenge, &por)
+            .expect("Failed to sign challenge");
+        signature
+            .verify(&challenge, &por)
+            .expect("Fresh challenge signature should be valid");
+
+        // Signature must come from the key of the PoR node
+        let other_keypair = PorUtils::generate_owner_keypair();
+        let forged = ChallengeSignature::sign(&other_keypair, &challenge, &por).unwrap();
+        assert!(forged.verify(&challenge, &por).is_err());
+
+        // Signature is bound to the PoR it was made for
+        let other_por = ProofOfRepresentation::create(&owner_keypair, node_peer_id, Duration::from_secs(60))
+            .expect("Failed to create POR");
+        assert!(signature.verify(&challenge, &other_por).is_err());
+    }
+
+    #[test]
+    fn test_challenge_replay_rejected() {
+        use crate::challenge::{new_challenge, ChallengeSignature};
+
+        let owner_keypair = PorUtils::generate_owner_keypair();
+        let node_keypair = PorUtils::generate_owner_keypair();
+        let node_peer_id = PorUtils::peer_id_from_keypair(&node_keypair);
+        let por = ProofOfRepresentation::create(&owner_keypair, node_peer_id, Duration::from_secs(3600))
+            .expect("Failed to create POR");
+
+        // Answer captured from an earlier connection
+        let old_challenge = new_challenge();
+        let captured = ChallengeSignature::sign(&node_keypair, &old_challenge, &por).unwrap();
+
+        // Replayed against a fresh nonce it must fail
+        let new_challenge = new_challenge();
+        assert_ne!(old_challenge, new_challenge);
+        assert!(captured.verify(&new_challenge, &por).is_err());
+    }
+
+    #[test]
+    fn test_challenge_fields_optional_on_wire() {
+        use crate::definitions::{AuthResult, PorAuthRequest, PorAuthResponse};
+        use serde::Serialize;
+
+        // Messages from peers that predate challenges
+        #[derive(Serialize)]
+        struct OldRequest {
+            por: ProofOfRepresentation,
+            metadata: std::collections::HashMap<String, String>,
+        }
+        #[derive(Serialize)]
+        struct OldResponse {
+            result: AuthResult,
+        }
+
+        let owner_keypair = PorUtils::generate_owner_keypair();
+        let node_peer_id = PorUtils::peer_id_from_keypair(&PorUtils::generate_owner_keypair());
+        let por = ProofOfRepresentation::create(&owner_keypair, node_peer_id, Duration::from_secs(3600))
+            .expect("Failed to create POR");
+
+        let bytes = serde_cbor::to_vec(&OldRequest { por, metadata: Default::default() }).unwrap();
+        let request: PorAuthRequest = serde_cbor::from_slice(&bytes).unwrap();
+        assert!(request.challenge_signature.is_none());
+
+        let bytes = serde_cbor::to_vec(&OldResponse { result: AuthResult::Ok(Default::default()) }).unwrap();
+        let response: PorAuthResponse = serde_cbor::from_slice(&bytes).unwrap();
+        assert!(response.challenge.is_none());
+    }
 }
@@ -0,0 +1,251 @@
+#![cfg(test)]
+
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, Multiaddr, PeerId};
+use libp2p_swarm::{Swarm, SwarmEvent};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+use xauth::{
+    behaviours::PorAuthBehaviour,
+    challenge::{new_challenge, ChallengeSignature, CHALLENGE_REQUIRED},
+    definitions::{AuthResult, PorAuthRequest, RejectCode},
+    events::PorAuthEvent,
+    por::por::{PorUtils, ProofOfRepresentation},
+};
+
+fn create_por(owner_key: &identity::Keypair, peer_id: PeerId) -> ProofOfRepresentation {
+    ProofOfRepresentation::create(owner_key, peer_id, Duration::from_secs(60))
+        .expect("Failed to create POR")
+}
+
+/// Creates a QUIC swarm; `challenge` enables answering and requiring challenges
+fn create_swarm(
+    keypair: identity::Keypair,
+    por: ProofOfRepresentation,
+    challenge: bool,
+) -> Swarm<PorAuthBehaviour> {
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let challenge_key = keypair.clone();
+
+    libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("Failed to create QUIC transport")
+        .with_behaviour(|_key| {
+            let behaviour = PorAuthBehaviour::new(por);
+            if challenge {
+                behaviour.with_challenge(challenge_key, true)
+            } else {
+                behaviour
+            }
+        })
+        .expect("Failed to create PorAuthBehaviour")
+        .with_swarm_config(|config| config.with_idle_connection_timeout(Duration::from_secs(30)))
+        .build()
+}
+
+async fn listen(swarm: &mut Swarm<PorAuthBehaviour>) -> Multiaddr {
+    swarm
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("Failed to listen");
+    timeout(Duration::from_secs(2), async {
+        loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+                return address;
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for listen address")
+}
+
+// Approve every valid PoR the behaviour forwards for verification
+fn approve(swarm: &mut Swarm<PorAuthBehaviour>, event: &SwarmEvent<PorAuthEvent>) {
+    if let SwarmEvent::Behaviour(PorAuthEvent::VerifyPorRequest { connection_id, por, metadata, .. }) = event {
+        let result = if por.validate().is_ok() {
+            AuthResult::Ok(metadata.clone())
+        } else {
+            AuthResult::Error("POR validation failed".to_string())
+        };
+        swarm
+            .behaviour_mut()
+            .submit_por_verification_result(*connection_id, result)
+            .expect("Failed to submit verification result");
+    }
+}
+
+#[tokio::test]
+async fn test_challenge_handshake_authenticates_both_sides() {
+    let owner_key = PorUtils::generate_owner_keypair();
+    let key1 = identity::Keypair::generate_ed25519();
+    let key2 = identity::Keypair::generate_ed25519();
+    let peer1 = key1.public().to_peer_id();
+    let peer2 = key2.public().to_peer_id();
+    let mut swarm1 = create_swarm(key1, create_por(&owner_key, peer1), true);
+    let mut swarm2 = create_swarm(key2, create_por(&owner_key, peer2), true);
+
+    let addr = listen(&mut swarm1).await;
+    swarm2.dial(addr).expect("Failed to dial");
+
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(10)
+        && !(swarm1.behaviour().is_peer_authenticated(&peer2)
+            && swarm2.behaviour().is_peer_authenticated(&peer1))
+    {
+        tokio::select! {
+            event = swarm1.select_next_some() => {
+                if let SwarmEvent::ConnectionEstablished { connection_id, .. } = event {
+                    swarm1.behaviour_mut().start_authentication(connection_id).unwrap();
+                }
+                approve(&mut swarm1, &event);
+            }
+            event = swarm2.select_next_some() => {
+                if let SwarmEvent::ConnectionEstablished { connection_id, .. } = event {
+                    swarm2.behaviour_mut().start_authentication(connection_id).unwrap();
+                }
+                approve(&mut swarm2, &event);
+            }
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+
+    assert!(swarm1.behaviour().is_peer_authenticated(&peer2), "Swarm1 should authenticate swarm2");
+    assert!(swarm2.behaviour().is_peer_authenticated(&peer1), "Swarm2 should authenticate swarm1");
+}
+
+#[tokio::test]
+async fn test_replayed_challenge_signature_rejected() {
+    let owner_key = PorUtils::generate_owner_keypair();
+
+    // Victim node: its PoR and a challenge answer captured from an earlier connection
+    let victim_key = identity::Keypair::generate_ed25519();
+    let victim_por = create_por(&owner_key, victim_key.public().to_peer_id());
+    let captured = PorAuthRequest {
+        por: victim_por.clone(),
+        metadata: Default::default(),
+        challenge_signature: Some(
+            ChallengeSignature::sign(&victim_key, &new_challenge(), &victim_por).unwrap(),
+        ),
+    };
+
+    let verifier_key = identity::Keypair::generate_ed25519();
+    let verifier_peer = verifier_key.public().to_peer_id();
+    let mut verifier = create_swarm(
+        verifier_key,
+        create_por(&owner_key, verifier_peer),
+        true,
+    );
+
+    // Attacker replays the victim's PoR without holding the victim's key
+    let attacker_key = identity::Keypair::generate_ed25519();
+    let attacker_peer = attacker_key.public().to_peer_id();
+    let mut attacker = create_swarm(attacker_key, victim_por.clone(), false);
+
+    let addr = listen(&mut verifier).await;
+    attacker.dial(addr).expect("Failed to dial");
+
+    let mut rejections = 0;
+    let mut forwarded = 0;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(10) && rejections < 2 {
+        tokio::select! {
+            event = verifier.select_next_some() => match event {
+                SwarmEvent::ConnectionEstablished { connection_id, .. } => {
+                    verifier.behaviour_mut().start_authentication(connection_id).unwrap();
+                }
+                SwarmEvent::Behaviour(PorAuthEvent::VerifyPorRequest { .. }) => forwarded += 1,
+                SwarmEvent::Behaviour(PorAuthEvent::OutboundAuthFailure { peer_id, code, .. }) => {
+                    assert_eq!(peer_id, attacker_peer);
+                    assert_eq!(code, Some(RejectCode::InvalidSignature));
+                    rejections += 1;
+                }
+                _ => {}
+            },
+            event = attacker.select_next_some() => match event {
+                SwarmEvent::ConnectionEstablished { connection_id, .. } => {
+                    // Replay before any nonce was issued on this connection
+                    attacker
+                        .behaviour_mut()
+                        .request_response
+                        .send_request(&verifier_peer, captured.clone());
+                    attacker.behaviour_mut().start_authentication(connection_id).unwrap();
+                }
+                SwarmEvent::Behaviour(PorAuthEvent::InboundAuthFailure { reason, .. })
+                    if reason == CHALLENGE_REQUIRED =>
+                {
+                    // Verifier issued a fresh nonce, the captured answer is for another one
+                    attacker
+                        .behaviour_mut()
+                        .request_response
+                        .send_request(&verifier_peer, captured.clone());
+                }
+                _ => {}
+            },
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+
+    assert_eq!(rejections, 2, "Both replays should be rejected");
+    assert_eq!(forwarded, 0, "Replayed PoR must not reach the application");
+    assert!(!verifier.behaviour().is_peer_authenticated(&attacker_peer));
+}
+
+#[tokio::test]
+async fn test_challenge_handshake_over_two_connections() {
+    let owner_key = PorUtils::generate_owner_keypair();
+    let key1 = identity::Keypair::generate_ed25519();
+    let key2 = identity::Keypair::generate_ed25519();
+    let peer1 = key1.public().to_peer_id();
+    let peer2 = key2.public().to_peer_id();
+    let mut swarm1 = create_swarm(key1, create_por(&owner_key, peer1), true);
+    let mut swarm2 = create_swarm(key2, create_por(&owner_key, peer2), true);
+
+    // Two connections between the same pair: requests may travel over either of them
+    let addr = listen(&mut swarm1).await;
+    swarm2.dial(addr.clone()).expect("Failed to dial");
+    swarm2.dial(addr).expect("Failed to dial again");
+
+    let mut mutual = (0, 0);
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(15) && mutual != (2, 2) {
+        tokio::select! {
+            event = swarm1.select_next_some() => {
+                match &event {
+                    SwarmEvent::ConnectionEstablished { connection_id, .. } => {
+                        swarm1.behaviour_mut().start_authentication(*connection_id).unwrap();
+                    }
+                    SwarmEvent::Behaviour(PorAuthEvent::MutualAuthSuccess { .. }) => mutual.0 += 1,
+                    SwarmEvent::Behaviour(PorAuthEvent::OutboundAuthFailure { reason, .. })
+                    | SwarmEvent::Behaviour(PorAuthEvent::InboundAuthFailure { reason, .. })
+                        if reason != CHALLENGE_REQUIRED =>
+                    {
+                        panic!("Swarm1 auth failed: {}", reason);
+                    }
+                    _ => {}
+                }
+                approve(&mut swarm1, &event);
+            }
+            event = swarm2.select_next_some() => {
+                match &event {
+                    SwarmEvent::ConnectionEstablished { connection_id, .. } => {
+                        swarm2.behaviour_mut().start_authentication(*connection_id).unwrap();
+                    }
+                    SwarmEvent::Behaviour(PorAuthEvent::MutualAuthSuccess { .. }) => mutual.1 += 1,
+                    SwarmEvent::Behaviour(PorAuthEvent::OutboundAuthFailure { reason, .. })
+                    | SwarmEvent::Behaviour(PorAuthEvent::InboundAuthFailure { reason, .. })
+                        if reason != CHALLENGE_REQUIRED =>
+                    {
+                        panic!("Swarm2 auth failed: {}", reason);
+                    }
+                    _ => {}
+                }
+                approve(&mut swarm2, &event);
+            }
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+
+    assert_eq!(mutual, (2, 2), "Both connections should authenticate on both sides");
+    assert!(swarm1.behaviour().is_peer_authenticated(&peer2));
+    assert!(swarm2.behaviour().is_peer_authenticated(&peer1));
+}
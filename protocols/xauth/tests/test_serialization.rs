@@ -58,6 +58,7 @@ fn test_por_auth_request_serialization() {
     let auth_request = PorAuthRequest {
         por: por.clone(),
         metadata: metadata.clone(),
+        challenge_signature: None,
     };
 
     // Тестируем сериализацию запроса
@@ -90,6 +91,7 @@ fn test_por_auth_response_serialization() {
     
     let success_response = PorAuthResponse {
         result: AuthResult::Ok(success_metadata.clone()),
+        challenge: None,
     };
 
     let serialized_success = serde_cbor::to_vec(&success_response)
@@ -111,6 +113,7 @@ fn test_por_auth_response_serialization() {
     // Тестируем ответ с ошибкой
     let error_response = PorAuthResponse {
         result: AuthResult::Error("Authentication failed".to_string()),
+        challenge: None,
     };
 
     let serialized_error = serde_cbor::to_vec(&error_response)
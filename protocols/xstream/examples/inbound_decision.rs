@@ -115,6 +115,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                     XStreamEvent::StreamClosed { peer_id, .. } => {
                                         println!("🔒 Сервер: Поток закрыт с {}", peer_id);
                                     }
+                                    XStreamEvent::StreamRejected { peer_id, reason, .. } => {
+                                        println!("🚫 Сервер: Поток от {} отклонен: {:?}", peer_id, reason);
+                                    }
+                                    XStreamEvent::StreamBackpressure { .. }
+                                    | XStreamEvent::StreamDrained { .. } => {
+                                        // Пороги backpressure в примере не заданы
+                                    }
                                 }
                             }
                             _ => {}
@@ -173,7 +180,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                     XStreamEvent::StreamClosed { peer_id, .. } => {
                                         println!("🔒 Клиент: Поток закрыт с {}", peer_id);
                                     }
-                                    XStreamEvent::IncomingStream { .. } | XStreamEvent::IncomingStreamRequest { .. } => {
+                                    XStreamEvent::IncomingStream { .. }
+                                    | XStreamEvent::IncomingStreamRequest { .. }
+                                    | XStreamEvent::StreamRejected { .. }
+                                    | XStreamEvent::StreamBackpressure { .. }
+                                    | XStreamEvent::StreamDrained { .. } => {
                                         // Эти события не ожидаются на клиенте
                                     }
                                 }
@@ -0,0 +1,99 @@
+// backpressure.rs
+// Watermarks of bytes written to XStream but not yet accepted by the transport
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use libp2p::PeerId;
+use tokio::sync::mpsc;
+
+use super::types::XStreamID;
+
+/// How long a write may wait for the transport before its rest counts as buffered
+///
+/// Flow control windows on a fast link reopen well within it.
+pub const BACKPRESSURE_STALL_GRACE: Duration = Duration::from_millis(100);
+
+/// High and low water marks of bytes waiting for the transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackpressureThresholds {
+    /// StreamBackpressure is emitted when buffered bytes reach this mark
+    pub high_water: usize,
+    /// StreamDrained is emitted when buffered bytes fall to this mark
+    pub low_water: usize,
+}
+
+/// Water mark crossing reported by a stream to the behaviour
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackpressureSignal {
+    High {
+        peer_id: PeerId,
+        stream_id: XStreamID,
+        buffered_bytes: usize,
+    },
+    Drained {
+        peer_id: PeerId,
+        stream_id: XStreamID,
+        buffered_bytes: usize,
+    },
+}
+
+/// Counts buffered bytes of one stream, shared by all its clones
+#[derive(Debug, Clone)]
+pub struct BackpressureTracker {
+    thresholds: BackpressureThresholds,
+    peer_id: PeerId,
+    stream_id: XStreamID,
+    buffered: Arc<AtomicUsize>,
+    congested: Arc<AtomicBool>,
+    notifier: mpsc::UnboundedSender<BackpressureSignal>,
+}
+
+impl BackpressureTracker {
+    pub fn new(
+        thresholds: BackpressureThresholds,
+        peer_id: PeerId,
+        stream_id: XStreamID,
+        notifier: mpsc::UnboundedSender<BackpressureSignal>,
+    ) -> Self {
+        Self {
+            thresholds,
+            peer_id,
+            stream_id,
+            buffered: Arc::new(AtomicUsize::new(0)),
+            congested: Arc::new(AtomicBool::new(false)),
+            notifier,
+        }
+    }
+
+    /// Bytes of stalled writes not yet accepted by the transport
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered.load(Ordering::Acquire)
+    }
+
+    /// Bytes entered a write
+    pub fn add(&self, bytes: usize) {
+        let buffered = self.buffered.fetch_add(bytes, Ordering::AcqRel) + bytes;
+        // Сигнал только на пересечении отметки, а не на каждой записи
+        if buffered >= self.thresholds.high_water && !self.congested.swap(true, Ordering::AcqRel) {
+            let _ = self.notifier.send(BackpressureSignal::High {
+                peer_id: self.peer_id,
+                stream_id: self.stream_id,
+                buffered_bytes: buffered,
+            });
+        }
+    }
+
+    /// Bytes were accepted by the transport or dropped by a failed write
+    pub fn remove(&self, bytes: usize) {
+        let buffered = self.buffered.fetch_sub(bytes, Ordering::AcqRel) - bytes;
+        if buffered <= self.thresholds.low_water && self.congested.swap(false, Ordering::AcqRel) {
+            let _ = self.notifier.send(BackpressureSignal::Drained {
+                peer_id: self.peer_id,
+                stream_id: self.stream_id,
+                buffered_bytes: buffered,
+            });
+        }
+    }
+}
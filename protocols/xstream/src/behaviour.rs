@@ -1,4 +1,7 @@
-use super::consts::XSTREAM_PROTOCOL;
+use super::consts::{
+    XSTREAM_FEATURE_INTEGRITY, XSTREAM_FEATURE_SEQUENCE, XSTREAM_LEGACY_PROTOCOL_VERSION,
+    XSTREAM_PROTOCOL, XSTREAM_PROTOCOL_VERSION,
+};
 use super::types::{SubstreamRole, XStreamDirection, XStreamID, XStreamIDIterator};
 use futures::AsyncReadExt;
 use libp2p::{
@@ -9,18 +12,60 @@ use libp2p::{
     },
     Multiaddr, PeerId, Stream, StreamProtocol,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, info, trace, warn};
 
-use super::events::{XStreamEvent, IncomingConnectionApprovePolicy, InboundUpgradeDecision, EstablishedConnection, StreamOpenDecisionSender};
+use super::events::{XStreamEvent, StreamCloseReason, StreamRejectReason, IncomingConnectionApprovePolicy, InboundUpgradeDecision, EstablishedConnection, StreamOpenDecisionSender};
 use super::handler::{XStreamHandler, XStreamHandlerEvent, XStreamHandlerIn};
 use super::pending_streams::{
     PendingStreamsEvent, PendingStreamsManager, PendingStreamsMessage, SubstreamError,
     SubstreamsPair,
 };
-use super::xstream::XStream;
+use super::xstream::{XStream, XStreamLiveness};
+use super::xstream_error::StreamOpenError;
+use super::counters::XStreamByteCounters;
+use super::rate_limit::TokenBucket;
+use super::backpressure::{BackpressureSignal, BackpressureThresholds, BackpressureTracker};
+
+/// Outbound stream open waiting for both substreams to be negotiated
+struct PendingOpen {
+    peer_id: PeerId,
+    response: oneshot::Sender<Result<XStream, StreamOpenError>>,
+    started_at: Instant,
+}
+
+/// XStream byte totals of one connection
+#[derive(Debug, Default)]
+struct ConnectionBytes {
+    /// `(bytes_read, bytes_written)` of streams already finished
+    finished: (u64, u64),
+    /// Counters of streams still open
+    open: Vec<(XStreamByteCounters, XStreamLiveness)>,
+}
+
+impl ConnectionBytes {
+    fn totals(&self) -> (u64, u64) {
+        self.open.iter().fold(self.finished, |(read, written), (counters, _)| {
+            (read + counters.bytes_read(), written + counters.bytes_written())
+        })
+    }
+
+    /// Moves the bytes of finished streams into the running total
+    fn retire_finished(&mut self) {
+        let finished = &mut self.finished;
+        self.open.retain(|(counters, liveness)| {
+            if !liveness.is_finished() {
+                return true;
+            }
+            finished.0 += counters.bytes_read();
+            finished.1 += counters.bytes_written();
+            false
+        });
+    }
+}
 
 /// NetworkBehaviour for working with XStream
 pub struct XStreamNetworkBehaviour {
@@ -29,11 +74,28 @@ pub struct XStreamNetworkBehaviour {
     /// Events waiting to be processed
     events: Vec<ToSwarm<XStreamEvent, XStreamHandlerIn>>,
     /// Pending stream openings
-    pending_outgoing_streams: HashMap<XStreamID, oneshot::Sender<Result<XStream, String>>>,
+    pending_outgoing_streams: HashMap<XStreamID, PendingOpen>,
+    /// Pending opens older than this fail with StreamOpenError::Timeout, None disables reaping
+    pending_stream_timeout: Option<Duration>,
+    /// Timer driving the reaping of stale pending opens, created on the first poll
+    pending_reap_interval: Option<tokio::time::Interval>,
     /// Channel for stream closure notifications - sender only
-    closure_sender: mpsc::UnboundedSender<(PeerId, XStreamID)>,
-    /// Receiver for events from the dedicated closure task
-    stream_close_events: mpsc::UnboundedReceiver<XStreamEvent>,
+    closure_sender: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>,
+    /// Receiver for events from the dedicated closure task, with the direction of the closed stream
+    stream_close_events: mpsc::UnboundedReceiver<(XStreamEvent, XStreamDirection)>,
+    /// Streams without reads or writes for this long are closed, None disables the watchdog
+    idle_timeout: Option<Duration>,
+    /// Channel the idle watchdogs report streams they are about to close
+    idle_sender: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+    idle_receiver: mpsc::UnboundedReceiver<(PeerId, XStreamID)>,
+    /// Streams closed by the idle watchdog whose StreamClosed event is not emitted yet
+    idle_closed: HashSet<(PeerId, XStreamID)>,
+    /// New outbound streams are refused during shutdown
+    shutting_down: bool,
+    /// Protocol version written into headers of outbound substreams
+    header_version: u8,
+    /// Feature flags written into headers of outbound substreams
+    header_features: u8,
 
     // New fields for PendingStreamsManager
     /// Manager for handling paired streams
@@ -49,6 +111,28 @@ pub struct XStreamNetworkBehaviour {
     pub incoming_approve_policy: IncomingConnectionApprovePolicy,
 
     id_iter: XStreamIDIterator,
+
+    /// Byte totals of streams per connection they were opened on
+    connection_counters: HashMap<(PeerId, ConnectionId), ConnectionBytes>,
+
+    /// Max inbound streams per second surfaced from one peer, None disables the limit
+    inbound_rate_limit: Option<u32>,
+    /// Token buckets of peers that opened inbound streams
+    inbound_buckets: HashMap<PeerId, TokenBucket>,
+
+    /// Water marks of unsent stream data, None disables backpressure events
+    backpressure_thresholds: Option<BackpressureThresholds>,
+    /// Channel the streams report water mark crossings
+    backpressure_sender: mpsc::UnboundedSender<BackpressureSignal>,
+    backpressure_receiver: mpsc::UnboundedReceiver<BackpressureSignal>,
+
+    /// Max outbound streams per peer, pending opens included, None disables the limit
+    max_outbound_streams_per_peer: Option<usize>,
+    /// Opened outbound streams of each peer not yet reported closed
+    outbound_open: HashMap<PeerId, HashSet<XStreamID>>,
+    /// Outbound slots released by dropping the last clone of a stream
+    outbound_release_sender: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+    outbound_release_receiver: mpsc::UnboundedReceiver<(PeerId, XStreamID)>,
 }
 
 impl XStreamNetworkBehaviour {
@@ -64,6 +148,9 @@ impl XStreamNetworkBehaviour {
 
         // Channel for events from dedicated task to behavior
         let (event_sender, stream_close_events) = mpsc::unbounded_channel();
+        let (idle_sender, idle_receiver) = mpsc::unbounded_channel();
+        let (backpressure_sender, backpressure_receiver) = mpsc::unbounded_channel();
+        let (outbound_release_sender, outbound_release_receiver) = mpsc::unbounded_channel();
 
         // Channels for PendingStreamsManager
         let (message_sender, pending_streams_message_receiver) = mpsc::unbounded_channel();
@@ -73,18 +160,20 @@ impl XStreamNetworkBehaviour {
         tokio::spawn(async move {
             trace!("[CLOSURE_TASK] Started dedicated stream closure monitoring task");
 
-            while let Some((peer_id, stream_id)) = closure_receiver.recv().await {
+            while let Some((peer_id, stream_id, direction)) = closure_receiver.recv().await {
                 trace!(
                     "[CLOSURE_TASK] Received closure notification for stream {:?} from peer {}",
                     stream_id,
                     peer_id
                 );
 
-                // Send an event to the behavior
-                match event_sender.send(XStreamEvent::StreamClosed {
+                // Send an event to the behavior, the reason is resolved in poll from the stream state
+                let event = XStreamEvent::StreamClosed {
                     peer_id,
                     stream_id,
-                }) {
+                    reason: StreamCloseReason::LocalClose,
+                };
+                match event_sender.send((event, direction)) {
                     Ok(_) => trace!("[CLOSURE_TASK] Successfully sent StreamClosed event to behavior for stream {:?}", stream_id),
                     Err(e) => error!("[CLOSURE_TASK] Failed to send StreamClosed event: {}", e),
                 }
@@ -97,8 +186,17 @@ impl XStreamNetworkBehaviour {
             streams: HashMap::new(),
             events: Vec::new(),
             pending_outgoing_streams: HashMap::new(),
+            pending_stream_timeout: None,
+            pending_reap_interval: None,
             closure_sender,
             stream_close_events,
+            idle_timeout: None,
+            idle_sender,
+            idle_receiver,
+            idle_closed: HashSet::new(),
+            shutting_down: false,
+            header_version: XSTREAM_LEGACY_PROTOCOL_VERSION,
+            header_features: 0,
 
             // Initialize fields for PendingStreamsManager
             pending_streams_manager: Some(pending_streams_manager),
@@ -107,6 +205,16 @@ impl XStreamNetworkBehaviour {
             pending_streams_manager_task: None,
             incoming_approve_policy: policy,
             id_iter: XStreamIDIterator::new(),
+            connection_counters: HashMap::new(),
+            inbound_rate_limit: None,
+            inbound_buckets: HashMap::new(),
+            backpressure_thresholds: None,
+            backpressure_sender,
+            backpressure_receiver,
+            max_outbound_streams_per_peer: None,
+            outbound_open: HashMap::new(),
+            outbound_release_sender,
+            outbound_release_receiver,
         };
 
         // Start PendingStreamsManager in a separate task
@@ -115,6 +223,184 @@ impl XStreamNetworkBehaviour {
         behaviour
     }
 
+    /// Fails outbound opens whose negotiation did not finish within `timeout`
+    ///
+    /// Without it a peer that never completes negotiation keeps the
+    /// `open_stream` response channel pending forever.
+    pub fn with_pending_stream_timeout(mut self, timeout: Duration) -> Self {
+        self.pending_stream_timeout = Some(timeout);
+        self.pending_reap_interval = None;
+        self
+    }
+
+    /// Timeout for pending outbound opens, if enabled
+    pub fn pending_stream_timeout(&self) -> Option<Duration> {
+        self.pending_stream_timeout
+    }
+
+    /// Number of outbound opens still waiting for negotiation
+    pub fn pending_open_count(&self) -> usize {
+        self.pending_outgoing_streams.len()
+    }
+
+    /// Closes streams that had no reads or writes for `timeout`
+    ///
+    /// Such streams are reported as StreamClosed with StreamCloseReason::IdleTimeout.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Idle timeout of streams, if enabled
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Limits how many inbound streams per second one peer may open
+    ///
+    /// Excess streams are reset and reported as StreamRejected with
+    /// StreamRejectReason::RateLimited instead of IncomingStream.
+    pub fn with_inbound_rate_limit(mut self, streams_per_sec: u32) -> Self {
+        self.inbound_rate_limit = Some(streams_per_sec);
+        self
+    }
+
+    /// Inbound stream rate limit per peer, if enabled
+    pub fn inbound_rate_limit(&self) -> Option<u32> {
+        self.inbound_rate_limit
+    }
+
+    /// Takes a token from the peer's bucket, always true without a limit
+    fn take_inbound_token(&mut self, peer_id: PeerId) -> bool {
+        let Some(rate) = self.inbound_rate_limit else {
+            return true;
+        };
+        self.inbound_buckets
+            .entry(peer_id)
+            .or_insert_with(|| TokenBucket::new(rate))
+            .try_take()
+    }
+
+    /// Emits StreamBackpressure when a stream's unsent data reaches `high_water` bytes
+    ///
+    /// Unsent data is what stalled writes still hold: a write counts once the
+    /// transport has not accepted anything from it for BACKPRESSURE_STALL_GRACE.
+    /// StreamDrained follows once it falls to `low_water` bytes.
+    pub fn with_backpressure_thresholds(mut self, high_water: usize, low_water: usize) -> Self {
+        self.backpressure_thresholds = Some(BackpressureThresholds {
+            high_water,
+            low_water: low_water.min(high_water),
+        });
+        self
+    }
+
+    /// Backpressure water marks, if enabled
+    pub fn backpressure_thresholds(&self) -> Option<BackpressureThresholds> {
+        self.backpressure_thresholds
+    }
+
+    /// Limits simultaneous outbound streams to one peer, pending opens included
+    ///
+    /// Opens over the limit fail with StreamOpenError::TooManyStreams instead of queuing.
+    pub fn with_max_outbound_streams_per_peer(mut self, limit: usize) -> Self {
+        self.max_outbound_streams_per_peer = Some(limit);
+        self
+    }
+
+    /// Outbound stream limit per peer, if enabled
+    pub fn max_outbound_streams_per_peer(&self) -> Option<usize> {
+        self.max_outbound_streams_per_peer
+    }
+
+    /// Frees the slot of an outbound stream to the peer
+    fn release_outbound_slot(&mut self, peer_id: &PeerId, stream_id: &XStreamID) {
+        if let Some(streams) = self.outbound_open.get_mut(peer_id) {
+            streams.remove(stream_id);
+            if streams.is_empty() {
+                self.outbound_open.remove(peer_id);
+            }
+        }
+    }
+
+    /// Outbound streams to the peer that are open or being opened
+    pub fn outbound_stream_count(&self, peer_id: &PeerId) -> usize {
+        let pending = self
+            .pending_outgoing_streams
+            .values()
+            .filter(|pending| pending.peer_id == *peer_id)
+            .count();
+        let open = self.outbound_open.get(peer_id).map_or(0, |streams| streams.len());
+        pending + open
+    }
+
+    /// Sets the protocol version written into outbound headers
+    ///
+    /// Defaults to XSTREAM_LEGACY_PROTOCOL_VERSION, which omits the version byte so
+    /// peers that predate it can parse the header. Set a newer version only when
+    /// every remote peer is known to support it.
+    pub fn with_header_version(mut self, version: u8) -> Self {
+        self.header_version = version;
+        self
+    }
+
+    /// Protocol version written into outbound headers
+    pub fn header_version(&self) -> u8 {
+        self.header_version
+    }
+
+    /// Feature flags written into outbound headers
+    pub fn header_features(&self) -> u8 {
+        self.header_features
+    }
+
+    /// Enables the CRC32 integrity check on streams opened by this side
+    ///
+    /// The peer learns it from the header feature flags and checks the stream too.
+    /// Inbound streams are checked whenever their opener enabled it.
+    pub fn with_integrity_check(mut self) -> Self {
+        self.header_features |= XSTREAM_FEATURE_INTEGRITY;
+        self
+    }
+
+    /// Returns true if streams opened by this side are integrity checked
+    pub fn has_integrity_check(&self) -> bool {
+        self.header_features & XSTREAM_FEATURE_INTEGRITY != 0
+    }
+
+    /// Enables diagnostic sequence numbers on streams opened by this side
+    ///
+    /// Off by default. The peer learns it from the header feature flags and it
+    /// can be combined with the integrity check.
+    pub fn with_sequence_check(mut self) -> Self {
+        self.header_features |= XSTREAM_FEATURE_SEQUENCE;
+        self
+    }
+
+    /// Returns true if streams opened by this side carry sequence numbers
+    pub fn has_sequence_check(&self) -> bool {
+        self.header_features & XSTREAM_FEATURE_SEQUENCE != 0
+    }
+
+    /// Fails and removes pending opens older than pending_stream_timeout
+    fn reap_stale_pending_opens(&mut self) {
+        let Some(timeout) = self.pending_stream_timeout else {
+            return;
+        };
+        let stale: Vec<XStreamID> = self
+            .pending_outgoing_streams
+            .iter()
+            .filter(|(_, pending)| pending.started_at.elapsed() >= timeout)
+            .map(|(stream_id, _)| *stream_id)
+            .collect();
+
+        for stream_id in stale {
+            if let Some(pending) = self.pending_outgoing_streams.remove(&stream_id) {
+                warn!("Stream open {:?} timed out after {:?}", stream_id, timeout);
+                let _ = pending.response.send(Err(StreamOpenError::Timeout));
+            }
+        }
+    }
+
     /// Starts PendingStreamsManager in a separate task
     fn start_pending_streams_manager(&mut self) {
         if let Some(manager) = self.pending_streams_manager.take() {
@@ -139,12 +425,24 @@ impl XStreamNetworkBehaviour {
                 let stream_id = pair.key.stream_id;
                 let peer_id = pair.key.peer_id;
 
+                // Лишние потоки сбрасываются: при drop подпотоков удаленная сторона получает reset
+                if pair.key.direction == XStreamDirection::Inbound && !self.take_inbound_token(peer_id) {
+                    warn!("Inbound stream {:?} from {} rate limited", stream_id, peer_id);
+                    self.events
+                        .push(ToSwarm::GenerateEvent(XStreamEvent::StreamRejected {
+                            peer_id,
+                            stream_id,
+                            reason: StreamRejectReason::RateLimited,
+                        }));
+                    return;
+                }
+
                 // Split both streams into read and write parts
                 let (main_read, main_write) = AsyncReadExt::split(pair.main);
                 let (error_read, error_write) = AsyncReadExt::split(pair.error);
 
                 // Create XStream with both main and error streams
-                let xstream = XStream::new(
+                let mut xstream = XStream::new(
                     stream_id,
                     peer_id,
                     main_read,
@@ -155,6 +453,38 @@ impl XStreamNetworkBehaviour {
                     self.closure_sender.clone(),
                 );
 
+                // Режимы включает открывающая сторона через заголовок
+                let features = pair
+                    .header
+                    .as_ref()
+                    .map_or(self.header_features, |header| header.features);
+                if features & XSTREAM_FEATURE_INTEGRITY != 0 {
+                    xstream = xstream.with_integrity_check();
+                }
+                if features & XSTREAM_FEATURE_SEQUENCE != 0 {
+                    xstream = xstream.with_sequence_check();
+                }
+
+                // Итоги соединения живут до его закрытия, счетчики - пока открыт поток
+                self.connection_counters
+                    .entry((peer_id, pair.key.connection_id))
+                    .or_default()
+                    .open
+                    .push((xstream.counters(), xstream.liveness()));
+
+                if let Some(timeout) = self.idle_timeout {
+                    xstream.start_idle_watchdog(timeout, self.idle_sender.clone());
+                }
+
+                if let Some(thresholds) = self.backpressure_thresholds {
+                    xstream = xstream.with_backpressure(BackpressureTracker::new(
+                        thresholds,
+                        peer_id,
+                        stream_id,
+                        self.backpressure_sender.clone(),
+                    ));
+                }
+
                 // Generate event for new stream
                 if pair.key.direction == XStreamDirection::Inbound {
                     self.events
@@ -163,9 +493,12 @@ impl XStreamNetworkBehaviour {
                         }));
                 } else {
                     // Check if there's a waiting sender for this peer
-                    if let Some(sender) = self.pending_outgoing_streams.remove(&stream_id) {
-                        // Send successful result
-                        let _ = sender.send(Ok(xstream));
+                    if let Some(pending) = self.pending_outgoing_streams.remove(&stream_id) {
+                        // Поток занимает слот до закрытия или drop последнего клона
+                        let xstream = xstream.with_outbound_slot(self.outbound_release_sender.clone());
+                        if pending.response.send(Ok(xstream)).is_ok() {
+                            self.outbound_open.entry(peer_id).or_default().insert(stream_id);
+                        }
                     }
 
                     // Also send StreamEstablished event for backward compatibility
@@ -228,6 +561,25 @@ impl XStreamNetworkBehaviour {
                                 error: format!("Error reading stream header: {}", error),
                             }));
                     }
+                    SubstreamError::SubstreamVersionMismatch { key, role, version } => {
+                        warn!(
+                            "Rejected substream {:?} with unsupported version {} for key {:?}",
+                            role, version, key
+                        );
+
+                        // Both substreams carry the header, report the stream once
+                        if role == SubstreamRole::Main {
+                            self.events
+                                .push(ToSwarm::GenerateEvent(XStreamEvent::StreamRejected {
+                                    peer_id: key.peer_id,
+                                    stream_id: key.stream_id,
+                                    reason: StreamRejectReason::VersionMismatch {
+                                        expected: XSTREAM_PROTOCOL_VERSION,
+                                        received: version,
+                                    },
+                                }));
+                        }
+                    }
                 }
             }
         }
@@ -255,34 +607,98 @@ impl XStreamNetworkBehaviour {
         return stream_id;
     }
 
+    /// Refuses new outbound streams and returns the open ones so they can be closed
+    pub fn begin_shutdown(&mut self) -> Vec<XStream> {
+        self.shutting_down = true;
+        self.streams.values().cloned().collect()
+    }
+
+    /// True after begin_shutdown
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down
+    }
+
     /// Asynchronously opens a new stream and returns XStream or an error
     pub async fn open_stream(
         &mut self,
         peer_id: PeerId,
-        response: oneshot::Sender<Result<XStream, String>>,
+        response: oneshot::Sender<Result<XStream, StreamOpenError>>,
     ) {
+        if self.shutting_down {
+            let _ = response.send(Err(StreamOpenError::ShuttingDown));
+            return;
+        }
+
+        if let Some(limit) = self.max_outbound_streams_per_peer {
+            if self.outbound_stream_count(&peer_id) >= limit {
+                debug!("Outbound stream limit {} reached for peer {}", limit, peer_id);
+                let _ = response.send(Err(StreamOpenError::TooManyStreams));
+                return;
+            }
+        }
+
         // Request stream opening
         let stream_id = self.request_open_stream(peer_id);
-        self.pending_outgoing_streams.insert(stream_id, response);
+        self.pending_outgoing_streams.insert(
+            stream_id,
+            PendingOpen {
+                peer_id,
+                response,
+                started_at: Instant::now(),
+            },
+        );
     }
 
     /// Handles stream opening errors for specific stream_id
     pub fn handle_stream_open_error(&mut self, stream_id: XStreamID, error: String) {
-        if let Some(sender) = self.pending_outgoing_streams.remove(&stream_id) {
-            let _ = sender.send(Err(error));
+        if let Some(pending) = self.pending_outgoing_streams.remove(&stream_id) {
+            let _ = pending.response.send(Err(StreamOpenError::Failed(error)));
+        }
+    }
+
+    /// Payload byte totals `(bytes_read, bytes_written)` of all streams per open connection
+    ///
+    /// Sampled on each call from the counters of open streams plus the totals of closed ones.
+    pub fn connection_byte_totals(&self) -> HashMap<(PeerId, ConnectionId), (u64, u64)> {
+        self.connection_counters
+            .iter()
+            .map(|(key, bytes)| (*key, bytes.totals()))
+            .collect()
+    }
+
+    /// Folds counters of finished streams to a peer into their connection totals
+    fn retire_finished_counters(&mut self, peer_id: &PeerId) {
+        for ((peer, _), bytes) in self.connection_counters.iter_mut() {
+            if peer == peer_id {
+                bytes.retire_finished();
+            }
         }
     }
 
+    /// Payload byte totals `(bytes_read, bytes_written)` of all streams to a peer
+    pub fn peer_byte_totals(&self, peer_id: &PeerId) -> (u64, u64) {
+        self.connection_byte_totals()
+            .into_iter()
+            .filter(|((peer, _), _)| peer == peer_id)
+            .fold((0, 0), |(read, written), (_, (r, w))| (read + r, written + w))
+    }
+
     /// Notifies that a stream is closed
     pub fn notify_stream_closed(&mut self, peer_id: PeerId, stream_id: XStreamID) {
         debug!("Manual notification of stream closure: {:?}", stream_id);
+        self.retire_finished_counters(&peer_id);
         // Remove the stream from the active streams map
-        self.streams.remove(&(peer_id, stream_id));
+        let reason = self
+            .streams
+            .remove(&(peer_id, stream_id))
+            .and_then(|stream| stream.close_reason())
+            .unwrap_or(StreamCloseReason::LocalClose);
         // Generate the appropriate event
         self.events
             .push(ToSwarm::GenerateEvent(XStreamEvent::StreamClosed {
                 peer_id,
                 stream_id,
+                reason,
             }));
     }
 
@@ -332,6 +748,8 @@ impl NetworkBehaviour for XStreamNetworkBehaviour {
         //handler.set_peer_id(peer);
         // Provide closure sender to the handler
         handler.set_closure_sender(self.closure_sender.clone());
+        handler.set_header_version(self.header_version);
+        handler.set_header_features(self.header_features);
         Ok(handler)
     }
 
@@ -352,10 +770,21 @@ impl NetworkBehaviour for XStreamNetworkBehaviour {
         handler.set_peer_id(peer);
         // Provide closure sender to the handler
         handler.set_closure_sender(self.closure_sender.clone());
+        handler.set_header_version(self.header_version);
+        handler.set_header_features(self.header_features);
         Ok(handler)
     }
 
-    fn on_swarm_event(&mut self, _event: libp2p::swarm::FromSwarm) {}
+    fn on_swarm_event(&mut self, event: libp2p::swarm::FromSwarm) {
+        if let FromSwarm::ConnectionClosed(closed) = event {
+            self.connection_counters
+                .remove(&(closed.peer_id, closed.connection_id));
+            if closed.remaining_established == 0 {
+                self.inbound_buckets.remove(&closed.peer_id);
+                self.outbound_open.remove(&closed.peer_id);
+            }
+        }
+    }
 
     fn on_connection_handler_event(
         &mut self,
@@ -436,14 +865,20 @@ impl NetworkBehaviour for XStreamNetworkBehaviour {
             }
             XStreamHandlerEvent::StreamClosed { stream_id } => {
                 debug!("Handler reported stream closed: {:?}", stream_id);
-                // Remove the stream from HashMap
-                self.streams.remove(&(peer_id, stream_id));
+                self.retire_finished_counters(&peer_id);
+                // Remove the stream from HashMap; a stream still open here lost its connection
+                let reason = self
+                    .streams
+                    .remove(&(peer_id, stream_id))
+                    .and_then(|stream| stream.close_reason())
+                    .unwrap_or(StreamCloseReason::Reset);
 
                 // Send stream closed event
                 self.events
                     .push(ToSwarm::GenerateEvent(XStreamEvent::StreamClosed {
                         peer_id,
                         stream_id,
+                        reason,
                     }));
             }
             XStreamHandlerEvent::IncomingStreamRequest { peer_id, connection_id, decision_sender } => {
@@ -491,6 +926,24 @@ impl NetworkBehaviour for XStreamNetworkBehaviour {
     ) -> Poll<ToSwarm<Self::ToSwarm, libp2p::swarm::THandlerInEvent<Self>>> {
         trace!("[POLL] Polling XStreamNetworkBehaviour");
 
+        // Reap outbound opens stuck in negotiation
+        // Таймер создается здесь: builder может вызываться вне runtime Tokio
+        if let (Some(timeout), None) = (self.pending_stream_timeout, &self.pending_reap_interval) {
+            let period = (timeout / 2).max(Duration::from_millis(1));
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            self.pending_reap_interval = Some(interval);
+        }
+        let mut reap_due = false;
+        if let Some(interval) = self.pending_reap_interval.as_mut() {
+            while interval.poll_tick(cx).is_ready() {
+                reap_due = true;
+            }
+        }
+        if reap_due {
+            self.reap_stale_pending_opens();
+        }
+
         // First check for messages from PendingStreamsManager
         match self.pending_streams_message_receiver.poll_recv(cx) {
             Poll::Ready(Some(message)) => {
@@ -511,18 +964,53 @@ impl NetworkBehaviour for XStreamNetworkBehaviour {
             }
         }
 
+        // Streams the idle watchdog is closing, must be known before their closure events
+        while let Poll::Ready(Some(key)) = self.idle_receiver.poll_recv(cx) {
+            self.idle_closed.insert(key);
+        }
+
+        // Water mark crossings reported by stream writes
+        while let Poll::Ready(Some(signal)) = self.backpressure_receiver.poll_recv(cx) {
+            let event = match signal {
+                BackpressureSignal::High { peer_id, stream_id, buffered_bytes } => {
+                    XStreamEvent::StreamBackpressure { peer_id, stream_id, buffered_bytes }
+                }
+                BackpressureSignal::Drained { peer_id, stream_id, buffered_bytes } => {
+                    XStreamEvent::StreamDrained { peer_id, stream_id, buffered_bytes }
+                }
+            };
+            self.events.push(ToSwarm::GenerateEvent(event));
+        }
+
+        // Outbound streams whose last clone was dropped
+        while let Poll::Ready(Some((peer_id, stream_id))) = self.outbound_release_receiver.poll_recv(cx) {
+            self.release_outbound_slot(&peer_id, &stream_id);
+        }
+
         // Check for events from the dedicated closure task
         match self.stream_close_events.poll_recv(cx) {
-            Poll::Ready(Some(event)) => {
-                if let XStreamEvent::StreamClosed { peer_id, stream_id } = &event {
+            Poll::Ready(Some((mut event, direction))) => {
+                if let XStreamEvent::StreamClosed { peer_id, stream_id, reason } = &mut event {
                     trace!("[POLL] Received dedicated task closure notification for stream {:?} from peer {}", stream_id, peer_id);
 
                     // Remove the stream from the map if it still exists
-                    if self.streams.remove(&(*peer_id, *stream_id)).is_some() {
+                    let removed = self.streams.remove(&(*peer_id, *stream_id));
+                    if let Some(stream_reason) = removed.as_ref().and_then(|stream| stream.close_reason()) {
+                        *reason = stream_reason;
+                    }
+                    if removed.is_some() {
                         trace!("[POLL] Stream {:?} removed from map", stream_id);
                     } else {
                         trace!("[POLL] Stream {:?} was already removed from map", stream_id);
                     }
+                    if self.idle_closed.remove(&(*peer_id, *stream_id)) {
+                        *reason = StreamCloseReason::IdleTimeout;
+                    }
+                    // Входящий поток может иметь тот же id, что и наш исходящий
+                    if direction == XStreamDirection::Outbound {
+                        self.release_outbound_slot(peer_id, stream_id);
+                    }
+                    self.retire_finished_counters(peer_id);
                 }
 
                 // Return the event immediately
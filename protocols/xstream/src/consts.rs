@@ -1,3 +1,24 @@
 use libp2p::StreamProtocol;
 
 pub const XSTREAM_PROTOCOL: StreamProtocol = StreamProtocol::new("/xstream/");
+
+/// Версия формата заголовка XStream с байтом версии
+///
+/// Пишется только по явному выбору (with_header_version): узел без поддержки
+/// версий читает флаг версии в байте роли и принимает любой подпоток за Main.
+pub const XSTREAM_PROTOCOL_VERSION: u8 = 1;
+
+/// Неявная версия заголовков без байта версии, пишется по умолчанию
+pub const XSTREAM_LEGACY_PROTOCOL_VERSION: u8 = 0;
+
+/// Версия заголовка с байтом флагов возможностей после байта версии
+pub const XSTREAM_FEATURES_PROTOCOL_VERSION: u8 = 2;
+
+/// Флаг возможности: контрольная сумма CRC32 в конце основного потока
+pub const XSTREAM_FEATURE_INTEGRITY: u8 = 0x01;
+
+/// Флаг возможности: порядковые номера сообщений основного потока (диагностика)
+pub const XSTREAM_FEATURE_SEQUENCE: u8 = 0x02;
+
+/// Все флаги возможностей, известные этой реализации
+pub const XSTREAM_KNOWN_FEATURES: u8 = XSTREAM_FEATURE_INTEGRITY | XSTREAM_FEATURE_SEQUENCE;
@@ -0,0 +1,71 @@
+// counters.rs
+// Per-stream byte counters for the XStream main stream
+// Счетчики разделяются всеми клонами потока и переживают сам поток
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Byte counters of the main stream payload
+///
+/// Counts application bytes (before encryption on write, after decryption on read).
+/// Cloning shares the same counters, so a metrics collector can keep a handle
+/// after the stream itself is dropped.
+#[derive(Debug, Clone)]
+pub struct XStreamByteCounters {
+    bytes_read: Arc<AtomicU64>,
+    bytes_written: Arc<AtomicU64>,
+    created_at: Instant,
+    /// Milliseconds after `created_at` of the last read or write
+    last_activity_ms: Arc<AtomicU64>,
+}
+
+impl Default for XStreamByteCounters {
+    fn default() -> Self {
+        Self {
+            bytes_read: Arc::default(),
+            bytes_written: Arc::default(),
+            created_at: Instant::now(),
+            last_activity_ms: Arc::default(),
+        }
+    }
+}
+
+impl XStreamByteCounters {
+    /// Creates zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time since the last read or write, or since creation if there was none
+    pub fn idle_for(&self) -> Duration {
+        let last_activity =
+            self.created_at + Duration::from_millis(self.last_activity_ms.load(Ordering::Relaxed));
+        last_activity.elapsed()
+    }
+
+    fn touch(&self) {
+        let now_ms = self.created_at.elapsed().as_millis() as u64;
+        self.last_activity_ms.fetch_max(now_ms, Ordering::Relaxed);
+    }
+
+    /// Total bytes read from the main stream
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Total bytes written to the main stream
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_read(&self, bytes: usize) {
+        self.bytes_read.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    pub(crate) fn add_written(&self, bytes: usize) {
+        self.bytes_written.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+}
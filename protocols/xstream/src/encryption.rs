@@ -0,0 +1,249 @@
+// encryption.rs
+// Optional application-layer encryption for the XStream main stream
+// Дополнительное шифрование поверх транспорта (например, для путей через relay)
+//
+// Wire format of one direction:
+//   [salt: SALT_SIZE bytes] then frames of [u32 BE length][ChaCha20-Poly1305 ciphertext + tag]
+// Ключ направления выводится через HKDF-SHA256 из общего ключа, случайной соли
+// пишущей стороны, метки направления и идентификаторов обоих пиров.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use libp2p::PeerId;
+use sha2::Sha256;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, Mutex};
+
+use super::types::XStreamDirection;
+
+/// Size of the shared key in bytes
+pub const SHARED_KEY_SIZE: usize = 32;
+/// Size of the random salt sent by each writer before its first frame
+pub const SALT_SIZE: usize = 32;
+/// Size of the authentication tag appended to every frame
+pub const TAG_SIZE: usize = 16;
+/// Size of the frame length prefix
+pub const FRAME_HEADER_SIZE: usize = 4;
+/// Maximum plaintext carried by one frame, larger writes are split
+pub const MAX_FRAME_PLAINTEXT: usize = 64 * 1024;
+/// Read-ahead capacity used when encryption enables the buffer itself
+pub const ENCRYPTED_READ_AHEAD_CAPACITY: usize = 64 * 1024;
+
+/// HKDF info prefix, versioned together with the wire format
+const KEY_INFO: &[u8] = b"xstream-encryption-v1";
+/// Direction label for data written by the outbound (opening) side
+const OUTBOUND_WRITER_LABEL: &[u8] = b"outbound->inbound";
+/// Direction label for data written by the inbound (accepting) side
+const INBOUND_WRITER_LABEL: &[u8] = b"inbound->outbound";
+
+/// Authenticated cipher pair for one XStream
+///
+/// Every writer picks a fresh random salt, so a direction key is never reused
+/// across streams or peers even though the shared key is. Frames are sealed with
+/// ChaCha20-Poly1305 under a per-direction counter nonce; a modified, reordered
+/// or truncated frame fails to open.
+#[derive(Clone)]
+pub struct XStreamCipher {
+    sealer: Arc<Mutex<Sealer>>,
+    opener: Arc<Mutex<Opener>>,
+}
+
+struct Sealer {
+    aead: ChaCha20Poly1305,
+    // Соль отправляется перед первым кадром
+    preamble: Option<[u8; SALT_SIZE]>,
+    counter: u64,
+}
+
+struct Opener {
+    shared_key: [u8; SHARED_KEY_SIZE],
+    info: Vec<u8>,
+    // None, пока не получена соль удаленной стороны
+    aead: Option<ChaCha20Poly1305>,
+    buffer: Vec<u8>,
+    counter: u64,
+}
+
+impl XStreamCipher {
+    /// Creates a cipher pair for the local side of a stream with `remote_peer_id`
+    pub fn new(
+        shared_key: [u8; SHARED_KEY_SIZE],
+        local_peer_id: PeerId,
+        remote_peer_id: PeerId,
+        direction: XStreamDirection,
+    ) -> Self {
+        let (write_label, read_label) = match direction {
+            XStreamDirection::Outbound => (OUTBOUND_WRITER_LABEL, INBOUND_WRITER_LABEL),
+            XStreamDirection::Inbound => (INBOUND_WRITER_LABEL, OUTBOUND_WRITER_LABEL),
+        };
+        let salt: [u8; SALT_SIZE] = rand::random();
+        let write_info = key_info(write_label, &local_peer_id, &remote_peer_id);
+        let read_info = key_info(read_label, &remote_peer_id, &local_peer_id);
+
+        Self {
+            sealer: Arc::new(Mutex::new(Sealer {
+                aead: derive_aead(&shared_key, &salt, &write_info),
+                preamble: Some(salt),
+                counter: 0,
+            })),
+            opener: Arc::new(Mutex::new(Opener {
+                shared_key,
+                info: read_info,
+                aead: None,
+                buffer: Vec::new(),
+                counter: 0,
+            })),
+        }
+    }
+
+    /// Encrypts outgoing data into wire bytes
+    ///
+    /// Must be called in write order: the output of each call is written
+    /// before the output of the next one.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut sealer = self.sealer.lock().unwrap();
+        let frames = plaintext.len().div_ceil(MAX_FRAME_PLAINTEXT);
+        let mut wire = Vec::with_capacity(
+            SALT_SIZE + plaintext.len() + frames * (FRAME_HEADER_SIZE + TAG_SIZE),
+        );
+        if let Some(salt) = sealer.preamble.take() {
+            wire.extend_from_slice(&salt);
+        }
+
+        for chunk in plaintext.chunks(MAX_FRAME_PLAINTEXT) {
+            let nonce = next_nonce(&mut sealer.counter)?;
+            let ciphertext = sealer
+                .aead
+                .encrypt(Nonce::from_slice(&nonce), Payload { msg: chunk, aad: &[] })
+                .map_err(|_| Error::other("XStream frame encryption failed"))?;
+            wire.extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+            wire.extend_from_slice(&ciphertext);
+        }
+        Ok(wire)
+    }
+
+    /// Plaintext bytes of one `seal` output whose frames were written in full
+    ///
+    /// `wire_len` is the length `seal` returned for `plaintext_len` bytes, including
+    /// the salt if that call carried it. A partly written frame counts as nothing:
+    /// the peer cannot open it.
+    pub fn sealed_plaintext_written(plaintext_len: usize, wire_len: usize, wire_written: usize) -> usize {
+        let frames = plaintext_len.div_ceil(MAX_FRAME_PLAINTEXT);
+        let preamble = wire_len - plaintext_len - frames * (FRAME_HEADER_SIZE + TAG_SIZE);
+        let mut remaining = wire_written.saturating_sub(preamble);
+        let mut plaintext = 0;
+        while plaintext < plaintext_len {
+            let chunk = (plaintext_len - plaintext).min(MAX_FRAME_PLAINTEXT);
+            let frame = FRAME_HEADER_SIZE + chunk + TAG_SIZE;
+            if remaining < frame {
+                break;
+            }
+            remaining -= frame;
+            plaintext += chunk;
+        }
+        plaintext
+    }
+
+    /// Decrypts incoming wire bytes, returning the plaintext of all complete frames
+    ///
+    /// An incomplete frame stays buffered until the rest arrives.
+    /// Any authentication failure is an `InvalidData` error.
+    pub fn open(&self, wire: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut opener = self.opener.lock().unwrap();
+        opener.buffer.extend_from_slice(wire);
+
+        if opener.aead.is_none() {
+            if opener.buffer.len() < SALT_SIZE {
+                return Ok(Vec::new());
+            }
+            let salt: Vec<u8> = opener.buffer.drain(..SALT_SIZE).collect();
+            opener.aead = Some(derive_aead(&opener.shared_key, &salt, &opener.info));
+        }
+
+        let mut plaintext = Vec::new();
+        loop {
+            if opener.buffer.len() < FRAME_HEADER_SIZE {
+                return Ok(plaintext);
+            }
+            let mut header = [0u8; FRAME_HEADER_SIZE];
+            header.copy_from_slice(&opener.buffer[..FRAME_HEADER_SIZE]);
+            let frame_len = u32::from_be_bytes(header) as usize;
+            if !(TAG_SIZE..=MAX_FRAME_PLAINTEXT + TAG_SIZE).contains(&frame_len) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid encrypted frame length {}", frame_len),
+                ));
+            }
+            if opener.buffer.len() < FRAME_HEADER_SIZE + frame_len {
+                return Ok(plaintext);
+            }
+
+            let frame: Vec<u8> = opener.buffer.drain(..FRAME_HEADER_SIZE + frame_len).collect();
+            let nonce = next_nonce(&mut opener.counter)?;
+            let aead = opener.aead.as_ref().expect("key derived above");
+            let data = aead
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload { msg: &frame[FRAME_HEADER_SIZE..], aad: &[] },
+                )
+                .map_err(|_| {
+                    Error::new(ErrorKind::InvalidData, "XStream frame authentication failed")
+                })?;
+            plaintext.extend_from_slice(&data);
+        }
+    }
+
+    /// Checks that the remote side did not stop in the middle of a frame
+    pub fn finish(&self) -> Result<(), Error> {
+        let opener = self.opener.lock().unwrap();
+        if opener.buffer.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Encrypted stream truncated, {} bytes of an incomplete frame", opener.buffer.len()),
+            ))
+        }
+    }
+}
+
+impl std::fmt::Debug for XStreamCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Ключевой материал не выводим
+        f.debug_struct("XStreamCipher").finish_non_exhaustive()
+    }
+}
+
+/// Builds the HKDF info: prefix, direction label, writer and reader peer ids
+fn key_info(label: &[u8], writer: &PeerId, reader: &PeerId) -> Vec<u8> {
+    let mut info = Vec::with_capacity(KEY_INFO.len() + label.len() + 2 * 40);
+    info.extend_from_slice(KEY_INFO);
+    info.extend_from_slice(label);
+    for peer in [writer, reader] {
+        let bytes = peer.to_bytes();
+        // Длина перед идентификатором делает конкатенацию однозначной
+        info.push(bytes.len() as u8);
+        info.extend_from_slice(&bytes);
+    }
+    info
+}
+
+fn derive_aead(shared_key: &[u8; SHARED_KEY_SIZE], salt: &[u8], info: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), shared_key)
+        .expand(info, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Builds the 96-bit nonce for the next frame from the direction counter
+fn next_nonce(counter: &mut u64) -> Result<[u8; 12], Error> {
+    let value = *counter;
+    *counter = counter
+        .checked_add(1)
+        .ok_or_else(|| Error::other("XStream frame counter exhausted"))?;
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&value.to_be_bytes());
+    Ok(nonce)
+}
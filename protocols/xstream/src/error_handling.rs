@@ -4,6 +4,7 @@
 use futures::AsyncReadExt;
 use libp2p::{PeerId, Stream};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::{Mutex, mpsc, oneshot, watch};
 use tracing::{debug, error, info, warn};
 
@@ -19,6 +20,8 @@ pub struct ErrorDataStore {
     shared_state: Arc<Mutex<SharedErrorState>>,
     /// Notifier for when error data becomes available
     notify: Arc<tokio::sync::Notify>,
+    /// Mirror of `error_received` readable without taking the lock
+    received_flag: Arc<AtomicBool>,
 }
 
 #[derive(Debug)]
@@ -43,6 +46,7 @@ impl ErrorDataStore {
         Self {
             shared_state: Arc::new(Mutex::new(shared_state)),
             notify: Arc::new(tokio::sync::Notify::new()),
+            received_flag: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -106,6 +110,7 @@ impl ErrorDataStore {
             // Store the error data
             state.error_data = Some(data);
             state.error_received = true;
+            self.received_flag.store(true, Ordering::Release);
         }
 
         // Notify all waiters
@@ -121,6 +126,11 @@ impl ErrorDataStore {
         state.error_received
     }
 
+    /// Synchronous variant of has_error for non-async callers
+    pub fn has_error_now(&self) -> bool {
+        self.received_flag.load(Ordering::Acquire)
+    }
+
     /// Get cached error data if available (non-blocking)
     pub async fn get_cached_error(&self) -> Option<Vec<u8>> {
         let state = self.shared_state.lock().await;
@@ -157,6 +167,7 @@ impl ErrorDataStore {
             state.error_data = None;
             state.error_received = false;
             state.is_closed = false;
+            self.received_flag.store(false, Ordering::Release);
         }
         
         debug!("Error cache cleared");
@@ -205,7 +216,7 @@ impl ErrorReaderTask {
         direction: XStreamDirection,
         error_stream: Arc<tokio::sync::Mutex<futures::io::ReadHalf<Stream>>>,
         error_data_store: ErrorDataStore,
-        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>,
     ) -> Self {
         let (shutdown_sender, mut shutdown_receiver) = oneshot::channel::<()>();
 
@@ -251,7 +262,7 @@ impl ErrorReaderTask {
             error_data_store.close().await;
             
             // Notify about stream closure if connection was lost
-            if let Err(e) = closure_notifier.send((peer_id, stream_id)) {
+            if let Err(e) = closure_notifier.send((peer_id, stream_id, direction)) {
                 debug!("Failed to send closure notification for stream {:?}: {:?}", stream_id, e);
             }
             
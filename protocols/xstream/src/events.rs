@@ -75,6 +75,35 @@ pub enum EstablishedConnection {
     },
 }
 
+/// Причина закрытия потока
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamCloseReason {
+    /// Поток закрыт локальной стороной (write_eof или close)
+    LocalClose,
+    /// Удаленная сторона закрыла поток чистым EOF
+    RemoteEof,
+    /// Поток завершен ошибкой, записанной в error stream
+    Error,
+    /// Соединение сброшено или разорвано без EOF
+    Reset,
+    /// Не было чтения или записи дольше idle timeout
+    IdleTimeout,
+}
+
+/// Причина отклонения входящего потока
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamRejectReason {
+    /// Версия заголовка удаленной стороны не поддерживается
+    VersionMismatch {
+        /// Версия протокола этой стороны
+        expected: u8,
+        /// Версия из заголовка входящего потока
+        received: u8,
+    },
+    /// Пир открывает входящие потоки чаще заданного лимита
+    RateLimited,
+}
+
 /// События, генерируемые XStreamNetworkBehaviour
 #[derive(Debug)]
 pub enum XStreamEvent {
@@ -100,6 +129,35 @@ pub enum XStreamEvent {
         peer_id: PeerId,
         /// Идентификатор потока
         stream_id: XStreamID,
+        /// Причина закрытия
+        reason: StreamCloseReason,
+    },
+    /// Входящий поток отклонен до установления
+    StreamRejected {
+        /// Идентификатор пира
+        peer_id: PeerId,
+        /// Идентификатор потока из заголовка
+        stream_id: XStreamID,
+        /// Причина отклонения
+        reason: StreamRejectReason,
+    },
+    /// Неотправленные в транспорт данные потока достигли верхней отметки
+    StreamBackpressure {
+        /// Идентификатор пира
+        peer_id: PeerId,
+        /// Идентификатор потока
+        stream_id: XStreamID,
+        /// Байты, ожидающие приема транспортом
+        buffered_bytes: usize,
+    },
+    /// Неотправленные данные опустились до нижней отметки после StreamBackpressure
+    StreamDrained {
+        /// Идентификатор пира
+        peer_id: PeerId,
+        /// Идентификатор потока
+        stream_id: XStreamID,
+        /// Байты, ожидающие приема транспортом
+        buffered_bytes: usize,
     },
     /// Входящий поток (для обратной совместимости)
     IncomingStream {
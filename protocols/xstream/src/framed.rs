@@ -0,0 +1,103 @@
+// framed.rs
+// Length-prefixed message framing on top of XStream
+
+use std::io;
+
+use super::header::{decode_frame_length, encode_frame_length, FRAME_LENGTH_PREFIX_SIZE};
+use super::xstream::XStream;
+use super::xstream_error::{ErrorOnRead, XStreamReadResult};
+
+/// Максимальный размер сообщения по умолчанию (16 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// XStream, передающий сообщения с префиксом длины u32
+///
+/// Каждое сообщение записывается как 4 байта длины в network byte order,
+/// за которыми следует тело сообщения.
+#[derive(Debug, Clone)]
+pub struct FramedXStream {
+    stream: XStream,
+    max_message_size: usize,
+}
+
+impl FramedXStream {
+    /// Оборачивает поток с максимальным размером сообщения DEFAULT_MAX_MESSAGE_SIZE
+    pub fn new(stream: XStream) -> Self {
+        Self {
+            stream,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Устанавливает максимальный размер сообщения, ограниченный u32::MAX
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size.min(u32::MAX as usize);
+        self
+    }
+
+    /// Максимальный размер сообщения
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Underlying stream
+    pub fn stream(&self) -> &XStream {
+        &self.stream
+    }
+
+    /// Returns the underlying stream
+    pub fn into_inner(self) -> XStream {
+        self.stream
+    }
+
+    /// Sends one message, prefix and body are written in a single write
+    pub async fn send_message(&self, message: &[u8]) -> Result<(), io::Error> {
+        if message.len() > self.max_message_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Message of {} bytes exceeds max message size {}",
+                    message.len(),
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        let mut frame = encode_frame_length(message.len() as u32);
+        frame.extend_from_slice(message);
+        self.stream.write_all(frame).await?;
+        self.stream.flush().await
+    }
+
+    /// Receives one message
+    ///
+    /// An error sent by the server is returned as an XStream error, with the part
+    /// of the message body read before it as partial data. Concurrent calls on
+    /// clones of the stream receive whole messages one after another.
+    pub async fn recv_message(&self) -> XStreamReadResult<Vec<u8>> {
+        // Префикс и тело читаются под одной блокировкой
+        let _message_read = self.stream.lock_message_read().await;
+        // Частично прочитанный префикс не является данными сообщения
+        let prefix = self
+            .stream
+            .read_exact(FRAME_LENGTH_PREFIX_SIZE)
+            .await
+            .map_err(|error| ErrorOnRead::error_only(error.into_error()))?;
+        let length = decode_frame_length(&prefix).map_err(ErrorOnRead::io_error_only)? as usize;
+
+        if length > self.max_message_size {
+            return Err(ErrorOnRead::io_error_only(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Frame of {} bytes exceeds max message size {}",
+                    length, self.max_message_size
+                ),
+            )));
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.stream.read_exact(length).await
+    }
+}
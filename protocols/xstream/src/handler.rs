@@ -22,7 +22,7 @@ use tokio::sync::{mpsc, oneshot};
 use tokio::time::{sleep, timeout};
 use tracing::{debug, error, info, trace, warn};
 
-use super::consts::XSTREAM_PROTOCOL;
+use super::consts::{XSTREAM_LEGACY_PROTOCOL_VERSION, XSTREAM_PROTOCOL};
 use super::events::{InboundUpgradeDecision, IncomingConnectionApprovePolicy, EstablishedConnection, StreamOpenDecisionSender};
 
 /// Возможные события, которые handler может отправить в behaviour
@@ -155,7 +155,7 @@ pub struct XStreamHandler {
     /// Peer ID для потоков
     peer_id: PeerId,
     /// Sender for closure notifications
-    closure_sender: Option<mpsc::UnboundedSender<(PeerId, XStreamID)>>,
+    closure_sender: Option<mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>>,
 
     outgoing_event_sender: mpsc::UnboundedSender<XStreamHandlerEvent>,
     /// Receiver for messages from PendingStreamsManager
@@ -167,6 +167,10 @@ pub struct XStreamHandler {
     established_connection: EstablishedConnection,
     /// Отслеживание активных исходящих запросов (stream_id -> XStreamOpenInfo)
     active_outbound_requests: HashMap<XStreamID, XStreamOpenInfo>,
+    /// Версия протокола в заголовках исходящих подпотоков
+    header_version: u8,
+    /// Флаги возможностей в заголовках исходящих подпотоков
+    header_features: u8,
 }
 
 impl XStreamHandler {
@@ -190,6 +194,8 @@ impl XStreamHandler {
             remote_peer_id: peer_id,
             established_connection: established_connection,
             active_outbound_requests: HashMap::new(),
+            header_version: XSTREAM_LEGACY_PROTOCOL_VERSION,
+            header_features: 0,
         }
     }
 
@@ -198,10 +204,20 @@ impl XStreamHandler {
         self.peer_id = peer_id;
     }
     /// Sets the closure sender
-    pub fn set_closure_sender(&mut self, sender: mpsc::UnboundedSender<(PeerId, XStreamID)>) {
+    pub fn set_closure_sender(&mut self, sender: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>) {
         self.closure_sender = Some(sender);
     }
 
+    /// Устанавливает версию протокола для заголовков исходящих подпотоков
+    pub fn set_header_version(&mut self, version: u8) {
+        self.header_version = version;
+    }
+
+    /// Устанавливает флаги возможностей для заголовков исходящих подпотоков
+    pub fn set_header_features(&mut self, features: u8) {
+        self.header_features = features;
+    }
+
     /// Получает изменяемый XStream по его ID
     pub fn get_stream_mut(&mut self, stream_id: XStreamID) -> Option<&mut XStream> {
         self.streams.iter_mut().find(|s| s.id == stream_id)
@@ -238,9 +254,11 @@ impl XStreamHandler {
         tokio::spawn({
             let stream_id = info.stream_id;
             let role = info.role;
+            let version = self.header_version;
+            let features = self.header_features;
             async move {
                 // Создаем заголовок
-                let header = XStreamHeader::new(stream_id, role);
+                let header = XStreamHeader::with_version(stream_id, role, version).with_features(features);
 
                 // Разделяем поток
                 let (mut read, mut write) = AsyncReadExt::split(stream);
@@ -4,26 +4,76 @@ use futures::AsyncWriteExt;
 use libp2p::Stream;
 use std::io::{self, Cursor};
 
+use super::consts::{
+    XSTREAM_FEATURES_PROTOCOL_VERSION, XSTREAM_KNOWN_FEATURES, XSTREAM_LEGACY_PROTOCOL_VERSION,
+    XSTREAM_PROTOCOL_VERSION,
+};
 use super::types::{SubstreamRole, XStreamID};
 
+/// Bit of the stream type byte telling that a version byte follows it
+const HEADER_VERSION_FLAG: u8 = 0x80;
+
 /// Header for stream identification
 #[derive(Debug, Clone)]
 pub struct XStreamHeader {
     pub stream_id: XStreamID,
     pub stream_type: SubstreamRole,
+    /// Protocol version, XSTREAM_LEGACY_PROTOCOL_VERSION for headers without a version byte
+    pub version: u8,
+    /// Feature flags, written only by XSTREAM_FEATURES_PROTOCOL_VERSION headers
+    pub features: u8,
 }
 
 impl XStreamHeader {
-    /// Create a new XStreamHeader
+    /// Create a new XStreamHeader in the legacy format every peer can parse
     pub fn new(stream_id: XStreamID, stream_type: SubstreamRole) -> Self {
+        Self::with_version(stream_id, stream_type, XSTREAM_LEGACY_PROTOCOL_VERSION)
+    }
+
+    /// Create a new XStreamHeader with an explicit protocol version
+    pub fn with_version(stream_id: XStreamID, stream_type: SubstreamRole, version: u8) -> Self {
         Self {
             stream_id,
             stream_type,
+            version,
+            features: 0,
+        }
+    }
+
+    /// Sets the feature flags, switching to XSTREAM_FEATURES_PROTOCOL_VERSION if any is set
+    pub fn with_features(mut self, features: u8) -> Self {
+        if features != 0 {
+            self.version = XSTREAM_FEATURES_PROTOCOL_VERSION;
         }
+        self.features = features;
+        self
+    }
+
+    /// Check if the header version and feature flags can be handled by this implementation
+    pub fn is_supported_version(&self) -> bool {
+        is_supported_version(self.version) && self.features & !XSTREAM_KNOWN_FEATURES == 0
     }
+
+    /// Returns true if the feature flag is set
+    pub fn has_feature(&self, feature: u8) -> bool {
+        self.features & feature != 0
+    }
+}
+
+/// Check if a protocol version can be handled by this implementation
+pub fn is_supported_version(version: u8) -> bool {
+    matches!(
+        version,
+        XSTREAM_LEGACY_PROTOCOL_VERSION
+            | XSTREAM_PROTOCOL_VERSION
+            | XSTREAM_FEATURES_PROTOCOL_VERSION
+    )
 }
 
-/// Write a stream header (stream_id and stream_type)
+/// Write a stream header (stream_id, stream_type, version and feature flags)
+///
+/// Legacy headers are written without the version byte, the feature flags byte
+/// follows the version byte only in XSTREAM_FEATURES_PROTOCOL_VERSION headers.
 pub async fn write_header<W>(writer: &mut W, header: &XStreamHeader) -> Result<(), io::Error>
 where
     W: AsyncWriteExt + Unpin,
@@ -34,8 +84,16 @@ where
     // Write stream ID (u128) in network byte order
     header_buf.write_u128::<NetworkEndian>(header.stream_id.into())?;
 
-    // Write stream type (1 byte)
-    header_buf.write_u8(header.stream_type as u8)?;
+    // Write stream type (1 byte), flagged when a version byte follows
+    if header.version == XSTREAM_LEGACY_PROTOCOL_VERSION {
+        header_buf.write_u8(header.stream_type as u8)?;
+    } else {
+        header_buf.write_u8(header.stream_type as u8 | HEADER_VERSION_FLAG)?;
+        header_buf.write_u8(header.version)?;
+        if header.version == XSTREAM_FEATURES_PROTOCOL_VERSION {
+            header_buf.write_u8(header.features)?;
+        }
+    }
 
     // Write the header to the stream
     writer.write_all(&header_buf).await?;
@@ -61,14 +119,51 @@ where
     let mut type_buf = [0u8; 1];
     reader.read_exact(&mut type_buf).await?;
 
-    let stream_type = SubstreamRole::from(type_buf[0]);
+    let stream_type = SubstreamRole::from(type_buf[0] & !HEADER_VERSION_FLAG);
+
+    // Read version (1 byte) if flagged, older peers don't send it
+    let version = if type_buf[0] & HEADER_VERSION_FLAG != 0 {
+        let mut version_buf = [0u8; 1];
+        reader.read_exact(&mut version_buf).await?;
+        version_buf[0]
+    } else {
+        XSTREAM_LEGACY_PROTOCOL_VERSION
+    };
+
+    // Read feature flags (1 byte) of the version that carries them
+    let features = if version == XSTREAM_FEATURES_PROTOCOL_VERSION {
+        let mut features_buf = [0u8; 1];
+        reader.read_exact(&mut features_buf).await?;
+        features_buf[0]
+    } else {
+        0
+    };
 
     Ok(XStreamHeader {
         stream_id,
         stream_type,
+        version,
+        features,
     })
 }
 
+/// Size of the length prefix of a framed message
+pub const FRAME_LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Encode the u32 length prefix of a framed message in network byte order
+pub fn encode_frame_length(length: u32) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(FRAME_LENGTH_PREFIX_SIZE);
+    prefix
+        .write_u32::<NetworkEndian>(length)
+        .expect("writing to Vec cannot fail");
+    prefix
+}
+
+/// Decode the u32 length prefix of a framed message
+pub fn decode_frame_length(prefix: &[u8]) -> Result<u32, io::Error> {
+    Cursor::new(prefix).read_u32::<NetworkEndian>()
+}
+
 /// Write a stream header directly to a Stream
 pub async fn write_header_to_stream(
     stream: &mut futures::io::WriteHalf<Stream>,
@@ -144,4 +239,113 @@ mod tests {
         assert_eq!(read_main.stream_type, SubstreamRole::Main);
         assert_eq!(read_error.stream_type, SubstreamRole::Error);
     }
+
+    #[tokio::test]
+    async fn test_header_version_roundtrip() {
+        let header = XStreamHeader::with_version(XStreamID(7), SubstreamRole::Error, XSTREAM_PROTOCOL_VERSION);
+        let mut buffer = Vec::new();
+        write_header(&mut AsyncCursor::new(&mut buffer), &header).await.unwrap();
+        assert_eq!(buffer.len(), 18);
+
+        let read = read_header(&mut AsyncCursor::new(&buffer)).await.unwrap();
+        assert_eq!(read.version, XSTREAM_PROTOCOL_VERSION);
+        assert_eq!(read.stream_type, SubstreamRole::Error);
+        assert!(read.is_supported_version());
+    }
+
+    /// Parser of peers that predate the version byte, kept as it was
+    async fn read_baseline_header(buffer: &[u8]) -> (XStreamID, SubstreamRole, usize) {
+        let mut reader = AsyncCursor::new(buffer);
+        let mut id_buf = [0u8; 16];
+        reader.read_exact(&mut id_buf).await.unwrap();
+        let mut type_buf = [0u8; 1];
+        reader.read_exact(&mut type_buf).await.unwrap();
+        let consumed = reader.position() as usize;
+        (XStreamID(u128::from_be_bytes(id_buf)), SubstreamRole::from(type_buf[0]), consumed)
+    }
+
+    #[tokio::test]
+    async fn test_default_header_readable_by_baseline_peer() {
+        for role in [SubstreamRole::Main, SubstreamRole::Error] {
+            let header = XStreamHeader::new(XStreamID(9), role);
+            assert_eq!(header.version, XSTREAM_LEGACY_PROTOCOL_VERSION);
+            let mut buffer = Vec::new();
+            write_header(&mut AsyncCursor::new(&mut buffer), &header).await.unwrap();
+
+            let (stream_id, read_role, consumed) = read_baseline_header(&buffer).await;
+            assert_eq!(stream_id.0, 9);
+            assert_eq!(read_role, role, "baseline peer must see the role we wrote");
+            assert_eq!(consumed, buffer.len(), "baseline peer must consume the whole header");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_v1_header_misread_by_baseline_peer() {
+        // Поэтому v1 пишется только узлам, про которые известно, что они его понимают
+        let header = XStreamHeader::with_version(XStreamID(9), SubstreamRole::Error, XSTREAM_PROTOCOL_VERSION);
+        let mut buffer = Vec::new();
+        write_header(&mut AsyncCursor::new(&mut buffer), &header).await.unwrap();
+
+        let (stream_id, read_role, consumed) = read_baseline_header(&buffer).await;
+        assert_eq!(stream_id.0, 9);
+        assert_eq!(read_role, SubstreamRole::Main, "0x81 is not Error for a baseline peer");
+        assert!(consumed < buffer.len(), "the version byte is left in the stream data");
+    }
+
+    #[tokio::test]
+    async fn test_header_legacy_parsing() {
+        // Header of the implicit version: stream id and stream type only
+        let mut buffer = 42u128.to_be_bytes().to_vec();
+        buffer.push(SubstreamRole::Error as u8);
+
+        let read = read_header(&mut AsyncCursor::new(&buffer)).await.unwrap();
+        assert_eq!(read.stream_id.0, 42);
+        assert_eq!(read.stream_type, SubstreamRole::Error);
+        assert_eq!(read.version, XSTREAM_LEGACY_PROTOCOL_VERSION);
+        assert!(read.is_supported_version());
+    }
+
+    #[tokio::test]
+    async fn test_header_unknown_version() {
+        let header = XStreamHeader::with_version(XStreamID(1), SubstreamRole::Main, 99);
+        let mut buffer = Vec::new();
+        write_header(&mut AsyncCursor::new(&mut buffer), &header).await.unwrap();
+
+        let read = read_header(&mut AsyncCursor::new(&buffer)).await.unwrap();
+        assert_eq!(read.version, 99);
+        assert!(!read.is_supported_version());
+    }
+
+    #[tokio::test]
+    async fn test_header_features_roundtrip() {
+        let header = XStreamHeader::new(XStreamID(5), SubstreamRole::Main)
+            .with_features(crate::consts::XSTREAM_FEATURE_INTEGRITY);
+        assert_eq!(header.version, XSTREAM_FEATURES_PROTOCOL_VERSION);
+        let mut buffer = Vec::new();
+        write_header(&mut AsyncCursor::new(&mut buffer), &header).await.unwrap();
+        assert_eq!(buffer.len(), 19);
+
+        let read = read_header(&mut AsyncCursor::new(&buffer)).await.unwrap();
+        assert_eq!(read.version, XSTREAM_FEATURES_PROTOCOL_VERSION);
+        assert!(read.has_feature(crate::consts::XSTREAM_FEATURE_INTEGRITY));
+        assert!(read.is_supported_version());
+
+        // Неизвестный флаг меняет формат потока, такой заголовок не поддерживается
+        let unknown = XStreamHeader::new(XStreamID(5), SubstreamRole::Main).with_features(0x80);
+        let mut buffer = Vec::new();
+        write_header(&mut AsyncCursor::new(&mut buffer), &unknown).await.unwrap();
+        let read = read_header(&mut AsyncCursor::new(&buffer)).await.unwrap();
+        assert!(!read.is_supported_version());
+    }
+
+    #[test]
+    fn test_frame_length_roundtrip() {
+        for length in [0u32, 1, 0x0102_0304, u32::MAX] {
+            let prefix = encode_frame_length(length);
+            assert_eq!(prefix.len(), FRAME_LENGTH_PREFIX_SIZE);
+            assert_eq!(decode_frame_length(&prefix).unwrap(), length);
+        }
+        assert_eq!(encode_frame_length(0x0102_0304), vec![1, 2, 3, 4]);
+        assert!(decode_frame_length(&[0, 1]).is_err());
+    }
 }
@@ -0,0 +1,114 @@
+// integrity.rs
+// Optional CRC32 integrity check of the XStream main stream
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Size of the CRC32 trailer appended on write_eof
+pub const INTEGRITY_TRAILER_SIZE: usize = 4;
+
+/// CRC32 (IEEE 802.3) lookup table
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Feeds `data` into a running (non-finalized) CRC32 state
+fn crc32_update(mut state: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        state = CRC32_TABLE[((state ^ *byte as u32) & 0xFF) as usize] ^ (state >> 8);
+    }
+    state
+}
+
+/// CRC32 of `data`
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(!0, data)
+}
+
+/// Running digests of both directions of the main stream, shared by all clones
+///
+/// The writer feeds every written byte and appends the digest on write_eof;
+/// the reader feeds every read byte and read_to_end checks the trailer.
+#[derive(Debug, Clone)]
+pub struct XStreamIntegrity {
+    written: Arc<Mutex<u32>>,
+    read: Arc<Mutex<u32>>,
+}
+
+impl Default for XStreamIntegrity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XStreamIntegrity {
+    pub fn new() -> Self {
+        Self {
+            written: Arc::new(Mutex::new(!0)),
+            read: Arc::new(Mutex::new(!0)),
+        }
+    }
+
+    /// Adds plaintext written to the main stream
+    pub fn record_written(&self, data: &[u8]) {
+        let mut state = self.written.lock().unwrap();
+        *state = crc32_update(*state, data);
+    }
+
+    /// Adds plaintext read from the main stream before read_to_end
+    pub fn record_read(&self, data: &[u8]) {
+        let mut state = self.read.lock().unwrap();
+        *state = crc32_update(*state, data);
+    }
+
+    /// Trailer with the digest of everything written so far
+    pub fn trailer(&self) -> [u8; INTEGRITY_TRAILER_SIZE] {
+        (!*self.written.lock().unwrap()).to_be_bytes()
+    }
+
+    /// Checks the trailer at the end of `data`, the rest of the stream up to EOF
+    ///
+    /// Returns `data` without the trailer, or `InvalidData` if the trailer is
+    /// missing or does not match what was read.
+    pub fn verify(&self, mut data: Vec<u8>) -> Result<Vec<u8>, io::Error> {
+        if data.len() < INTEGRITY_TRAILER_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Integrity trailer missing before EOF",
+            ));
+        }
+
+        let trailer = data.split_off(data.len() - INTEGRITY_TRAILER_SIZE);
+        let expected = u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let actual = {
+            let mut state = self.read.lock().unwrap();
+            *state = crc32_update(*state, &data);
+            !*state
+        };
+
+        if actual != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Integrity check failed: expected crc32 {:08x}, got {:08x}",
+                    expected, actual
+                ),
+            ));
+        }
+        Ok(data)
+    }
+}
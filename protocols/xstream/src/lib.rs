@@ -13,6 +13,14 @@ pub mod xstream_state;
 pub mod xstream;
 pub mod error_handling;
 pub mod xstream_error;
+pub mod encryption;
+pub mod counters;
+pub mod read_ahead;
+pub mod framed;
+pub mod integrity;
+pub mod rate_limit;
+pub mod sequence;
+pub mod backpressure;
 // Добавьте следующее для подключения тестов:
 #[cfg(test)]
 mod tests;
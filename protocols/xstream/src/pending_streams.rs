@@ -52,6 +52,8 @@ pub struct SubstreamsPair {
     pub key: SubstreamKey,
     pub main: Stream,
     pub error: Stream,
+    // Header of the main substream, None for outbound pairs
+    pub header: Option<XStreamHeader>,
 }
 
 // Events that can be sent to the PendingStreamsManager
@@ -92,12 +94,18 @@ pub enum SubstreamError {
         connection_id: ConnectionId,
         error: std::io::Error,
     },
+    SubstreamVersionMismatch {
+        key: SubstreamKey,
+        role: SubstreamRole,
+        version: u8,
+    },
 }
 
 // A struct to hold a pending stream and its metadata
 struct PendingStream {
     stream: Stream,
     role: SubstreamRole,
+    header: Option<XStreamHeader>,
     timestamp: Instant,
 }
 
@@ -201,6 +209,7 @@ impl PendingStreamsManager {
     ) {
         let key: SubstreamKey;
         let actual_role: SubstreamRole;
+        let main_header: Option<XStreamHeader>;
 
         // TODO: wrap it into async move!!!
         if direction == XStreamDirection::Inbound {
@@ -260,10 +269,35 @@ impl PendingStreamsManager {
 
             // Create a key for this stream
             key = SubstreamKey::new(direction, peer_id, connection_id, header.stream_id);
-            actual_role = header.stream_type
+
+            // Reject unknown versions before parsing anything else from the stream
+            if !header.is_supported_version() {
+                warn!(
+                    "Unsupported header version {} or features {:#04x} for key {:?}",
+                    header.version, header.features, key
+                );
+                let _ = AsyncWriteExt::close(&mut stream).await;
+                if let Some(mut pending) = self.pending_streams.remove(&key) {
+                    let _ = AsyncWriteExt::close(&mut pending.stream).await;
+                }
+                let _ = self
+                    .message_sender
+                    .send(PendingStreamsMessage::SubstreamError(
+                        SubstreamError::SubstreamVersionMismatch {
+                            key,
+                            role: header.stream_type,
+                            version: header.version,
+                        },
+                    ));
+                return;
+            }
+
+            actual_role = header.stream_type;
+            main_header = Some(header);
         } else {
             key = SubstreamKey::new(direction, peer_id, connection_id, stream_id);
-            actual_role = role
+            actual_role = role;
+            main_header = None;
         }
 
         debug!(
@@ -296,10 +330,10 @@ impl PendingStreamsManager {
             }
 
             // Roles are different, create a pair
-            let (main_stream, error_stream) = if actual_role == SubstreamRole::Main {
-                (stream, pending.stream)
+            let (main_stream, error_stream, main_header) = if actual_role == SubstreamRole::Main {
+                (stream, pending.stream, main_header)
             } else {
-                (pending.stream, stream)
+                (pending.stream, stream, pending.header)
             };
 
             // Create the pair and send it
@@ -307,6 +341,7 @@ impl PendingStreamsManager {
                 key: key.clone(),
                 main: main_stream,
                 error: error_stream,
+                header: main_header,
             };
 
             info!("Created substream pair for {:?}", key);
@@ -320,6 +355,7 @@ impl PendingStreamsManager {
                 PendingStream {
                     stream,
                     role: actual_role,
+                    header: main_header,
                     timestamp: Instant::now(),
                 },
             );
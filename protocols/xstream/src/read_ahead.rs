@@ -0,0 +1,347 @@
+// read_ahead.rs
+// Optional read-ahead buffer for the main stream
+// Фоновая задача заранее читает данные в ограниченный буфер,
+// а операции чтения XStream обслуживаются из него
+
+use futures::AsyncReadExt;
+use libp2p::Stream;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Mutex, mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use super::encryption::XStreamCipher;
+
+/// Maximum size of a single chunk read by the background task
+pub const READ_AHEAD_CHUNK_SIZE: usize = 4096;
+
+/// Read-ahead statistics shared by all clones of a stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadAheadStats {
+    /// Read operations served by the buffer
+    pub reads: u64,
+    /// Read operations that had to wait for data from the network
+    pub waits: u64,
+}
+
+/// Item produced by the background task, in stream order
+#[derive(Debug)]
+enum ReadAheadItem {
+    Data(Vec<u8>),
+    Eof,
+    Error(ErrorKind, String),
+}
+
+/// Sticky end of the stream, reported after all buffered data
+#[derive(Debug, Clone)]
+enum ReadAheadEnd {
+    Eof,
+    Error(ErrorKind, String),
+    // Задача остановлена локально (close_read или shutdown), EOF не получен
+    Closed,
+}
+
+impl ReadAheadEnd {
+    fn to_io_error(&self, context: String) -> Error {
+        match self {
+            ReadAheadEnd::Eof => Error::new(ErrorKind::UnexpectedEof, context),
+            ReadAheadEnd::Error(kind, message) => Error::new(*kind, message.clone()),
+            ReadAheadEnd::Closed => Error::new(ErrorKind::BrokenPipe, "Read half has been closed"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ReadAheadState {
+    rx: mpsc::Receiver<ReadAheadItem>,
+    // Данные, полученные фоновой задачей, но еще не выданные читателю
+    pending: Vec<u8>,
+    end: Option<ReadAheadEnd>,
+}
+
+/// Records the end of the stream unless one is already recorded
+fn signal_end(end_signal: &watch::Sender<Option<ReadAheadEnd>>, end: ReadAheadEnd) {
+    end_signal.send_if_modified(|current| {
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(end);
+        true
+    });
+}
+
+/// Aborts the background task when the last clone of the buffer is dropped
+#[derive(Debug)]
+struct FillerGuard(JoinHandle<()>);
+
+impl Drop for FillerGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Bounded buffer filled ahead of reads by a background task
+///
+/// Data, EOF and read errors are delivered in the order they were read from the
+/// network: buffered data is always served before the end of the stream is reported.
+/// Failed reads return the consumed bytes alongside the error.
+/// With a cipher set, data is decrypted as it moves from the task into the buffer.
+#[derive(Debug, Clone)]
+pub struct ReadAheadBuffer {
+    state: Arc<Mutex<ReadAheadState>>,
+    filler: Arc<FillerGuard>,
+    // Конец потока, как его увидела фоновая задача; данные до него могут быть еще в канале
+    end_signal: Arc<watch::Sender<Option<ReadAheadEnd>>>,
+    cipher: Arc<OnceLock<XStreamCipher>>,
+    capacity: usize,
+    reads: Arc<AtomicU64>,
+    waits: Arc<AtomicU64>,
+}
+
+/// Result of a buffered read: data, or consumed bytes with the error
+pub type ReadAheadResult = Result<Vec<u8>, (Vec<u8>, Error)>;
+
+impl ReadAheadBuffer {
+    /// Starts the background task reading from `read_half` into a buffer of about `capacity` bytes
+    pub fn start(
+        read_half: Arc<Mutex<Option<futures::io::ReadHalf<Stream>>>>,
+        capacity: usize,
+    ) -> Self {
+        let capacity = capacity.max(1);
+        let chunk_size = capacity.min(READ_AHEAD_CHUNK_SIZE);
+        let slots = (capacity / chunk_size).max(1);
+        let (tx, rx) = mpsc::channel(slots);
+        let end_signal = Arc::new(watch::Sender::new(None));
+        let filler_end_signal = end_signal.clone();
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let mut chunk = vec![0u8; chunk_size];
+                let result = {
+                    let mut guard = read_half.lock().await;
+                    match guard.as_mut() {
+                        Some(reader) => reader.read(&mut chunk).await,
+                        // ReadHalf закрыт через close_read()
+                        None => Err(Error::new(ErrorKind::BrokenPipe, "Read half has been closed")),
+                    }
+                };
+
+                let item = match result {
+                    Ok(0) => ReadAheadItem::Eof,
+                    Ok(n) => {
+                        chunk.truncate(n);
+                        ReadAheadItem::Data(chunk)
+                    }
+                    Err(e) => ReadAheadItem::Error(e.kind(), e.to_string()),
+                };
+                let finished = !matches!(item, ReadAheadItem::Data(_));
+                if finished {
+                    let end = match &item {
+                        ReadAheadItem::Error(kind, message) => ReadAheadEnd::Error(*kind, message.clone()),
+                        _ => ReadAheadEnd::Eof,
+                    };
+                    signal_end(&filler_end_signal, end);
+                }
+
+                if tx.send(item).await.is_err() || finished {
+                    debug!("Read-ahead task finished");
+                    break;
+                }
+            }
+        });
+
+        Self {
+            state: Arc::new(Mutex::new(ReadAheadState {
+                rx,
+                pending: Vec::new(),
+                end: None,
+            })),
+            filler: Arc::new(FillerGuard(handle)),
+            end_signal,
+            cipher: Arc::new(OnceLock::new()),
+            capacity,
+            reads: Arc::new(AtomicU64::new(0)),
+            waits: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Decrypts all data not yet handed out with `cipher`
+    ///
+    /// The task only queues raw chunks, so this is safe until the first read.
+    pub fn set_cipher(&self, cipher: XStreamCipher) {
+        let _ = self.cipher.set(cipher);
+    }
+
+    /// Approximate buffer capacity in bytes
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns read statistics
+    pub fn stats(&self) -> ReadAheadStats {
+        ReadAheadStats {
+            reads: self.reads.load(Ordering::Relaxed),
+            waits: self.waits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stops the background task, releasing the read half lock
+    pub fn shutdown(&self) {
+        self.filler.0.abort();
+        signal_end(&self.end_signal, ReadAheadEnd::Closed);
+    }
+
+    /// Takes all buffered data without waiting
+    pub async fn take_buffered(&self) -> Vec<u8> {
+        let mut state = self.state.lock().await;
+        while let Ok(item) = state.rx.try_recv() {
+            self.accept(&mut state, item);
+        }
+        std::mem::take(&mut state.pending)
+    }
+
+    /// Returns available data, waiting for at least one chunk
+    pub async fn read(&self) -> ReadAheadResult {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().await;
+        let mut waited = false;
+
+        loop {
+            if !state.pending.is_empty() {
+                return Ok(std::mem::take(&mut state.pending));
+            }
+            if let Some(end) = self.pull(&mut state, &mut waited).await {
+                return Err((Vec::new(), end.to_io_error("End of file".to_string())));
+            }
+        }
+    }
+
+    /// Copies available data into `buf`, waiting for at least one chunk
+    ///
+    /// Data that does not fit stays buffered for the next read.
+    pub async fn read_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().await;
+        let mut waited = false;
+
+        loop {
+            if !state.pending.is_empty() {
+                let n = buf.len().min(state.pending.len());
+                buf[..n].copy_from_slice(&state.pending[..n]);
+                state.pending.drain(..n);
+                return Ok(n);
+            }
+            if let Some(end) = self.pull(&mut state, &mut waited).await {
+                return Err(end.to_io_error("End of file".to_string()));
+            }
+        }
+    }
+
+    /// Returns exactly `size` bytes
+    pub async fn read_exact(&self, size: usize) -> ReadAheadResult {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().await;
+        let mut waited = false;
+
+        loop {
+            if state.pending.len() >= size {
+                let rest = state.pending.split_off(size);
+                return Ok(std::mem::replace(&mut state.pending, rest));
+            }
+            if let Some(end) = self.pull(&mut state, &mut waited).await {
+                let partial = std::mem::take(&mut state.pending);
+                let context = format!("EOF after reading {} of {} bytes", partial.len(), size);
+                return Err((partial, end.to_io_error(context)));
+            }
+        }
+    }
+
+    /// Returns all data up to EOF
+    pub async fn read_to_end(&self) -> ReadAheadResult {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().await;
+        let mut waited = false;
+
+        loop {
+            if let Some(end) = self.pull(&mut state, &mut waited).await {
+                let data = std::mem::take(&mut state.pending);
+                return match end {
+                    ReadAheadEnd::Eof => Ok(data),
+                    error => Err((data, error.to_io_error(String::new()))),
+                };
+            }
+        }
+    }
+
+    /// Waits until the background task reaches the end of the stream
+    ///
+    /// Ok on EOF, the read error otherwise, BrokenPipe if the read half was closed
+    /// locally. Neither takes the buffer lock nor buffers past `capacity`: if the
+    /// peer sends more than fits, the end is seen only once reads make room.
+    pub async fn wait_end(&self) -> Result<(), Error> {
+        let mut end_signal = self.end_signal.subscribe();
+        // Отправитель живет в self, поэтому ожидание не обрывается
+        let end = end_signal
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|end| end.clone())
+            .unwrap_or(ReadAheadEnd::Closed);
+        match end {
+            ReadAheadEnd::Eof => Ok(()),
+            end => Err(end.to_io_error(String::new())),
+        }
+    }
+
+    /// Moves the next item into the buffer, returning the end of the stream once reached
+    async fn pull(&self, state: &mut ReadAheadState, waited: &mut bool) -> Option<ReadAheadEnd> {
+        if let Some(end) = &state.end {
+            return Some(end.clone());
+        }
+
+        let item = match state.rx.try_recv() {
+            Ok(item) => Some(item),
+            Err(mpsc::error::TryRecvError::Empty) => {
+                if !*waited {
+                    *waited = true;
+                    self.waits.fetch_add(1, Ordering::Relaxed);
+                }
+                state.rx.recv().await
+            }
+            Err(mpsc::error::TryRecvError::Disconnected) => None,
+        };
+
+        match item {
+            Some(item) => self.accept(state, item),
+            // Задача остановлена без EOF (close_read или shutdown)
+            None => state.end = Some(ReadAheadEnd::Closed),
+        }
+        state.end.clone()
+    }
+
+    fn accept(&self, state: &mut ReadAheadState, item: ReadAheadItem) {
+        if state.end.is_some() {
+            return;
+        }
+        let cipher = self.cipher.get();
+        match item {
+            ReadAheadItem::Data(data) => match cipher.map(|cipher| cipher.open(&data)) {
+                None => state.pending.extend_from_slice(&data),
+                // Неполный кадр остается в шифре до следующего фрагмента
+                Some(Ok(plaintext)) => state.pending.extend_from_slice(&plaintext),
+                Some(Err(e)) => state.end = Some(ReadAheadEnd::Error(e.kind(), e.to_string())),
+            },
+            ReadAheadItem::Eof => {
+                state.end = match cipher.map(|cipher| cipher.finish()) {
+                    Some(Err(e)) => Some(ReadAheadEnd::Error(e.kind(), e.to_string())),
+                    _ => Some(ReadAheadEnd::Eof),
+                }
+            }
+            ReadAheadItem::Error(kind, message) => {
+                state.end = Some(ReadAheadEnd::Error(kind, message))
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// sequence.rs
+// Diagnostic sequence numbers on messages of the XStream main stream
+
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Size of the prefix of a sequenced message: u64 sequence number and u32 length
+pub const SEQUENCE_PREFIX_SIZE: usize = 12;
+
+/// Largest message accepted in sequence mode; the length prefix comes from the peer
+pub const MAX_SEQUENCED_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Prefix of a sequenced message, both fields in network byte order
+pub fn encode_sequence_prefix(sequence: u64, len: u32) -> [u8; SEQUENCE_PREFIX_SIZE] {
+    let mut prefix = [0u8; SEQUENCE_PREFIX_SIZE];
+    prefix[..8].copy_from_slice(&sequence.to_be_bytes());
+    prefix[8..].copy_from_slice(&len.to_be_bytes());
+    prefix
+}
+
+/// Sequence number and message length from a prefix
+pub fn decode_sequence_prefix(prefix: &[u8]) -> (u64, u32) {
+    let mut sequence = [0u8; 8];
+    let mut len = [0u8; 4];
+    sequence.copy_from_slice(&prefix[..8]);
+    len.copy_from_slice(&prefix[8..SEQUENCE_PREFIX_SIZE]);
+    (u64::from_be_bytes(sequence), u32::from_be_bytes(len))
+}
+
+/// Sequence counters of both directions of the main stream, shared by all clones
+///
+/// The writer numbers every message from 0; the reader expects exactly the
+/// next number and reports anything else as `InvalidData`.
+#[derive(Debug, Clone, Default)]
+pub struct XStreamSequence {
+    next_write: Arc<AtomicU64>,
+    next_read: Arc<AtomicU64>,
+}
+
+impl XStreamSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefixes `data` with the next write sequence number
+    ///
+    /// Must be called under the write lock so numbers follow the write order.
+    pub fn tag(&self, data: &[u8]) -> Result<Vec<u8>, io::Error> {
+        let prefix = self.next_prefix(data.len())?;
+        let mut message = Vec::with_capacity(SEQUENCE_PREFIX_SIZE + data.len());
+        message.extend_from_slice(&prefix);
+        message.extend_from_slice(data);
+        Ok(message)
+    }
+
+    /// Prefix of the next message of `len` bytes, for messages written in several parts
+    ///
+    /// Takes the next write sequence number, so the same locking rule as `tag` applies.
+    pub fn next_prefix(&self, len: usize) -> Result<[u8; SEQUENCE_PREFIX_SIZE], io::Error> {
+        if len > MAX_SEQUENCED_MESSAGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Sequenced message of {} bytes exceeds {} bytes",
+                    len, MAX_SEQUENCED_MESSAGE_SIZE
+                ),
+            ));
+        }
+        let sequence = self.next_write.fetch_add(1, Ordering::SeqCst);
+        Ok(encode_sequence_prefix(sequence, len as u32))
+    }
+
+    /// Checks that `sequence` is the next expected one
+    pub fn check(&self, sequence: u64) -> Result<(), io::Error> {
+        let expected = self.next_read.load(Ordering::SeqCst);
+        if sequence != expected {
+            let problem = if sequence < expected { "duplicated or reordered" } else { "gap" };
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Sequence check failed ({}): expected {}, got {}",
+                    problem, expected, sequence
+                ),
+            ));
+        }
+        self.next_read.store(expected + 1, Ordering::SeqCst);
+        Ok(())
+    }
+}
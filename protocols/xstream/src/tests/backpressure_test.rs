@@ -0,0 +1,265 @@
+//! Тест событий StreamBackpressure и StreamDrained при медленном читателе
+
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, swarm::{dial_opts::DialOpts, Swarm, SwarmEvent}, Multiaddr, PeerId};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::events::XStreamEvent;
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+
+const HIGH_WATER: usize = 1024 * 1024;
+const LOW_WATER: usize = 64 * 1024;
+
+fn create_swarm(behaviour: XStreamNetworkBehaviour) -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| behaviour)
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+/// Запись в поток, который не читают, вызывает StreamBackpressure, медленное чтение - StreamDrained
+#[tokio::test]
+async fn test_backpressure_with_slow_reader() {
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new());
+    let (mut client, _) = create_swarm(
+        XStreamNetworkBehaviour::new().with_backpressure_thresholds(HIGH_WATER, LOW_WATER),
+    );
+    assert_eq!(
+        client.behaviour().backpressure_thresholds().map(|t| (t.high_water, t.low_water)),
+        Some((HIGH_WATER, LOW_WATER))
+    );
+    assert_eq!(server.behaviour().backpressure_thresholds(), None);
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер не читает входящий поток, пока тест не разрешит
+    let (start_reading_tx, start_reading_rx) = oneshot::channel::<()>();
+    let (read_total_tx, mut read_total_rx) = mpsc::unbounded_channel::<usize>();
+    let server_task = tokio::spawn(async move {
+        let mut start_reading_rx = Some(start_reading_rx);
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                let Some(start_reading_rx) = start_reading_rx.take() else {
+                    continue;
+                };
+                let read_total_tx = read_total_tx.clone();
+                tokio::spawn(async move {
+                    let _ = start_reading_rx.await;
+                    let mut total = 0;
+                    while let Ok(data) = stream.read().await {
+                        if data.is_empty() {
+                            break;
+                        }
+                        total += data.len();
+                        let _ = read_total_tx.send(total);
+                        tokio::time::sleep(Duration::from_millis(1)).await;
+                    }
+                });
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+
+    let (stream_tx, mut stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+    let stream = timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                result = &mut stream_rx => {
+                    break result.unwrap().expect("❌ Поток не открылся");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some(stream_tx) = stream_tx.take() {
+                            client.behaviour_mut().open_stream(peer_id, stream_tx).await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Поток не открылся вовремя");
+
+    // Небольшая запись укладывается в окно транспорта и не оставляет буферизованных байт
+    stream.write_all(vec![1u8; 1024]).await.expect("❌ Запись не удалась");
+    assert_eq!(stream.buffered_bytes(), Some(0));
+
+    let payload_len = 16 * 1024 * 1024;
+    let writer = stream.clone();
+    let write_task = tokio::spawn(async move { writer.write_all(vec![7u8; payload_len]).await });
+
+    let buffered = timeout(Duration::from_secs(5), async {
+        loop {
+            match client.select_next_some().await {
+                SwarmEvent::Behaviour(XStreamEvent::StreamDrained { .. }) => {
+                    panic!("❌ ПАНИКА: StreamDrained пришло раньше StreamBackpressure");
+                }
+                SwarmEvent::Behaviour(XStreamEvent::StreamBackpressure { peer_id, stream_id, buffered_bytes }) => {
+                    assert_eq!(peer_id, server_peer_id);
+                    assert_eq!(stream_id, stream.id);
+                    break buffered_bytes;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("❌ StreamBackpressure не пришло при непрочитанном потоке");
+    assert!(buffered >= HIGH_WATER, "❌ ПАНИКА: событие до верхней отметки: {}", buffered);
+    assert!(stream.buffered_bytes().unwrap() > LOW_WATER);
+
+    // Медленный читатель постепенно освобождает окно
+    start_reading_tx.send(()).unwrap();
+    let drained = timeout(Duration::from_secs(30), async {
+        loop {
+            tokio::select! {
+                event = client.select_next_some() => {
+                    if let SwarmEvent::Behaviour(XStreamEvent::StreamDrained { stream_id, buffered_bytes, .. }) = event {
+                        assert_eq!(stream_id, stream.id);
+                        break buffered_bytes;
+                    }
+                }
+                Some(_) = read_total_rx.recv() => {}
+            }
+        }
+    })
+    .await
+    .expect("❌ StreamDrained не пришло после начала чтения");
+    assert!(drained <= LOW_WATER, "❌ ПАНИКА: StreamDrained выше нижней отметки: {}", drained);
+
+    timeout(Duration::from_secs(30), async {
+        loop {
+            tokio::select! {
+                _ = client.select_next_some() => {}
+                total = read_total_rx.recv() => {
+                    if total.expect("❌ Сервер перестал читать") >= 1024 + payload_len {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Сервер не дочитал данные");
+    write_task.await.unwrap().expect("❌ Большая запись не удалась");
+    assert_eq!(stream.buffered_bytes(), Some(0));
+
+    server_task.abort();
+}
+
+/// Большая запись в поток, который читают сразу, не вызывает StreamBackpressure
+#[tokio::test]
+async fn test_large_write_to_fast_reader_no_backpressure() {
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new());
+    let (mut client, _) = create_swarm(
+        XStreamNetworkBehaviour::new().with_backpressure_thresholds(HIGH_WATER, LOW_WATER),
+    );
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер читает входящий поток без задержек
+    let (read_total_tx, mut read_total_rx) = mpsc::unbounded_channel::<usize>();
+    let server_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                let read_total_tx = read_total_tx.clone();
+                tokio::spawn(async move {
+                    let mut total = 0;
+                    while let Ok(data) = stream.read().await {
+                        if data.is_empty() {
+                            break;
+                        }
+                        total += data.len();
+                        let _ = read_total_tx.send(total);
+                    }
+                });
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+
+    let (stream_tx, mut stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+    let stream = timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                result = &mut stream_rx => {
+                    break result.unwrap().expect("❌ Поток не открылся");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some(stream_tx) = stream_tx.take() {
+                            client.behaviour_mut().open_stream(peer_id, stream_tx).await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Поток не открылся вовремя");
+
+    // Одна запись во много раз больше верхней отметки
+    let payload_len = 16 * HIGH_WATER;
+    let writer = stream.clone();
+    let write_task = tokio::spawn(async move { writer.write_all(vec![7u8; payload_len]).await });
+
+    timeout(Duration::from_secs(30), async {
+        loop {
+            tokio::select! {
+                event = client.select_next_some() => {
+                    if let SwarmEvent::Behaviour(XStreamEvent::StreamBackpressure { buffered_bytes, .. }) = event {
+                        panic!("❌ ПАНИКА: StreamBackpressure при быстром читателе: {}", buffered_bytes);
+                    }
+                }
+                total = read_total_rx.recv() => {
+                    if total.expect("❌ Сервер перестал читать") >= payload_len {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Сервер не дочитал данные");
+    write_task.await.unwrap().expect("❌ Большая запись не удалась");
+    assert_eq!(stream.buffered_bytes(), Some(0));
+
+    server_task.abort();
+}
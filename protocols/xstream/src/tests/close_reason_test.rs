@@ -0,0 +1,156 @@
+//! Тест причины закрытия в XStreamEvent::StreamClosed
+
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, swarm::{dial_opts::DialOpts, Swarm, SwarmEvent}, Multiaddr, PeerId};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::events::{StreamCloseReason, XStreamEvent};
+use crate::types::XStreamID;
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+
+fn create_swarm() -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| XStreamNetworkBehaviour::new())
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+async fn listen(server: &mut Swarm<XStreamNetworkBehaviour>) -> Multiaddr {
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            return address;
+        }
+    }
+}
+
+/// Клиент подключается к серверу и открывает один поток
+async fn open_client_stream(
+    client: &mut Swarm<XStreamNetworkBehaviour>,
+    server_peer_id: PeerId,
+    listen_addr: Multiaddr,
+) -> XStream {
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+
+    let (stream_tx, mut stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+    timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                result = &mut stream_rx => {
+                    return result.unwrap().expect("❌ Поток не открылся");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some(stream_tx) = stream_tx.take() {
+                            client.behaviour_mut().open_stream(peer_id, stream_tx).await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Поток не открылся вовремя")
+}
+
+/// Ждет StreamClosed для потока и возвращает причину
+async fn wait_close_reason(swarm: &mut Swarm<XStreamNetworkBehaviour>, id: XStreamID) -> StreamCloseReason {
+    timeout(Duration::from_secs(5), async {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::StreamClosed { stream_id, reason, .. }) =
+                swarm.select_next_some().await
+            {
+                if stream_id == id {
+                    return reason;
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ StreamClosed не получен вовремя")
+}
+
+/// Поток, закрытый через close(), сообщает LocalClose
+#[tokio::test]
+async fn test_graceful_close_reports_local_close() {
+    let (mut server, server_peer_id) = create_swarm();
+    let (mut client, _) = create_swarm();
+    let listen_addr = listen(&mut server).await;
+
+    // Сервер читает входящие потоки до EOF
+    let server_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                tokio::spawn(async move {
+                    let _ = stream.read_to_end().await;
+                });
+            }
+        }
+    });
+
+    let mut stream = open_client_stream(&mut client, server_peer_id, listen_addr).await;
+    stream.write_all(b"hello".to_vec()).await.expect("❌ Запись не удалась");
+    stream.close().await.expect("❌ Не удалось закрыть поток");
+
+    let reason = wait_close_reason(&mut client, stream.id).await;
+    assert_eq!(reason, StreamCloseReason::LocalClose, "❌ Неверная причина закрытия");
+    assert_eq!(stream.close_reason(), Some(StreamCloseReason::LocalClose));
+
+    server_task.abort();
+}
+
+/// Поток, завершенный через error_write, сообщает Error
+#[tokio::test]
+async fn test_error_close_reports_error() {
+    let (mut server, server_peer_id) = create_swarm();
+    let (mut client, _) = create_swarm();
+    let listen_addr = listen(&mut server).await;
+
+    // Сервер отвечает ошибкой на первый входящий поток и ждет его StreamClosed
+    let server_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                let _ = stream.read().await;
+                stream.error_write(b"failed".to_vec()).await.expect("❌ Не удалось записать ошибку");
+                assert_eq!(stream.close_reason(), Some(StreamCloseReason::Error));
+                return wait_close_reason(&mut server, stream.id).await;
+            }
+        }
+    });
+
+    let stream = open_client_stream(&mut client, server_peer_id, listen_addr).await;
+    stream.write_all(b"request".to_vec()).await.expect("❌ Запись не удалась");
+    let client_task = tokio::spawn(async move {
+        loop {
+            client.select_next_some().await;
+        }
+    });
+
+    let reason = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не закрыл поток вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    assert_eq!(reason, StreamCloseReason::Error, "❌ Неверная причина закрытия");
+
+    client_task.abort();
+}
@@ -74,7 +74,7 @@ async fn test_handler_inbound_stream_processing() {
                             println!("❌ Node B: Stream error - peer: {}, stream_id: {:?}, error: {}", peer_id, stream_id, error);
                             let _ = event_sender.send(format!("StreamError: {}", error));
                         }
-                        crate::events::XStreamEvent::StreamClosed { peer_id, stream_id } => {
+                        crate::events::XStreamEvent::StreamClosed { peer_id, stream_id, .. } => {
                             println!("🔒 Node B: Stream closed - peer: {}, stream_id: {}", peer_id, stream_id);
                             let _ = event_sender.send(format!("StreamClosed: {}", stream_id));
                         }
@@ -12,6 +12,7 @@ use tokio::time::{sleep, timeout};
 use crate::behaviour::XStreamNetworkBehaviour;
 use crate::events::{XStreamEvent, IncomingConnectionApprovePolicy};
 use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
 
 /// Тестирует сценарий отклонения соединения
 #[tokio::test]
@@ -47,7 +48,7 @@ async fn test_connection_reject_scenario() {
     // Создаем каналы для передачи событий
     let (server_request_tx, server_request_rx) = oneshot::channel();
     let (server_reject_tx, server_reject_rx) = oneshot::channel();
-    let (stream_tx, stream_rx) = oneshot::channel::<Result<XStream, String>>();
+    let (stream_tx, stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
 
     // Запускаем серверную задачу с graceful shutdown
     let server_handle = tokio::spawn({
@@ -245,7 +246,7 @@ async fn test_connection_reject_scenario() {
         Ok(Err(error)) => {
             println!("❌ Клиент: Получена ошибка при открытии потока: {}", error);
             // Проверяем, что ошибка содержит информацию об отклонении
-            assert!(error.contains("not authorized"), 
+            assert!(error.to_string().contains("not authorized"), 
                 "❌ Ошибка должна содержать информацию об отклонении: {}", error);
         }
         Err(_) => {
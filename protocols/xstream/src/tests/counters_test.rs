@@ -0,0 +1,34 @@
+//! Tests for per-stream byte counters
+//! Проверяет подсчет байт основного потока и их общий доступ для клонов
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Counters track payload bytes on both sides and are shared by clones
+#[tokio::test]
+async fn test_byte_counters_track_payload() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+    let counters = client.counters();
+
+    assert_eq!(client.bytes_written(), 0, "❌ ПАНИКА: Новый поток не должен иметь записанных байт");
+    assert_eq!(server.bytes_read(), 0, "❌ ПАНИКА: Новый поток не должен иметь прочитанных байт");
+
+    client.write_all(vec![1u8; 100]).await.unwrap();
+    test_pair.client_stream.write_all(vec![2u8; 28]).await.unwrap();
+    client.flush().await.unwrap();
+
+    let data = server.read_exact(128).await.expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(data.len(), 128);
+
+    assert_eq!(client.bytes_written(), 128, "❌ ПАНИКА: Клоны должны разделять счетчик записи");
+    assert_eq!(counters.bytes_written(), 128, "❌ ПАНИКА: Снимок счетчиков должен видеть запись");
+    assert_eq!(server.bytes_read(), 128, "❌ ПАНИКА: Неверный счетчик чтения на сервере");
+    assert_eq!(client.bytes_read(), 0, "❌ ПАНИКА: Клиент ничего не читал");
+
+    // Счетчики переживают сам поток
+    drop(client);
+    assert_eq!(counters.bytes_written(), 128, "❌ ПАНИКА: Счетчики должны жить дольше потока");
+
+    shutdown_manager.shutdown().await;
+}
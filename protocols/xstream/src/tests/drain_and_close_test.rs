@@ -0,0 +1,77 @@
+//! Tests for drain_and_close
+//! Проверяет, что непрочитанные данные вычитываются и учитываются перед закрытием
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Trailing data sent by the peer is drained and counted
+/// Хвост данных сервера вычитывается и возвращается его размер
+#[tokio::test]
+async fn test_drain_and_close_reports_trailing_bytes() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let mut client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let response = b"response".to_vec();
+    let trailing = vec![0x42; 10_000];
+    server.write_all(response.clone()).await.unwrap();
+    server.write_all(trailing.clone()).await.unwrap();
+    server.write_eof().await.unwrap();
+
+    let received = timeout(Duration::from_secs(5), client.read_exact(response.len()))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения ответа")
+        .expect("❌ ПАНИКА: Не удалось прочитать ответ");
+    assert_eq!(received, response, "❌ ПАНИКА: Ответ искажен");
+
+    let drained = client
+        .drain_and_close(Duration::from_secs(5))
+        .await
+        .expect("❌ ПАНИКА: drain_and_close завершился ошибкой");
+    assert_eq!(drained, trailing.len(), "❌ ПАНИКА: Неверное число вычитанных байт");
+    assert!(client.is_local_closed(), "❌ ПАНИКА: Поток не закрыт после drain");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Without EOF draining stops at the timeout and still closes the stream
+/// Без EOF вычитывание останавливается по таймауту
+#[tokio::test]
+async fn test_drain_and_close_stops_at_timeout() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let mut client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    server.write_all(vec![0x01; 100]).await.unwrap();
+    server.flush().await.unwrap();
+
+    let drained = timeout(Duration::from_secs(5), client.drain_and_close(Duration::from_millis(300)))
+        .await
+        .expect("❌ ПАНИКА: drain_and_close не уложился в таймаут")
+        .expect("❌ ПАНИКА: drain_and_close завершился ошибкой");
+    assert_eq!(drained, 100, "❌ ПАНИКА: Неверное число вычитанных байт");
+    assert!(client.is_local_closed(), "❌ ПАНИКА: Поток не закрыт после таймаута");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// An error sent by the server during the drain is returned
+/// Ошибка сервера во время вычитывания не проглатывается
+#[tokio::test]
+async fn test_drain_and_close_returns_pending_error() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let mut client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    server.error_write(b"server failed".to_vec()).await.unwrap();
+
+    let result = timeout(Duration::from_secs(5), client.drain_and_close(Duration::from_secs(5)))
+        .await
+        .expect("❌ ПАНИКА: drain_and_close завис");
+    let error = result.expect_err("❌ ПАНИКА: Ошибка сервера проглочена");
+    assert!(error.is_xstream_error(), "❌ ПАНИКА: Ожидалась XStreamError, получено {:?}", error);
+
+    shutdown_manager.shutdown().await;
+}
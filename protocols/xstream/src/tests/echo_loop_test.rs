@@ -0,0 +1,82 @@
+//! Tests for utils::echo_loop
+//! Проверяет побайтовое эхо и передачу ошибки сервера клиенту
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+use crate::utils::echo_loop;
+
+/// 1 MiB round-trips byte-identical and echo_loop reports the total
+#[tokio::test]
+async fn test_echo_loop_round_trip_1mib() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let payload: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let echo_task = tokio::spawn(echo_loop(server));
+
+    // Запись идет параллельно с чтением, иначе эхо упрется в окно транспорта
+    let writer = client.clone();
+    let sent = payload.clone();
+    let write_task = tokio::spawn(async move {
+        writer.write_all(sent).await?;
+        writer.write_eof().await
+    });
+
+    let echoed = timeout(Duration::from_secs(30), client.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения эха")
+        .expect("❌ ПАНИКА: Не удалось прочитать эхо");
+    write_task
+        .await
+        .unwrap()
+        .expect("❌ ПАНИКА: Клиент не смог отправить данные");
+
+    assert_eq!(echoed.len(), payload.len(), "❌ ПАНИКА: Неверная длина эха");
+    assert!(echoed == payload, "❌ ПАНИКА: Эхо отличается от отправленных данных");
+
+    let total = timeout(Duration::from_secs(5), echo_task)
+        .await
+        .expect("❌ ПАНИКА: echo_loop не завершился после EOF")
+        .unwrap()
+        .expect("❌ ПАНИКА: echo_loop завершился ошибкой");
+    assert_eq!(total, payload.len(), "❌ ПАНИКА: echo_loop вернул неверный объем");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A failed echo write is returned and sent to the client through the error stream
+#[tokio::test]
+async fn test_echo_loop_reports_write_error() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    // Закрытая запись сервера ломает эхо на первом же блоке
+    server.write_eof().await.unwrap();
+    let echo_task = tokio::spawn(echo_loop(server));
+
+    client.write_all(b"ping".to_vec()).await.unwrap();
+    client.flush().await.unwrap();
+
+    let error = timeout(Duration::from_secs(5), echo_task)
+        .await
+        .expect("❌ ПАНИКА: echo_loop не завершился")
+        .unwrap()
+        .expect_err("❌ ПАНИКА: Ожидалась ошибка записи");
+    assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe);
+
+    let error_data = timeout(Duration::from_secs(5), client.error_read())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения ошибки")
+        .expect("❌ ПАНИКА: Клиент не получил ошибку сервера");
+    assert_eq!(
+        error_data,
+        error.to_string().into_bytes(),
+        "❌ ПАНИКА: Данные ошибки искажены"
+    );
+
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,158 @@
+//! Tests for per-stream application-layer encryption
+//! Проверяет, что данные на проводе зашифрованы и аутентифицированы, а после расшифровки совпадают
+
+use crate::encryption::{FRAME_HEADER_SIZE, SALT_SIZE, TAG_SIZE, XStreamCipher};
+use crate::tests::xstream_tests::create_xstream_test_pair;
+use crate::types::XStreamDirection;
+use libp2p::PeerId;
+
+const SHARED_KEY: [u8; 32] = [7u8; 32];
+
+/// Ciphertext on the wire differs from plaintext and opens back to it
+/// Сервер читает без шифрования, то есть видит байты на проводе
+#[tokio::test]
+async fn test_encrypted_write_differs_on_wire() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let plaintext = b"Secret payload over a relayed path".to_vec();
+
+    let mut client = test_pair
+        .client_stream
+        .clone()
+        .with_encryption(SHARED_KEY, test_pair.client_peer_id);
+    let mut raw_server = test_pair.server_stream.clone();
+    assert!(client.is_encrypted(), "❌ ПАНИКА: Клиентский поток должен быть зашифрован");
+    assert!(!raw_server.is_encrypted(), "❌ ПАНИКА: Серверный поток не должен быть зашифрован");
+
+    client.write_all(plaintext.clone()).await.expect("❌ ПАНИКА: Запись не удалась");
+    client.flush().await.expect("❌ ПАНИКА: Flush не удался");
+
+    // Соль, заголовок кадра и тег аутентификации
+    let wire_len = SALT_SIZE + FRAME_HEADER_SIZE + plaintext.len() + TAG_SIZE;
+    let wire = raw_server
+        .read_exact(wire_len)
+        .await
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert!(
+        !wire.windows(plaintext.len()).any(|window| window == plaintext.as_slice()),
+        "❌ ПАНИКА: На проводе не должно быть открытого текста"
+    );
+
+    let cipher = XStreamCipher::new(
+        SHARED_KEY,
+        test_pair.server_peer_id,
+        test_pair.client_peer_id,
+        XStreamDirection::Inbound,
+    );
+    let opened = cipher.open(&wire).expect("❌ ПАНИКА: Кадр должен пройти проверку");
+    assert_eq!(opened, plaintext, "❌ ПАНИКА: Расшифрованные данные должны совпадать с исходными");
+
+    client.close().await.unwrap();
+    raw_server.close().await.unwrap();
+    shutdown_manager.shutdown().await;
+}
+
+/// Both sides encrypted: data round-trips transparently in both directions
+#[tokio::test]
+async fn test_encrypted_round_trip_both_directions() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let request = b"ping over encrypted xstream".to_vec();
+    let response = b"pong over encrypted xstream".to_vec();
+
+    let mut client = test_pair
+        .client_stream
+        .clone()
+        .with_encryption(SHARED_KEY, test_pair.client_peer_id);
+    let mut server = test_pair
+        .server_stream
+        .clone()
+        .with_encryption(SHARED_KEY, test_pair.server_peer_id);
+
+    // Несколько записей подряд, чтобы проверить последовательность кадров
+    client.write_all(request[..10].to_vec()).await.unwrap();
+    client.write_all(request[10..].to_vec()).await.unwrap();
+    client.flush().await.unwrap();
+
+    let received = server
+        .read_exact(request.len())
+        .await
+        .expect("❌ ПАНИКА: Сервер не смог прочитать запрос");
+    assert_eq!(received, request, "❌ ПАНИКА: Запрос искажен при расшифровке");
+
+    server.write_all(response.clone()).await.unwrap();
+    server.flush().await.unwrap();
+
+    let received = client
+        .read_exact(response.len())
+        .await
+        .expect("❌ ПАНИКА: Клиент не смог прочитать ответ");
+    assert_eq!(received, response, "❌ ПАНИКА: Ответ искажен при расшифровке");
+
+    client.close().await.unwrap();
+    server.close().await.unwrap();
+    shutdown_manager.shutdown().await;
+}
+
+/// A modified frame fails the read with InvalidData instead of yielding altered data
+#[tokio::test]
+async fn test_tampered_frame_rejected() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let plaintext = b"transfer 10 tokens".to_vec();
+
+    // Клиент пишет без шифрования кадр, запечатанный вручную и измененный в пути
+    let mut raw_client = test_pair.client_stream.clone();
+    let mut server = test_pair
+        .server_stream
+        .clone()
+        .with_encryption(SHARED_KEY, test_pair.server_peer_id);
+    let cipher = XStreamCipher::new(
+        SHARED_KEY,
+        test_pair.client_peer_id,
+        test_pair.server_peer_id,
+        XStreamDirection::Outbound,
+    );
+    let mut wire = cipher.seal(&plaintext).unwrap();
+    wire[SALT_SIZE + FRAME_HEADER_SIZE] ^= 0x01;
+    raw_client.write_all(wire).await.unwrap();
+    raw_client.flush().await.unwrap();
+
+    let error = server
+        .read_exact(plaintext.len())
+        .await
+        .expect_err("❌ ПАНИКА: Измененный кадр должен быть отклонен");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "❌ ПАНИКА: Ожидалась ошибка InvalidData");
+    assert!(!error.has_partial_data(), "❌ ПАНИКА: Данные измененного кадра не должны выдаваться");
+
+    raw_client.close().await.unwrap();
+    server.close().await.unwrap();
+    shutdown_manager.shutdown().await;
+}
+
+/// Streams under the same shared key never share a keystream, and keys are bound to the peers
+#[test]
+fn test_keys_unique_per_stream_and_bound_to_peers() {
+    let local = PeerId::random();
+    let remote = PeerId::random();
+    let plaintext = b"identical payload";
+
+    // Оба направления и повторные потоки с тем же ключом дают разный шифротекст
+    let first = XStreamCipher::new(SHARED_KEY, local, remote, XStreamDirection::Outbound);
+    let second = XStreamCipher::new(SHARED_KEY, local, remote, XStreamDirection::Outbound);
+    let reverse = XStreamCipher::new(SHARED_KEY, remote, local, XStreamDirection::Outbound);
+    let first_wire = first.seal(plaintext).unwrap();
+    let second_wire = second.seal(plaintext).unwrap();
+    let reverse_wire = reverse.seal(plaintext).unwrap();
+    assert_ne!(first_wire[SALT_SIZE..], second_wire[SALT_SIZE..], "❌ ПАНИКА: Повтор keystream между потоками");
+    assert_ne!(first_wire[SALT_SIZE..], reverse_wire[SALT_SIZE..], "❌ ПАНИКА: Повтор keystream между пирами");
+
+    let reader = XStreamCipher::new(SHARED_KEY, remote, local, XStreamDirection::Inbound);
+    assert_eq!(reader.open(&first_wire).unwrap(), plaintext, "❌ ПАНИКА: Получатель должен открыть кадр");
+
+    // Получатель, ожидающий другого отправителя, кадр не откроет
+    let impostor = XStreamCipher::new(SHARED_KEY, remote, PeerId::random(), XStreamDirection::Inbound);
+    let error = impostor.open(&first_wire).expect_err("❌ ПАНИКА: Ключ должен быть привязан к пирам");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // Кадр, отправленный в обратном направлении, тоже не откроется
+    let wrong_direction = XStreamCipher::new(SHARED_KEY, remote, local, XStreamDirection::Outbound);
+    assert!(wrong_direction.open(&first_wire).is_err(), "❌ ПАНИКА: Ключ должен быть привязан к направлению");
+}
@@ -18,7 +18,7 @@ fn create_test_peer_id() -> PeerId {
 }
 
 // Helper to create test closure notifier
-fn create_test_closure_notifier() -> mpsc::UnboundedSender<(PeerId, XStreamID)> {
+fn create_test_closure_notifier() -> mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)> {
     let (tx, _rx) = mpsc::unbounded_channel();
     tx
 }
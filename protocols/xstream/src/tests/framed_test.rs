@@ -0,0 +1,154 @@
+//! Tests for FramedXStream length-prefixed messages
+//! Проверяет обмен сообщениями, границы размера и передачу серверной ошибки
+
+use std::io::ErrorKind;
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::framed::{FramedXStream, DEFAULT_MAX_MESSAGE_SIZE};
+use crate::header::encode_frame_length;
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// Messages of different sizes arrive intact and in order
+/// Сообщения, включая пустое и максимального размера, доходят без искажений
+#[tokio::test]
+async fn test_framed_roundtrip() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = FramedXStream::new(test_pair.client_stream.clone()).with_max_message_size(MAX_MESSAGE_SIZE);
+    let server = FramedXStream::new(test_pair.server_stream.clone()).with_max_message_size(MAX_MESSAGE_SIZE);
+    assert_eq!(client.max_message_size(), MAX_MESSAGE_SIZE);
+    assert_eq!(FramedXStream::new(test_pair.client_stream.clone()).max_message_size(), DEFAULT_MAX_MESSAGE_SIZE);
+
+    let messages = vec![
+        b"hello".to_vec(),
+        Vec::new(),
+        (0..MAX_MESSAGE_SIZE).map(|i| (i % 251) as u8).collect::<Vec<u8>>(),
+        b"after limit".to_vec(),
+    ];
+
+    let to_send = messages.clone();
+    let sender = tokio::spawn(async move {
+        for message in &to_send {
+            client.send_message(message).await.expect("❌ ПАНИКА: Не удалось отправить сообщение");
+        }
+    });
+
+    for expected in &messages {
+        let received = timeout(Duration::from_secs(5), server.recv_message())
+            .await
+            .expect("❌ ПАНИКА: Таймаут чтения сообщения")
+            .expect("❌ ПАНИКА: Не удалось прочитать сообщение");
+        assert_eq!(&received, expected, "❌ ПАНИКА: Сообщение искажено");
+    }
+    sender.await.unwrap();
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Oversized messages are rejected on both sides
+/// Слишком большое сообщение не отправляется, а слишком длинный кадр не читается
+#[tokio::test]
+async fn test_framed_message_size_limit() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = FramedXStream::new(test_pair.client_stream.clone()).with_max_message_size(MAX_MESSAGE_SIZE);
+    let server = FramedXStream::new(test_pair.server_stream.clone()).with_max_message_size(MAX_MESSAGE_SIZE);
+
+    let error = client
+        .send_message(&vec![0u8; MAX_MESSAGE_SIZE + 1])
+        .await
+        .expect_err("❌ ПАНИКА: Сообщение больше лимита отправлено");
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert_eq!(client.stream().bytes_written(), 0, "❌ ПАНИКА: Данные записаны в поток");
+
+    // Кадр с заявленной длиной больше лимита
+    test_pair
+        .client_stream
+        .write_all(encode_frame_length(MAX_MESSAGE_SIZE as u32 + 1))
+        .await
+        .unwrap();
+    test_pair.client_stream.flush().await.unwrap();
+
+    let error = timeout(Duration::from_secs(5), server.recv_message())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения кадра")
+        .expect_err("❌ ПАНИКА: Кадр больше лимита прочитан");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Server error ends the frame read with an XStream error
+/// Ошибка сервера приходит клиенту вместо сообщения
+#[tokio::test]
+async fn test_framed_server_error() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = FramedXStream::new(test_pair.client_stream.clone());
+    let error_data = b"request rejected".to_vec();
+
+    test_pair
+        .server_stream
+        .error_write(error_data.clone())
+        .await
+        .expect("❌ ПАНИКА: Не удалось записать ошибку");
+
+    let error = timeout(Duration::from_secs(5), client.recv_message())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения кадра")
+        .expect_err("❌ ПАНИКА: Ожидалась ошибка сервера");
+    assert!(error.is_xstream_error(), "❌ ПАНИКА: Ожидалась XStream ошибка: {:?}", error);
+    assert_eq!(error.as_xstream_error().unwrap().data(), error_data.as_slice());
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Concurrent readers on clones of one stream each receive whole messages
+/// Параллельные recv_message не разрывают сообщение между префиксом и телом
+#[tokio::test]
+async fn test_framed_concurrent_readers() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = FramedXStream::new(test_pair.client_stream.clone());
+    let server = FramedXStream::new(test_pair.server_stream.clone());
+    const MESSAGES: usize = 20;
+
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let reader = FramedXStream::new(server.stream().clone());
+            tokio::spawn(async move {
+                let mut received = Vec::new();
+                for _ in 0..MESSAGES / 2 {
+                    let message = timeout(Duration::from_secs(5), reader.recv_message())
+                        .await
+                        .expect("❌ ПАНИКА: Таймаут чтения сообщения")
+                        .expect("❌ ПАНИКА: Не удалось прочитать сообщение");
+                    received.push(message);
+                }
+                received
+            })
+        })
+        .collect();
+
+    // Каждое сообщение заполнено своим номером, разрыв кадра дал бы смешанные байты
+    for i in 0..MESSAGES {
+        client
+            .send_message(&vec![i as u8; 1000 + i])
+            .await
+            .expect("❌ ПАНИКА: Не удалось отправить сообщение");
+    }
+
+    let mut seen = Vec::new();
+    for reader in readers {
+        for message in reader.await.unwrap() {
+            let number = message[0] as usize;
+            assert_eq!(message.len(), 1000 + number, "❌ ПАНИКА: Неверная длина сообщения {}", number);
+            assert!(message.iter().all(|&b| b as usize == number), "❌ ПАНИКА: Сообщение {} искажено", number);
+            seen.push(number);
+        }
+    }
+    seen.sort_unstable();
+    assert_eq!(seen, (0..MESSAGES).collect::<Vec<_>>(), "❌ ПАНИКА: Сообщения потеряны или повторены");
+    drop(server);
+
+    shutdown_manager.shutdown().await;
+}
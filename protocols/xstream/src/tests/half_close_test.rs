@@ -0,0 +1,83 @@
+//! Tests for independent half lifecycles in XStream
+//! Проверяет, что закрытие одной половины потока не мешает другой
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Client closes its write half, keeps reading while the server keeps sending,
+/// then the server closes too
+/// Клиент закрывает запись и продолжает читать, сервер пишет до своего закрытия
+#[tokio::test]
+async fn test_half_close_keeps_other_direction_open() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let request = b"request before half close".to_vec();
+    let chunks: Vec<Vec<u8>> = (0..3)
+        .map(|i| format!("server chunk {}", i).into_bytes())
+        .collect();
+
+    assert!(!client.is_write_closed(), "❌ ПАНИКА: Запись не должна быть закрыта до write_eof");
+    assert!(!server.is_read_eof(), "❌ ПАНИКА: EOF не должен быть получен до write_eof");
+
+    client.write_all(request.clone()).await.unwrap();
+    client.write_eof().await.expect("❌ ПАНИКА: Клиент не смог закрыть запись");
+
+    timeout(Duration::from_secs(1), client.on_write_closed())
+        .await
+        .expect("❌ ПАНИКА: on_write_closed не сработал после write_eof");
+    assert!(client.is_write_closed(), "❌ ПАНИКА: Запись клиента должна быть закрыта");
+    assert!(!client.is_read_eof(), "❌ ПАНИКА: Чтение клиента не должно быть закрыто");
+
+    // Сервер дочитывает запрос до EOF
+    let received = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать запрос");
+    assert_eq!(received, request, "❌ ПАНИКА: Запрос искажен");
+
+    timeout(Duration::from_secs(1), server.on_read_eof())
+        .await
+        .expect("❌ ПАНИКА: on_read_eof не сработал на сервере");
+    assert!(!server.is_write_closed(), "❌ ПАНИКА: Запись сервера не должна быть закрыта");
+
+    // Сервер продолжает писать, клиент продолжает читать
+    for chunk in &chunks {
+        server.write_all(chunk.clone()).await.expect("❌ ПАНИКА: Сервер не смог писать после EOF от клиента");
+        server.flush().await.unwrap();
+
+        let data = timeout(Duration::from_secs(5), client.read_exact(chunk.len()))
+            .await
+            .expect("❌ ПАНИКА: Таймаут чтения на клиенте")
+            .expect("❌ ПАНИКА: Клиент не смог читать после закрытия своей записи");
+        assert_eq!(&data, chunk, "❌ ПАНИКА: Данные сервера искажены");
+    }
+    assert!(!client.is_read_eof(), "❌ ПАНИКА: EOF не должен быть получен до закрытия сервера");
+
+    // Ожидание EOF на клиенте запускаем до закрытия сервера
+    let client_eof_waiter = client.clone();
+    let waiter = tokio::spawn(async move { client_eof_waiter.on_read_eof().await });
+
+    server.write_eof().await.expect("❌ ПАНИКА: Сервер не смог закрыть запись");
+    timeout(Duration::from_secs(1), server.on_write_closed())
+        .await
+        .expect("❌ ПАНИКА: on_write_closed не сработал на сервере");
+
+    let rest = timeout(Duration::from_secs(5), client.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения до EOF на клиенте")
+        .expect("❌ ПАНИКА: Клиент не смог дочитать до EOF");
+    assert!(rest.is_empty(), "❌ ПАНИКА: После последнего чанка данных быть не должно");
+
+    timeout(Duration::from_secs(1), waiter)
+        .await
+        .expect("❌ ПАНИКА: on_read_eof не сработал на клиенте")
+        .unwrap();
+    assert!(client.is_read_eof(), "❌ ПАНИКА: Клиент должен видеть EOF");
+
+    // Обе половины уже закрыты через write_eof, поток полностью завершен
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,142 @@
+//! Тест закрытия потоков без активности по idle timeout
+
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, swarm::{dial_opts::DialOpts, Swarm, SwarmEvent}, Multiaddr, PeerId};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::events::{StreamCloseReason, XStreamEvent};
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+
+fn create_swarm(behaviour: XStreamNetworkBehaviour) -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| behaviour)
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+/// Нетронутый поток закрывается по idle timeout, поток с регулярной записью остается открытым
+#[tokio::test]
+async fn test_idle_stream_closed_active_stream_kept() {
+    let idle_timeout = Duration::from_millis(200);
+
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new());
+    let (mut client, _) = create_swarm(XStreamNetworkBehaviour::new().with_idle_timeout(idle_timeout));
+    assert_eq!(client.behaviour().idle_timeout(), Some(idle_timeout));
+    assert_eq!(server.behaviour().idle_timeout(), None);
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер держит входящие потоки и читает из них, чтобы запись клиента не блокировалась
+    let server_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                tokio::spawn(async move {
+                    while let Ok(data) = stream.read().await {
+                        if data.is_empty() {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+
+    let (idle_tx, mut idle_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let (active_tx, mut active_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut senders = Some((idle_tx, active_tx));
+
+    let (idle_stream, active_stream) = timeout(Duration::from_secs(5), async {
+        let mut idle_stream = None;
+        let mut active_stream = None;
+        loop {
+            tokio::select! {
+                result = &mut idle_rx, if idle_stream.is_none() => {
+                    idle_stream = Some(result.unwrap().expect("❌ Поток не открылся"));
+                }
+                result = &mut active_rx, if active_stream.is_none() => {
+                    active_stream = Some(result.unwrap().expect("❌ Поток не открылся"));
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some((idle_tx, active_tx)) = senders.take() {
+                            client.behaviour_mut().open_stream(peer_id, idle_tx).await;
+                            client.behaviour_mut().open_stream(peer_id, active_tx).await;
+                        }
+                    }
+                }
+            }
+            if idle_stream.is_some() && active_stream.is_some() {
+                break (idle_stream.unwrap(), active_stream.unwrap());
+            }
+        }
+    })
+    .await
+    .expect("❌ Потоки не открылись вовремя");
+
+    // Пингуем активный поток чаще, чем истекает idle timeout
+    let mut ping = tokio::time::interval(Duration::from_millis(50));
+    let closed = timeout(Duration::from_secs(2), async {
+        loop {
+            tokio::select! {
+                _ = ping.tick() => {
+                    active_stream.write_all(b"ping".to_vec()).await.expect("❌ Запись не удалась");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::Behaviour(XStreamEvent::StreamClosed { stream_id, reason, .. }) = event {
+                        break (stream_id, reason);
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Нетронутый поток не закрылся по idle timeout");
+
+    assert_eq!(closed, (idle_stream.id, StreamCloseReason::IdleTimeout));
+    assert!(idle_stream.is_closed());
+
+    // Активный поток пережил несколько idle timeout подряд
+    let keep_alive = timeout(idle_timeout * 3, async {
+        loop {
+            tokio::select! {
+                _ = ping.tick() => {
+                    active_stream.write_all(b"ping".to_vec()).await.expect("❌ Запись не удалась");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::Behaviour(XStreamEvent::StreamClosed { stream_id, .. }) = event {
+                        assert_ne!(stream_id, active_stream.id, "❌ Активный поток закрыт");
+                    }
+                }
+            }
+        }
+    })
+    .await;
+    assert!(keep_alive.is_err());
+    assert!(!active_stream.is_closed());
+
+    server_task.abort();
+}
@@ -1,7 +1,13 @@
 use crate::behaviour::XStreamNetworkBehaviour;
-use crate::events::{IncomingConnectionApprovePolicy, InboundUpgradeDecision, XStreamEvent, StreamOpenDecisionSender};
-use libp2p::{PeerId, swarm::{ConnectionId, SwarmEvent}};
+use crate::consts::{XSTREAM_FEATURES_PROTOCOL_VERSION, XSTREAM_LEGACY_PROTOCOL_VERSION, XSTREAM_PROTOCOL_VERSION};
+use crate::events::{IncomingConnectionApprovePolicy, InboundUpgradeDecision, StreamRejectReason, XStreamEvent, StreamOpenDecisionSender};
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, Multiaddr, PeerId, Swarm, swarm::{dial_opts::DialOpts, ConnectionId, SwarmEvent}};
+use std::time::Duration;
 use tokio::sync::oneshot;
+use tokio::time::timeout;
 
 #[tokio::test]
 async fn test_inbound_upgrade_auto_approve() {
@@ -109,3 +115,119 @@ async fn test_stream_open_decision_sender_reject() {
         Err(_) => panic!("Failed to receive decision"),
     }
 }
+
+fn create_swarm(behaviour: XStreamNetworkBehaviour) -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| behaviour)
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+/// Открывает поток от клиента с заданной версией заголовка и возвращает
+/// первое событие сервера: IncomingStream или StreamRejected
+async fn open_with_header_version(version: u8) -> XStreamEvent {
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new_with_policy(
+        IncomingConnectionApprovePolicy::ApproveViaEvent,
+    ));
+    let (mut client, _) = create_swarm(XStreamNetworkBehaviour::new().with_header_version(version));
+    assert_eq!(client.behaviour().header_version(), version);
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер одобряет входящие апгрейды и ждет итоговое событие потока
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server.select_next_some().await {
+                SwarmEvent::Behaviour(XStreamEvent::IncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                SwarmEvent::Behaviour(event @ XStreamEvent::IncomingStream { .. })
+                | SwarmEvent::Behaviour(event @ XStreamEvent::StreamRejected { .. }) => return event,
+                _ => continue,
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+    let (stream_tx, _stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+    let client_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::ConnectionEstablished { peer_id, .. } = client.select_next_some().await {
+                if let Some(stream_tx) = stream_tx.take() {
+                    client.behaviour_mut().open_stream(peer_id, stream_tx).await;
+                }
+            }
+        }
+    });
+
+    let event = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не получил поток вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    client_task.abort();
+    event
+}
+
+#[tokio::test]
+async fn test_inbound_upgrade_matching_version_accepted() {
+    let event = open_with_header_version(XSTREAM_PROTOCOL_VERSION).await;
+    assert!(
+        matches!(event, XStreamEvent::IncomingStream { .. }),
+        "❌ Поток текущей версии должен быть принят: {:?}",
+        event
+    );
+}
+
+#[tokio::test]
+async fn test_inbound_upgrade_legacy_version_accepted() {
+    // Заголовки без байта версии по-прежнему разбираются
+    let event = open_with_header_version(XSTREAM_LEGACY_PROTOCOL_VERSION).await;
+    assert!(
+        matches!(event, XStreamEvent::IncomingStream { .. }),
+        "❌ Поток неявной версии должен быть принят: {:?}",
+        event
+    );
+}
+
+#[tokio::test]
+async fn test_inbound_upgrade_version_mismatch_rejected() {
+    let unknown_version = XSTREAM_FEATURES_PROTOCOL_VERSION + 1;
+    let event = open_with_header_version(unknown_version).await;
+    match event {
+        XStreamEvent::StreamRejected { reason, .. } => assert_eq!(
+            reason,
+            StreamRejectReason::VersionMismatch {
+                expected: XSTREAM_PROTOCOL_VERSION,
+                received: unknown_version,
+            }
+        ),
+        other => panic!("❌ Ожидалось StreamRejected, получено {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_default_header_version_is_legacy() {
+    // Узлы без поддержки байта версии должны разбирать заголовки по умолчанию
+    assert_eq!(
+        XStreamNetworkBehaviour::new().header_version(),
+        XSTREAM_LEGACY_PROTOCOL_VERSION,
+        "❌ По умолчанию должен писаться заголовок без байта версии"
+    );
+}
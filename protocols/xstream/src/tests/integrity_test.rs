@@ -0,0 +1,230 @@
+//! Тест проверки целостности основного потока (CRC32 в конце потока)
+
+use futures::{AsyncRead, AsyncWrite};
+use libp2p::core::muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent};
+use libp2p::core::transport::Transport;
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, swarm::{dial_opts::DialOpts, Swarm, SwarmEvent}, Multiaddr, PeerId};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::events::XStreamEvent;
+use crate::integrity::{crc32, XStreamIntegrity, INTEGRITY_TRAILER_SIZE};
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+
+/// Маркер в полезной нагрузке, в котором тестовый транспорт портит байт
+const CORRUPT_MARKER: &[u8] = b"CORRUPT-ME";
+
+/// Подпоток, инвертирующий байт в первом встреченном маркере при чтении
+struct CorruptingSubstream<S> {
+    inner: S,
+    corrupted: bool,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CorruptingSubstream<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = &poll {
+            if !this.corrupted {
+                if let Some(pos) = buf[..*n].windows(CORRUPT_MARKER.len()).position(|w| w == CORRUPT_MARKER) {
+                    buf[pos] ^= 0xFF;
+                    this.corrupted = true;
+                }
+            }
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CorruptingSubstream<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Мультиплексор, оборачивающий все подпотоки в CorruptingSubstream
+struct CorruptingMuxer<M> {
+    inner: M,
+}
+
+impl<M> StreamMuxer for CorruptingMuxer<M>
+where
+    M: StreamMuxer + Unpin,
+    M::Substream: Unpin,
+{
+    type Substream = CorruptingSubstream<M::Substream>;
+    type Error = M::Error;
+
+    fn poll_inbound(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Self::Substream, Self::Error>> {
+        Pin::new(&mut self.inner)
+            .poll_inbound(cx)
+            .map_ok(|inner| CorruptingSubstream { inner, corrupted: false })
+    }
+
+    fn poll_outbound(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Self::Substream, Self::Error>> {
+        Pin::new(&mut self.inner)
+            .poll_outbound(cx)
+            .map_ok(|inner| CorruptingSubstream { inner, corrupted: false })
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+fn create_swarm(behaviour: XStreamNetworkBehaviour, corrupting: bool) -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair))
+        .map(move |(peer_id, connection), _| {
+            let muxer = if corrupting {
+                StreamMuxerBox::new(CorruptingMuxer { inner: connection })
+            } else {
+                StreamMuxerBox::new(connection)
+            };
+            (peer_id, muxer)
+        });
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| behaviour)
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+/// Клиент с проверкой целостности отправляет `payload` серверу,
+/// возвращается результат read_to_end на сервере
+async fn send_checked(payload: Vec<u8>, corrupting_server: bool) -> Result<Vec<u8>, io::Error> {
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new(), corrupting_server);
+    let (mut client, _) = create_swarm(XStreamNetworkBehaviour::new().with_integrity_check(), false);
+    assert!(client.behaviour().has_integrity_check());
+    assert!(!server.behaviour().has_integrity_check());
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер узнает о проверке из флагов заголовка и читает поток до EOF
+    let server_task = tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStream { stream }) =
+                server.select_next_some().await
+            {
+                assert!(stream.has_integrity_check(), "❌ Сервер не включил проверку по заголовку");
+                return stream.read_to_end().await.map_err(|e| e.to_io_error());
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+    let (stream_tx, mut stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+    let stream = timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                result = &mut stream_rx => {
+                    return result.unwrap().expect("❌ Поток не открылся");
+                }
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some(stream_tx) = stream_tx.take() {
+                            client.behaviour_mut().open_stream(peer_id, stream_tx).await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Поток не открылся вовремя");
+    assert!(stream.has_integrity_check());
+
+    let client_task = tokio::spawn(async move {
+        loop {
+            client.select_next_some().await;
+        }
+    });
+    stream.write_all(payload).await.expect("❌ Запись не удалась");
+    stream.write_eof().await.expect("❌ Не удалось закрыть запись");
+
+    let result = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не дочитал поток вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    client_task.abort();
+    result
+}
+
+fn payload() -> Vec<u8> {
+    let mut payload = vec![0x11; 1024];
+    payload.extend_from_slice(CORRUPT_MARKER);
+    payload.extend(vec![0x22; 1024]);
+    payload
+}
+
+#[test]
+fn test_crc32_known_vector() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b""), 0);
+}
+
+#[test]
+fn test_integrity_verify_roundtrip() {
+    let writer = XStreamIntegrity::new();
+    writer.record_written(b"hello ");
+    writer.record_written(b"world");
+    let mut wire = b"hello world".to_vec();
+    wire.extend_from_slice(&writer.trailer());
+
+    // Часть данных прочитана до read_to_end
+    let reader = XStreamIntegrity::new();
+    reader.record_read(&wire[..6]);
+    assert_eq!(reader.verify(wire[6..].to_vec()).unwrap(), b"world");
+
+    let short = XStreamIntegrity::new().verify(vec![0; INTEGRITY_TRAILER_SIZE - 1]);
+    assert_eq!(short.unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+/// Без повреждений данные доходят без дайджеста в конце
+#[tokio::test]
+async fn test_integrity_check_passes() {
+    let data = send_checked(payload(), false).await.expect("❌ Проверка целостности не прошла");
+    assert_eq!(data, payload(), "❌ Данные отличаются от отправленных");
+}
+
+/// Байт, испорченный транспортом, обнаруживается на read_to_end
+#[tokio::test]
+async fn test_integrity_check_detects_corruption() {
+    let error = send_checked(payload(), true)
+        .await
+        .expect_err("❌ Поврежденные данные прошли проверку");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData, "❌ Неверный тип ошибки: {}", error);
+}
@@ -109,3 +109,69 @@ pub mod decision_flow_test;
 
 #[cfg(test)]
 pub mod connection_reject_test;
+
+#[cfg(test)]
+pub mod encryption_test;
+
+#[cfg(test)]
+pub mod half_close_test;
+
+#[cfg(test)]
+pub mod counters_test;
+
+#[cfg(test)]
+pub mod read_until_any_test;
+
+#[cfg(test)]
+pub mod read_ahead_test;
+
+#[cfg(test)]
+pub mod write_all_counted_test;
+
+#[cfg(test)]
+pub mod pending_stream_timeout_test;
+
+#[cfg(test)]
+pub mod idle_timeout_test;
+
+#[cfg(test)]
+pub mod framed_test;
+
+#[cfg(test)]
+pub mod write_vectored_test;
+
+#[cfg(test)]
+pub mod close_reason_test;
+
+#[cfg(test)]
+pub mod integrity_test;
+
+#[cfg(test)]
+pub mod read_buf_test;
+
+#[cfg(test)]
+pub mod read_cancellable_test;
+
+#[cfg(test)]
+pub mod rate_limit_test;
+
+#[cfg(test)]
+pub mod write_all_flushed_test;
+
+#[cfg(test)]
+pub mod drain_and_close_test;
+
+#[cfg(test)]
+pub mod sequence_test;
+
+#[cfg(test)]
+pub mod readiness_test;
+
+#[cfg(test)]
+pub mod remote_finish_test;
+
+#[cfg(test)]
+pub mod backpressure_test;
+
+#[cfg(test)]
+pub mod echo_loop_test;
@@ -0,0 +1,94 @@
+//! Тест таймаута для исходящих потоков, застрявших в согласовании
+
+use libp2p::futures::StreamExt;
+use libp2p::{identity, quic, swarm::{dial_opts::DialOpts, Swarm, SwarmEvent}, Multiaddr, PeerId};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::events::{IncomingConnectionApprovePolicy, XStreamEvent};
+use crate::xstream::XStream;
+use crate::xstream_error::StreamOpenError;
+
+fn create_swarm(behaviour: XStreamNetworkBehaviour) -> (Swarm<XStreamNetworkBehaviour>, PeerId) {
+    let keypair = identity::Keypair::generate_ed25519();
+    let peer_id = keypair.public().to_peer_id();
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
+    let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+        .with_tokio()
+        .with_other_transport(|_key| quic_transport)
+        .expect("❌ Не удалось создать QUIC транспорт")
+        .with_behaviour(|_key| behaviour)
+        .expect("❌ Не удалось создать XStream поведение")
+        .build();
+    (swarm, peer_id)
+}
+
+/// Сервер не принимает решение о входящем потоке, open_stream завершается таймаутом
+#[tokio::test]
+async fn test_stalled_negotiation_resolves_to_timeout() {
+    let pending_timeout = Duration::from_millis(300);
+
+    let (mut server, server_peer_id) = create_swarm(XStreamNetworkBehaviour::new_with_policy(
+        IncomingConnectionApprovePolicy::ApproveViaEvent,
+    ));
+    let (mut client, _) = create_swarm(
+        XStreamNetworkBehaviour::new().with_pending_stream_timeout(pending_timeout),
+    );
+    assert_eq!(client.behaviour().pending_stream_timeout(), Some(pending_timeout));
+
+    server
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось запустить прослушивание");
+    let listen_addr: Multiaddr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = server.select_next_some().await {
+            break address;
+        }
+    };
+
+    // Сервер копит решения и никогда на них не отвечает
+    let server_task = tokio::spawn(async move {
+        let mut stalled = Vec::new();
+        loop {
+            if let SwarmEvent::Behaviour(XStreamEvent::IncomingStreamRequest { decision_sender, .. }) =
+                server.select_next_some().await
+            {
+                stalled.push(decision_sender);
+            }
+        }
+    });
+
+    client
+        .dial(DialOpts::peer_id(server_peer_id).addresses(vec![listen_addr]).build())
+        .unwrap();
+
+    let (stream_tx, mut stream_rx) = oneshot::channel::<Result<XStream, StreamOpenError>>();
+    let mut stream_tx = Some(stream_tx);
+
+    let result = timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                result = &mut stream_rx => break result,
+                event = client.select_next_some() => {
+                    if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                        if let Some(tx) = stream_tx.take() {
+                            client.behaviour_mut().open_stream(peer_id, tx).await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ open_stream завис вместо ошибки таймаута");
+
+    match result {
+        Ok(Err(error)) => assert_eq!(error, StreamOpenError::Timeout),
+        Ok(Ok(stream)) => panic!("❌ Поток не должен был открыться: {:?}", stream),
+        Err(_) => panic!("❌ Канал ответа закрыт без результата"),
+    }
+    assert_eq!(client.behaviour().pending_open_count(), 0);
+
+    server_task.abort();
+}
@@ -0,0 +1,28 @@
+//! Тест token bucket для лимита входящих потоков
+
+use std::time::{Duration, Instant};
+
+use crate::rate_limit::TokenBucket;
+
+/// Полный bucket отдает `rate` токенов, затем пополняется со временем
+#[test]
+fn test_token_bucket_burst_and_refill() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(5);
+
+    let taken = (0..20).filter(|_| bucket.try_take_at(start)).count();
+    assert_eq!(taken, 5, "❌ Полный bucket должен отдать ровно 5 токенов");
+
+    assert!(
+        !bucket.try_take_at(start + Duration::from_millis(100)),
+        "❌ За 100мс при 5/с токен не накапливается"
+    );
+    assert!(
+        bucket.try_take_at(start + Duration::from_millis(250)),
+        "❌ За 250мс должен накопиться один токен"
+    );
+
+    let later = start + Duration::from_secs(10);
+    let taken = (0..20).filter(|_| bucket.try_take_at(later)).count();
+    assert_eq!(taken, 5, "❌ Bucket не должен копить больше своей емкости");
+}
@@ -0,0 +1,104 @@
+//! Tests for the optional read-ahead buffer
+//! Проверяет целостность данных, порядок EOF и сокращение ожиданий при чтении
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Bursty data arrives intact and most reads are served without waiting
+/// Данные пачкой приходят без искажений, большинство чтений не ждут сеть
+#[tokio::test]
+async fn test_read_ahead_preserves_data_and_reduces_waits() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_read_ahead(64 * 1024);
+    assert_eq!(server.read_ahead_stats().unwrap_or_default().reads, 0);
+    assert!(client.read_ahead_stats().is_none(), "❌ ПАНИКА: У клиента read-ahead не включен");
+
+    let messages: Vec<Vec<u8>> = (0..32u8).map(|i| vec![i; 512]).collect();
+    for message in &messages {
+        client.write_all(message.clone()).await.unwrap();
+    }
+    client.flush().await.unwrap();
+
+    // Даем фоновой задаче заполнить буфер
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    for (i, message) in messages.iter().enumerate() {
+        let data = timeout(Duration::from_secs(5), server.read_exact(message.len()))
+            .await
+            .expect("❌ ПАНИКА: Таймаут чтения из буфера")
+            .expect("❌ ПАНИКА: Сервер не смог прочитать сообщение");
+        assert_eq!(&data, message, "❌ ПАНИКА: Сообщение {} искажено", i);
+    }
+
+    let stats = server.read_ahead_stats().expect("❌ ПАНИКА: Статистика read-ahead отсутствует");
+    assert_eq!(stats.reads, messages.len() as u64, "❌ ПАНИКА: Неверное число чтений");
+    assert!(
+        stats.waits * 4 < stats.reads,
+        "❌ ПАНИКА: Слишком много чтений ждали сеть: {:?}",
+        stats
+    );
+    assert_eq!(server.bytes_read(), 32 * 512, "❌ ПАНИКА: Счетчик чтения должен учитывать буферизованные данные");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Buffered data is delivered before EOF, then EOF is reported
+/// Буферизованные данные выдаются раньше EOF
+#[tokio::test]
+async fn test_read_ahead_respects_eof_order() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_read_ahead(1024);
+
+    let payload: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    client.write_all(payload.clone()).await.unwrap();
+    client.write_eof().await.expect("❌ ПАНИКА: Клиент не смог закрыть запись");
+
+    // Буфер меньше данных: фоновая задача упирается в лимит и ждет читателя
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!server.is_read_eof(), "❌ ПАНИКА: EOF не должен быть выдан раньше данных");
+
+    let head = timeout(Duration::from_secs(5), server.read_exact(1000))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать начало");
+    assert_eq!(head, payload[..1000], "❌ ПАНИКА: Начало данных искажено");
+
+    let rest = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения до EOF")
+        .expect("❌ ПАНИКА: Сервер не смог дочитать до EOF");
+    assert_eq!(rest, payload[1000..], "❌ ПАНИКА: Остаток данных искажен");
+    assert!(server.is_read_eof(), "❌ ПАНИКА: EOF должен быть получен после данных");
+
+    // После EOF чтение сообщает UnexpectedEof без данных
+    let after_eof = server.read().await.expect_err("❌ ПАНИКА: Чтение после EOF должно вернуть ошибку");
+    assert_eq!(after_eof.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert!(!after_eof.has_partial_data(), "❌ ПАНИКА: После EOF данных быть не должно");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// read_exact past EOF returns the buffered tail as partial data
+/// read_exact за пределами EOF возвращает остаток как частичные данные
+#[tokio::test]
+async fn test_read_ahead_partial_data_on_eof() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_read_ahead(4096);
+
+    client.write_all(b"short tail".to_vec()).await.unwrap();
+    client.write_eof().await.expect("❌ ПАНИКА: Клиент не смог закрыть запись");
+
+    let error = timeout(Duration::from_secs(5), server.read_exact(64))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения")
+        .expect_err("❌ ПАНИКА: read_exact должен завершиться ошибкой EOF");
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert_eq!(error.partial_data(), b"short tail", "❌ ПАНИКА: Частичные данные потеряны");
+
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,99 @@
+//! Tests for XStream::read_buf
+//! Чтение в буфер вызывающей стороны без выделения памяти на каждый вызов
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Reads the stream into a fixed buffer until EOF, reassembling the data
+async fn read_all_into(stream: &crate::xstream::XStream, buf: &mut [u8]) -> Vec<u8> {
+    let mut received = Vec::new();
+    loop {
+        match timeout(Duration::from_secs(5), stream.read_buf(buf))
+            .await
+            .expect("❌ ПАНИКА: Таймаут read_buf")
+        {
+            Ok(n) => {
+                assert!(n > 0 && n <= buf.len(), "❌ ПАНИКА: Неверное число байт: {}", n);
+                received.extend_from_slice(&buf[..n]);
+            }
+            Err(e) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof, "❌ ПАНИКА: Ожидался EOF: {:?}", e);
+                return received;
+            }
+        }
+    }
+}
+
+fn payload() -> Vec<u8> {
+    (0..100_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+/// Data larger than the buffer arrives intact over several calls
+/// Данные больше буфера собираются из нескольких вызовов без искажений
+#[tokio::test]
+async fn test_read_buf_reassembles_data() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let payload = payload();
+    client.write_all(payload.clone()).await.unwrap();
+    client.write_eof().await.expect("❌ ПАНИКА: Клиент не смог закрыть запись");
+
+    let mut buf = [0u8; 8 * 1024];
+    let received = read_all_into(&server, &mut buf).await;
+    assert_eq!(received, payload, "❌ ПАНИКА: Собранные данные искажены");
+    assert_eq!(server.bytes_read(), payload.len() as u64, "❌ ПАНИКА: Неверный счетчик чтения");
+    assert!(server.is_read_eof(), "❌ ПАНИКА: EOF должен быть получен");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A buffer smaller than a read-ahead chunk leaves the rest buffered
+/// Остаток порции read-ahead, не поместившийся в буфер, не теряется
+#[tokio::test]
+async fn test_read_buf_with_read_ahead() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_read_ahead(64 * 1024);
+
+    let payload = payload();
+    client.write_all(payload.clone()).await.unwrap();
+    client.write_eof().await.expect("❌ ПАНИКА: Клиент не смог закрыть запись");
+
+    let mut buf = [0u8; 1000];
+    let received = read_all_into(&server, &mut buf).await;
+    assert_eq!(received, payload, "❌ ПАНИКА: Собранные данные искажены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A server error surfaces from read_buf on the client
+/// Ошибка сервера возвращается из read_buf как XStreamError
+#[tokio::test]
+async fn test_read_buf_surfaces_server_error() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    server
+        .error_write(b"request rejected".to_vec())
+        .await
+        .expect("❌ ПАНИКА: Сервер не смог записать ошибку");
+
+    let mut buf = [0u8; 8 * 1024];
+    let error = timeout(Duration::from_secs(5), client.read_buf(&mut buf))
+        .await
+        .expect("❌ ПАНИКА: Таймаут read_buf")
+        .expect_err("❌ ПАНИКА: Ожидалась ошибка сервера");
+    assert!(error.is_xstream_error(), "❌ ПАНИКА: Ожидалась XStreamError: {:?}", error);
+    assert_eq!(
+        error.as_xstream_error().unwrap().data(),
+        b"request rejected",
+        "❌ ПАНИКА: Данные ошибки искажены"
+    );
+
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,71 @@
+//! Tests for XStream::read_cancellable
+//! Отмена чтения оставляет поток пригодным для следующего чтения
+
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+use crate::xstream_error::ReadError;
+
+/// A read against a silent peer is cancelled, the next read gets the data
+/// Чтение у молчащего пира отменяется, следующее чтение получает данные
+#[tokio::test]
+async fn test_read_cancellable_then_read() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+
+    let error = timeout(Duration::from_secs(5), client.read_cancellable(token))
+        .await
+        .expect("❌ ПАНИКА: Отмена не прервала чтение")
+        .expect_err("❌ ПАНИКА: Молчащий пир не должен вернуть данные");
+    assert!(error.is_cancelled(), "❌ ПАНИКА: Ожидалась отмена: {:?}", error);
+    assert!(matches!(error.error(), ReadError::Cancelled));
+    assert!(!error.has_partial_data(), "❌ ПАНИКА: Отмененное чтение не должно отдавать данные");
+    assert!(!client.is_closed(), "❌ ПАНИКА: Отмена не должна закрывать поток");
+
+    server.write_all(b"after cancel".to_vec()).await.unwrap();
+    server.flush().await.unwrap();
+
+    let data = timeout(Duration::from_secs(5), client.read_cancellable(CancellationToken::new()))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения после отмены")
+        .expect("❌ ПАНИКА: Чтение после отмены не удалось");
+    assert_eq!(data, b"after cancel", "❌ ПАНИКА: Данные после отмены искажены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// An already cancelled token returns immediately even with data pending
+/// Уже отмененный токен не потребляет данные из потока
+#[tokio::test]
+async fn test_read_cancellable_precancelled_keeps_data() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    client.write_all(b"kept".to_vec()).await.unwrap();
+    client.flush().await.unwrap();
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let error = server.read_cancellable(token).await.expect_err("❌ ПАНИКА: Ожидалась отмена");
+    assert!(error.is_cancelled());
+    assert_eq!(error.kind(), std::io::ErrorKind::Interrupted);
+
+    let data = timeout(Duration::from_secs(5), server.read_exact(4))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения")
+        .expect("❌ ПАНИКА: Данные потеряны после отмены");
+    assert_eq!(data, b"kept");
+
+    shutdown_manager.shutdown().await;
+}
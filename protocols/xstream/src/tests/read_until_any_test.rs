@@ -0,0 +1,74 @@
+//! Tests for read_until_any() in XStream
+//! Проверяет чтение до одного из разделителей и сохранение частичных данных
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+const DELIMITERS: &[&[u8]] = &[b"\n", b"\r\n"];
+
+/// Lines ending with `\n` and `\r\n` report the delimiter that ended them
+#[tokio::test]
+async fn test_read_until_any_reports_delimiter() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    client.write_all(b"first\nsecond\r\nrest".to_vec()).await.unwrap();
+    client.flush().await.unwrap();
+
+    let (line, delimiter) = server
+        .read_until_any(DELIMITERS, 64)
+        .await
+        .expect("❌ ПАНИКА: Не удалось прочитать первую строку");
+    assert_eq!(line, b"first\n".to_vec(), "❌ ПАНИКА: Неверная первая строка");
+    assert_eq!(delimiter, 0, "❌ ПАНИКА: Первая строка должна закончиться на \\n");
+
+    let (line, delimiter) = server
+        .read_until_any(DELIMITERS, 64)
+        .await
+        .expect("❌ ПАНИКА: Не удалось прочитать вторую строку");
+    assert_eq!(line, b"second\r\n".to_vec(), "❌ ПАНИКА: Неверная вторая строка");
+    assert_eq!(delimiter, 1, "❌ ПАНИКА: Вторая строка должна закончиться на \\r\\n");
+
+    // После разделителя ничего не должно быть прочитано заранее
+    let rest = server.read_exact(4).await.expect("❌ ПАНИКА: Остаток потерян");
+    assert_eq!(rest, b"rest".to_vec(), "❌ ПАНИКА: Остаток искажен");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Exceeding max_len and EOF both keep the bytes read so far
+#[tokio::test]
+async fn test_read_until_any_preserves_partial_data() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    client.write_all(b"too long line\nend".to_vec()).await.unwrap();
+    client.write_eof().await.unwrap();
+
+    let error = server
+        .read_until_any(DELIMITERS, 4)
+        .await
+        .expect_err("❌ ПАНИКА: Превышение max_len должно быть ошибкой");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "❌ ПАНИКА: Неверный тип ошибки");
+    assert_eq!(error.partial_data(), b"too ", "❌ ПАНИКА: Частичные данные потеряны");
+
+    let (line, delimiter) = server.read_until_any(DELIMITERS, 64).await.unwrap();
+    assert_eq!(line, b"long line\n".to_vec());
+    assert_eq!(delimiter, 0);
+
+    let error = server
+        .read_until_any(DELIMITERS, 64)
+        .await
+        .expect_err("❌ ПАНИКА: EOF без разделителя должен быть ошибкой");
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof, "❌ ПАНИКА: Ожидался EOF");
+    assert_eq!(error.partial_data(), b"end", "❌ ПАНИКА: Данные до EOF потеряны");
+
+    let error = server
+        .read_until_any(&[], 64)
+        .await
+        .expect_err("❌ ПАНИКА: Пустой список разделителей должен быть ошибкой");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    shutdown_manager.shutdown().await;
+}
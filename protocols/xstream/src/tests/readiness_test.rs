@@ -0,0 +1,69 @@
+//! Tests for the is_readable / is_writable predicates
+//! Проверяет переходы после write_eof, EOF от удаленной стороны и ошибки
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// A fresh stream is readable and writable on both sides
+/// Новый поток доступен для чтения и записи
+#[tokio::test]
+async fn test_fresh_stream_is_readable_and_writable() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    assert!(client.is_readable(), "❌ ПАНИКА: Клиент должен быть доступен для чтения");
+    assert!(client.is_writable(), "❌ ПАНИКА: Клиент должен быть доступен для записи");
+    assert!(server.is_readable(), "❌ ПАНИКА: Сервер должен быть доступен для чтения");
+    assert!(server.is_writable(), "❌ ПАНИКА: Сервер должен быть доступен для записи");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// write_eof makes the writer non-writable, the reader becomes non-readable after EOF
+/// После write_eof запись недоступна, а сервер после EOF не читает, но пишет
+#[tokio::test]
+async fn test_write_eof_and_remote_eof_transitions() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    client.write_all(b"payload".to_vec()).await.expect("❌ ПАНИКА: Запись не удалась");
+    client.write_eof().await.expect("❌ ПАНИКА: write_eof не удался");
+    assert!(!client.is_writable(), "❌ ПАНИКА: Запись доступна после write_eof");
+    assert!(client.is_readable(), "❌ ПАНИКА: write_eof не должен закрывать чтение");
+
+    let received = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(received, b"payload".to_vec(), "❌ ПАНИКА: Данные искажены");
+    assert!(!server.is_readable(), "❌ ПАНИКА: Чтение доступно после EOF");
+    assert!(server.is_writable(), "❌ ПАНИКА: EOF от клиента не должен закрывать запись сервера");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A pending error from the server makes the client non-readable
+/// Ожидающая ошибка делает поток недоступным для чтения
+#[tokio::test]
+async fn test_pending_error_makes_stream_unreadable() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    server.error_write(b"server failed".to_vec()).await.expect("❌ ПАНИКА: error_write не удался");
+
+    timeout(Duration::from_secs(5), async {
+        while !client.has_pending_error().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("❌ ПАНИКА: Ошибка сервера не дошла до клиента");
+    assert!(!client.is_readable(), "❌ ПАНИКА: Чтение доступно при ожидающей ошибке");
+
+    shutdown_manager.shutdown().await;
+}
@@ -2,7 +2,7 @@
 // Real integration tests for XStream data exchange without stubs
 
 use crate::behaviour::XStreamNetworkBehaviour;
-use crate::events::XStreamEvent;
+use crate::events::{StreamCloseReason, XStreamEvent};
 use crate::types::{XStreamDirection, XStreamID};
 use libp2p::{PeerId, swarm::{Swarm, SwarmEvent}};
 use libp2p_swarm_test::SwarmExt;
@@ -67,9 +67,9 @@ async fn test_real_xstream_data_exchange() {
                             println!("❌ Node A: Stream error - peer: {}, stream_id: {:?}, error: {}", peer_id, stream_id, error);
                             let _ = event_sender_a_clone.send(XStreamEvent::StreamError { peer_id, stream_id, error });
                         }
-                        XStreamEvent::StreamClosed { peer_id, stream_id } => {
+                        XStreamEvent::StreamClosed { peer_id, stream_id, reason } => {
                             println!("🔒 Node A: Stream closed - peer: {}, stream_id: {}", peer_id, stream_id);
-                            let _ = event_sender_a_clone.send(XStreamEvent::StreamClosed { peer_id, stream_id });
+                            let _ = event_sender_a_clone.send(XStreamEvent::StreamClosed { peer_id, stream_id, reason });
                         }
                         XStreamEvent::IncomingStreamRequest { .. } => {
                             // Игнорируем событие запроса на апгрейд в тестах
@@ -102,9 +102,9 @@ async fn test_real_xstream_data_exchange() {
                             println!("❌ Node B: Stream error - peer: {}, stream_id: {:?}, error: {}", peer_id, stream_id, error);
                             let _ = event_sender_b_clone.send(XStreamEvent::StreamError { peer_id, stream_id, error });
                         }
-                        XStreamEvent::StreamClosed { peer_id, stream_id } => {
+                        XStreamEvent::StreamClosed { peer_id, stream_id, reason } => {
                             println!("🔒 Node B: Stream closed - peer: {}, stream_id: {}", peer_id, stream_id);
-                            let _ = event_sender_b_clone.send(XStreamEvent::StreamClosed { peer_id, stream_id });
+                            let _ = event_sender_b_clone.send(XStreamEvent::StreamClosed { peer_id, stream_id, reason });
                         }
                         XStreamEvent::IncomingStreamRequest { .. } => {
                             // Игнорируем событие запроса на апгрейд в тестах
@@ -234,12 +234,14 @@ async fn test_xstream_event_structure() {
     let closed_event = XStreamEvent::StreamClosed {
         peer_id,
         stream_id,
+        reason: StreamCloseReason::LocalClose,
     };
     
     match closed_event {
-        XStreamEvent::StreamClosed { peer_id: p, stream_id: s } => {
+        XStreamEvent::StreamClosed { peer_id: p, stream_id: s, reason } => {
             assert_eq!(p, peer_id, "Peer ID should match");
             assert_eq!(s, stream_id, "Stream ID should match");
+            assert_eq!(reason, StreamCloseReason::LocalClose, "Reason should match");
             println!("✅ StreamClosed event structure is correct");
         }
         _ => panic!("Unexpected event type"),
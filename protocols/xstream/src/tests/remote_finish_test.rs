@@ -0,0 +1,147 @@
+//! Tests for finish_writing / await_remote_finish half-close helpers
+//! Проверяет, что каждая сторона видит завершение записи другой, не теряя данных
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Both sides finish writing at different times and each observes the other's finish
+/// Клиент завершает запись первым, сервер позже; данные остаются для чтения
+#[tokio::test]
+async fn test_both_sides_observe_remote_finish() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone().with_read_ahead(64 * 1024);
+    let server = test_pair.server_stream.clone().with_read_ahead(64 * 1024);
+
+    client.write_all(b"request".to_vec()).await.expect("❌ ПАНИКА: Запись клиента не удалась");
+    client.finish_writing().await.expect("❌ ПАНИКА: finish_writing клиента не удался");
+    assert!(client.is_write_local_closed(), "❌ ПАНИКА: Запись клиента не закрыта");
+    assert!(!client.is_read_eof(), "❌ ПАНИКА: finish_writing не должен закрывать чтение");
+
+    server
+        .await_remote_finish(Duration::from_secs(5))
+        .await
+        .expect("❌ ПАНИКА: Сервер не увидел завершения записи клиента");
+
+    // Сервер продолжает писать после завершения клиента
+    server.write_all(b"response".to_vec()).await.expect("❌ ПАНИКА: Запись сервера не удалась");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let still_open = timeout(Duration::from_millis(200), client.await_remote_finish(Duration::from_secs(5))).await;
+    assert!(still_open.is_err(), "❌ ПАНИКА: Клиент увидел завершение до finish_writing сервера");
+    server.finish_writing().await.expect("❌ ПАНИКА: finish_writing сервера не удался");
+
+    client
+        .await_remote_finish(Duration::from_secs(5))
+        .await
+        .expect("❌ ПАНИКА: Клиент не увидел завершения записи сервера");
+
+    // Ожидание завершения не потребляет данные
+    let at_server = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(at_server, b"request".to_vec(), "❌ ПАНИКА: Данные клиента потеряны");
+    let at_client = timeout(Duration::from_secs(5), client.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на клиенте")
+        .expect("❌ ПАНИКА: Клиент не смог прочитать данные");
+    assert_eq!(at_client, b"response".to_vec(), "❌ ПАНИКА: Данные сервера потеряны");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Without read-ahead the finish is observed through a concurrent reader
+/// Без read-ahead завершение фиксирует читатель в другой задаче
+#[tokio::test]
+async fn test_remote_finish_observed_via_reader() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let reader = server.clone();
+    let reader_task = tokio::spawn(async move { reader.read_to_end().await });
+
+    client.write_all(b"payload".to_vec()).await.unwrap();
+    client.finish_writing().await.expect("❌ ПАНИКА: finish_writing не удался");
+
+    server
+        .await_remote_finish(Duration::from_secs(5))
+        .await
+        .expect("❌ ПАНИКА: Сервер не увидел завершения записи клиента");
+    let received = reader_task.await.unwrap().expect("❌ ПАНИКА: Читатель не получил данные");
+    assert_eq!(received, b"payload".to_vec(), "❌ ПАНИКА: Данные искажены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A peer that keeps the write half open makes await_remote_finish time out
+/// Пир без finish_writing приводит к TimedOut
+#[tokio::test]
+async fn test_await_remote_finish_times_out() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let server = test_pair.server_stream.clone().with_read_ahead(1024);
+
+    let error = server
+        .await_remote_finish(Duration::from_millis(300))
+        .await
+        .expect_err("❌ ПАНИКА: Завершение без finish_writing клиента");
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut, "❌ ПАНИКА: Ожидался TimedOut: {}", error);
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Waiting for the finish keeps the read-ahead bound and does not block readers
+/// Ожидание не буферизует больше емкости и не мешает параллельному чтению
+#[tokio::test]
+async fn test_await_remote_finish_respects_read_ahead_capacity() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_read_ahead(1024);
+
+    let payload: Vec<u8> = (0..16 * 1024u32).map(|i| (i % 251) as u8).collect();
+    client.write_all(payload.clone()).await.expect("❌ ПАНИКА: Запись клиента не удалась");
+    client.finish_writing().await.expect("❌ ПАНИКА: finish_writing клиента не удался");
+
+    // Данные больше буфера: EOF не виден, пока их никто не читает
+    let error = server
+        .await_remote_finish(Duration::from_millis(300))
+        .await
+        .expect_err("❌ ПАНИКА: Read-ahead буферизовал данные сверх емкости");
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut, "❌ ПАНИКА: Ожидался TimedOut: {}", error);
+
+    let waiter = server.clone();
+    let wait_task = tokio::spawn(async move { waiter.await_remote_finish(Duration::from_secs(5)).await });
+    let received = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Ожидание завершения заблокировало чтение")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(received, payload, "❌ ПАНИКА: Данные искажены");
+    wait_task
+        .await
+        .unwrap()
+        .expect("❌ ПАНИКА: Сервер не увидел завершения записи клиента");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// A read half closed locally is not reported as the peer's finish
+/// close_read во время ожидания дает BrokenPipe, а не EOF
+#[tokio::test]
+async fn test_await_remote_finish_after_close_read() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let server = test_pair.server_stream.clone().with_read_ahead(1024);
+
+    let waiter = server.clone();
+    let wait_task = tokio::spawn(async move { waiter.await_remote_finish(Duration::from_secs(5)).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    server.close_read().await;
+
+    let error = wait_task
+        .await
+        .unwrap()
+        .expect_err("❌ ПАНИКА: close_read принят за завершение записи пира");
+    assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe, "❌ ПАНИКА: Ожидался BrokenPipe: {}", error);
+
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,126 @@
+//! Тест диагностических порядковых номеров сообщений основного потока
+
+use std::io;
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::behaviour::XStreamNetworkBehaviour;
+use crate::consts::{XSTREAM_FEATURE_INTEGRITY, XSTREAM_FEATURE_SEQUENCE};
+use crate::sequence::{encode_sequence_prefix, XStreamSequence, MAX_SEQUENCED_MESSAGE_SIZE};
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Сообщение в формате sequence mode, записываемое в обход нумерации
+fn frame(sequence: u64, data: &[u8]) -> Vec<u8> {
+    let mut frame = encode_sequence_prefix(sequence, data.len() as u32).to_vec();
+    frame.extend_from_slice(data);
+    frame
+}
+
+#[test]
+fn test_sequence_check_accepts_only_next_number() {
+    let sequence = XStreamSequence::new();
+    sequence.check(0).expect("❌ Первый номер должен быть 0");
+    sequence.check(1).expect("❌ Следующий номер отклонен");
+
+    let duplicate = sequence.check(1).unwrap_err();
+    assert_eq!(duplicate.kind(), io::ErrorKind::InvalidData);
+    let gap = sequence.check(5).unwrap_err();
+    assert_eq!(gap.kind(), io::ErrorKind::InvalidData);
+    sequence.check(2).expect("❌ Ошибка не должна сдвигать ожидаемый номер");
+}
+
+#[test]
+fn test_sequence_mode_is_negotiated_by_feature_flag() {
+    let behaviour = XStreamNetworkBehaviour::new();
+    assert!(!behaviour.has_sequence_check(), "❌ Режим должен быть выключен по умолчанию");
+    let behaviour = behaviour.with_sequence_check();
+    assert!(behaviour.has_sequence_check());
+    assert_eq!(behaviour.header_features(), XSTREAM_FEATURE_SEQUENCE);
+
+    // Режим совмещается с проверкой целостности
+    let behaviour = behaviour.with_integrity_check();
+    assert!(behaviour.has_sequence_check() && behaviour.has_integrity_check());
+    assert_eq!(
+        behaviour.header_features(),
+        XSTREAM_FEATURE_SEQUENCE | XSTREAM_FEATURE_INTEGRITY
+    );
+}
+
+/// Сообщения write_all читаются по порядку без ошибок
+#[tokio::test]
+async fn test_ordered_messages_verify() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone().with_sequence_check();
+    let server = test_pair.server_stream.clone().with_sequence_check();
+
+    let messages: Vec<Vec<u8>> = vec![b"first".to_vec(), Vec::new(), vec![0x33; 4096]];
+    for message in &messages {
+        client.write_all(message.clone()).await.expect("❌ Запись не удалась");
+    }
+    client.flush().await.unwrap();
+
+    for expected in &messages {
+        let received = timeout(Duration::from_secs(5), server.read_sequenced())
+            .await
+            .expect("❌ Таймаут чтения сообщения")
+            .expect("❌ Упорядоченные сообщения не прошли проверку");
+        assert_eq!(&received, expected, "❌ Сообщение искажено");
+    }
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Переставленные и пропущенные сообщения обнаруживаются как InvalidData
+#[tokio::test]
+async fn test_reordered_messages_detected() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_sequence_check();
+
+    // Сообщения 0 и 1 приходят в обратном порядке
+    let reordered = [frame(1, b"second"), frame(0, b"first")];
+    let slices: Vec<&[u8]> = reordered.iter().map(|f| f.as_slice()).collect();
+    client.write_all_vectored(&slices).await.expect("❌ Запись не удалась");
+    client.flush().await.unwrap();
+
+    let error = timeout(Duration::from_secs(5), server.read_sequenced())
+        .await
+        .expect("❌ Таймаут чтения сообщения")
+        .expect_err("❌ Переставленное сообщение прошло проверку");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData, "❌ Неверный тип ошибки: {:?}", error);
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Длина сообщения больше предела отклоняется до выделения памяти
+#[tokio::test]
+async fn test_oversized_length_prefix_rejected() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone().with_sequence_check();
+
+    let prefix = encode_sequence_prefix(0, u32::MAX);
+    client.write_all(prefix.to_vec()).await.expect("❌ Запись не удалась");
+    client.flush().await.unwrap();
+
+    let error = timeout(Duration::from_secs(5), server.read_sequenced())
+        .await
+        .expect("❌ Таймаут чтения сообщения")
+        .expect_err("❌ Слишком длинное сообщение принято");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData, "❌ Неверный тип ошибки: {:?}", error);
+
+    let oversized = vec![0u8; MAX_SEQUENCED_MESSAGE_SIZE + 1];
+    let error = XStreamSequence::new().tag(&oversized).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Чтение без включенного режима отклоняется
+#[tokio::test]
+async fn test_read_sequenced_requires_sequence_mode() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let error = test_pair.server_stream.read_sequenced().await.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    shutdown_manager.shutdown().await;
+}
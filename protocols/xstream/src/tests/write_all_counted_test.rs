@@ -0,0 +1,143 @@
+//! Tests for write_all_counted partial progress reporting
+//! Проверяет, что при обрыве соединения виден объем реально записанных данных
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::encryption::MAX_FRAME_PLAINTEXT;
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+const SHARED_KEY: [u8; 32] = [7u8; 32];
+
+/// Full write without errors reports the whole buffer
+/// Запись без ошибок возвращает полный размер и отсутствие ошибки
+#[tokio::test]
+async fn test_write_all_counted_complete() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let data = b"counted write".to_vec();
+    let (written, error) = client.write_all_counted(data.clone()).await;
+    assert!(error.is_none(), "❌ ПАНИКА: Неожиданная ошибка записи: {:?}", error);
+    assert_eq!(written, data.len(), "❌ ПАНИКА: Записано не все");
+    assert_eq!(client.bytes_written(), data.len() as u64, "❌ ПАНИКА: Счетчик не совпадает");
+    client.flush().await.unwrap();
+
+    let received = timeout(Duration::from_secs(5), server.read_exact(data.len()))
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(received, data, "❌ ПАНИКА: Данные искажены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Connection killed mid-write reports how much was written before the loss
+/// Обрыв соединения во время записи возвращает частичный счетчик
+#[tokio::test]
+async fn test_write_all_counted_partial_on_connection_loss() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+
+    // Сервер не читает, поэтому запись упрется в окно управления потоком
+    let total = 16 * 1024 * 1024;
+    let writer = client.clone();
+    let write_task = tokio::spawn(async move { writer.write_all_counted(vec![0xAB; total]).await });
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!write_task.is_finished(), "❌ ПАНИКА: Запись не должна завершиться без чтения");
+
+    // Обрываем соединение, останавливая сервер
+    let _ = shutdown_manager.server_shutdown.send(()).await;
+
+    let (written, error) = timeout(Duration::from_secs(10), write_task)
+        .await
+        .expect("❌ ПАНИКА: Запись не завершилась после обрыва соединения")
+        .unwrap();
+
+    assert!(error.is_some(), "❌ ПАНИКА: Ожидалась ошибка записи");
+    assert!(written > 0, "❌ ПАНИКА: Часть данных должна быть записана до обрыва");
+    assert!(written < total, "❌ ПАНИКА: Все данные не могли быть записаны");
+    assert_eq!(
+        client.bytes_written(),
+        written as u64,
+        "❌ ПАНИКА: Счетчик байтов должен совпадать с частичным результатом"
+    );
+
+    shutdown_manager.shutdown().await;
+}
+
+/// With encryption the count is in plaintext bytes, never the larger ciphertext size
+/// Считаются только байты приложения из полностью записанных кадров
+#[tokio::test]
+async fn test_write_all_counted_encrypted_counts_plaintext() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair
+        .client_stream
+        .clone()
+        .with_encryption(SHARED_KEY, test_pair.client_peer_id);
+
+    let data = vec![0x5A; 3 * MAX_FRAME_PLAINTEXT + 17];
+    let (written, error) = client.write_all_counted(data.clone()).await;
+    assert!(error.is_none(), "❌ ПАНИКА: Неожиданная ошибка записи: {:?}", error);
+    assert_eq!(written, data.len(), "❌ ПАНИКА: Счетчик должен быть в байтах открытого текста");
+    assert_eq!(client.bytes_written(), data.len() as u64, "❌ ПАНИКА: Счетчик не совпадает");
+
+    // Сервер не читает, следующая запись упрется в окно управления потоком
+    let total = 16 * 1024 * 1024;
+    let writer = client.clone();
+    let write_task = tokio::spawn(async move { writer.write_all_counted(vec![0xAB; total]).await });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let _ = shutdown_manager.server_shutdown.send(()).await;
+
+    let (written, error) = timeout(Duration::from_secs(10), write_task)
+        .await
+        .expect("❌ ПАНИКА: Запись не завершилась после обрыва соединения")
+        .unwrap();
+    assert!(error.is_some(), "❌ ПАНИКА: Ожидалась ошибка записи");
+    assert!(written < total, "❌ ПАНИКА: Все данные не могли быть записаны");
+    assert_eq!(
+        written % MAX_FRAME_PLAINTEXT,
+        0,
+        "❌ ПАНИКА: Частично записанный кадр не должен учитываться: {}",
+        written
+    );
+    assert_eq!(
+        client.bytes_written(),
+        (data.len() + written) as u64,
+        "❌ ПАНИКА: Счетчик байтов должен совпадать с частичным результатом"
+    );
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Sequence mode tags a counted write like write_all, the prefix is not counted
+/// Префикс номера не входит в счетчик, а сообщение проходит проверку порядка
+#[tokio::test]
+async fn test_write_all_counted_tags_sequence() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone().with_sequence_check();
+    let server = test_pair.server_stream.clone().with_sequence_check();
+
+    let first = b"first counted".to_vec();
+    let second = b"second counted".to_vec();
+    let (written, error) = client.write_all_counted(first.clone()).await;
+    assert!(error.is_none(), "❌ ПАНИКА: Неожиданная ошибка записи: {:?}", error);
+    assert_eq!(written, first.len(), "❌ ПАНИКА: Префикс не должен учитываться");
+    client
+        .write_all_vectored(&[&second[..6], &second[6..]])
+        .await
+        .expect("❌ ПАНИКА: Запись не удалась");
+    client.flush().await.unwrap();
+
+    for expected in [first, second] {
+        let received = timeout(Duration::from_secs(5), server.read_sequenced())
+            .await
+            .expect("❌ ПАНИКА: Таймаут чтения сообщения")
+            .expect("❌ ПАНИКА: Сообщение не прошло проверку порядка");
+        assert_eq!(received, expected, "❌ ПАНИКА: Сообщение искажено");
+    }
+
+    shutdown_manager.shutdown().await;
+}
@@ -0,0 +1,52 @@
+//! Tests for write_all_flushed ordering
+//! Проверяет, что данные из write_all_flushed приходят до EOF следующего write_eof
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Data written with write_all_flushed is read in full before the EOF
+/// Сервер читает все данные, затем EOF, без потерь и перестановок
+#[tokio::test]
+async fn test_write_all_flushed_before_write_eof() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let first = b"first chunk;".to_vec();
+    let second = vec![0x5A; 32 * 1024];
+    client.write_all_flushed(first.clone()).await.expect("❌ ПАНИКА: Первая запись не удалась");
+    client.write_all_flushed(second.clone()).await.expect("❌ ПАНИКА: Вторая запись не удалась");
+    assert_eq!(
+        client.bytes_written(),
+        (first.len() + second.len()) as u64,
+        "❌ ПАНИКА: Счетчик не совпадает"
+    );
+    client.write_eof().await.expect("❌ ПАНИКА: write_eof не удался");
+
+    let received = timeout(Duration::from_secs(5), server.read_to_end())
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    let mut expected = first;
+    expected.extend_from_slice(&second);
+    assert_eq!(received.len(), expected.len(), "❌ ПАНИКА: Получено не все до EOF");
+    assert_eq!(received, expected, "❌ ПАНИКА: Данные искажены или переставлены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Writing after write_eof fails instead of being silently dropped
+/// Запись после EOF возвращает ошибку
+#[tokio::test]
+async fn test_write_all_flushed_after_write_eof_fails() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+
+    client.write_eof().await.expect("❌ ПАНИКА: write_eof не удался");
+    let result = client.write_all_flushed(b"late".to_vec()).await;
+    assert!(result.is_err(), "❌ ПАНИКА: Запись после EOF должна завершиться ошибкой");
+
+    shutdown_manager.shutdown().await;
+}
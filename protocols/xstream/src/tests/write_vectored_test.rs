@@ -0,0 +1,56 @@
+//! Tests for write_all_vectored scatter-gather writes
+//! Проверяет, что несколько буферов приходят на другую сторону одним сообщением
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::tests::xstream_tests::create_xstream_test_pair;
+
+/// Three segments arrive as one concatenated payload
+/// Заголовок, тело и хвост читаются сервером как единые данные
+#[tokio::test]
+async fn test_write_all_vectored_three_segments() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+    let server = test_pair.server_stream.clone();
+
+    let header = b"HDR:".to_vec();
+    let body: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+    let trailer = b":END".to_vec();
+    let expected = [header.as_slice(), body.as_slice(), trailer.as_slice()].concat();
+
+    // Сервер читает параллельно, чтобы большое тело не уперлось в окно управления потоком
+    let expected_len = expected.len();
+    let reader = tokio::spawn(async move { server.read_exact(expected_len).await });
+
+    client
+        .write_all_vectored(&[&header, &body, &trailer])
+        .await
+        .expect("❌ ПАНИКА: Не удалось записать сегменты");
+    client.flush().await.unwrap();
+    assert_eq!(client.bytes_written(), expected.len() as u64, "❌ ПАНИКА: Счетчик не совпадает");
+
+    let received = timeout(Duration::from_secs(5), reader)
+        .await
+        .expect("❌ ПАНИКА: Таймаут чтения на сервере")
+        .unwrap()
+        .expect("❌ ПАНИКА: Сервер не смог прочитать данные");
+    assert_eq!(received, expected, "❌ ПАНИКА: Данные искажены");
+
+    shutdown_manager.shutdown().await;
+}
+
+/// Vectored write respects the closed write half
+/// После write_eof запись сегментов отклоняется
+#[tokio::test]
+async fn test_write_all_vectored_after_write_eof() {
+    let (test_pair, shutdown_manager) = create_xstream_test_pair().await;
+    let client = test_pair.client_stream.clone();
+
+    client.write_eof().await.expect("❌ ПАНИКА: Не удалось закрыть запись");
+    let result = client.write_all_vectored(&[b"a", b"b"]).await;
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::BrokenPipe);
+    assert_eq!(client.bytes_written(), 0);
+
+    shutdown_manager.shutdown().await;
+}
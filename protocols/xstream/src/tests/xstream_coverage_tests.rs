@@ -501,7 +501,7 @@ async fn test_concurrent_operations() {
 #[tokio::test]
 async fn test_close_notification() {
     // Create a custom closure channel to verify notification
-    let (notif_tx, mut notif_rx) = mpsc::unbounded_channel::<(PeerId, XStreamID)>();
+    let (notif_tx, mut notif_rx) = mpsc::unbounded_channel::<(PeerId, XStreamID, XStreamDirection)>();
 
     let (mut test_pair, shutdown_manager) = with_timeout(create_xstream_test_pair()).await;
     let stream_id = test_pair.client_stream.id;
@@ -514,7 +514,7 @@ async fn test_close_notification() {
 
     // Manually notify as if this came from the stream's closure notifier
     notif_tx
-        .send((peer_id, stream_id))
+        .send((peer_id, stream_id, XStreamDirection::Outbound))
         .expect("Failed to send notification");
 
     // We should receive the notification with timeout
@@ -524,6 +524,7 @@ async fn test_close_notification() {
 
     assert_eq!(notification.0, peer_id);
     assert_eq!(notification.1, stream_id);
+    assert_eq!(notification.2, XStreamDirection::Outbound);
 
     with_timeout(shutdown_manager.shutdown()).await;
 }
@@ -67,9 +67,10 @@ async fn test_state_transitions() {
     let notif = timeout(Duration::from_millis(100), rx.recv()).await;
     assert!(notif.is_ok(), "Должны были получить уведомление");
 
-    if let Ok(Some((notif_peer_id, notif_stream_id))) = notif {
+    if let Ok(Some((notif_peer_id, notif_stream_id, notif_direction))) = notif {
         assert_eq!(notif_peer_id, peer_id);
         assert_eq!(notif_stream_id, stream_id);
+        assert_eq!(notif_direction, XStreamDirection::Outbound);
     }
 
     // Попытка изменить состояние после FullyClosed должна оставить его FullyClosed
@@ -8,7 +8,7 @@ use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::{sleep, timeout};
 
-use crate::{behaviour::XStreamNetworkBehaviour, events::XStreamEvent, xstream::XStream};
+use crate::{behaviour::XStreamNetworkBehaviour, events::XStreamEvent, xstream::XStream, xstream_error::StreamOpenError};
 
 // Structure to hold paired streams for testing
 pub struct XStreamTestPair {
@@ -60,7 +60,7 @@ pub async fn create_xstream_test_pair() -> (XStreamTestPair, ShutdownManager) {
 
     // Channel for requesting stream opening
     let (stream_req_tx, mut stream_req_rx) =
-        mpsc::channel::<(PeerId, oneshot::Sender<Result<XStream, String>>)>(1);
+        mpsc::channel::<(PeerId, oneshot::Sender<Result<XStream, StreamOpenError>>)>(1);
 
     // Create shutdown channels for both server and client tasks
     let (server_shutdown_tx, mut server_shutdown_rx) = mpsc::channel::<()>(1);
@@ -167,7 +167,7 @@ pub async fn create_xstream_test_pair() -> (XStreamTestPair, ShutdownManager) {
                         },
                         None => {
                             // Create a new channel that will never be consumed, just to keep select! happy
-                            let (_, new_rx) = mpsc::channel::<(PeerId, oneshot::Sender<Result<XStream, String>>)>(1);
+                            let (_, new_rx) = mpsc::channel::<(PeerId, oneshot::Sender<Result<XStream, StreamOpenError>>)>(1);
                             stream_req_rx = new_rx;
                             println!("Client: Stream request channel closed, continuing to run");
                         }
@@ -1,3 +1,8 @@
+use std::io;
+
+use super::types::XStreamDirection;
+use super::xstream::XStream;
+
 pub struct IdIterator {
     current: u128,
 }
@@ -17,3 +22,37 @@ impl Iterator for IdIterator {
         Some(current)
     }
 }
+
+/// Echoes everything read from `stream` back to the peer until EOF
+///
+/// После EOF отправляет свой EOF и возвращает число отраженных байт.
+/// Ошибка чтения или записи входящего потока передается клиенту через
+/// поток ошибок и возвращается вызывающему.
+pub async fn echo_loop(stream: XStream) -> io::Result<usize> {
+    let mut total = 0;
+    loop {
+        let data = match stream.read().await {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(report_echo_error(&stream, e.to_io_error()).await),
+        };
+        if data.is_empty() {
+            break;
+        }
+        let len = data.len();
+        if let Err(e) = stream.write_all(data).await {
+            return Err(report_echo_error(&stream, e).await);
+        }
+        total += len;
+    }
+    stream.write_eof().await?;
+    Ok(total)
+}
+
+/// Sends the echo failure to the client, only inbound streams have an error stream to write
+async fn report_echo_error(stream: &XStream, error: io::Error) -> io::Error {
+    if stream.direction == XStreamDirection::Inbound {
+        let _ = stream.error_write(error.to_string().into_bytes()).await;
+    }
+    error
+}
@@ -6,10 +6,21 @@ use futures::AsyncReadExt;
 use futures::AsyncWriteExt;
 use libp2p::{PeerId, Stream};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{Mutex, mpsc};
 use tokio::select;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+use super::counters::XStreamByteCounters;
+use super::events::StreamCloseReason;
+use super::encryption::{ENCRYPTED_READ_AHEAD_CAPACITY, SHARED_KEY_SIZE, XStreamCipher};
+use super::integrity::XStreamIntegrity;
+use super::sequence::{
+    decode_sequence_prefix, XStreamSequence, MAX_SEQUENCED_MESSAGE_SIZE, SEQUENCE_PREFIX_SIZE,
+};
+use super::backpressure::{BackpressureTracker, BACKPRESSURE_STALL_GRACE};
+use super::read_ahead::{ReadAheadBuffer, ReadAheadResult, ReadAheadStats};
 use super::types::{XStreamDirection, XStreamID, XStreamState};
 use super::xstream_state::XStreamStateManager;
 use super::error_handling::{ErrorDataStore, ErrorReaderTask};
@@ -38,6 +49,72 @@ pub struct XStream {
     // Error handling components
     error_data_store: ErrorDataStore,
     error_reader_task: Arc<Mutex<Option<ErrorReaderTask>>>,
+
+    // Optional application-layer encryption of the main stream
+    cipher: Option<XStreamCipher>,
+
+    // Optional CRC32 integrity check of the main stream
+    integrity: Option<XStreamIntegrity>,
+    // Optional diagnostic sequence numbers on messages of the main stream
+    sequence: Option<XStreamSequence>,
+
+    // Byte counters of the main stream, shared by all clones
+    counters: XStreamByteCounters,
+
+    // Optional read-ahead buffer of the main stream
+    read_ahead: Option<ReadAheadBuffer>,
+
+    // Optional watermarks of bytes not yet accepted by the transport
+    backpressure: Option<BackpressureTracker>,
+
+    // Held by a message read across its prefix and body, shared by all clones
+    message_read: Arc<Mutex<()>>,
+
+    // Shared by all clones, runs the drop notifications once the last clone is gone
+    drop_guard: Arc<XStreamDropGuard>,
+}
+
+/// Notifications sent when the last clone of an XStream is dropped
+///
+/// A stream dropped without close() is reported closed, and its outbound slot
+/// is returned to the behaviour.
+#[derive(Debug)]
+struct XStreamDropGuard {
+    peer_id: PeerId,
+    stream_id: XStreamID,
+    state_manager: XStreamStateManager,
+    outbound_release: std::sync::OnceLock<mpsc::UnboundedSender<(PeerId, XStreamID)>>,
+}
+
+impl Drop for XStreamDropGuard {
+    fn drop(&mut self) {
+        // If stream is not fully closed, notify about drop
+        if !self.state_manager.is_closed() {
+            self.state_manager.notify_state_change("XStream dropped");
+        }
+        if let Some(release) = self.outbound_release.get() {
+            let _ = release.send((self.peer_id, self.stream_id));
+        }
+    }
+}
+
+/// Tells whether a stream is finished without keeping it alive
+#[derive(Debug, Clone)]
+pub(crate) struct XStreamLiveness(std::sync::Weak<XStreamDropGuard>);
+
+impl XStreamLiveness {
+    /// True once the stream is closed or its last clone is dropped
+    pub(crate) fn is_finished(&self) -> bool {
+        self.0.upgrade().is_none_or(|guard| guard.state_manager.is_closed())
+    }
+}
+
+/// Read operation served by the read-ahead buffer
+#[derive(Debug, Clone, Copy)]
+enum ReadAheadOp {
+    Read,
+    Exact(usize),
+    ToEnd,
 }
 
 impl XStream {
@@ -50,7 +127,7 @@ impl XStream {
         stream_error_read: futures::io::ReadHalf<Stream>,
         stream_error_write: futures::io::WriteHalf<Stream>,
         direction: XStreamDirection,
-        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>,
     ) -> Self {
         info!(
             "Creating new XStream with id: {:?} for peer: {}, direction: {:?}",
@@ -80,6 +157,13 @@ impl XStream {
             Arc::new(Mutex::new(None))
         };
 
+        let drop_guard = Arc::new(XStreamDropGuard {
+            peer_id,
+            stream_id: id,
+            state_manager: state_manager.clone(),
+            outbound_release: std::sync::OnceLock::new(),
+        });
+
         Self {
             // Обернуть ReadHalf в Some для безопасного закрытия через присвоение None
             stream_main_read: Arc::new(Mutex::new(Some(stream_main_read))),
@@ -93,6 +177,237 @@ impl XStream {
             state_manager,
             error_data_store,
             error_reader_task,
+            cipher: None,
+            integrity: None,
+            sequence: None,
+            counters: XStreamByteCounters::new(),
+            read_ahead: None,
+            backpressure: None,
+            message_read: Arc::new(Mutex::new(())),
+            drop_guard,
+        }
+    }
+
+    /// Serializes message reads of all clones, so a concurrent reader never takes
+    /// the body of a message whose prefix another reader consumed
+    pub(crate) async fn lock_message_read(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.message_read.lock().await
+    }
+
+    /// Ties the stream to an outbound slot released through `release` once all clones are dropped
+    pub(crate) fn with_outbound_slot(self, release: mpsc::UnboundedSender<(PeerId, XStreamID)>) -> Self {
+        let _ = self.drop_guard.outbound_release.set(release);
+        self
+    }
+
+    /// Enables authenticated encryption of the main stream with a shared key
+    ///
+    /// Both peers must enable encryption with the same key before exchanging data.
+    /// Each direction is sealed with ChaCha20-Poly1305 under a key derived from the
+    /// shared key, a random per-stream salt and both peer ids, so tampered data fails
+    /// the read with `InvalidData`. Decryption runs on the read-ahead buffer, which is
+    /// enabled with a default capacity unless `with_read_ahead` was called first.
+    /// Reads and writes stay transparent; the error stream is not encrypted.
+    /// Clones made before this call do not encrypt.
+    pub fn with_encryption(mut self, shared_key: [u8; SHARED_KEY_SIZE], local_peer_id: PeerId) -> Self {
+        let cipher = XStreamCipher::new(shared_key, local_peer_id, self.peer_id, self.direction);
+        let read_ahead = self
+            .read_ahead
+            .take()
+            .unwrap_or_else(|| ReadAheadBuffer::start(self.stream_main_read.clone(), ENCRYPTED_READ_AHEAD_CAPACITY));
+        read_ahead.set_cipher(cipher.clone());
+        self.read_ahead = Some(read_ahead);
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Returns true if the main stream is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Enables the CRC32 integrity check of the main stream
+    ///
+    /// `write_eof` appends a digest of everything written and `read_to_end`
+    /// verifies the peer's digest, failing with `InvalidData` on mismatch.
+    /// Both peers must enable it; the behaviour negotiates this via the header
+    /// version. The data must be finished with `read_to_end`, other reads
+    /// return the trailer as data. Clones made before this call are not checked.
+    pub fn with_integrity_check(mut self) -> Self {
+        self.integrity = Some(XStreamIntegrity::new());
+        self
+    }
+
+    /// Returns true if the main stream is integrity checked
+    pub fn has_integrity_check(&self) -> bool {
+        self.integrity.is_some()
+    }
+
+    /// Enables diagnostic sequence numbers on messages of the main stream
+    ///
+    /// Every `write_all` and `write_all_flushed` call is sent as one message
+    /// prefixed with a u64 sequence number and u32 length. `read_sequenced`
+    /// returns one message and fails with `InvalidData` if a number is out of
+    /// order, duplicated or missing. Other writes and reads see the raw prefix,
+    /// so with this mode on only the methods above should be used. Both peers
+    /// must enable it; the behaviour negotiates this via the header feature
+    /// flags. Can be combined with the integrity check.
+    pub fn with_sequence_check(mut self) -> Self {
+        self.sequence = Some(XStreamSequence::new());
+        self
+    }
+
+    /// Returns true if messages of the main stream carry sequence numbers
+    pub fn has_sequence_check(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Enables a read-ahead buffer of about `capacity` bytes on the main stream
+    ///
+    /// A background task reads ahead of the application and `read`, `read_exact`
+    /// and `read_to_end` are served from the buffer. Buffered data is always
+    /// returned before EOF or a read error. Must be called before the first read
+    /// and before `with_encryption`; clones made before this call read directly
+    /// from the stream.
+    pub fn with_read_ahead(mut self, capacity: usize) -> Self {
+        let read_ahead = ReadAheadBuffer::start(self.stream_main_read.clone(), capacity);
+        if let Some(cipher) = &self.cipher {
+            read_ahead.set_cipher(cipher.clone());
+        }
+        self.read_ahead = Some(read_ahead);
+        self
+    }
+
+    /// Reports write buffering crossing the tracker's water marks
+    pub(crate) fn with_backpressure(mut self, tracker: BackpressureTracker) -> Self {
+        self.backpressure = Some(tracker);
+        self
+    }
+
+    /// Bytes handed to writes and not yet accepted by the transport,
+    /// None unless the behaviour tracks backpressure
+    pub fn buffered_bytes(&self) -> Option<usize> {
+        self.backpressure.as_ref().map(|tracker| tracker.buffered_bytes())
+    }
+
+    /// Closes the stream after `timeout` without a read or write
+    ///
+    /// Every read or write resets the timer. The watchdog reports the stream
+    /// on `idle_notifier` right before closing it.
+    pub(crate) fn start_idle_watchdog(
+        &self,
+        timeout: Duration,
+        idle_notifier: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+    ) {
+        let mut stream = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if stream.is_closed() {
+                    return;
+                }
+                let idle = stream.counters.idle_for();
+                if idle >= timeout {
+                    break;
+                }
+                tokio::time::sleep(timeout - idle).await;
+            }
+
+            debug!("Stream {:?} idle for {:?}, closing", stream.id, timeout);
+            let _ = idle_notifier.send((stream.peer_id, stream.id));
+            if let Err(e) = stream.close().await {
+                warn!("Failed to close idle stream {:?}: {}", stream.id, e);
+            }
+        });
+    }
+
+    /// Returns read-ahead statistics if the buffer is enabled
+    pub fn read_ahead_stats(&self) -> Option<ReadAheadStats> {
+        self.read_ahead.as_ref().map(|read_ahead| read_ahead.stats())
+    }
+
+    /// Counts received bytes, including partial data carried by a read error
+    fn count_read_result(&self, result: XStreamReadResult<Vec<u8>>) -> XStreamReadResult<Vec<u8>> {
+        match &result {
+            Ok(data) => self.counters.add_read(data.len()),
+            Err(error_on_read) => self.counters.add_read(error_on_read.partial_data.len()),
+        }
+        result
+    }
+
+    /// Feeds data of a partial read into the integrity digest
+    fn record_integrity_read(&self, result: XStreamReadResult<Vec<u8>>) -> XStreamReadResult<Vec<u8>> {
+        if let Some(integrity) = &self.integrity {
+            match &result {
+                Ok(data) => integrity.record_read(data),
+                Err(error_on_read) => integrity.record_read(&error_on_read.partial_data),
+            }
+        }
+        result
+    }
+
+    /// Checks the integrity trailer of data read up to EOF
+    fn verify_integrity(&self, result: XStreamReadResult<Vec<u8>>) -> XStreamReadResult<Vec<u8>> {
+        match (&self.integrity, result) {
+            // Поврежденные данные не отдаем даже как частичные
+            (Some(integrity), Ok(data)) => integrity.verify(data).map_err(ErrorOnRead::io_error_only),
+            (_, result) => result,
+        }
+    }
+
+    /// Serves a read from the read-ahead buffer, racing server errors on outbound streams
+    async fn read_via_read_ahead(&self, read_ahead: &ReadAheadBuffer, op: ReadAheadOp) -> XStreamReadResult<Vec<u8>> {
+        if self.direction != XStreamDirection::Outbound {
+            let result = Self::execute_read_ahead_op(read_ahead, op).await;
+            return self.finish_read_ahead_op(op, result);
+        }
+
+        let error_result = select! {
+            result = Self::execute_read_ahead_op(read_ahead, op) => {
+                return self.finish_read_ahead_op(op, result);
+            },
+            error_result = self.error_data_store.wait_for_error() => error_result,
+        };
+
+        match error_result {
+            Ok(error_data) => {
+                // Server sent an error - отдаем уже буферизованные данные как частичные
+                let partial_data = read_ahead.take_buffered().await;
+                Err(ErrorOnRead::from_xstream_error(partial_data, XStreamError::new(error_data)))
+            }
+            Err(_) => {
+                // Error stream closed, continue reading from the buffer
+                debug!("Error stream closed, reading from read-ahead buffer");
+                let result = Self::execute_read_ahead_op(read_ahead, op).await;
+                self.finish_read_ahead_op(op, result)
+            }
+        }
+    }
+
+    async fn execute_read_ahead_op(read_ahead: &ReadAheadBuffer, op: ReadAheadOp) -> ReadAheadResult {
+        match op {
+            ReadAheadOp::Read => read_ahead.read().await,
+            ReadAheadOp::Exact(size) => read_ahead.read_exact(size).await,
+            ReadAheadOp::ToEnd => read_ahead.read_to_end().await,
+        }
+    }
+
+    /// Updates stream state from a buffered read result
+    fn finish_read_ahead_op(&self, op: ReadAheadOp, result: ReadAheadResult) -> XStreamReadResult<Vec<u8>> {
+        match result {
+            Ok(data) => {
+                if matches!(op, ReadAheadOp::ToEnd) {
+                    self.state_manager.signal_read_eof();
+                }
+                Ok(data)
+            }
+            Err((partial_data, e)) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    self.state_manager.signal_read_eof();
+                } else {
+                    self.state_manager.handle_connection_error(&e, "read-ahead error");
+                }
+                Err(ErrorOnRead::from_io_error(partial_data, e))
+            }
         }
     }
 
@@ -218,6 +533,11 @@ impl XStream {
         self.state_manager.is_closed()
     }
 
+    /// Why the stream was closed, None while it is still open
+    pub fn close_reason(&self) -> Option<StreamCloseReason> {
+        self.state_manager.close_reason()
+    }
+
     /// Check if the stream is closed locally
     pub fn is_local_closed(&self) -> bool {
         self.state_manager.is_local_closed()
@@ -238,6 +558,95 @@ impl XStream {
         self.state_manager.is_read_remote_closed()
     }
 
+    /// Total payload bytes read from the main stream
+    pub fn bytes_read(&self) -> u64 {
+        self.counters.bytes_read()
+    }
+
+    /// Total payload bytes written to the main stream
+    pub fn bytes_written(&self) -> u64 {
+        self.counters.bytes_written()
+    }
+
+    /// Shared handle to the byte counters that outlives the stream
+    pub fn counters(&self) -> XStreamByteCounters {
+        self.counters.clone()
+    }
+
+    /// Handle telling the behaviour when the stream is finished
+    pub(crate) fn liveness(&self) -> XStreamLiveness {
+        XStreamLiveness(Arc::downgrade(&self.drop_guard))
+    }
+
+    /// Check if the read half is finished (EOF received or read half closed locally)
+    pub fn is_read_eof(&self) -> bool {
+        self.state_manager.is_read_eof()
+    }
+
+    /// Check if the local write half is closed
+    pub fn is_write_closed(&self) -> bool {
+        self.state_manager.is_write_closed()
+    }
+
+    /// Can a read be attempted right now: no EOF seen on the read half, the
+    /// stream is not closed and no error from the remote is pending
+    pub fn is_readable(&self) -> bool {
+        let error_pending =
+            self.direction == XStreamDirection::Outbound && self.error_data_store.has_error_now();
+        self.check_readable_basic().is_ok() && !self.state_manager.is_read_eof() && !error_pending
+    }
+
+    /// Can a write be attempted right now: the write half is open and the
+    /// stream is not closed locally or remotely
+    pub fn is_writable(&self) -> bool {
+        self.check_writable().is_ok()
+    }
+
+    /// Done sending, still receiving: closes only the write half
+    ///
+    /// Same as write_eof; returns once the EOF is flushed to the peer.
+    pub async fn finish_writing(&self) -> Result<(), std::io::Error> {
+        self.write_eof().await
+    }
+
+    /// Resolves once the peer has finished sending, without consuming data
+    ///
+    /// With read-ahead the remaining data stays buffered for the next reads; if
+    /// the peer sends more than the buffer holds, the EOF is seen only once reads
+    /// make room. Without it the EOF has to be observed by a concurrent reader.
+    /// Fails with TimedOut if the peer is still sending after `timeout`, and with
+    /// BrokenPipe if the read half was closed locally.
+    pub async fn await_remote_finish(&self, timeout: Duration) -> Result<(), std::io::Error> {
+        let finished = async {
+            match &self.read_ahead {
+                Some(read_ahead) => read_ahead.wait_end().await,
+                None => {
+                    self.state_manager.wait_read_eof().await;
+                    Ok(())
+                }
+            }
+        };
+        tokio::time::timeout(timeout, finished).await.map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("Peer did not finish sending on stream {:?} within {:?}", self.id, timeout),
+            )
+        })?
+    }
+
+    /// Resolves once the read half is finished: EOF received from the remote
+    /// or the read half closed locally. The write half is not affected and
+    /// can keep sending data.
+    pub async fn on_read_eof(&self) {
+        self.state_manager.wait_read_eof().await
+    }
+
+    /// Resolves once the local write half is closed (write_eof, close_write or close).
+    /// The read half is not affected and can keep receiving data.
+    pub async fn on_write_closed(&self) {
+        self.state_manager.wait_write_closed().await
+    }
+
     /// Basic readable check for internal operations (returns std::io::Error)
     fn check_readable_basic(&self) -> Result<(), std::io::Error> {
         if self.state_manager.is_read_remote_closed() {
@@ -303,6 +712,45 @@ impl XStream {
 
     // ===== ENHANCED STREAM OPERATIONS WITH ERROR HANDLING =====
 
+    /// Reads one message written by the peer's `write_all` in sequence mode
+    ///
+    /// Fails with `InvalidData` if the message's sequence number is not the next
+    /// expected one (reordered, duplicated or missing messages) or its length
+    /// exceeds `MAX_SEQUENCED_MESSAGE_SIZE`, and with
+    /// `InvalidInput` if the sequence check is not enabled on this stream.
+    pub async fn read_sequenced(&self) -> XStreamReadResult<Vec<u8>> {
+        let Some(sequence) = &self.sequence else {
+            return Err(ErrorOnRead::io_error_only(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Sequence check is not enabled on stream {:?}", self.id),
+            )));
+        };
+
+        let _message_read = self.lock_message_read().await;
+        // Частично прочитанный префикс не является данными сообщения
+        let prefix = self
+            .read_exact(SEQUENCE_PREFIX_SIZE)
+            .await
+            .map_err(|error| ErrorOnRead::error_only(error.into_error()))?;
+        let (number, len) = decode_sequence_prefix(&prefix);
+        sequence.check(number).map_err(ErrorOnRead::io_error_only)?;
+        // Длина пришла от пира, не выделяем под нее память без ограничения
+        if len as usize > MAX_SEQUENCED_MESSAGE_SIZE {
+            return Err(ErrorOnRead::io_error_only(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Sequenced message length {} exceeds {} bytes on stream {:?}",
+                    len, MAX_SEQUENCED_MESSAGE_SIZE, self.id
+                ),
+            )));
+        }
+
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        self.read_exact(len as usize).await
+    }
+
     /// Reads exact number of bytes from the main stream with error awareness
     pub async fn read_exact(&self, size: usize) -> XStreamReadResult<Vec<u8>> {
         // Check stream state first
@@ -314,12 +762,16 @@ impl XStream {
         }
 
         // For outbound streams, read with error awareness
-        if self.direction == XStreamDirection::Outbound {
+        let result = if let Some(read_ahead) = &self.read_ahead {
+            self.read_via_read_ahead(read_ahead, ReadAheadOp::Exact(size)).await
+        } else if self.direction == XStreamDirection::Outbound {
             self.read_exact_with_error_awareness(size).await
         } else {
             // For inbound streams, simple read
             self.read_exact_simple(size).await
-        }
+        };
+
+        self.record_integrity_read(self.count_read_result(result))
     }
 
     /// Simple read_exact for inbound streams
@@ -359,6 +811,7 @@ impl XStream {
                     match read_result {
                         Ok(0) => {
                             // EOF reached before reading all data
+                            self.state_manager.signal_read_eof();
                             let partial_data = buf[0..bytes_read].to_vec();
                             let eof_error = std::io::Error::new(
                                 std::io::ErrorKind::UnexpectedEof,
@@ -412,12 +865,16 @@ impl XStream {
         }
 
         // For outbound streams, read with error awareness
-        if self.direction == XStreamDirection::Outbound {
+        let result = if let Some(read_ahead) = &self.read_ahead {
+            self.read_via_read_ahead(read_ahead, ReadAheadOp::ToEnd).await
+        } else if self.direction == XStreamDirection::Outbound {
             self.read_to_end_with_error_awareness().await
         } else {
             // For inbound streams, simple read
             self.read_to_end_simple().await
-        }
+        };
+
+        self.verify_integrity(self.count_read_result(result))
     }
 
     /// Simple read_to_end for inbound streams
@@ -433,7 +890,11 @@ impl XStream {
                 Ok(buf)
             })
         }).await {
-            Ok(data) => Ok(data),
+            Ok(data) => {
+                // read_to_end завершается только на EOF
+                self.state_manager.signal_read_eof();
+                Ok(data)
+            }
             Err(e) => Err(ErrorOnRead::io_error_only(e)),
         }
     }
@@ -460,6 +921,7 @@ impl XStream {
                     match read_result {
                         Ok(0) => {
                             // EOF reached - normal completion
+                            self.state_manager.signal_read_eof();
                             debug!("Read to end completed, total bytes: {}", buf.len());
                             return Ok(buf);
                         },
@@ -503,12 +965,16 @@ impl XStream {
         }
 
         // For outbound streams, read with error awareness
-        if self.direction == XStreamDirection::Outbound {
+        let result = if let Some(read_ahead) = &self.read_ahead {
+            self.read_via_read_ahead(read_ahead, ReadAheadOp::Read).await
+        } else if self.direction == XStreamDirection::Outbound {
             self.read_with_error_awareness().await
         } else {
             // For inbound streams, simple read
             self.read_simple().await
-        }
+        };
+
+        self.record_integrity_read(self.count_read_result(result))
     }
 
     /// Simple read for inbound streams
@@ -553,6 +1019,7 @@ impl XStream {
                 match read_result {
                     Ok(0) => {
                         // EOF reached
+                        self.state_manager.signal_read_eof();
                         let eof_error = std::io::Error::new(
                             std::io::ErrorKind::UnexpectedEof,
                             "End of file"
@@ -588,6 +1055,156 @@ impl XStream {
         }
     }
 
+    /// Reads like `read` until `token` is cancelled
+    ///
+    /// On cancellation the pending read is dropped before any data is consumed
+    /// and `ReadError::Cancelled` is returned; the stream stays readable, so a
+    /// later read gets the data that arrives afterwards.
+    pub async fn read_cancellable(&self, token: CancellationToken) -> XStreamReadResult<Vec<u8>> {
+        select! {
+            biased;
+            _ = token.cancelled() => {
+                debug!("Read from stream {:?} cancelled", self.id);
+                Err(ErrorOnRead::cancelled())
+            }
+            result = self.read() => result,
+        }
+    }
+
+    /// Reads available data into `buf` without allocating, returns the number of bytes read
+    ///
+    /// Same semantics as `read`: EOF is an `UnexpectedEof` error and on outbound
+    /// streams an error sent by the server is returned instead of data. At most
+    /// `buf.len()` bytes are consumed, the rest stays in the stream.
+    pub async fn read_buf(&self, buf: &mut [u8]) -> XStreamReadResult<usize> {
+        // Check stream state first
+        self.check_readable()?;
+
+        // Check for immediate error
+        if let Some(error) = self.check_for_immediate_error().await {
+            return Err(ErrorOnRead::xstream_error_only(error));
+        }
+
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let result = if self.direction == XStreamDirection::Outbound {
+            select! {
+                result = self.read_main_into(buf) => result,
+                error_result = self.error_data_store.wait_for_error() => match error_result {
+                    Ok(error_data) => {
+                        // Server sent an error
+                        return Err(ErrorOnRead::xstream_error_only(XStreamError::new(error_data)));
+                    }
+                    Err(_) => {
+                        // Error stream closed, perform normal read
+                        debug!("Error stream closed, performing normal read");
+                        self.read_main_into(buf).await
+                    }
+                },
+            }
+        } else {
+            self.read_main_into(buf).await
+        };
+
+        let n = result.map_err(ErrorOnRead::io_error_only)?;
+        let data = &buf[..n];
+        self.counters.add_read(n);
+        if let Some(integrity) = &self.integrity {
+            integrity.record_read(data);
+        }
+        Ok(n)
+    }
+
+    /// Reads into `buf` from the read-ahead buffer or directly from the main stream
+    async fn read_main_into(&self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        self.check_readable_basic()?;
+
+        let result = if let Some(read_ahead) = &self.read_ahead {
+            read_ahead.read_into(buf).await
+        } else {
+            let mut guard = self.stream_main_read.lock().await;
+            match guard.as_mut() {
+                Some(read_half) => match read_half.read(buf).await {
+                    Ok(0) => Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "End of file",
+                    )),
+                    result => result,
+                },
+                None => {
+                    // ReadHalf закрыт через close_read()
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        format!("Cannot read from stream {:?}: ReadHalf has been closed", self.id),
+                    ));
+                }
+            }
+        };
+
+        if let Err(e) = &result {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                debug!("Detected EOF while reading");
+                self.state_manager.signal_read_eof();
+            } else {
+                self.state_manager.handle_connection_error(e, "read error");
+            }
+        }
+        result
+    }
+
+    /// Reads until one of `delimiters` is found, reading at most `max_len` bytes
+    ///
+    /// Returns the data including the delimiter and the index of the delimiter that
+    /// ended the read. If several delimiters match at the same position the longest
+    /// wins, so `\r\n` is reported over `\n`. Reads byte by byte, so nothing past
+    /// the delimiter is consumed. On error the bytes read so far are kept in
+    /// `partial_data`.
+    pub async fn read_until_any(
+        &self,
+        delimiters: &[&[u8]],
+        max_len: usize,
+    ) -> XStreamReadResult<(Vec<u8>, usize)> {
+        if delimiters.is_empty() || delimiters.iter().any(|d| d.is_empty()) {
+            return Err(ErrorOnRead::io_error_only(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "read_until_any requires non-empty delimiters",
+            )));
+        }
+
+        let mut buf = Vec::new();
+        while buf.len() < max_len {
+            match self.read_exact(1).await {
+                Ok(byte) => buf.extend_from_slice(&byte),
+                Err(mut error_on_read) => {
+                    // Сохраняем уже прочитанные байты перед частичными данными ошибки
+                    buf.append(&mut error_on_read.partial_data);
+                    error_on_read.partial_data = buf;
+                    return Err(error_on_read);
+                }
+            }
+
+            let matched = delimiters
+                .iter()
+                .enumerate()
+                .filter(|(_, delimiter)| buf.ends_with(delimiter))
+                .max_by_key(|(_, delimiter)| delimiter.len())
+                .map(|(index, _)| index);
+            if let Some(index) = matched {
+                return Ok((buf, index));
+            }
+        }
+
+        Err(ErrorOnRead::from_io_error(
+            buf,
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("No delimiter found within {} bytes", max_len),
+            ),
+        ))
+    }
+
     // ===== CONVENIENCE METHODS FOR BACKWARD COMPATIBILITY =====
 
     /// Read ignoring XStream errors (backward compatibility)
@@ -609,6 +1226,10 @@ impl XStream {
                                 format!("XStream error: {}", xs_error)
                             ))
                         }
+                        ReadError::Cancelled => Err(std::io::Error::new(
+                            std::io::ErrorKind::Interrupted,
+                            "Read cancelled",
+                        )),
                     }
                 }
             }
@@ -633,24 +1254,141 @@ impl XStream {
                                 format!("XStream error: {}", xs_error)
                             ))
                         }
+                        ReadError::Cancelled => Err(std::io::Error::new(
+                            std::io::ErrorKind::Interrupted,
+                            "Read cancelled",
+                        )),
                     }
                 }
             }
         }
     }
 
-    // ===== WRITE OPERATIONS (UNCHANGED) =====
+    // ===== WRITE OPERATIONS =====
 
     /// Writes all data to the main stream
     pub async fn write_all(&self, buf: Vec<u8>) -> Result<(), std::io::Error> {
-        self.execute_main_write_op(|writer| {
-            let data = buf.clone();
-            Box::pin(async move {
-                writer.write_all(&data).await?;
-                Ok(())
-            })
-        })
-        .await
+        self.write_message(&[&buf], false).await.1
+    }
+
+    /// Writes all buffers in order without merging them into one allocation
+    ///
+    /// The buffers form one message: with sequence numbers enabled they share
+    /// one prefix. Partial writes may end in the middle of a buffer, the rest
+    /// continues from there. With encryption enabled every buffer is sealed
+    /// into its own frames.
+    pub async fn write_all_vectored(&self, bufs: &[&[u8]]) -> Result<(), std::io::Error> {
+        self.write_message(bufs, false).await.1
+    }
+
+    /// Writes all data like `write_all`, but reports partial progress on failure
+    ///
+    /// Returns the number of bytes of `buf` that reached the transport and the
+    /// error that stopped the write, if any. A connection lost mid-write yields
+    /// `(n, Some(e))` where `n` is the count written before the loss. With
+    /// encryption enabled only bytes of fully written frames are counted.
+    pub async fn write_all_counted(&self, buf: Vec<u8>) -> (usize, Option<std::io::Error>) {
+        let (written, result) = self.write_message(&[&buf], false).await;
+        (written, result.err())
+    }
+
+    /// Writes all data and flushes it under one write lock
+    ///
+    /// Resolves once the muxer accepted the bytes: for QUIC they are queued in the
+    /// connection's send buffer, for yamux written to the transport socket. This is
+    /// not a delivery acknowledgement: the peer may not have received or read the data,
+    /// and it can still be lost if the connection drops. Confirm delivery at the
+    /// application level, e.g. by waiting for a response.
+    ///
+    /// No other write can slip between the data and the flush, so a following
+    /// `write_eof` always comes after these bytes.
+    pub async fn write_all_flushed(&self, buf: Vec<u8>) -> Result<(), std::io::Error> {
+        self.write_message(&[&buf], true).await.1
+    }
+
+    /// Common path of all main stream writes: one message made of `bufs`
+    ///
+    /// Under the write lock the message gets its sequence prefix, is recorded for
+    /// the integrity trailer, sealed and written with backpressure tracking.
+    /// Returns how many bytes of `bufs` reached the transport, with encryption only
+    /// those of fully written frames, and the error that stopped the write.
+    async fn write_message(&self, bufs: &[&[u8]], flush: bool) -> (usize, Result<(), std::io::Error>) {
+        if let Err(e) = self.check_writable() {
+            return (0, Err(e));
+        }
+        let total: usize = bufs.iter().map(|buf| buf.len()).sum();
+
+        let (written, result) = {
+            let mut guard = self.stream_main_write.lock().await;
+            let Some(ref mut writer) = *guard else {
+                let error = std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    format!("Cannot write to stream {:?}: WriteHalf has been closed", self.id),
+                );
+                return (0, Err(error));
+            };
+
+            // Номер берется под блокировкой записи, чтобы совпадать с порядком сообщений
+            let prefix = match &self.sequence {
+                Some(sequence) => match sequence.next_prefix(total) {
+                    Ok(prefix) => Some(prefix),
+                    Err(e) => return (0, Err(e)),
+                },
+                None => None,
+            };
+            let parts: Vec<&[u8]> = prefix
+                .iter()
+                .map(|prefix| prefix.as_slice())
+                .chain(bufs.iter().copied())
+                .collect();
+            if let Some(integrity) = &self.integrity {
+                parts.iter().for_each(|part| integrity.record_written(part));
+            }
+
+            // Шифруем под блокировкой записи, чтобы порядок кадров совпадал с порядком данных
+            let sealed: Option<Vec<Vec<u8>>> = match &self.cipher {
+                Some(cipher) => match parts.iter().map(|part| cipher.seal(part)).collect() {
+                    Ok(sealed) => Some(sealed),
+                    Err(e) => return (0, Err(e)),
+                },
+                None => None,
+            };
+            let segments: Vec<&[u8]> = match &sealed {
+                Some(sealed) => sealed.iter().map(|segment| segment.as_slice()).collect(),
+                None => parts.clone(),
+            };
+
+            let (wire_written, mut result) =
+                write_segments_tracked(writer, &segments, self.backpressure.as_ref()).await;
+            if result.is_ok() && flush {
+                result = writer.flush().await;
+            }
+
+            // На проводе префикс и кадры шифра, в счетчик идут только байты bufs
+            let mut remaining = wire_written;
+            let mut written = 0;
+            for (index, (part, segment)) in parts.iter().zip(&segments).enumerate() {
+                let is_prefix = prefix.is_some() && index == 0;
+                let part_written = match &sealed {
+                    Some(_) => XStreamCipher::sealed_plaintext_written(part.len(), segment.len(), remaining),
+                    None => remaining.min(part.len()),
+                };
+                if !is_prefix {
+                    written += part_written;
+                }
+                if remaining < segment.len() {
+                    break;
+                }
+                remaining -= segment.len();
+            }
+            (written, result)
+        };
+
+        self.counters.add_written(written);
+        if let Err(e) = &result {
+            self.state_manager.handle_partial_write_error(e, written, total);
+        }
+        (written, result)
     }
 
     /// Flushes the main stream
@@ -671,9 +1409,19 @@ impl XStream {
             ));
         }
 
+        let cipher = self.cipher.clone();
+        let integrity = self.integrity.clone();
         let result = self
             .execute_main_write_op(|writer| {
                 Box::pin(async move {
+                    // Дайджест записанного идет последним перед EOF
+                    if let Some(integrity) = integrity {
+                        let mut trailer = integrity.trailer().to_vec();
+                        if let Some(cipher) = cipher {
+                            trailer = cipher.seal(&trailer)?;
+                        }
+                        writer.write_all(&trailer).await?;
+                    }
                     writer.flush().await?;
                     writer.close().await?;
                     Ok(())
@@ -857,16 +1605,55 @@ impl XStream {
         Ok(())
     }
 
+    /// Reads and discards pending inbound data, then closes the stream gracefully
+    ///
+    /// Draining stops at EOF or when `timeout` elapses, whichever comes first,
+    /// and returns the number of bytes discarded. A read error, including an
+    /// error sent by the server on outbound streams, is returned after the
+    /// stream is closed instead of being swallowed.
+    pub async fn drain_and_close(&mut self, timeout: Duration) -> XStreamReadResult<usize> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut buf = vec![0u8; 4096];
+        let mut drained = 0;
+
+        let drain_error = loop {
+            match tokio::time::timeout_at(deadline, self.read_buf(&mut buf)).await {
+                Ok(Ok(n)) => drained += n,
+                Ok(Err(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    break None;
+                }
+                Ok(Err(e)) => break Some(e),
+                Err(_) => {
+                    debug!("Drain of stream {:?} timed out after {} bytes", self.id, drained);
+                    break None;
+                }
+            }
+        };
+
+        let close_result = self.close().await;
+        if let Some(e) = drain_error {
+            return Err(e);
+        }
+        close_result.map_err(ErrorOnRead::io_error_only)?;
+        debug!("Stream {:?} drained {} bytes before close", self.id, drained);
+        Ok(drained)
+    }
+
     /// Безопасно закрывает чтение из основного потока
     /// Явно вызывает drop внутреннего ReadHalf через присвоение None
     /// Drop ReadHalf уведомляет транспорт (TCP/QUIC), что мы больше не читаем данные
     /// Удаленная сторона получит ошибку записи (BrokenPipe или RST), если продолжит писать
     /// XStream остается клонируемым - все клоны разделяют один и тот же ReadHalf
     pub async fn close_read(&self) {
+        // Фоновая задача read-ahead держит ReadHalf во время чтения
+        if let Some(read_ahead) = &self.read_ahead {
+            read_ahead.shutdown();
+        }
         let mut guard = self.stream_main_read.lock().await;
         // Явно вызываем drop через присвоение None
         let old_read_half = std::mem::replace(&mut *guard, None);
         drop(old_read_half); // Явный drop для ясности
+        self.state_manager.signal_read_eof();
         debug!("Stream {:?} read half closed via close_read()", self.id);
     }
 
@@ -878,6 +1665,16 @@ impl XStream {
     pub async fn close_write(&self) -> Result<(), std::io::Error> {
         let mut guard = self.stream_main_write.lock().await;
         if let Some(mut write_half) = guard.take() {
+            // Без write_eof дайджест еще не отправлен
+            if let Some(integrity) = &self.integrity {
+                if !self.state_manager.is_write_local_closed() {
+                    let mut trailer = integrity.trailer().to_vec();
+                    if let Some(cipher) = &self.cipher {
+                        trailer = cipher.seal(&trailer)?;
+                    }
+                    write_half.write_all(&trailer).await?;
+                }
+            }
             // Сначала flush и close для корректного завершения
             write_half.flush().await?;
             write_half.close().await?;
@@ -890,6 +1687,88 @@ impl XStream {
     }
 }
 
+/// Writes all segments in order, reporting progress to the backpressure tracker if any
+///
+/// Only bytes the transport stopped accepting are tracked: once a single
+/// poll_write stays pending for BACKPRESSURE_STALL_GRACE, the rest of the write
+/// counts as buffered until the transport takes it. A large write that keeps
+/// moving on a fast link is never counted. Returns the number of bytes written
+/// with the error that stopped the write.
+async fn write_segments_tracked(
+    writer: &mut futures::io::WriteHalf<Stream>,
+    segments: &[&[u8]],
+    backpressure: Option<&BackpressureTracker>,
+) -> (usize, Result<(), std::io::Error>) {
+    let total: usize = segments.iter().map(|segment| segment.len()).sum();
+    let mut written = 0;
+    // Байты, уже учтенные как буферизованные
+    let mut counted = 0;
+    // Позиция записи: индекс сегмента и смещение внутри него
+    let (mut index, mut offset) = (0, 0);
+    let result = loop {
+        while index < segments.len() && offset == segments[index].len() {
+            index += 1;
+            offset = 0;
+        }
+        if index == segments.len() {
+            break Ok(());
+        }
+
+        let mut slices = Vec::with_capacity(segments.len() - index);
+        slices.push(std::io::IoSlice::new(&segments[index][offset..]));
+        slices.extend(segments[index + 1..].iter().map(|segment| std::io::IoSlice::new(segment)));
+
+        let write = writer.write_vectored(&slices);
+        let result = match backpressure {
+            Some(tracker) if counted == 0 => {
+                tokio::pin!(write);
+                select! {
+                    result = &mut write => result,
+                    _ = tokio::time::sleep(BACKPRESSURE_STALL_GRACE) => {
+                        counted = total - written;
+                        tracker.add(counted);
+                        write.await
+                    }
+                }
+            }
+            _ => write.await,
+        };
+        match result {
+            Ok(0) => {
+                break Err(std::io::Error::new(
+                    std::io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ))
+            }
+            Ok(mut n) => {
+                written += n;
+                if let Some(tracker) = backpressure {
+                    let accepted = n.min(counted);
+                    if accepted > 0 {
+                        counted -= accepted;
+                        tracker.remove(accepted);
+                    }
+                }
+                while n > 0 {
+                    let step = n.min(segments[index].len() - offset);
+                    offset += step;
+                    n -= step;
+                    if offset == segments[index].len() && n > 0 {
+                        index += 1;
+                        offset = 0;
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => break Err(e),
+        }
+    };
+    if let Some(tracker) = backpressure.filter(|_| counted > 0) {
+        tracker.remove(counted);
+    }
+    (written, result)
+}
+
 impl Clone for XStream {
     fn clone(&self) -> Self {
         debug!(
@@ -908,6 +1787,14 @@ impl Clone for XStream {
             state_manager: self.state_manager.clone(),
             error_data_store: self.error_data_store.clone(),
             error_reader_task: self.error_reader_task.clone(),
+            cipher: self.cipher.clone(),
+            integrity: self.integrity.clone(),
+            sequence: self.sequence.clone(),
+            counters: self.counters.clone(),
+            read_ahead: self.read_ahead.clone(),
+            backpressure: self.backpressure.clone(),
+            message_read: self.message_read.clone(),
+            drop_guard: self.drop_guard.clone(),
         }
     }
 }
@@ -916,11 +1803,7 @@ impl Drop for XStream {
     fn drop(&mut self) {
         debug!("Dropping XStream with id: {:?}", self.id);
 
-        // If stream is not fully closed, notify about drop
-        if !self.state_manager.is_closed() {
-            self.state_manager.notify_state_change("XStream dropped");
-        }
-
+        // Closure of a dropped stream is reported by the drop guard of the last clone
         // The error reader task will be shut down when the Arc is dropped
         // or when close() is called explicitly
     }
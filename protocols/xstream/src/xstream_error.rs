@@ -73,6 +73,39 @@ impl fmt::Display for XStreamError {
 
 impl std::error::Error for XStreamError {}
 
+/// Ошибка открытия исходящего потока
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamOpenError {
+    /// Согласование субпотоков не завершилось за pending_stream_timeout
+    Timeout,
+    /// Пир не объявил поддержку протокола XStream в Identify
+    ProtocolUnsupported,
+    /// Превышен лимит одновременных исходящих потоков к пиру
+    TooManyStreams,
+    /// Поведение останавливается и не открывает новые потоки
+    ShuttingDown,
+    /// Подпотоки не удалось открыть или согласовать
+    Failed(String),
+}
+
+impl fmt::Display for StreamOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamOpenError::Timeout => write!(f, "Stream open timed out"),
+            StreamOpenError::ProtocolUnsupported => {
+                write!(f, "Peer does not support the XStream protocol")
+            }
+            StreamOpenError::TooManyStreams => {
+                write!(f, "Too many outbound streams to the peer")
+            }
+            StreamOpenError::ShuttingDown => write!(f, "Cannot open stream: shutting down"),
+            StreamOpenError::Failed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for StreamOpenError {}
+
 /// Ошибка чтения с частично прочитанными данными
 #[derive(Debug, Clone)]
 pub struct ErrorOnRead {
@@ -89,6 +122,8 @@ pub enum ReadError {
     Io(IoErrorWrapper),
     /// XStream ошибка от сервера
     XStream(XStreamError),
+    /// Чтение отменено через CancellationToken, поток остается пригодным для чтения
+    Cancelled,
 }
 
 /// Обертка для io::Error чтобы сделать её Clone
@@ -164,6 +199,7 @@ impl ErrorOnRead {
         match &self.error {
             ReadError::Io(io_wrapper) => io_wrapper.kind(),
             ReadError::XStream(_) => io::ErrorKind::Other,
+            ReadError::Cancelled => io::ErrorKind::Interrupted,
         }
     }
 
@@ -211,6 +247,11 @@ impl ErrorOnRead {
         matches!(self.error, ReadError::XStream(_))
     }
 
+    /// Проверяет, было ли чтение отменено
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.error, ReadError::Cancelled)
+    }
+
     /// Возвращает IO ошибку, если это IO ошибка
     pub fn as_io_error(&self) -> Option<&IoErrorWrapper> {
         match &self.error {
@@ -270,6 +311,11 @@ impl ErrorOnRead {
         Self::from_xstream_error(Vec::new(), error)
     }
 
+    /// Создает ErrorOnRead для отмененного чтения
+    pub fn cancelled() -> Self {
+        Self::error_only(ReadError::Cancelled)
+    }
+
     /// Compatibility method: converts ErrorOnRead to io::Error for legacy code
     pub fn to_io_error(self) -> io::Error {
         match self.error {
@@ -277,6 +323,7 @@ impl ErrorOnRead {
             ReadError::XStream(xs_error) => {
                 io::Error::new(io::ErrorKind::Other, format!("XStream error: {}", xs_error))
             }
+            ReadError::Cancelled => io::Error::new(io::ErrorKind::Interrupted, "Read cancelled"),
         }
     }
 
@@ -291,6 +338,7 @@ impl fmt::Display for ReadError {
         match self {
             ReadError::Io(err) => write!(f, "IO error: {}", err),
             ReadError::XStream(err) => write!(f, "XStream error: {}", err),
+            ReadError::Cancelled => write!(f, "Read cancelled"),
         }
     }
 }
@@ -300,6 +348,7 @@ impl std::error::Error for ReadError {
         match self {
             ReadError::Io(_) => None, // IoErrorWrapper не implement Error
             ReadError::XStream(err) => Some(err),
+            ReadError::Cancelled => None,
         }
     }
 }
@@ -371,6 +420,7 @@ pub mod utils {
                     | io::ErrorKind::NotConnected
             ),
             ReadError::XStream(_) => false, // XStream ошибки не критические для соединения
+            ReadError::Cancelled => false,
         }
     }
 
@@ -385,6 +435,7 @@ pub mod utils {
                     format!("XStream error: {} bytes", xs_err.len())
                 }
             }
+            ReadError::Cancelled => "Read cancelled".to_string(),
         }
     }
 }
@@ -3,9 +3,10 @@
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU8, Ordering};
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::{Mutex, mpsc, watch};
 use tracing::{debug, error, info, warn};
 
+use super::events::StreamCloseReason;
 use super::types::{XStreamDirection, XStreamID, XStreamState};
 use libp2p::PeerId;
 
@@ -21,20 +22,31 @@ pub struct XStreamStateManager {
     /// Direction of the stream (inbound or outbound)
     direction: XStreamDirection,
     /// Closure notifier for sending state change events
-    closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+    closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>,
     /// Saved error data, if any was received
     error_data: Arc<Mutex<Option<Vec<u8>>>>,
     /// Flag indicating that an error was written
     error_written: Arc<AtomicU8>,
+    /// Set once EOF has been observed on the read half
+    read_eof: Arc<watch::Sender<bool>>,
+    /// Set once the local write half has been closed
+    write_closed: Arc<watch::Sender<bool>>,
+    /// What closed the stream first (see CLOSE_CAUSE_* constants)
+    close_cause: Arc<AtomicU8>,
 }
 
+const CLOSE_CAUSE_NONE: u8 = 0;
+const CLOSE_CAUSE_LOCAL: u8 = 1;
+const CLOSE_CAUSE_REMOTE_EOF: u8 = 2;
+const CLOSE_CAUSE_RESET: u8 = 3;
+
 impl XStreamStateManager {
     /// Creates a new state manager starting in Open state
     pub fn new(
         stream_id: XStreamID,
         peer_id: PeerId,
         direction: XStreamDirection,
-        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID)>,
+        closure_notifier: mpsc::UnboundedSender<(PeerId, XStreamID, XStreamDirection)>,
     ) -> Self {
         Self {
             state: Arc::new(AtomicU8::new(XStreamState::Open as u8)),
@@ -44,6 +56,9 @@ impl XStreamStateManager {
             closure_notifier,
             error_data: Arc::new(Mutex::new(None)),
             error_written: Arc::new(AtomicU8::new(0)),
+            read_eof: Arc::new(watch::channel(false).0),
+            write_closed: Arc::new(watch::channel(false).0),
+            close_cause: Arc::new(AtomicU8::new(CLOSE_CAUSE_NONE)),
         }
     }
 
@@ -106,7 +121,7 @@ impl XStreamStateManager {
             "Sending state change notification for stream {:?} due to: {}",
             self.stream_id, reason
         );
-        match self.closure_notifier.send((self.peer_id, self.stream_id)) {
+        match self.closure_notifier.send((self.peer_id, self.stream_id, self.direction)) {
             Ok(_) => debug!(
                 "State change notification sent successfully for stream {:?}",
                 self.stream_id
@@ -120,6 +135,8 @@ impl XStreamStateManager {
 
     /// Mark the stream as write locally closed (EOF sent)
     pub fn mark_write_local_closed(&self) {
+        self.record_close_cause(CLOSE_CAUSE_LOCAL);
+        self.signal_write_closed();
         let current = self.state();
         match current {
             XStreamState::Open => self.set_state(XStreamState::WriteLocalClosed),
@@ -130,6 +147,8 @@ impl XStreamStateManager {
 
     /// Mark the stream as read remotely closed (EOF received)
     pub fn mark_read_remote_closed(&self) {
+        self.record_close_cause(CLOSE_CAUSE_REMOTE_EOF);
+        self.signal_read_eof();
         let current = self.state();
         match current {
             XStreamState::Open => self.set_state(XStreamState::ReadRemoteClosed),
@@ -140,6 +159,8 @@ impl XStreamStateManager {
 
     /// Mark the stream as locally closed
     pub fn mark_local_closed(&self) {
+        self.record_close_cause(CLOSE_CAUSE_LOCAL);
+        self.signal_write_closed();
         let current = self.state();
         match current {
             XStreamState::Open | XStreamState::WriteLocalClosed => {
@@ -156,6 +177,8 @@ impl XStreamStateManager {
 
     /// Mark the stream as remotely closed
     pub fn mark_remote_closed(&self) {
+        self.record_close_cause(CLOSE_CAUSE_REMOTE_EOF);
+        self.signal_read_eof();
         let current = self.state();
         match current {
             XStreamState::Open => self.set_state(XStreamState::RemoteClosed),
@@ -164,12 +187,70 @@ impl XStreamStateManager {
         }
     }
 
+    /// Records that EOF was observed on the read half without changing the state
+    pub fn signal_read_eof(&self) {
+        self.read_eof.send_replace(true);
+    }
+
+    /// Records that the local write half was closed without changing the state
+    pub fn signal_write_closed(&self) {
+        self.write_closed.send_replace(true);
+    }
+
+    /// Check if EOF has been observed on the read half
+    pub fn is_read_eof(&self) -> bool {
+        *self.read_eof.borrow()
+    }
+
+    /// Check if the local write half has been closed
+    pub fn is_write_closed(&self) -> bool {
+        *self.write_closed.borrow()
+    }
+
+    /// Waits until EOF is observed on the read half
+    pub async fn wait_read_eof(&self) {
+        let mut rx = self.read_eof.subscribe();
+        // Sender lives as long as self, so wait_for can't fail here
+        let _ = rx.wait_for(|eof| *eof).await;
+    }
+
+    /// Waits until the local write half is closed
+    pub async fn wait_write_closed(&self) {
+        let mut rx = self.write_closed.subscribe();
+        let _ = rx.wait_for(|closed| *closed).await;
+    }
+
     /// Mark the stream as errored
     pub fn mark_error(&self, reason: &str) {
         self.set_state(XStreamState::Error);
         self.notify_state_change(&format!("Error: {}", reason));
     }
 
+    /// Records the first close cause, later ones are ignored
+    fn record_close_cause(&self, cause: u8) {
+        let _ = self.close_cause.compare_exchange(
+            CLOSE_CAUSE_NONE,
+            cause,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
+    /// Why the stream was closed, derived from the final state
+    ///
+    /// Returns None while nothing has closed the stream yet.
+    pub fn close_reason(&self) -> Option<StreamCloseReason> {
+        if self.state() == XStreamState::Error {
+            return Some(StreamCloseReason::Error);
+        }
+        match self.close_cause.load(Ordering::Acquire) {
+            CLOSE_CAUSE_LOCAL => Some(StreamCloseReason::LocalClose),
+            CLOSE_CAUSE_REMOTE_EOF => Some(StreamCloseReason::RemoteEof),
+            CLOSE_CAUSE_RESET => Some(StreamCloseReason::Reset),
+            _ => None,
+        }
+    }
+
     /// Check if the stream is closed (either locally, remotely, or both)
     pub fn is_closed(&self) -> bool {
         matches!(
@@ -247,6 +328,8 @@ impl XStreamStateManager {
     pub fn handle_connection_error(&self, error: &std::io::Error, context: &str) -> bool {
         // Check if this is a connection error
         if self.is_connection_closed_error(error) {
+            // A reset overrides a clean close recorded earlier
+            self.close_cause.store(CLOSE_CAUSE_RESET, Ordering::Release);
             self.mark_remote_closed();
             self.notify_state_change(&format!("{}: {:?}", context, error.kind()));
             return true;
@@ -254,6 +337,19 @@ impl XStreamStateManager {
         false
     }
 
+    /// Handle a write error that interrupted a write after `written` of `total` bytes
+    pub fn handle_partial_write_error(
+        &self,
+        error: &std::io::Error,
+        written: usize,
+        total: usize,
+    ) -> bool {
+        self.handle_connection_error(
+            error,
+            &format!("write operation error after {} of {} bytes", written, total),
+        )
+    }
+
     /// Checks if an error indicates that the connection was closed by remote
     pub fn is_connection_closed_error(&self, e: &std::io::Error) -> bool {
         matches!(
@@ -290,6 +386,9 @@ impl Clone for XStreamStateManager {
             closure_notifier: self.closure_notifier.clone(),
             error_data: self.error_data.clone(),
             error_written: self.error_written.clone(),
+            read_eof: self.read_eof.clone(),
+            write_closed: self.write_closed.clone(),
+            close_cause: self.close_cause.clone(),
         }
     }
 }
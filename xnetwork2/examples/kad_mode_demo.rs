@@ -36,7 +36,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("✅ Текущий режим Kademlia: {}", mode);
     
     println!("\n🔧 Тестируем режим Server...");
-    commander.set_kad_mode(KadMode::Server).await?;
+    commander.force_kad_mode(KadMode::Server).await?;
     let mode = commander.get_kad_mode().await?;
     println!("✅ Текущий режим Kademlia: {}", mode);
     
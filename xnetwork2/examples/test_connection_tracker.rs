@@ -61,7 +61,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // Set Kademlia mode to server to test integration
     println!("🔄 Setting Kademlia mode to server...");
-    commander.set_kad_mode(KadMode::Server).await?;
+    commander.force_kad_mode(KadMode::Server).await?;
     println!("✅ Kademlia mode set to server");
 
     // Enable mDNS for local discovery
@@ -39,7 +39,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // Устанавливаем режим Server
     println!("🔄 Устанавливаем режим Server...");
-    node.set_kad_mode(KadMode::Server).await?;
+    node.force_kad_mode(KadMode::Server).await?;
     println!("✅ Режим установлен: Server");
 
     // Проверяем что режим изменился
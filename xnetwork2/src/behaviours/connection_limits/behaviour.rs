@@ -0,0 +1,211 @@
+//! NetworkBehaviour wrapping libp2p connection limits with denial events
+
+use std::collections::VecDeque;
+use std::task::{Context, Poll};
+use libp2p::core::{transport::PortUse, Endpoint};
+use libp2p::swarm::{
+    ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
+    THandlerOutEvent, ToSwarm,
+};
+use libp2p::{connection_limits, Multiaddr, PeerId};
+
+/// Caps on established connections, None means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// Maximum established inbound connections
+    pub max_established_incoming: Option<u32>,
+    /// Maximum established outbound connections
+    pub max_established_outgoing: Option<u32>,
+    /// Maximum established connections with a single peer
+    pub max_established_per_peer: Option<u32>,
+}
+
+impl ConnectionLimits {
+    /// Limits without any caps
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Set maximum established inbound connections
+    pub fn with_max_established_incoming(mut self, limit: Option<u32>) -> Self {
+        self.max_established_incoming = limit;
+        self
+    }
+
+    /// Set maximum established outbound connections
+    pub fn with_max_established_outgoing(mut self, limit: Option<u32>) -> Self {
+        self.max_established_outgoing = limit;
+        self
+    }
+
+    /// Set maximum established connections per peer
+    pub fn with_max_established_per_peer(mut self, limit: Option<u32>) -> Self {
+        self.max_established_per_peer = limit;
+        self
+    }
+
+    fn to_libp2p(self) -> connection_limits::ConnectionLimits {
+        connection_limits::ConnectionLimits::default()
+            .with_max_established_incoming(self.max_established_incoming)
+            .with_max_established_outgoing(self.max_established_outgoing)
+            .with_max_established_per_peer(self.max_established_per_peer)
+    }
+}
+
+/// Events emitted by ConnectionLimitsBehaviour
+#[derive(Debug)]
+pub enum ConnectionLimitsEvent {
+    /// A connection was refused because a limit was reached
+    LimitReached {
+        /// Remote peer, unknown for pending connections
+        peer_id: Option<PeerId>,
+        /// Our role on the refused connection
+        endpoint: Endpoint,
+        reason: String,
+    },
+}
+
+/// NetworkBehaviour enforcing ConnectionLimits
+pub struct ConnectionLimitsBehaviour {
+    inner: connection_limits::Behaviour,
+    limits: ConnectionLimits,
+    /// Events waiting to be emitted to the swarm
+    pending_events: VecDeque<ConnectionLimitsEvent>,
+}
+
+impl Default for ConnectionLimitsBehaviour {
+    fn default() -> Self {
+        Self::new(ConnectionLimits::default())
+    }
+}
+
+impl ConnectionLimitsBehaviour {
+    /// Create a behaviour enforcing the given limits
+    pub fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            inner: connection_limits::Behaviour::new(limits.to_libp2p()),
+            limits,
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Currently enforced limits
+    pub fn limits(&self) -> ConnectionLimits {
+        self.limits
+    }
+
+    /// Replace the limits, existing connections above a new limit stay open
+    pub fn set_limits(&mut self, limits: ConnectionLimits) {
+        *self.inner.limits_mut() = limits.to_libp2p();
+        self.limits = limits;
+    }
+
+    /// Record a denial so it is reported instead of silently dropped
+    fn report<T>(
+        &mut self,
+        result: Result<T, ConnectionDenied>,
+        peer_id: Option<PeerId>,
+        endpoint: Endpoint,
+    ) -> Result<T, ConnectionDenied> {
+        if let Err(denied) = &result {
+            self.pending_events.push_back(ConnectionLimitsEvent::LimitReached {
+                peer_id,
+                endpoint,
+                reason: denied.to_string(),
+            });
+        }
+        result
+    }
+}
+
+impl NetworkBehaviour for ConnectionLimitsBehaviour {
+    type ConnectionHandler = <connection_limits::Behaviour as NetworkBehaviour>::ConnectionHandler;
+    type ToSwarm = ConnectionLimitsEvent;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        let result = self
+            .inner
+            .handle_pending_inbound_connection(connection_id, local_addr, remote_addr);
+        self.report(result, None, Endpoint::Listener)
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        peer: PeerId,
+        local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        let result = self.inner.handle_established_inbound_connection(
+            connection_id,
+            peer,
+            local_addr,
+            remote_addr,
+        );
+        self.report(result, Some(peer), Endpoint::Listener)
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        addresses: &[Multiaddr],
+        effective_role: Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        let result = self.inner.handle_pending_outbound_connection(
+            connection_id,
+            maybe_peer,
+            addresses,
+            effective_role,
+        );
+        self.report(result, maybe_peer, Endpoint::Dialer)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        role_override: Endpoint,
+        port_use: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        let result = self.inner.handle_established_outbound_connection(
+            connection_id,
+            peer,
+            addr,
+            role_override,
+            port_use,
+        );
+        self.report(result, Some(peer), Endpoint::Dialer)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        // Inner behaviour counts connections from swarm events
+        self.inner.on_swarm_event(event);
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        self.inner
+            .on_connection_handler_event(peer_id, connection_id, event);
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(ToSwarm::GenerateEvent(event));
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,19 @@
+//! ConnectionLimits commands for XNetwork2
+
+use tokio::sync::oneshot;
+
+use super::behaviour::ConnectionLimits;
+
+/// Commands for ConnectionLimits behaviour
+#[derive(Debug)]
+pub enum ConnectionLimitsCommand {
+    /// Get the configured connection limits
+    GetLimits {
+        response: oneshot::Sender<Result<ConnectionLimits, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Replace the limits at runtime
+    SetLimits {
+        limits: ConnectionLimits,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+}
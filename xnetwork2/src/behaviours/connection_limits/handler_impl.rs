@@ -0,0 +1,45 @@
+//! BehaviourHandler implementation for ConnectionLimitsBehaviour
+
+use async_trait::async_trait;
+use command_swarm::BehaviourHandler;
+use tracing::{debug, info};
+
+use super::behaviour::{ConnectionLimitsBehaviour, ConnectionLimitsEvent};
+use super::command::ConnectionLimitsCommand;
+
+/// Handler for ConnectionLimitsBehaviour
+#[derive(Default)]
+pub struct ConnectionLimitsHandler;
+
+#[async_trait]
+impl BehaviourHandler for ConnectionLimitsHandler {
+    type Behaviour = ConnectionLimitsBehaviour;
+    type Event = ConnectionLimitsEvent;
+    type Command = ConnectionLimitsCommand;
+
+    async fn handle_cmd(&mut self, behaviour: &mut Self::Behaviour, cmd: Self::Command) {
+        match cmd {
+            ConnectionLimitsCommand::GetLimits { response } => {
+                let limits = behaviour.limits();
+                debug!("📊 [ConnectionLimitsHandler] Limits: {:?}", limits);
+                let _ = response.send(Ok(limits));
+            }
+            ConnectionLimitsCommand::SetLimits { limits, response } => {
+                info!("🔧 [ConnectionLimitsHandler] New limits: {:?}", limits);
+                behaviour.set_limits(limits);
+                let _ = response.send(Ok(()));
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, _behaviour: &mut Self::Behaviour, event: &Self::Event) {
+        match event {
+            ConnectionLimitsEvent::LimitReached { peer_id, endpoint, reason } => {
+                info!(
+                    "🚫 [ConnectionLimitsHandler] {:?} connection with {:?} refused: {}",
+                    endpoint, peer_id, reason
+                );
+            }
+        }
+    }
+}
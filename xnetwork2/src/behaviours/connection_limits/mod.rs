@@ -0,0 +1,13 @@
+//! Connection limits behaviour for XNetwork2
+//!
+//! Caps inbound, outbound and per-peer established connections using
+//! libp2p's connection limits, reporting every refused connection.
+
+pub mod behaviour;
+pub mod command;
+pub mod handler_impl;
+
+// Re-export for convenience
+pub use behaviour::{ConnectionLimits, ConnectionLimitsBehaviour, ConnectionLimitsEvent};
+pub use command::ConnectionLimitsCommand;
+pub use handler_impl::ConnectionLimitsHandler;
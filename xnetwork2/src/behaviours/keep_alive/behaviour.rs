@@ -1,6 +1,8 @@
 //! Custom NetworkBehaviour for KeepAlive
 
+use std::collections::VecDeque;
 use std::task::{Context, Poll};
+use libp2p::core::ConnectedPoint;
 use libp2p::swarm::{
     ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, NotifyHandler, ToSwarm,
 };
@@ -13,6 +15,8 @@ use super::handler::KeepAliveConnectionHandler;
 pub struct KeepAliveBehaviour {
     /// Keep-alive status
     enabled: bool,
+    /// Events waiting to be emitted to the swarm
+    pending_events: VecDeque<KeepAliveEvent>,
 }
 
 /// Events emitted by KeepAliveBehaviour
@@ -20,6 +24,14 @@ pub struct KeepAliveBehaviour {
 pub enum KeepAliveEvent {
     /// No events for now, but required by the trait
     Dummy,
+    /// Remote address of an established connection changed (e.g. QUIC connection migration)
+    /// AddressChange приходит только в on_swarm_event, поэтому пробрасываем его в SwarmHandler
+    AddressChanged {
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        old: ConnectedPoint,
+        new: ConnectedPoint,
+    },
 }
 
 impl KeepAliveBehaviour {
@@ -27,6 +39,7 @@ impl KeepAliveBehaviour {
     pub fn new() -> Self {
         Self {
             enabled: true, // Default to enabled
+            pending_events: VecDeque::new(),
         }
     }
 
@@ -66,7 +79,16 @@ impl NetworkBehaviour for KeepAliveBehaviour {
         Ok(KeepAliveConnectionHandler::new(self.enabled))
     }
 
-    fn on_swarm_event(&mut self, _event: FromSwarm) {}
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        if let FromSwarm::AddressChange(change) = event {
+            self.pending_events.push_back(KeepAliveEvent::AddressChanged {
+                peer_id: change.peer_id,
+                connection_id: change.connection_id,
+                old: change.old.clone(),
+                new: change.new.clone(),
+            });
+        }
+    }
 
     fn on_connection_handler_event(
         &mut self,
@@ -80,6 +102,9 @@ impl NetworkBehaviour for KeepAliveBehaviour {
         &mut self,
         _cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, libp2p::swarm::THandlerInEvent<Self>>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(ToSwarm::GenerateEvent(event));
+        }
         Poll::Pending
     }
 }
@@ -39,6 +39,9 @@ impl BehaviourHandler for KeepAliveHandler {
             KeepAliveEvent::Dummy => {
                 // No events to handle for KeepAliveBehaviour
             }
+            KeepAliveEvent::AddressChanged { .. } => {
+                // Обрабатывается в SwarmHandler (conntracker и NodeEvent)
+            }
         }
     }
 }
@@ -9,6 +9,9 @@ pub mod xauth;
 pub mod xstream;
 pub mod xroutes;
 pub mod keep_alive;
+pub mod peer_filter;
+pub mod connection_limits;
+pub mod reconnect;
 
 // Re-export handlers for convenience
 pub use identify::IdentifyHandler;
@@ -17,11 +20,19 @@ pub use xauth::XAuthHandler;
 pub use xstream::XStreamHandler;
 pub use xroutes::XRoutesHandler;
 pub use keep_alive::KeepAliveHandler;
+pub use peer_filter::PeerFilterHandler;
+pub use connection_limits::ConnectionLimitsHandler;
+pub use reconnect::ReconnectHandler;
 
 // Re-export command types
 pub use identify::IdentifyCommand;
 pub use ping::PingCommand;
 pub use xauth::XAuthCommand;
-pub use xstream::XStreamCommand;
+pub use xstream::{
+    AsyncInboundDecision, InboundDecision, InboundStreamPolicy, StreamTagMetrics, XStreamCommand,
+};
 pub use xroutes::XRoutesCommand;
 pub use keep_alive::KeepAliveCommand;
+pub use peer_filter::PeerFilterCommand;
+pub use connection_limits::ConnectionLimitsCommand;
+pub use reconnect::ReconnectCommand;
@@ -0,0 +1,138 @@
+//! Custom NetworkBehaviour that gates connections through a PeerFilter
+
+use std::collections::VecDeque;
+use std::task::{Context, Poll};
+use libp2p::swarm::{
+    dummy, ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, ToSwarm,
+};
+use libp2p::{Multiaddr, PeerId};
+
+use super::filter::PeerFilter;
+
+/// Custom NetworkBehaviour for peer filtering
+#[derive(Default)]
+pub struct PeerFilterBehaviour {
+    /// Active filter, None allows all peers
+    filter: Option<Box<dyn PeerFilter>>,
+    /// Events waiting to be emitted to the swarm
+    pending_events: VecDeque<PeerFilterEvent>,
+}
+
+/// Events emitted by PeerFilterBehaviour
+#[derive(Debug)]
+pub enum PeerFilterEvent {
+    /// Inbound connection from a blocked peer was denied
+    InboundDenied { peer_id: PeerId, remote_addr: Multiaddr },
+    /// Outbound connection to a blocked peer was denied
+    OutboundDenied { peer_id: PeerId },
+}
+
+/// Error returned as ConnectionDenied cause for blocked peers
+#[derive(Debug)]
+pub struct PeerBlocked(pub PeerId);
+
+impl std::fmt::Display for PeerBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Peer {} is blocked by peer filter", self.0)
+    }
+}
+
+impl std::error::Error for PeerBlocked {}
+
+impl PeerFilterBehaviour {
+    /// Create a new PeerFilterBehaviour with an optional filter
+    pub fn new(filter: Option<Box<dyn PeerFilter>>) -> Self {
+        Self {
+            filter,
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Check if the peer is allowed for outbound connections
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        self.filter
+            .as_ref()
+            .map_or(true, |filter| filter.allow_outbound(peer_id))
+    }
+
+    /// Check outbound permission and record denial
+    fn check_outbound(&mut self, peer_id: PeerId) -> Result<(), ConnectionDenied> {
+        if self.is_peer_allowed(&peer_id) {
+            return Ok(());
+        }
+        self.pending_events
+            .push_back(PeerFilterEvent::OutboundDenied { peer_id });
+        Err(ConnectionDenied::new(PeerBlocked(peer_id)))
+    }
+}
+
+impl NetworkBehaviour for PeerFilterBehaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = PeerFilterEvent;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        _local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<dummy::ConnectionHandler, ConnectionDenied> {
+        if let Some(filter) = self.filter.as_ref() {
+            if !filter.allow_inbound(&peer, remote_addr) {
+                self.pending_events.push_back(PeerFilterEvent::InboundDenied {
+                    peer_id: peer,
+                    remote_addr: remote_addr.clone(),
+                });
+                return Err(ConnectionDenied::new(PeerBlocked(peer)));
+            }
+        }
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        _addresses: &[Multiaddr],
+        _effective_role: libp2p::core::Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        // Отказываем до установки соединения, если peer_id известен заранее
+        if let Some(peer_id) = maybe_peer {
+            self.check_outbound(peer_id)?;
+        }
+        Ok(vec![])
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        _addr: &Multiaddr,
+        _role_override: libp2p::core::Endpoint,
+        _port_use: libp2p::core::transport::PortUse,
+    ) -> Result<dummy::ConnectionHandler, ConnectionDenied> {
+        self.check_outbound(peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, _event: FromSwarm) {}
+
+    fn on_connection_handler_event(
+        &mut self,
+        _peer_id: PeerId,
+        _connection_id: ConnectionId,
+        event: libp2p::swarm::THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, libp2p::swarm::THandlerInEvent<Self>>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(ToSwarm::GenerateEvent(event));
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,14 @@
+//! PeerFilter commands for XNetwork2
+
+use libp2p::PeerId;
+use tokio::sync::oneshot;
+
+/// Commands for PeerFilter behaviour
+#[derive(Debug)]
+pub enum PeerFilterCommand {
+    /// Check whether outbound connections to the peer are allowed
+    IsPeerAllowed {
+        peer_id: PeerId,
+        response: oneshot::Sender<Result<bool, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+}
@@ -0,0 +1,130 @@
+//! Peer filter trait and built-in allowlist/denylist implementations
+
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+
+/// Decides which peers may establish connections with the node
+pub trait PeerFilter: Send + 'static {
+    /// Allow an inbound connection from the peer on the given remote address
+    fn allow_inbound(&self, peer_id: &PeerId, remote_addr: &Multiaddr) -> bool;
+
+    /// Allow an outbound connection to the peer
+    fn allow_outbound(&self, peer_id: &PeerId) -> bool;
+}
+
+/// Allows only the listed peers
+#[derive(Debug, Clone, Default)]
+pub struct AllowList {
+    peers: HashSet<PeerId>,
+}
+
+impl AllowList {
+    /// Create an allowlist from a set of peers
+    pub fn new(peers: HashSet<PeerId>) -> Self {
+        Self { peers }
+    }
+
+    /// Add a peer to the allowlist
+    pub fn allow(&mut self, peer_id: PeerId) {
+        self.peers.insert(peer_id);
+    }
+
+    /// Remove a peer from the allowlist
+    pub fn remove(&mut self, peer_id: &PeerId) -> bool {
+        self.peers.remove(peer_id)
+    }
+}
+
+impl FromIterator<PeerId> for AllowList {
+    fn from_iter<I: IntoIterator<Item = PeerId>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl PeerFilter for AllowList {
+    fn allow_inbound(&self, peer_id: &PeerId, _remote_addr: &Multiaddr) -> bool {
+        self.peers.contains(peer_id)
+    }
+
+    fn allow_outbound(&self, peer_id: &PeerId) -> bool {
+        self.peers.contains(peer_id)
+    }
+}
+
+/// Blocks the listed peers, allows everyone else
+#[derive(Debug, Clone, Default)]
+pub struct DenyList {
+    peers: HashSet<PeerId>,
+}
+
+impl DenyList {
+    /// Create a denylist from a set of peers
+    pub fn new(peers: HashSet<PeerId>) -> Self {
+        Self { peers }
+    }
+
+    /// Add a peer to the denylist
+    pub fn deny(&mut self, peer_id: PeerId) {
+        self.peers.insert(peer_id);
+    }
+
+    /// Remove a peer from the denylist
+    pub fn remove(&mut self, peer_id: &PeerId) -> bool {
+        self.peers.remove(peer_id)
+    }
+}
+
+impl FromIterator<PeerId> for DenyList {
+    fn from_iter<I: IntoIterator<Item = PeerId>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl PeerFilter for DenyList {
+    fn allow_inbound(&self, peer_id: &PeerId, _remote_addr: &Multiaddr) -> bool {
+        !self.peers.contains(peer_id)
+    }
+
+    fn allow_outbound(&self, peer_id: &PeerId) -> bool {
+        !self.peers.contains(peer_id)
+    }
+}
+
+/// Decides which addresses may be dialed, true allows the dial
+pub type DialAddressFilter = Arc<dyn Fn(&Multiaddr) -> bool + Send + Sync>;
+
+/// Rewrites an address right before it is dialed
+pub type AddressRewriter = Arc<dyn Fn(Multiaddr) -> Multiaddr + Send + Sync>;
+
+/// Blocks private, shared (CGNAT), loopback and link-local IP addresses
+///
+/// Addresses without an IP component (DNS, /memory) are allowed.
+pub fn deny_private_ranges() -> impl Fn(&Multiaddr) -> bool + Send + Sync + Clone + 'static {
+    |addr: &Multiaddr| {
+        !addr.iter().any(|protocol| match protocol {
+            Protocol::Ip4(ip) => is_private_ipv4(&ip),
+            Protocol::Ip6(ip) => is_private_ipv6(&ip),
+            _ => false,
+        })
+    }
+}
+
+pub(crate) fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 100.64.0.0/10 shared address space (carrier-grade NAT)
+    let shared = first == 100 && (second & 0xc0) == 64;
+    ip.is_private() || shared || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified()
+}
+
+pub(crate) fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_private_ipv4(&ipv4);
+    }
+    let first = ip.segments()[0];
+    // fc00::/7 unique local, fe80::/10 link-local
+    ip.is_loopback() || ip.is_unspecified() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+}
@@ -0,0 +1,40 @@
+//! BehaviourHandler implementation for PeerFilterBehaviour
+
+use async_trait::async_trait;
+use command_swarm::BehaviourHandler;
+use tracing::{debug, info};
+
+use super::behaviour::{PeerFilterBehaviour, PeerFilterEvent};
+use super::command::PeerFilterCommand;
+
+/// Handler for PeerFilterBehaviour
+#[derive(Default)]
+pub struct PeerFilterHandler;
+
+#[async_trait]
+impl BehaviourHandler for PeerFilterHandler {
+    type Behaviour = PeerFilterBehaviour;
+    type Event = PeerFilterEvent;
+    type Command = PeerFilterCommand;
+
+    async fn handle_cmd(&mut self, behaviour: &mut Self::Behaviour, cmd: Self::Command) {
+        match cmd {
+            PeerFilterCommand::IsPeerAllowed { peer_id, response } => {
+                let allowed = behaviour.is_peer_allowed(&peer_id);
+                debug!("📊 [PeerFilterHandler] Peer {} allowed: {}", peer_id, allowed);
+                let _ = response.send(Ok(allowed));
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, _behaviour: &mut Self::Behaviour, event: &Self::Event) {
+        match event {
+            PeerFilterEvent::InboundDenied { peer_id, remote_addr } => {
+                info!("🚫 [PeerFilterHandler] Inbound connection denied: {} from {}", peer_id, remote_addr);
+            }
+            PeerFilterEvent::OutboundDenied { peer_id } => {
+                info!("🚫 [PeerFilterHandler] Outbound connection denied: {}", peer_id);
+            }
+        }
+    }
+}
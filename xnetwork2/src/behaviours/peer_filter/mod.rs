@@ -0,0 +1,17 @@
+//! Peer filter behaviour for XNetwork2
+//!
+//! Connection-level allowlist/denylist gate consulted before any protocol
+//! (including authentication) runs on a connection.
+
+pub mod behaviour;
+pub mod command;
+pub mod filter;
+pub mod handler_impl;
+
+// Re-export for convenience
+pub use behaviour::{PeerFilterBehaviour, PeerFilterEvent};
+pub use command::PeerFilterCommand;
+pub use filter::{
+    deny_private_ranges, AddressRewriter, AllowList, DenyList, DialAddressFilter, PeerFilter,
+};
+pub use handler_impl::PeerFilterHandler;
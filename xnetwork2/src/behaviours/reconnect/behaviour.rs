@@ -0,0 +1,183 @@
+//! NetworkBehaviour re-dialing sticky peers
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use libp2p::core::transport::PortUse;
+use libp2p::core::Endpoint;
+use libp2p::swarm::{
+    dummy, ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler,
+    THandlerInEvent, THandlerOutEvent, ToSwarm,
+};
+use libp2p::{Multiaddr, PeerId};
+use tokio::time::Sleep;
+
+/// Backoff stops doubling after this many failed attempts
+pub const MAX_BACKOFF_DOUBLINGS: u32 = 6;
+
+/// Peer that should stay connected
+struct StickyPeer {
+    address: Multiaddr,
+    /// Attempts made since the last established connection
+    attempt: u32,
+    /// Scheduled re-dial, None while connected or dialing
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+/// Events emitted by ReconnectBehaviour
+#[derive(Debug)]
+pub enum ReconnectEvent {
+    /// Re-dial of a sticky peer is due
+    ///
+    /// The swarm handler dials `address`, so the dial goes through the address
+    /// filter, the rewriter and the dial limiter like any other dial.
+    Attempt { peer_id: PeerId, attempt: u32, address: Multiaddr },
+}
+
+/// Re-dials sticky peers after their last connection closes
+pub struct ReconnectBehaviour {
+    peers: HashMap<PeerId, StickyPeer>,
+    backoff: Duration,
+}
+
+impl ReconnectBehaviour {
+    /// Create a behaviour for the given peers with the initial backoff
+    pub fn new(peers: Vec<(PeerId, Multiaddr)>, backoff: Duration) -> Self {
+        Self {
+            peers: peers
+                .into_iter()
+                .map(|(peer_id, address)| {
+                    (
+                        peer_id,
+                        StickyPeer {
+                            address,
+                            attempt: 0,
+                            timer: None,
+                        },
+                    )
+                })
+                .collect(),
+            backoff,
+        }
+    }
+
+    /// Stop re-dialing a peer, returns true if it was sticky
+    pub fn remove_peer(&mut self, peer_id: &PeerId) -> bool {
+        self.peers.remove(peer_id).is_some()
+    }
+
+    /// Stop re-dialing all peers
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Sticky peers and their addresses
+    pub fn peers(&self) -> Vec<(PeerId, Multiaddr)> {
+        self.peers
+            .iter()
+            .map(|(peer_id, peer)| (*peer_id, peer.address.clone()))
+            .collect()
+    }
+
+    /// Schedule the next attempt after a re-dial that could not be issued
+    pub fn dial_not_issued(&mut self, peer_id: &PeerId) {
+        if self.peers.get(peer_id).is_some_and(|p| p.attempt > 0 && p.timer.is_none()) {
+            self.schedule(peer_id);
+        }
+    }
+
+    /// Delay before the given attempt: backoff, 2 * backoff, 4 * backoff, ...
+    fn delay(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(MAX_BACKOFF_DOUBLINGS);
+        self.backoff * 2u32.pow(doublings)
+    }
+
+    fn schedule(&mut self, peer_id: &PeerId) {
+        let Some(attempt) = self.peers.get(peer_id).map(|p| p.attempt + 1) else {
+            return;
+        };
+        let delay = self.delay(attempt);
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.attempt = attempt;
+            peer.timer = Some(Box::pin(tokio::time::sleep(delay)));
+        }
+    }
+}
+
+impl NetworkBehaviour for ReconnectBehaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = ReconnectEvent;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _peer: PeerId,
+        _local_addr: &Multiaddr,
+        _remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _peer: PeerId,
+        _addr: &Multiaddr,
+        _role_override: Endpoint,
+        _port_use: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        match event {
+            FromSwarm::ConnectionEstablished(established) => {
+                if let Some(peer) = self.peers.get_mut(&established.peer_id) {
+                    peer.attempt = 0;
+                    peer.timer = None;
+                }
+            }
+            FromSwarm::ConnectionClosed(closed) if closed.remaining_established == 0 => {
+                self.schedule(&closed.peer_id);
+            }
+            FromSwarm::DialFailure(failure) => {
+                if let Some(peer_id) = failure.peer_id {
+                    // Повторяем только наши собственные попытки, а не чужие dial
+                    if self.peers.get(&peer_id).is_some_and(|p| p.attempt > 0) {
+                        self.schedule(&peer_id);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _peer_id: PeerId,
+        _connection_id: ConnectionId,
+        _event: THandlerOutEvent<Self>,
+    ) {
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        for (peer_id, peer) in self.peers.iter_mut() {
+            let Some(timer) = peer.timer.as_mut() else {
+                continue;
+            };
+            if timer.as_mut().poll(cx).is_ready() {
+                peer.timer = None;
+                return Poll::Ready(ToSwarm::GenerateEvent(ReconnectEvent::Attempt {
+                    peer_id: *peer_id,
+                    attempt: peer.attempt,
+                    address: peer.address.clone(),
+                }));
+            }
+        }
+
+        Poll::Pending
+    }
+}
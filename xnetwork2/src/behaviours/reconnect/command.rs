@@ -0,0 +1,13 @@
+//! Reconnect commands for XNetwork2
+
+use libp2p::{Multiaddr, PeerId};
+use tokio::sync::oneshot;
+
+/// Commands for Reconnect behaviour
+#[derive(Debug)]
+pub enum ReconnectCommand {
+    /// Get peers the node keeps re-dialing
+    GetStickyPeers {
+        response: oneshot::Sender<Result<Vec<(PeerId, Multiaddr)>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+}
@@ -0,0 +1,40 @@
+//! BehaviourHandler implementation for ReconnectBehaviour
+
+use async_trait::async_trait;
+use command_swarm::BehaviourHandler;
+use tracing::{debug, info};
+
+use super::behaviour::{ReconnectBehaviour, ReconnectEvent};
+use super::command::ReconnectCommand;
+
+/// Handler for ReconnectBehaviour
+#[derive(Default)]
+pub struct ReconnectHandler;
+
+#[async_trait]
+impl BehaviourHandler for ReconnectHandler {
+    type Behaviour = ReconnectBehaviour;
+    type Event = ReconnectEvent;
+    type Command = ReconnectCommand;
+
+    async fn handle_cmd(&mut self, behaviour: &mut Self::Behaviour, cmd: Self::Command) {
+        match cmd {
+            ReconnectCommand::GetStickyPeers { response } => {
+                let peers = behaviour.peers();
+                debug!("📊 [ReconnectHandler] Sticky peers: {:?}", peers);
+                let _ = response.send(Ok(peers));
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, _behaviour: &mut Self::Behaviour, event: &Self::Event) {
+        match event {
+            ReconnectEvent::Attempt { peer_id, attempt, .. } => {
+                info!(
+                    "🔁 [ReconnectHandler] Reconnecting to {} (attempt {})",
+                    peer_id, attempt
+                );
+            }
+        }
+    }
+}
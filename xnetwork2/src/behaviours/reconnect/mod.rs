@@ -0,0 +1,13 @@
+//! Auto-reconnect behaviour for XNetwork2
+//!
+//! Re-dials "sticky" peers with exponential backoff whenever their
+//! last connection closes, until they are explicitly disconnected.
+
+pub mod behaviour;
+pub mod command;
+pub mod handler_impl;
+
+// Re-export for convenience
+pub use behaviour::{ReconnectBehaviour, ReconnectEvent, MAX_BACKOFF_DOUBLINGS};
+pub use command::ReconnectCommand;
+pub use handler_impl::ReconnectHandler;
@@ -2,6 +2,7 @@
 
 use libp2p::{PeerId, swarm::ConnectionId};
 use tokio::sync::oneshot;
+use xauth::definitions::RejectCode;
 
 /// Commands for XAuth behaviour
 #[derive(Debug)]
@@ -19,4 +20,10 @@ pub enum XAuthCommand {
     RejectAuth { peer_id: PeerId },
     /// Submit PoR verification result
     SubmitPorVerification { peer_id: PeerId, approved: bool },
+    /// Reject PoR verification with a typed reason
+    RejectPorVerification {
+        peer_id: PeerId,
+        code: RejectCode,
+        message: String,
+    },
 }
@@ -123,6 +123,37 @@ impl BehaviourHandler for XAuthHandler {
                     );
                 }
             }
+            XAuthCommand::RejectPorVerification { peer_id, code, message } => {
+                debug!(
+                    "🔄 [XAuthHandler] Processing RejectPorVerification command for peer: {:?}, code: {:?}",
+                    peer_id, code
+                );
+
+                if let Some((connection_id, _verification)) =
+                    behaviour.get_pending_verification(&peer_id)
+                {
+                    let result = xauth::definitions::AuthResult::reject(code, message);
+                    match behaviour.submit_por_verification_result(connection_id, result) {
+                        Ok(_) => {
+                            info!(
+                                "🚫 [XAuthHandler] PoR verification rejected for peer: {:?}, code: {:?}",
+                                peer_id, code
+                            );
+                        }
+                        Err(e) => {
+                            debug!(
+                                "❌ [XAuthHandler] Failed to reject PoR verification for peer {:?}: {}",
+                                peer_id, e
+                            );
+                        }
+                    }
+                } else {
+                    debug!(
+                        "❌ [XAuthHandler] No pending PoR verification found for peer: {:?}",
+                        peer_id
+                    );
+                }
+            }
         }
     }
 
@@ -165,10 +196,11 @@ impl BehaviourHandler for XAuthHandler {
                 connection_id,
                 address,
                 reason,
+                code,
             } => {
                 debug!(
-                    "❌ [XAuthHandler] Outbound authentication failed - Peer: {:?}, Connection: {:?}, Address: {}, Reason: {}",
-                    peer_id, connection_id, address, reason
+                    "❌ [XAuthHandler] Outbound authentication failed - Peer: {:?}, Connection: {:?}, Address: {}, Reason: {}, Code: {:?}",
+                    peer_id, connection_id, address, reason, code
                 );
             }
             xauth::events::PorAuthEvent::InboundAuthFailure {
@@ -176,10 +208,11 @@ impl BehaviourHandler for XAuthHandler {
                 connection_id,
                 address,
                 reason,
+                code,
             } => {
                 debug!(
-                    "❌ [XAuthHandler] Inbound authentication failed - Peer: {:?}, Connection: {:?}, Address: {}, Reason: {}",
-                    peer_id, connection_id, address, reason
+                    "❌ [XAuthHandler] Inbound authentication failed - Peer: {:?}, Connection: {:?}, Address: {}, Reason: {}, Code: {:?}",
+                    peer_id, connection_id, address, reason, code
                 );
             }
             xauth::events::PorAuthEvent::AuthTimeout {
@@ -5,3 +5,16 @@ mod handler;
 
 pub use command::XAuthCommand;
 pub use handler::XAuthHandler;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use libp2p::PeerId;
+use xauth::definitions::AuthResult;
+
+/// Validates requester metadata when a PoR verification request arrives
+pub type MetadataValidator =
+    Arc<dyn Fn(&PeerId, &HashMap<String, String>) -> AuthResult + Send + Sync>;
+
+/// Decides whether authentication with a peer starts right after connecting
+pub type AutoAuthPolicy = Arc<dyn Fn(&PeerId) -> bool + Send + Sync>;
@@ -2,7 +2,7 @@
 use std::time::Duration;
 
 use libp2p::{
-    PeerId,
+    PeerId, StreamProtocol,
     identify::{self, Config},
     identity::PublicKey,
     kad, mdns, relay,
@@ -10,6 +10,7 @@ use libp2p::{
 };
 use libp2p::autonat::v2;
 
+use super::store::KadStore;
 use super::types::{XROUTES_IDENTIFY_PROTOCOL, KadMode};
 
 /// Composite behaviour for XRoutes with toggle components
@@ -21,7 +22,7 @@ pub struct XRoutesBehaviour {
     /// mDNS discovery behaviour
     pub mdns: Toggle<mdns::tokio::Behaviour>,
     /// Kademlia DHT discovery behaviour
-    pub kad: Toggle<kad::Behaviour<kad::store::MemoryStore>>,
+    pub kad: Toggle<kad::Behaviour<KadStore>>,
     /// Relay server behaviour
     pub relay_server: Toggle<relay::Behaviour>,
     /// Relay client behaviour
@@ -117,6 +118,17 @@ impl XRoutesBehaviour {
         local_public_key: PublicKey,
         config: &super::types::XRoutesConfig,
         relay_client: Option<relay::client::Behaviour>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let kad_store = KadStore::memory(local_public_key.to_peer_id());
+        Self::new_with_kad_store(local_public_key, config, relay_client, kad_store)
+    }
+
+    /// Create a new XRoutesBehaviour whose Kademlia uses the given record store
+    pub fn new_with_kad_store(
+        local_public_key: PublicKey,
+        config: &super::types::XRoutesConfig,
+        relay_client: Option<relay::client::Behaviour>,
+        kad_store: KadStore,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         // Create identify behaviour - disabled since it's already in main behaviour
 
@@ -132,7 +144,7 @@ impl XRoutesBehaviour {
         // Create mDNS behaviour
         let mdns = if config.enable_mdns {
             Toggle::from(Some(mdns::tokio::Behaviour::new(
-                mdns::Config::default(),
+                config.mdns_config(),
                 local_peer_id,
             )?))
         } else {
@@ -141,16 +153,15 @@ impl XRoutesBehaviour {
 
         // Create Kademlia behaviour
         let kad = if config.enable_kad {
-            let store = kad::store::MemoryStore::new(local_peer_id);
-            let mut kad_config = kad::Config::default();
-            kad_config.set_query_timeout(Duration::from_secs(3 * 60));
+            let mut kad_config = kad::Config::new(config.kad_protocol.clone());
+            kad_config.set_query_timeout(config.kad_query_timeout);
             kad_config.set_periodic_bootstrap_interval(Some(Duration::from_secs(5)));
             kad_config.set_replication_interval(Some(Duration::from_secs(5)));
             kad_config.set_provider_publication_interval(Some(Duration::from_secs(5)));
             
             let mut kad_behaviour = kad::Behaviour::with_config(
                 local_peer_id,
-                store,
+                kad_store,
                 kad_config,
             );
             
@@ -267,13 +278,14 @@ impl XRoutesBehaviour {
         self.identify = Toggle::from(None);
     }
 
-    /// Enable mDNS behaviour
+    /// Enable mDNS behaviour with the given config
     pub fn enable_mdns(
         &mut self,
         local_peer_id: PeerId,
+        mdns_config: mdns::Config,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.mdns = Toggle::from(Some(mdns::tokio::Behaviour::new(
-            mdns::Config::default(),
+            mdns_config,
             local_peer_id,
         )?));
         Ok(())
@@ -284,10 +296,12 @@ impl XRoutesBehaviour {
         self.mdns = Toggle::from(None);
     }
 
-    /// Enable Kademlia behaviour
-    pub fn enable_kad(&mut self, local_peer_id: PeerId) {
-        let store = kad::store::MemoryStore::new(local_peer_id);
-        let kad_config = kad::Config::default();
+    /// Enable Kademlia behaviour with the given protocol name
+    ///
+    /// Kademlia enabled at runtime always starts with an empty in-memory store.
+    pub fn enable_kad(&mut self, local_peer_id: PeerId, protocol: StreamProtocol) {
+        let store = KadStore::memory(local_peer_id);
+        let kad_config = kad::Config::new(protocol);
         self.kad = Toggle::from(Some(kad::Behaviour::with_config(
             local_peer_id,
             store,
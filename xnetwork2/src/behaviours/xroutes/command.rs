@@ -80,6 +80,13 @@ pub enum XRoutesCommand {
         /// Response channel with closest peers
         response: tokio::sync::oneshot::Sender<Result<Vec<PeerId>, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Get closest peers to an arbitrary key through Kademlia DHT
+    GetClosestPeersToKey {
+        /// Key to search for
+        key: Vec<u8>,
+        /// Response channel with closest peers, empty if the routing table is empty
+        response: tokio::sync::oneshot::Sender<Result<Vec<PeerId>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Find peer addresses with automatic search and timeout
     FindPeerAddresses {
         /// Peer ID to find
@@ -132,18 +139,42 @@ pub enum XRoutesCommand {
         /// Response channel for completion
         response: tokio::sync::oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
     },
-    /// Set Kademlia mode (client, server, auto)
-    SetKadMode {
-        /// Mode to set
-        mode: KadMode,
-        /// Response channel for completion
-        response: tokio::sync::oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
-    },
     /// Get current Kademlia mode
+    ///
+    /// The mode is set by SwarmLevelCommand::SetKadMode, which checks for an
+    /// external address and emits NodeEvent::KadModeChanged.
     GetKadMode {
         /// Response channel with current mode
         response: tokio::sync::oneshot::Sender<Result<KadMode, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Export Kademlia routing table entries
+    ExportRoutingTable {
+        /// Response channel with peers and their addresses
+        response: tokio::sync::oneshot::Sender<Result<Vec<(PeerId, Vec<Multiaddr>)>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Seed Kademlia routing table with previously exported entries
+    ImportRoutingTable {
+        /// Peers and their addresses to add
+        entries: Vec<(PeerId, Vec<Multiaddr>)>,
+        /// Response channel with number of imported addresses
+        response: tokio::sync::oneshot::Sender<Result<usize, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Store a record locally and publish it to the DHT
+    PutRecord {
+        /// Record key
+        key: Vec<u8>,
+        /// Record value
+        value: Vec<u8>,
+        /// Response channel, completes once the record is in the local store
+        response: tokio::sync::oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get a record from the local Kademlia store
+    GetLocalRecord {
+        /// Record key
+        key: Vec<u8>,
+        /// Response channel with the record value, None if absent
+        response: tokio::sync::oneshot::Sender<Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Get all connections
     GetConnections {
         /// Response channel with all connections
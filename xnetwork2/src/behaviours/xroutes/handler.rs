@@ -4,6 +4,7 @@
 use async_trait::async_trait;
 use command_swarm::{BehaviourHandler, ConnectionId};
 use libp2p::identity::PublicKey;
+use libp2p::kad::store::RecordStore;
 use libp2p::{identify, mdns, kad, identity, PeerId, Multiaddr};
 use std::collections::HashMap;
 use std::time::{SystemTime, Duration};
@@ -13,7 +14,7 @@ use tracing::{debug, info};
 use super::behaviour::{XRoutesBehaviour, XRoutesBehaviourEvent};
 use super::command::{XRoutesCommand, MdnsCacheStatus};
 use super::pending_task_manager::PendingTaskManager;
-use super::types::{XRoutesConfig, XROUTES_IDENTIFY_PROTOCOL};
+use super::types::{KadQueryError, XRoutesConfig, XROUTES_IDENTIFY_PROTOCOL};
 use crate::conntracker::{ConnectionInfo, PeerConnections, ConnectionStats};
 
 /// Record for mDNS peer with TTL
@@ -131,6 +132,10 @@ impl XRoutesHandler {
         // Group addresses by peer_id
         let mut peers_by_id: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
         for (peer_id, addr) in list {
+            if !self.config.mdns_address_allowed(addr) {
+                debug!("🚫 [XRoutesHandler] Skipping mDNS address outside selected interface: {} -> {}", peer_id, addr);
+                continue;
+            }
             peers_by_id.entry(*peer_id).or_default().push(addr.clone());
         }
 
@@ -273,20 +278,21 @@ impl XRoutesHandler {
                         }
                     }
                     kad::QueryResult::GetClosestPeers(Err(e)) => {
+                        // Единственная ошибка GetClosestPeers - запрос не сошелся за kad_query_timeout
                         // Сначала проверяем задачи FindPeerAddresses с таймаутом
                         if let Some(target_peer_id) = self.kad_state.find_addresses_tasks.get_task_extra(&id) {
                             let error_msg = format!("{:?}", e);
-                            let _ = self.kad_state.find_addresses_tasks.set_task_error(&id, e.into());
+                            let _ = self.kad_state.find_addresses_tasks.set_task_error(&id, Box::new(KadQueryError::QueryTimeout));
                             debug!("❌ [XRoutesHandler] Find peer addresses failed for peer {:?}: {}", target_peer_id, error_msg);
                         }
                         // Затем проверяем обычные операции FindPeer
                         else if let Some((target_peer_id, response)) = self.kad_state.pending_find_peer.remove(&id) {
                             let error_msg = format!("{:?}", e);
-                            let _ = response.send(Err(e.into()));
+                            let _ = response.send(Err(Box::new(KadQueryError::QueryTimeout)));
                             debug!("❌ [XRoutesHandler] Find peer failed for peer {:?}: {}", target_peer_id, error_msg);
                         } else if let Some(response) = self.kad_state.pending_closest_peers.remove(&id) {
                             let error_msg = format!("{:?}", e);
-                            let _ = response.send(Err(e.into()));
+                            let _ = response.send(Err(Box::new(KadQueryError::QueryTimeout)));
                             debug!("❌ [XRoutesHandler] Get closest peers failed: {}", error_msg);
                         }
                     }
@@ -335,7 +341,7 @@ impl BehaviourHandler for XRoutesHandler {
             XRoutesCommand::EnableMdns { response } => {
                 debug!("🔄 [XRoutesHandler] Enabling mDNS behaviour");
             
-                if let Err(e) = behaviour.enable_mdns(self.local_peer_id) {
+                if let Err(e) = behaviour.enable_mdns(self.local_peer_id, self.config.mdns_config()) {
                     debug!("❌ [XRoutesHandler] Failed to enable mDNS: {}", e);
                     let _ = response.send(Err(e.into()));
                 } else {
@@ -352,7 +358,7 @@ impl BehaviourHandler for XRoutesHandler {
             }
             XRoutesCommand::EnableKad { response } => {
                 debug!("🔄 [XRoutesHandler] Enabling Kademlia behaviour");
-                behaviour.enable_kad(self.local_peer_id);
+                behaviour.enable_kad(self.local_peer_id, self.config.kad_protocol.clone());
                 info!("✅ [XRoutesHandler] Kademlia behaviour enabled");
                 let _ = response.send(Ok(()));
             }
@@ -431,6 +437,23 @@ impl BehaviourHandler for XRoutesHandler {
                     debug!("❌ [XRoutesHandler] Cannot get closest peers: Kademlia not enabled");
                 }
             }
+            XRoutesCommand::GetClosestPeersToKey { key, response } => {
+                debug!("🔄 [XRoutesHandler] Get closest peers for key of {} bytes", key.len());
+                if let Some(kad) = behaviour.kad.as_mut() {
+                    // Пустая таблица маршрутизации: спрашивать некого
+                    if kad.kbuckets().all(|bucket| bucket.num_entries() == 0) {
+                        let _ = response.send(Ok(Vec::new()));
+                        debug!("🔍 [XRoutesHandler] Routing table is empty, no closest peers");
+                    } else {
+                        let query_id = kad.get_closest_peers(key);
+                        self.kad_state.pending_closest_peers.insert(query_id, response);
+                        info!("✅ [XRoutesHandler] Get closest peers to key started (query_id: {:?})", query_id);
+                    }
+                } else {
+                    let _ = response.send(Err("Kademlia behaviour not enabled".into()));
+                    debug!("❌ [XRoutesHandler] Cannot get closest peers: Kademlia not enabled");
+                }
+            }
             XRoutesCommand::FindPeerAddresses { peer_id, timeout, response } => {
                 debug!("🔄 [XRoutesHandler] Find peer addresses with timeout: {:?} for peer: {:?}", timeout, peer_id);
                 if let Some(kad) = behaviour.kad.as_mut() {
@@ -518,7 +541,7 @@ impl BehaviourHandler for XRoutesHandler {
                 // Set custom TTL
                 self.mdns_state.default_ttl_seconds = ttl_seconds;
                 
-                if let Err(e) = behaviour.enable_mdns(self.local_peer_id) {
+                if let Err(e) = behaviour.enable_mdns(self.local_peer_id, self.config.mdns_config()) {
                     debug!("❌ [XRoutesHandler] Failed to enable mDNS with TTL: {}", e);
                     let _ = response.send(Err(e.into()));
                 } else {
@@ -542,20 +565,6 @@ impl BehaviourHandler for XRoutesHandler {
                 info!("⚠️ [XRoutesHandler] AutoNAT v2 servers are discovered automatically, manual server addition may not be needed");
                 let _ = response.send(Ok(()));
             }
-            XRoutesCommand::SetKadMode { mode, response } => {
-                debug!("🔄 [XRoutesHandler] Setting Kademlia mode to: {:?}", mode);
-                
-                match behaviour.set_kad_mode(mode) {
-                    Ok(()) => {
-                        info!("✅ [XRoutesHandler] Kademlia mode set to: {}", mode);
-                        let _ = response.send(Ok(()));
-                    }
-                    Err(e) => {
-                        debug!("❌ [XRoutesHandler] Failed to set Kademlia mode: {}", e);
-                        let _ = response.send(Err(e));
-                    }
-                }
-            }
             XRoutesCommand::GetKadMode { response } => {
                 debug!("🔄 [XRoutesHandler] Getting current Kademlia mode");
                 
@@ -571,6 +580,79 @@ impl BehaviourHandler for XRoutesHandler {
                 }
             }
             // ConnectionTracker commands are now handled by SwarmHandler
+            XRoutesCommand::ExportRoutingTable { response } => {
+                debug!("🔄 [XRoutesHandler] Exporting Kademlia routing table");
+
+                if let Some(kad) = behaviour.kad.as_mut() {
+                    let mut entries: Vec<(PeerId, Vec<Multiaddr>)> = Vec::new();
+                    for bucket in kad.kbuckets() {
+                        for entry in bucket.iter() {
+                            let addresses: Vec<Multiaddr> = entry.node.value.iter().cloned().collect();
+                            // Записи без адресов устарели — их нельзя использовать для повторного подключения
+                            if addresses.is_empty() {
+                                continue;
+                            }
+                            entries.push((*entry.node.key.preimage(), addresses));
+                        }
+                    }
+                    info!("✅ [XRoutesHandler] Exported {} routing table entries", entries.len());
+                    let _ = response.send(Ok(entries));
+                } else {
+                    let _ = response.send(Err("Kademlia behaviour not enabled".into()));
+                }
+            }
+            XRoutesCommand::PutRecord { key, value, response } => {
+                debug!("🔄 [XRoutesHandler] Putting Kademlia record ({} bytes)", value.len());
+
+                if let Some(kad) = behaviour.kad.as_mut() {
+                    let record = kad::Record::new(kad::RecordKey::new(&key), value);
+                    // put_record сохраняет запись локально до запуска публикации
+                    match kad.put_record(record, kad::Quorum::One) {
+                        Ok(query_id) => {
+                            info!("✅ [XRoutesHandler] Record stored, publishing (query_id: {:?})", query_id);
+                            let _ = response.send(Ok(()));
+                        }
+                        Err(e) => {
+                            debug!("❌ [XRoutesHandler] Failed to store record: {:?}", e);
+                            let _ = response.send(Err(Box::new(e)));
+                        }
+                    }
+                } else {
+                    let _ = response.send(Err("Kademlia behaviour not enabled".into()));
+                }
+            }
+            XRoutesCommand::GetLocalRecord { key, response } => {
+                if let Some(kad) = behaviour.kad.as_mut() {
+                    let value = kad
+                        .store_mut()
+                        .get(&kad::RecordKey::new(&key))
+                        .map(|record| record.value.clone());
+                    let _ = response.send(Ok(value));
+                } else {
+                    let _ = response.send(Err("Kademlia behaviour not enabled".into()));
+                }
+            }
+            XRoutesCommand::ImportRoutingTable { entries, response } => {
+                debug!("🔄 [XRoutesHandler] Importing {} routing table entries", entries.len());
+
+                if let Some(kad) = behaviour.kad.as_mut() {
+                    let mut imported = 0;
+                    for (peer_id, addresses) in entries {
+                        if peer_id == self.local_peer_id {
+                            continue;
+                        }
+                        for addr in addresses {
+                            if let kad::RoutingUpdate::Success = kad.add_address(&peer_id, addr) {
+                                imported += 1;
+                            }
+                        }
+                    }
+                    info!("✅ [XRoutesHandler] Imported {} routing table addresses", imported);
+                    let _ = response.send(Ok(imported));
+                } else {
+                    let _ = response.send(Err("Kademlia behaviour not enabled".into()));
+                }
+            }
             XRoutesCommand::GetConnections { response } => {
                 debug!("🔄 [XRoutesHandler] ConnectionTracker commands are now handled by SwarmHandler");
                 let _ = response.send(Err("ConnectionTracker commands are now handled by SwarmHandler. Use SwarmLevelCommand::ConnectionTracker instead.".into()));
@@ -607,6 +689,11 @@ impl BehaviourHandler for XRoutesHandler {
             XRoutesBehaviourEvent::Identify(identify_event) => {
                 match identify_event {
                     identify::Event::Received { peer_id, info, .. } => {
+                        // Пиры с другим именем протокола Kademlia не попадают в таблицу маршрутизации
+                        if !info.protocols.contains(&self.config.kad_protocol) {
+                            debug!("⚠️ [XRoutesHandler] Peer {} does not support Kademlia protocol {}, skipping", peer_id, self.config.kad_protocol);
+                            return;
+                        }
                         // Добавляем адреса в Kademlia DHT
                         if let Some(kad) = behaviour.kad.as_mut() {
                             for addr in &info.listen_addrs {
@@ -6,10 +6,12 @@ mod behaviour;
 mod command;
 mod handler;
 mod pending_task_manager;
+mod store;
 pub mod types;
 
 pub use behaviour::{XRoutesBehaviour, XRoutesBehaviourEvent};
 pub use command::{XRoutesCommand, MdnsCacheStatus};
 pub use handler::XRoutesHandler;
 pub use pending_task_manager::PendingTaskManager;
-pub use types::{XRoutesConfig, XRoutesStatus};
+pub use store::{FileRecordStore, KadStore};
+pub use types::{KadQueryError, XRoutesConfig, XRoutesStatus, is_address_on_interface};
@@ -0,0 +1,274 @@
+//! Pluggable Kademlia record stores
+//!
+//! `RecordStore` uses generic iterator types and can't be boxed directly, so
+//! `KadStore` wraps any store behind an object-safe adapter.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use libp2p::PeerId;
+use libp2p::kad::store::{self, MemoryStore, RecordStore};
+use libp2p::kad::{ProviderRecord, Record, RecordKey};
+
+/// Object-safe view of a `RecordStore`
+trait DynRecordStore: Send {
+    fn get(&self, key: &RecordKey) -> Option<Cow<'_, Record>>;
+    fn put(&mut self, record: Record) -> store::Result<()>;
+    fn remove(&mut self, key: &RecordKey);
+    fn records(&self) -> Box<dyn Iterator<Item = Cow<'_, Record>> + '_>;
+    fn add_provider(&mut self, record: ProviderRecord) -> store::Result<()>;
+    fn providers(&self, key: &RecordKey) -> Vec<ProviderRecord>;
+    fn provided(&self) -> Box<dyn Iterator<Item = Cow<'_, ProviderRecord>> + '_>;
+    fn remove_provider(&mut self, key: &RecordKey, provider: &PeerId);
+}
+
+impl<S: RecordStore + Send + 'static> DynRecordStore for S {
+    fn get(&self, key: &RecordKey) -> Option<Cow<'_, Record>> {
+        RecordStore::get(self, key)
+    }
+
+    fn put(&mut self, record: Record) -> store::Result<()> {
+        RecordStore::put(self, record)
+    }
+
+    fn remove(&mut self, key: &RecordKey) {
+        RecordStore::remove(self, key)
+    }
+
+    fn records(&self) -> Box<dyn Iterator<Item = Cow<'_, Record>> + '_> {
+        Box::new(RecordStore::records(self))
+    }
+
+    fn add_provider(&mut self, record: ProviderRecord) -> store::Result<()> {
+        RecordStore::add_provider(self, record)
+    }
+
+    fn providers(&self, key: &RecordKey) -> Vec<ProviderRecord> {
+        RecordStore::providers(self, key)
+    }
+
+    fn provided(&self) -> Box<dyn Iterator<Item = Cow<'_, ProviderRecord>> + '_> {
+        Box::new(RecordStore::provided(self))
+    }
+
+    fn remove_provider(&mut self, key: &RecordKey, provider: &PeerId) {
+        RecordStore::remove_provider(self, key, provider)
+    }
+}
+
+/// Boxed record store used by the Kademlia behaviour
+pub struct KadStore {
+    inner: Box<dyn DynRecordStore>,
+}
+
+impl KadStore {
+    /// Wrap any record store
+    pub fn new<S: RecordStore + Send + 'static>(store: S) -> Self {
+        Self { inner: Box::new(store) }
+    }
+
+    /// In-memory store, records are lost on restart
+    pub fn memory(local_peer_id: PeerId) -> Self {
+        Self::new(MemoryStore::new(local_peer_id))
+    }
+}
+
+impl std::fmt::Debug for KadStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KadStore").finish_non_exhaustive()
+    }
+}
+
+impl RecordStore for KadStore {
+    type RecordsIter<'a> = Box<dyn Iterator<Item = Cow<'a, Record>> + 'a>;
+    type ProvidedIter<'a> = Box<dyn Iterator<Item = Cow<'a, ProviderRecord>> + 'a>;
+
+    fn get(&self, key: &RecordKey) -> Option<Cow<'_, Record>> {
+        self.inner.get(key)
+    }
+
+    fn put(&mut self, record: Record) -> store::Result<()> {
+        self.inner.put(record)
+    }
+
+    fn remove(&mut self, key: &RecordKey) {
+        self.inner.remove(key)
+    }
+
+    fn records(&self) -> Self::RecordsIter<'_> {
+        self.inner.records()
+    }
+
+    fn add_provider(&mut self, record: ProviderRecord) -> store::Result<()> {
+        self.inner.add_provider(record)
+    }
+
+    fn providers(&self, key: &RecordKey) -> Vec<ProviderRecord> {
+        self.inner.providers(key)
+    }
+
+    fn provided(&self) -> Self::ProvidedIter<'_> {
+        self.inner.provided()
+    }
+
+    fn remove_provider(&mut self, key: &RecordKey, provider: &PeerId) {
+        self.inner.remove_provider(key, provider)
+    }
+}
+
+/// Record store persisting records to a directory, one file per record
+///
+/// Records are served from memory and written through to disk. Each file is
+/// written to a temporary name and renamed, so a crash never leaves a partial
+/// record behind. Expiry times and provider records are not persisted.
+pub struct FileRecordStore {
+    dir: PathBuf,
+    memory: MemoryStore,
+}
+
+impl FileRecordStore {
+    /// Open the store, loading records previously saved in `dir`
+    pub fn open(
+        local_peer_id: PeerId,
+        dir: impl AsRef<Path>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut memory = MemoryStore::new(local_peer_id);
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "record") {
+                continue;
+            }
+            // Поврежденный файл не должен мешать загрузке остальных записей
+            let record = match fs::read(&path).map_err(Into::into).and_then(|data| decode_record(&data)) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("⚠️ [FileRecordStore] Skipping unreadable record {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            memory
+                .put(record)
+                .map_err(|e| format!("Failed to load record {}: {:?}", path.display(), e))?;
+        }
+
+        Ok(Self { dir, memory })
+    }
+
+    /// Directory the records are saved in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn record_path(&self, key: &RecordKey) -> PathBuf {
+        let name: String = key.to_vec().iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(format!("{}.record", name))
+    }
+}
+
+impl RecordStore for FileRecordStore {
+    type RecordsIter<'a> = <MemoryStore as RecordStore>::RecordsIter<'a>;
+    type ProvidedIter<'a> = <MemoryStore as RecordStore>::ProvidedIter<'a>;
+
+    fn get(&self, key: &RecordKey) -> Option<Cow<'_, Record>> {
+        self.memory.get(key)
+    }
+
+    fn put(&mut self, record: Record) -> store::Result<()> {
+        let path = self.record_path(&record.key);
+        let encoded = encode_record(&record);
+        self.memory.put(record)?;
+        // Запись на диск не должна ломать работу DHT, при ошибке запись остается в памяти
+        if let Err(e) = write_atomically(&path, &encoded) {
+            tracing::warn!("⚠️ [FileRecordStore] Failed to save record {}: {}", path.display(), e);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &RecordKey) {
+        self.memory.remove(key);
+        let path = self.record_path(key);
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("⚠️ [FileRecordStore] Failed to remove record {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn records(&self) -> Self::RecordsIter<'_> {
+        self.memory.records()
+    }
+
+    fn add_provider(&mut self, record: ProviderRecord) -> store::Result<()> {
+        self.memory.add_provider(record)
+    }
+
+    fn providers(&self, key: &RecordKey) -> Vec<ProviderRecord> {
+        self.memory.providers(key)
+    }
+
+    fn provided(&self) -> Self::ProvidedIter<'_> {
+        self.memory.provided()
+    }
+
+    fn remove_provider(&mut self, key: &RecordKey, provider: &PeerId) {
+        self.memory.remove_provider(key, provider)
+    }
+}
+
+/// Write `data` to a temporary file next to `path`, then rename it into place
+fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("record.tmp");
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Record file layout: key, value and publisher, each prefixed with a u32 BE length
+/// (publisher length 0 means no publisher)
+fn encode_record(record: &Record) -> Vec<u8> {
+    let key = record.key.to_vec();
+    let publisher = record.publisher.map(|p| p.to_bytes()).unwrap_or_default();
+    let mut out = Vec::with_capacity(12 + key.len() + record.value.len() + publisher.len());
+    for field in [&key, &record.value, &publisher] {
+        out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        out.extend_from_slice(field);
+    }
+    out
+}
+
+fn decode_record(mut data: &[u8]) -> Result<Record, Box<dyn std::error::Error + Send + Sync>> {
+    let mut fields = Vec::with_capacity(3);
+    for _ in 0..3 {
+        let mut len = [0u8; 4];
+        data.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        // Длина из файла не проверена, выделяем память только под реально оставшиеся байты
+        if len > data.len() {
+            return Err(format!("Record field length {} exceeds remaining {} bytes", len, data.len()).into());
+        }
+        let mut field = vec![0u8; len];
+        data.read_exact(&mut field)?;
+        fields.push(field);
+    }
+    let publisher = fields.pop().unwrap();
+    let value = fields.pop().unwrap();
+    let key = fields.pop().unwrap();
+
+    let mut record = Record::new(RecordKey::from(key), value);
+    if !publisher.is_empty() {
+        record.publisher = Some(PeerId::from_bytes(&publisher)?);
+    }
+    Ok(record)
+}
@@ -1,6 +1,54 @@
 //! Types for XRoutes behaviour
 
-use libp2p::StreamProtocol;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, StreamProtocol, kad, mdns};
+
+/// Check whether the address belongs to the network of the interface address
+///
+/// Loopback interfaces match any loopback address. Other interfaces match
+/// addresses in the same /24 (IPv4) or /64 (IPv6) network.
+/// Addresses without an IP component are rejected.
+pub fn is_address_on_interface(addr: &Multiaddr, interface: IpAddr) -> bool {
+    let ip = match addr.iter().next() {
+        Some(Protocol::Ip4(ip)) => IpAddr::V4(ip),
+        Some(Protocol::Ip6(ip)) => IpAddr::V6(ip),
+        _ => return false,
+    };
+
+    if interface.is_loopback() {
+        return ip.is_loopback();
+    }
+
+    match (ip, interface) {
+        (IpAddr::V4(ip), IpAddr::V4(iface)) => ip.octets()[..3] == iface.octets()[..3],
+        (IpAddr::V6(ip), IpAddr::V6(iface)) => ip.octets()[..8] == iface.octets()[..8],
+        _ => false,
+    }
+}
+
+/// Default Kademlia query timeout
+pub const DEFAULT_KAD_QUERY_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+
+/// Error of a Kademlia lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KadQueryError {
+    /// The query did not converge within the configured query timeout
+    QueryTimeout,
+}
+
+impl fmt::Display for KadQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KadQueryError::QueryTimeout => write!(f, "Kademlia query timed out"),
+        }
+    }
+}
+
+impl std::error::Error for KadQueryError {}
 
 /// Configuration for XRoutes behaviour
 #[derive(Debug, Clone)]
@@ -9,10 +57,16 @@ pub struct XRoutesConfig {
     pub enable_identify: bool,
     /// Enable mDNS local discovery
     pub enable_mdns: bool,
+    /// Restrict mDNS discovery to the network of this interface address (None = all interfaces)
+    pub mdns_interface: Option<IpAddr>,
     /// Enable Kademlia DHT discovery (legacy, use kad_mode instead)
     pub enable_kad: bool,
     /// Kademlia mode configuration
     pub kad_mode: Option<KadMode>,
+    /// Kademlia protocol name (nodes with different names form isolated DHTs)
+    pub kad_protocol: StreamProtocol,
+    /// Kademlia queries that do not finish in time fail with KadQueryError::QueryTimeout
+    pub kad_query_timeout: Duration,
     /// Enable relay server behaviour
     pub enable_relay_server: bool,
     // relay_client теперь всегда включен, поэтому enable_relay_client убран
@@ -31,8 +85,11 @@ impl Default for XRoutesConfig {
         Self {
             enable_identify: true,
             enable_mdns: true,
+            mdns_interface: None,
             enable_kad: true,
             kad_mode: None,
+            kad_protocol: kad::PROTOCOL_NAME,
+            kad_query_timeout: DEFAULT_KAD_QUERY_TIMEOUT,
             enable_relay_server: false,
             enable_dcutr: false,
             enable_autonat_server: false,
@@ -60,6 +117,31 @@ impl XRoutesConfig {
         self
     }
 
+    /// Restrict mDNS discovery to the given interface address
+    ///
+    /// Discovered addresses outside the interface network are dropped.
+    /// `None` keeps discovery on all interfaces.
+    pub fn with_mdns_interface(mut self, interface: Option<IpAddr>) -> Self {
+        self.mdns_interface = interface;
+        self
+    }
+
+    /// Build mDNS config for the selected interface
+    pub fn mdns_config(&self) -> mdns::Config {
+        mdns::Config {
+            enable_ipv6: matches!(self.mdns_interface, Some(IpAddr::V6(_))),
+            ..Default::default()
+        }
+    }
+
+    /// Check whether an mDNS discovered address passes the interface filter
+    pub fn mdns_address_allowed(&self, addr: &Multiaddr) -> bool {
+        match self.mdns_interface {
+            Some(interface) => is_address_on_interface(addr, interface),
+            None => true,
+        }
+    }
+
     /// Enable Kademlia DHT discovery
     pub fn with_kad(mut self, enable: bool) -> Self {
         self.enable_kad = enable;
@@ -87,13 +169,39 @@ impl XRoutesConfig {
         self
     }
 
+    /// Set Kademlia protocol name
+    ///
+    /// The name must start with `/`. Nodes configured with different protocol
+    /// names do not populate each other's routing tables.
+    pub fn with_kad_protocol(
+        mut self,
+        protocol: String,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if !protocol.starts_with('/') {
+            return Err(format!("Invalid Kademlia protocol name: {}. Must start with '/'", protocol).into());
+        }
+        self.kad_protocol = StreamProtocol::try_from_owned(protocol)?;
+        Ok(self)
+    }
+
+    /// Set Kademlia query timeout
+    ///
+    /// Lookups that never converge (e.g. no reachable peers) end after this time.
+    pub fn with_kad_query_timeout(mut self, timeout: Duration) -> Self {
+        self.kad_query_timeout = timeout;
+        self
+    }
+
     /// Create configuration with all behaviours disabled
     pub fn disabled() -> Self {
         Self {
             enable_identify: false,
             enable_mdns: false,
+            mdns_interface: None,
             enable_kad: false,
             kad_mode: None,
+            kad_protocol: kad::PROTOCOL_NAME,
+            kad_query_timeout: DEFAULT_KAD_QUERY_TIMEOUT,
             enable_relay_server: false,
             enable_dcutr: false,
             enable_autonat_server: false,
@@ -1,8 +1,22 @@
 //! XStream commands for XNetwork2
 
+use std::collections::HashMap;
+
 use libp2p::PeerId;
 use tokio::sync::oneshot;
 use xstream::xstream::XStream;
+use xstream::xstream_error::StreamOpenError;
+
+/// Aggregated metrics for streams opened with the same tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamTagMetrics {
+    /// Number of streams opened with the tag
+    pub stream_count: usize,
+    /// Payload bytes written to those streams
+    pub bytes_written: u64,
+    /// Payload bytes read from those streams
+    pub bytes_read: u64,
+}
 
 /// Commands for XStream behaviour
 #[derive(Debug)]
@@ -12,6 +26,20 @@ pub enum XStreamCommand {
         /// Peer ID to open stream to
         peer_id: PeerId,
         /// Response channel for the created XStream
-        response: oneshot::Sender<Result<XStream, String>>,
+        response: oneshot::Sender<Result<XStream, StreamOpenError>>,
+    },
+    /// Open a new XStream tagged with a purpose for per-tag metrics
+    OpenStreamTagged {
+        /// Peer ID to open stream to
+        peer_id: PeerId,
+        /// Purpose of the stream, e.g. "sync" or "rpc"
+        tag: String,
+        /// Response channel for the created XStream
+        response: oneshot::Sender<Result<XStream, StreamOpenError>>,
+    },
+    /// Get metrics aggregated by stream tag
+    GetMetricsByTag {
+        /// Response channel for the per-tag metrics
+        response: oneshot::Sender<Result<HashMap<String, StreamTagMetrics>, String>>,
     },
 }
@@ -1,15 +1,80 @@
 //! Handler for XStream behaviour
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
 use command_swarm::BehaviourHandler;
-use tracing::{debug, info};
+use libp2p::PeerId;
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
 use xstream::behaviour::XStreamNetworkBehaviour;
+use xstream::counters::XStreamByteCounters;
+use xstream::types::XStreamID;
+use xstream::xstream_error::StreamOpenError;
+
+use super::command::{StreamTagMetrics, XStreamCommand};
+
+/// Streams opened with one tag
+#[derive(Default)]
+struct TagStreams {
+    /// Live counters of streams that are still open
+    open: HashMap<(PeerId, XStreamID), XStreamByteCounters>,
+    /// Totals folded in from closed streams
+    closed: StreamTagMetrics,
+}
 
-use super::command::XStreamCommand;
+impl TagStreams {
+    /// Move the counters of a closed stream into the totals
+    fn fold_closed(&mut self, key: &(PeerId, XStreamID)) -> bool {
+        let Some(counters) = self.open.remove(key) else {
+            return false;
+        };
+        self.closed.stream_count += 1;
+        self.closed.bytes_written += counters.bytes_written();
+        self.closed.bytes_read += counters.bytes_read();
+        true
+    }
+
+    fn metrics(&self) -> StreamTagMetrics {
+        StreamTagMetrics {
+            stream_count: self.closed.stream_count + self.open.len(),
+            bytes_written: self.closed.bytes_written
+                + self.open.values().map(|c| c.bytes_written()).sum::<u64>(),
+            bytes_read: self.closed.bytes_read
+                + self.open.values().map(|c| c.bytes_read()).sum::<u64>(),
+        }
+    }
+}
 
 /// Handler for XStream behaviour
 #[derive(Default)]
-pub struct XStreamHandler;
+pub struct XStreamHandler {
+    /// Tagged streams grouped by tag
+    tagged_streams: Arc<Mutex<HashMap<String, TagStreams>>>,
+}
+
+impl XStreamHandler {
+    /// Metrics aggregated by stream tag
+    pub fn metrics_by_tag(&self) -> HashMap<String, StreamTagMetrics> {
+        let tagged_streams = self.tagged_streams.lock().unwrap();
+        tagged_streams
+            .iter()
+            .map(|(tag, streams)| (tag.clone(), streams.metrics()))
+            .collect()
+    }
+
+    /// Fold the counters of a closed tagged stream into its tag totals
+    fn release_tagged_stream(&self, peer_id: PeerId, stream_id: XStreamID) {
+        let key = (peer_id, stream_id);
+        let mut tagged_streams = self.tagged_streams.lock().unwrap();
+        for streams in tagged_streams.values_mut() {
+            if streams.fold_closed(&key) {
+                break;
+            }
+        }
+    }
+}
 
 #[async_trait]
 impl BehaviourHandler for XStreamHandler {
@@ -28,6 +93,41 @@ impl BehaviourHandler for XStreamHandler {
                 // Открываем XStream к указанному пиру
                 behaviour.open_stream(peer_id, response).await;
             }
+            XStreamCommand::OpenStreamTagged { peer_id, tag, response } => {
+                debug!(
+                    "🔄 [XStreamHandler] Processing OpenStreamTagged command - Peer: {:?}, Tag: {}",
+                    peer_id, tag
+                );
+
+                // Поток создается асинхронно, поэтому регистрируем счетчики в отдельной задаче
+                let (stream_tx, stream_rx) = oneshot::channel();
+                behaviour.open_stream(peer_id, stream_tx).await;
+
+                let tagged_streams = self.tagged_streams.clone();
+                tokio::spawn(async move {
+                    let result = match stream_rx.await {
+                        Ok(result) => result,
+                        Err(_) => Err(StreamOpenError::Failed(
+                            "Stream open request was dropped".to_string(),
+                        )),
+                    };
+                    if let Ok(stream) = &result {
+                        tagged_streams
+                            .lock()
+                            .unwrap()
+                            .entry(tag)
+                            .or_default()
+                            .open
+                            .insert((stream.peer_id, stream.id), stream.counters());
+                    } else {
+                        warn!("⚠️ [XStreamHandler] Failed to open tagged stream to {:?}", peer_id);
+                    }
+                    let _ = response.send(result);
+                });
+            }
+            XStreamCommand::GetMetricsByTag { response } => {
+                let _ = response.send(Ok(self.metrics_by_tag()));
+            }
         }
     }
 
@@ -39,11 +139,12 @@ impl BehaviourHandler for XStreamHandler {
                     peer_id, stream_id
                 );
             }
-            xstream::events::XStreamEvent::StreamClosed { peer_id, stream_id } => {
+            xstream::events::XStreamEvent::StreamClosed { peer_id, stream_id, .. } => {
                 debug!(
                     "📤 [XStreamHandler] Stream closed - Peer: {:?}, Stream ID: {:?}",
                     peer_id, stream_id
                 );
+                self.release_tagged_stream(*peer_id, *stream_id);
             }
             xstream::events::XStreamEvent::StreamError {
                 peer_id,
@@ -55,6 +156,36 @@ impl BehaviourHandler for XStreamHandler {
                     peer_id, stream_id, error
                 );
             }
+            xstream::events::XStreamEvent::StreamRejected {
+                peer_id,
+                stream_id,
+                reason,
+            } => {
+                debug!(
+                    "🚫 [XStreamHandler] Stream rejected - Peer: {:?}, Stream ID: {:?}, Reason: {:?}",
+                    peer_id, stream_id, reason
+                );
+            }
+            xstream::events::XStreamEvent::StreamBackpressure {
+                peer_id,
+                stream_id,
+                buffered_bytes,
+            } => {
+                debug!(
+                    "⏸️ [XStreamHandler] Stream backpressure - Peer: {:?}, Stream ID: {:?}, Buffered: {}",
+                    peer_id, stream_id, buffered_bytes
+                );
+            }
+            xstream::events::XStreamEvent::StreamDrained {
+                peer_id,
+                stream_id,
+                buffered_bytes,
+            } => {
+                debug!(
+                    "▶️ [XStreamHandler] Stream drained - Peer: {:?}, Stream ID: {:?}, Buffered: {}",
+                    peer_id, stream_id, buffered_bytes
+                );
+            }
             xstream::events::XStreamEvent::IncomingStream { stream } => {
                 debug!(
                     " [XStreamHandler] Incoming stream - Peer: {:?}, Stream ID: {:?}",
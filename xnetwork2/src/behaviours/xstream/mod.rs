@@ -3,5 +3,33 @@
 mod command;
 mod handler;
 
-pub use command::XStreamCommand;
+pub use command::{StreamTagMetrics, XStreamCommand};
 pub use handler::XStreamHandler;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use libp2p::swarm::ConnectionId;
+use libp2p::PeerId;
+
+/// Decision of the inbound stream policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InboundDecision {
+    /// Accept the stream without asking the application
+    Accept,
+    /// Reject the stream with a reason
+    Reject(String),
+    /// Leave the decision to the application via XStreamIncomingStreamRequest
+    Defer,
+}
+
+/// Decides about inbound streams before XStreamIncomingStreamRequest is emitted
+pub type InboundStreamPolicy = Arc<dyn Fn(&PeerId, ConnectionId) -> InboundDecision + Send + Sync>;
+
+/// Time given to an AsyncInboundDecision before the stream is rejected
+pub const DEFAULT_INBOUND_DECISION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Asynchronous inbound decision, runs when the sync policy defers or is not set
+pub type AsyncInboundDecision =
+    Arc<dyn Fn(PeerId, ConnectionId) -> BoxFuture<'static, InboundDecision> + Send + Sync>;
@@ -0,0 +1,590 @@
+//! Bootstrap node as a library
+//!
+//! Запускает узел в режиме Kademlia сервера и возвращает handle с адресом
+//! для подключения, чтобы другие крейты могли встраивать bootstrap узел.
+//! BootstrapConnect подключает клиентский узел к bootstrap пирам с повторами.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+use libp2p::core::transport::ListenerId;
+use libp2p::multiaddr::Protocol;
+use libp2p::{identity, Multiaddr, PeerId};
+use tokio::sync::broadcast;
+
+use crate::behaviours::connection_limits::ConnectionLimits;
+use crate::commander::Commander;
+use crate::node::Node;
+use crate::node_builder::NodeBuilder;
+use crate::node_events::NodeEvent;
+
+/// Конфигурация bootstrap сервера
+#[derive(Debug, Clone)]
+pub struct BootstrapConfig {
+    /// Адреса для прослушивания
+    pub listen_addrs: Vec<Multiaddr>,
+    /// Ключ узла, случайный если не задан
+    pub keypair: Option<identity::Keypair>,
+    /// Включить relay сервер
+    pub enable_relay_server: bool,
+    /// Таймаут ожидания адреса прослушивания
+    pub listen_timeout: Duration,
+    /// Ограничения на количество соединений
+    pub connection_limits: ConnectionLimits,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            listen_addrs: vec!["/ip4/0.0.0.0/udp/0/quic-v1".parse().unwrap()],
+            keypair: None,
+            enable_relay_server: false,
+            listen_timeout: Duration::from_secs(5),
+            connection_limits: ConnectionLimits::unlimited(),
+        }
+    }
+}
+
+impl BootstrapConfig {
+    /// Устанавливает единственный адрес для прослушивания
+    pub fn with_listen_addr(mut self, addr: Multiaddr) -> Self {
+        self.listen_addrs = vec![addr];
+        self
+    }
+
+    /// Устанавливает адреса для прослушивания
+    pub fn with_listen_addrs(mut self, addrs: Vec<Multiaddr>) -> Self {
+        self.listen_addrs = addrs;
+        self
+    }
+
+    /// Добавляет адрес для прослушивания
+    pub fn add_listen_addr(mut self, addr: Multiaddr) -> Self {
+        self.listen_addrs.push(addr);
+        self
+    }
+
+    /// Устанавливает ключ узла
+    pub fn with_keypair(mut self, keypair: identity::Keypair) -> Self {
+        self.keypair = Some(keypair);
+        self
+    }
+
+    /// Включает relay сервер
+    pub fn with_relay_server(mut self) -> Self {
+        self.enable_relay_server = true;
+        self
+    }
+
+    /// Устанавливает ограничения на количество соединений
+    pub fn with_connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.connection_limits = limits;
+        self
+    }
+}
+
+/// Изменения, примененные при перезагрузке конфигурации
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Новые адреса прослушивания
+    pub added_listen_addrs: Vec<Multiaddr>,
+    /// Адреса, на которых узел перестал слушать
+    pub removed_listen_addrs: Vec<Multiaddr>,
+    /// Новые ограничения соединений, None если не менялись
+    pub connection_limits: Option<ConnectionLimits>,
+}
+
+impl ConfigDiff {
+    /// Конфигурация не изменилась
+    pub fn is_empty(&self) -> bool {
+        self.added_listen_addrs.is_empty()
+            && self.removed_listen_addrs.is_empty()
+            && self.connection_limits.is_none()
+    }
+}
+
+/// События bootstrap сервера и BootstrapConnect
+#[derive(Debug, Clone)]
+pub enum BootstrapEvent {
+    /// Адрес из конфигурации успешно поднят
+    Listening {
+        /// Адрес из конфигурации
+        requested: Multiaddr,
+        /// Фактический адрес прослушивания
+        address: Multiaddr,
+    },
+    /// Не удалось начать прослушивание адреса из конфигурации
+    BindFailed {
+        requested: Multiaddr,
+        error: String,
+    },
+    /// Новая конфигурация применена
+    ConfigReloaded(ConfigDiff),
+    /// BootstrapConnect начал попытку подключения, нумерация с 1
+    ConnectAttempt { peer_id: PeerId, attempt: u32 },
+    /// Попытка подключения не удалась
+    ConnectFailed {
+        peer_id: PeerId,
+        attempt: u32,
+        error: String,
+        /// Задержка до следующей попытки, None если попытки исчерпаны
+        retry_in: Option<Duration>,
+    },
+    /// Подключение к bootstrap пиру установлено
+    Connected { peer_id: PeerId, attempt: u32 },
+}
+
+/// Активный listener bootstrap сервера
+#[derive(Debug, Clone)]
+struct Listener {
+    id: ListenerId,
+    /// Адрес из конфигурации
+    requested: Multiaddr,
+    /// Фактический адрес прослушивания без /p2p
+    address: Multiaddr,
+}
+
+/// Bootstrap сервер
+pub struct BootstrapServer;
+
+impl BootstrapServer {
+    /// Создает, запускает узел и ждет адреса прослушивания
+    ///
+    /// Каждый адрес поднимается независимо, ошибка одного не мешает
+    /// остальным. Ошибка возвращается, только если не поднялся ни один.
+    pub async fn start(
+        config: BootstrapConfig,
+    ) -> Result<BootstrapHandle, Box<dyn std::error::Error + Send + Sync>> {
+        if config.listen_addrs.is_empty() {
+            return Err("No listen addresses configured".into());
+        }
+
+        let mut builder = NodeBuilder::new()
+            .with_kad_server()
+            .with_connection_limits(config.connection_limits);
+        if let Some(keypair) = config.keypair.clone() {
+            builder = builder.with_keypair(keypair);
+        }
+        if config.enable_relay_server {
+            builder = builder.with_relay_server();
+        }
+
+        let mut node = builder.build().await?;
+        node.start().await?;
+
+        let (events, _) = broadcast::channel(64);
+        let mut startup_events = Vec::new();
+        let mut listeners = Vec::new();
+        for requested in &config.listen_addrs {
+            let event = match listen(&node, requested.clone(), config.listen_timeout).await {
+                Ok(listener) => {
+                    let event = BootstrapEvent::Listening {
+                        requested: listener.requested.clone(),
+                        address: listener.address.clone(),
+                    };
+                    listeners.push(listener);
+                    event
+                }
+                Err(e) => {
+                    println!("⚠️ Bootstrap server failed to listen on {}: {}", requested, e);
+                    BootstrapEvent::BindFailed {
+                        requested: requested.clone(),
+                        error: e.to_string(),
+                    }
+                }
+            };
+            startup_events.push(event);
+        }
+
+        if listeners.is_empty() {
+            let _ = node.force_shutdown().await;
+            return Err("Failed to listen on any configured address".into());
+        }
+
+        // Адреса объявляются как внешние, чтобы клиенты получали их через Kademlia
+        for listener in &listeners {
+            node.commander.add_external_address(listener.address.clone()).await?;
+        }
+
+        let address = listeners[0].address.clone().with(Protocol::P2p(node.peer_id));
+        println!("🚀 Bootstrap server started at {}", address);
+
+        Ok(BootstrapHandle {
+            node,
+            config,
+            listeners,
+            address,
+            startup_events,
+            events,
+        })
+    }
+}
+
+/// Начинает прослушивание и ждет адрес именно этого listener
+async fn listen(
+    node: &Node,
+    addr: Multiaddr,
+    timeout: Duration,
+) -> Result<Listener, Box<dyn std::error::Error + Send + Sync>> {
+    // Подписка до listen_on, чтобы не пропустить NewListenAddr
+    let mut events = node.subscribe();
+    let listener_id = node.commander.listen_on(addr.clone()).await?;
+
+    let wait = async {
+        loop {
+            match events.recv().await {
+                Ok(NodeEvent::NewListenAddr {
+                    listener_id: id,
+                    address,
+                }) if id == listener_id => return Ok(address),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err("Node event channel closed".into())
+                }
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, wait).await {
+        Ok(Ok(address)) => Ok(Listener {
+            id: listener_id,
+            requested: addr,
+            address,
+        }),
+        Ok(Err(e)) => {
+            let _ = node.commander.remove_listener(listener_id).await;
+            Err(e)
+        }
+        Err(_) => {
+            let _ = node.commander.remove_listener(listener_id).await;
+            Err(format!("Timeout waiting for listen address on {}", addr).into())
+        }
+    }
+}
+
+/// Handle запущенного bootstrap сервера
+pub struct BootstrapHandle {
+    node: Node,
+    config: BootstrapConfig,
+    listeners: Vec<Listener>,
+    address: Multiaddr,
+    /// Listening и BindFailed события запуска, до появления подписчиков
+    startup_events: Vec<BootstrapEvent>,
+    events: broadcast::Sender<BootstrapEvent>,
+}
+
+impl BootstrapHandle {
+    /// Адрес для подключения, включая /p2p/<peer_id>
+    pub fn address(&self) -> &Multiaddr {
+        &self.address
+    }
+
+    /// Все адреса для подключения, включая /p2p/<peer_id>
+    pub fn addresses(&self) -> Vec<Multiaddr> {
+        self.listeners
+            .iter()
+            .map(|listener| listener.address.clone().with(Protocol::P2p(self.node.peer_id)))
+            .collect()
+    }
+
+    /// Peer ID bootstrap узла
+    pub fn peer_id(&self) -> PeerId {
+        self.node.peer_id
+    }
+
+    /// Commander для дополнительных команд узлу
+    pub fn commander(&self) -> &Commander {
+        &self.node.commander
+    }
+
+    /// Текущая конфигурация
+    pub fn config(&self) -> &BootstrapConfig {
+        &self.config
+    }
+
+    /// События, отправленные при запуске сервера
+    pub fn startup_events(&self) -> &[BootstrapEvent] {
+        &self.startup_events
+    }
+
+    /// Подписка на события bootstrap сервера
+    pub fn subscribe(&self) -> broadcast::Receiver<BootstrapEvent> {
+        self.events.subscribe()
+    }
+
+    /// Применяет новую конфигурацию без перезапуска узла
+    ///
+    /// Меняются адреса прослушивания и ограничения соединений. Новые адреса
+    /// поднимаются до остановки старых; адреса, не поднявшиеся ранее, пробуются
+    /// снова. Ошибка одного адреса сообщается через BindFailed, как при запуске.
+    /// Если в итоге не остается ни одного адреса, узел продолжает работать
+    /// со старой конфигурацией.
+    pub async fn reload_config(
+        &mut self,
+        config: BootstrapConfig,
+    ) -> Result<ConfigDiff, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(keypair) = &config.keypair {
+            if keypair.public().to_peer_id() != self.node.peer_id {
+                return Err("Changing keypair requires restart".into());
+            }
+        }
+        if config.enable_relay_server != self.config.enable_relay_server {
+            return Err("Changing relay server mode requires restart".into());
+        }
+        if config.listen_addrs.is_empty() {
+            return Err("No listen addresses configured".into());
+        }
+
+        let mut diff = ConfigDiff::default();
+
+        // Сравниваем с поднятыми listeners, а не с прошлой конфигурацией
+        let mut added = Vec::new();
+        let mut bind_failures = Vec::new();
+        for requested in &config.listen_addrs {
+            if self.listeners.iter().any(|listener| &listener.requested == requested) {
+                continue;
+            }
+            match listen(&self.node, requested.clone(), config.listen_timeout).await {
+                Ok(listener) => added.push(listener),
+                Err(e) => {
+                    println!("⚠️ Bootstrap server failed to listen on {}: {}", requested, e);
+                    bind_failures.push(BootstrapEvent::BindFailed {
+                        requested: requested.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        let kept_count = self
+            .listeners
+            .iter()
+            .filter(|listener| config.listen_addrs.contains(&listener.requested))
+            .count();
+        if kept_count + added.len() == 0 {
+            return Err("Failed to listen on any configured address".into());
+        }
+
+        if config.connection_limits != self.config.connection_limits {
+            if let Err(e) = self
+                .node
+                .commander
+                .set_connection_limits(config.connection_limits)
+                .await
+            {
+                self.remove_listeners(&added).await;
+                return Err(e);
+            }
+            diff.connection_limits = Some(config.connection_limits);
+        }
+
+        let (kept, removed): (Vec<Listener>, Vec<Listener>) = self
+            .listeners
+            .drain(..)
+            .partition(|listener| config.listen_addrs.contains(&listener.requested));
+        self.remove_listeners(&removed).await;
+        for listener in &removed {
+            let _ = self
+                .node
+                .commander
+                .remove_external_address(listener.address.clone())
+                .await;
+        }
+        diff.removed_listen_addrs = removed.into_iter().map(|listener| listener.address).collect();
+
+        for event in bind_failures {
+            let _ = self.events.send(event);
+        }
+        for listener in &added {
+            let _ = self
+                .node
+                .commander
+                .add_external_address(listener.address.clone())
+                .await;
+            let _ = self.events.send(BootstrapEvent::Listening {
+                requested: listener.requested.clone(),
+                address: listener.address.clone(),
+            });
+            diff.added_listen_addrs.push(listener.address.clone());
+        }
+
+        self.listeners = kept;
+        self.listeners.extend(added);
+        let address = self.listeners[0].address.clone().with(Protocol::P2p(self.node.peer_id));
+        if address != self.address {
+            println!("🔄 Bootstrap server moved to {}", address);
+            self.address = address;
+        }
+
+        self.config = config;
+        let _ = self.events.send(BootstrapEvent::ConfigReloaded(diff.clone()));
+        Ok(diff)
+    }
+
+    /// Останавливает listeners, ошибки игнорируются
+    async fn remove_listeners(&self, listeners: &[Listener]) {
+        for listener in listeners {
+            let _ = self.node.commander.remove_listener(listener.id).await;
+        }
+    }
+
+    /// Количество подключенных пиров
+    pub async fn connected_peer_count(
+        &self,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let state = self.node.commander.get_network_state().await?;
+        Ok(state.connected_peers.len())
+    }
+
+    /// Останавливает bootstrap сервер
+    pub async fn shutdown(mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.node.force_shutdown().await
+    }
+}
+
+/// Политика повторов подключения к bootstrap пирам
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Максимальное количество попыток, включая первую
+    pub max_attempts: u32,
+    /// Задержка после первой неудачи, удваивается с каждой попыткой
+    pub base_backoff: Duration,
+    /// Верхняя граница задержки
+    pub max_backoff: Duration,
+    /// Доля задержки для случайного разброса, от 0.0 до 1.0
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Одна попытка без повторов
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Задержка после неудачной попытки с номером attempt (с 1)
+    ///
+    /// Разброс применяется после ограничения, затем результат снова
+    /// ограничивается max_backoff.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .base_backoff
+            .saturating_mul(1u32 << doublings)
+            .min(self.max_backoff);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // Случайное число в [-1.0, 1.0] без отдельной зависимости
+        let random = RandomState::new().hash_one(Instant::now()) as f64 / u64::MAX as f64;
+        let factor = 1.0 + jitter * (random * 2.0 - 1.0);
+        backoff.mul_f64(factor).min(self.max_backoff)
+    }
+}
+
+/// Подключение клиентского узла к bootstrap пирам с повторами
+pub struct BootstrapConnect {
+    peers: Vec<(PeerId, Multiaddr)>,
+    retry_policy: RetryPolicy,
+    dial_timeout: Duration,
+    events: broadcast::Sender<BootstrapEvent>,
+}
+
+impl BootstrapConnect {
+    /// Создает подключение к пирам с политикой повторов по умолчанию
+    pub fn new(peers: Vec<(PeerId, Multiaddr)>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            peers,
+            retry_policy: RetryPolicy::default(),
+            dial_timeout: Duration::from_secs(5),
+            events,
+        }
+    }
+
+    /// Устанавливает политику повторов
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Устанавливает таймаут одной попытки подключения
+    pub fn with_dial_timeout(mut self, timeout: Duration) -> Self {
+        self.dial_timeout = timeout;
+        self
+    }
+
+    /// Подписка на ConnectAttempt, ConnectFailed и Connected события
+    pub fn subscribe(&self) -> broadcast::Receiver<BootstrapEvent> {
+        self.events.subscribe()
+    }
+
+    /// Подключается ко всем пирам параллельно, возвращает подключенные
+    pub async fn connect(&self, commander: &Commander) -> Vec<PeerId> {
+        let attempts = self
+            .peers
+            .iter()
+            .map(|(peer_id, address)| self.connect_peer(commander, *peer_id, address.clone()));
+        futures::future::join_all(attempts)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Повторяет подключение к одному пиру до успеха или исчерпания попыток
+    async fn connect_peer(
+        &self,
+        commander: &Commander,
+        peer_id: PeerId,
+        address: Multiaddr,
+    ) -> Option<PeerId> {
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        for attempt in 1..=max_attempts {
+            let _ = self
+                .events
+                .send(BootstrapEvent::ConnectAttempt { peer_id, attempt });
+
+            match commander
+                .dial_and_wait(peer_id, address.clone(), self.dial_timeout)
+                .await
+            {
+                Ok(_) => {
+                    let _ = self.events.send(BootstrapEvent::Connected { peer_id, attempt });
+                    return Some(peer_id);
+                }
+                Err(e) => {
+                    let retry_in =
+                        (attempt < max_attempts).then(|| self.retry_policy.backoff(attempt));
+                    println!(
+                        "⚠️ Bootstrap connect to {} failed (attempt {}): {}",
+                        peer_id, attempt, e
+                    );
+                    let _ = self.events.send(BootstrapEvent::ConnectFailed {
+                        peer_id,
+                        attempt,
+                        error: e.to_string(),
+                        retry_in,
+                    });
+                    if let Some(delay) = retry_in {
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,20 +1,133 @@
 //! Commander for sending commands to XNetwork2 node
 
+use std::collections::HashMap;
+use std::fmt;
+
+use futures::stream::{BoxStream, StreamExt};
 use libp2p::core::transport::ListenerId;
+use libp2p::swarm::dial_opts::PeerCondition;
 use libp2p::{Multiaddr, PeerId};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 
-use crate::behaviours::{XAuthCommand, XStreamCommand};
+use crate::behaviours::{
+    ConnectionLimitsCommand, PeerFilterCommand, ReconnectCommand, StreamTagMetrics, XAuthCommand, XStreamCommand,
+};
+use crate::conntracker::AddrFilter;
 use crate::conntracker::commands::ConntrackerCommand;
 use crate::main_behaviour::XNetworkCommands;
-use crate::swarm_commands::{NetworkState, SwarmLevelCommand};
+use crate::rtt::PingError;
+use crate::swarm_commands::{
+    NetworkState, NetworkStateDelta, PeerIdentifyInfo, ReservationInfo, SwarmLevelCommand,
+};
 use xstream::xstream::XStream;
+use xstream::xstream_error::StreamOpenError;
+
+/// Step of a one-shot stream send
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamStep {
+    Open,
+    Write,
+    WriteEof,
+}
+
+impl fmt::Display for StreamStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamStep::Open => write!(f, "open"),
+            StreamStep::Write => write!(f, "write"),
+            StreamStep::WriteEof => write!(f, "write EOF"),
+        }
+    }
+}
+
+/// How long open_stream_and_send waits for the peer to finish writing before closing the stream
+const ONE_SHOT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Error of open_stream_and_send tagged with the step that failed
+#[derive(Debug)]
+pub struct StreamError {
+    pub step: StreamStep,
+    pub source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stream {} failed: {}", self.step, self.source)
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Error of request_response
+#[derive(Debug)]
+pub enum ReqRespError {
+    /// Opening the stream or sending the request failed
+    Send(StreamError),
+    /// No full response within the read timeout
+    Timeout,
+    /// Reading the response failed
+    Read(std::io::Error),
+    /// The server answered with error_write
+    Remote(Vec<u8>),
+}
+
+impl fmt::Display for ReqRespError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReqRespError::Send(e) => write!(f, "{}", e),
+            ReqRespError::Timeout => write!(f, "Response read timed out"),
+            ReqRespError::Read(e) => write!(f, "Response read failed: {}", e),
+            ReqRespError::Remote(data) => {
+                write!(f, "Remote error: {}", String::from_utf8_lossy(data))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReqRespError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReqRespError::Send(e) => Some(e),
+            ReqRespError::Read(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Error of the non-blocking try_* command variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    /// The command channel is full, the node is not keeping up
+    Backpressured,
+    /// The command channel is closed, the node is stopped
+    Closed,
+    /// The node did not answer within the default response timeout
+    ResponseTimeout,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Backpressured => write!(f, "Command queue is full"),
+            CommandError::Closed => write!(f, "Command channel is closed"),
+            CommandError::ResponseTimeout => write!(f, "No response from the node in time"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
 
 /// Commander for XNetwork2 node
 #[derive(Clone)]
 pub struct Commander {
     sender: mpsc::Sender<XNetworkCommands>,
     stopper: command_swarm::SwarmLoopStopper,
+    /// Bound on waiting for a command response, None waits forever
+    response_timeout: Option<std::time::Duration>,
 }
 
 impl Commander {
@@ -23,7 +136,35 @@ impl Commander {
         sender: mpsc::Sender<XNetworkCommands>,
         stopper: command_swarm::SwarmLoopStopper,
     ) -> Self {
-        Self { sender, stopper }
+        Self {
+            sender,
+            stopper,
+            response_timeout: None,
+        }
+    }
+
+    /// Commander whose calls fail with `CommandError::ResponseTimeout` when
+    /// the node does not answer within `timeout`, instead of waiting forever
+    ///
+    /// The bound covers the whole wait, so it must exceed the own timeout of
+    /// waiting commands such as dial_and_wait.
+    pub fn with_default_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.response_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait for the response of a sent command, bounded by the default timeout
+    async fn response<T>(
+        &self,
+        response_rx: oneshot::Receiver<T>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        match self.response_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, response_rx)
+                .await
+                .map_err(|_| CommandError::ResponseTimeout)?
+                .map_err(Into::into),
+            None => Ok(response_rx.await?),
+        }
     }
 
     /// Send a command to the node
@@ -37,6 +178,19 @@ impl Commander {
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
     }
 
+    /// Send a command without waiting for free space in the command queue
+    pub fn try_send(&self, command: XNetworkCommands) -> Result<(), CommandError> {
+        self.sender.try_send(command).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => CommandError::Backpressured,
+            mpsc::error::TrySendError::Closed(_) => CommandError::Closed,
+        })
+    }
+
+    /// Number of commands waiting to be processed by the node
+    pub fn command_queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
     /// Dial a peer
     pub async fn dial(
         &self,
@@ -50,7 +204,44 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Dial a peer, failing with `CommandError::Backpressured` if the queue is full
+    pub async fn try_dial(
+        &self,
+        peer_id: PeerId,
+        addr: Multiaddr,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::Dial {
+            peer_id,
+            addr,
+            response: response_tx,
+        });
+        self.try_send(command)?;
+        self.response(response_rx).await?
+    }
+
+    /// Dial a peer with an explicit dial condition
+    ///
+    /// `PeerCondition::Always` opens another connection even if the peer is
+    /// already connected; `addresses` are tried in addition to known ones.
+    pub async fn dial_with_opts(
+        &self,
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+        condition: PeerCondition,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DialWithOpts {
+            peer_id,
+            addresses,
+            condition,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Listen on an address
@@ -64,7 +255,21 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Stop listening, returns false if the listener does not exist
+    pub async fn remove_listener(
+        &self,
+        listener_id: ListenerId,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::RemoveListener {
+            listener_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Listen on an address and wait for first listen address event
@@ -80,7 +285,25 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Listen on an address and collect every bound address within `window`
+    ///
+    /// Addresses carry the `/p2p/<local peer id>` suffix, ready to be advertised.
+    pub async fn listen_and_collect(
+        &self,
+        addr: Multiaddr,
+        window: std::time::Duration,
+    ) -> Result<Vec<Multiaddr>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::ListenAndCollect {
+            addr,
+            window,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Dial a peer and wait for connection established
@@ -93,12 +316,176 @@ impl Commander {
         let (response_tx, response_rx) = oneshot::channel();
         let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DialAndWait {
             peer_id,
-            addr,
+            addresses: vec![addr],
             timeout,
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Disconnect from a peer, closing all its connections
+    pub async fn disconnect(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::Disconnect {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Reserve a slot on a relay and listen on the relayed address
+    pub async fn request_relay_reservation(
+        &self,
+        relay_peer: PeerId,
+        relay_addr: Multiaddr,
+        timeout: std::time::Duration,
+    ) -> Result<ReservationInfo, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::RequestRelayReservation {
+            relay_peer,
+            relay_addr,
+            timeout,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Release a relay reservation and stop listening through that relay
+    pub async fn release_relay_reservation(
+        &self,
+        relay_peer: PeerId,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::ReleaseRelayReservation {
+            relay_peer,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Try to upgrade a relayed connection to a peer into a direct one
+    ///
+    /// The outcome is reported as NodeEvent::HolePunchSucceeded or HolePunchFailed
+    pub async fn direct_connect(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DirectConnect {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get reachability derived from AutoNAT client probes
+    ///
+    /// Stays `NatStatus::Unknown` until the AutoNAT client is enabled and a probe completes
+    pub async fn nat_status(
+        &self,
+    ) -> Result<crate::nat::NatStatus, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetNatStatus {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get ping round-trip times of a peer, None until the first successful ping
+    pub async fn peer_rtt(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<Option<crate::rtt::PeerRtt>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetPeerRtt {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Measure the round-trip time to a connected peer
+    ///
+    /// libp2p ping can't be triggered on demand, so this waits for the next ping
+    /// round of the connection (every second) and returns its RTT. Fails with
+    /// `PingError::Disconnected` if the peer is not connected or disconnects,
+    /// and with `PingError::Timeout` if no result arrives within `timeout`.
+    pub async fn ping(
+        &self,
+        peer_id: PeerId,
+        timeout: std::time::Duration,
+    ) -> Result<std::time::Duration, PingError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::Ping {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command)
+            .await
+            .map_err(|e| PingError::Failed(e.to_string()))?;
+        match tokio::time::timeout(timeout, response_rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(PingError::Failed("Node stopped before the ping completed".to_string())),
+            Err(_) => Err(PingError::Timeout),
+        }
+    }
+
+    /// Get the number of dials in flight and queued by the concurrency limit
+    pub async fn dial_stats(
+        &self,
+    ) -> Result<crate::swarm_commands::DialStats, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetDialStats {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get listeners, connection counts, NAT status and routing table size in one call
+    pub async fn status(
+        &self,
+    ) -> Result<crate::swarm_commands::NodeStatus, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetStatus {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Close a single connection by its id
+    pub async fn disconnect_connection(
+        &self,
+        connection_id: libp2p::swarm::ConnectionId,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DisconnectConnection {
+            connection_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Disconnect from all connected peers, returning how many were disconnected
+    pub async fn disconnect_all(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DisconnectAll {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Send echo command and get response
@@ -112,7 +499,21 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Echo, failing with `CommandError::Backpressured` if the queue is full
+    pub async fn try_echo(
+        &self,
+        message: String,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::Echo {
+            message,
+            response: response_tx,
+        });
+        self.try_send(command)?;
+        self.response(response_rx).await?
     }
 
     /// Get network state
@@ -124,7 +525,106 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Check if mutual authentication with the peer succeeded
+    ///
+    /// Reset when the last connection with the peer closes.
+    pub async fn is_peer_authenticated(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::IsPeerAuthenticated {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Number of open XStreams per peer
+    ///
+    /// Streams are counted in both directions until their first StreamClosed;
+    /// a count that only grows points to leaked streams.
+    pub async fn stream_counts(
+        &self,
+    ) -> Result<HashMap<PeerId, usize>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetStreamCounts {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Connection, stream, auth, DHT and RTT counters at this moment
+    pub async fn metrics(
+        &self,
+    ) -> Result<crate::metrics::MetricsSnapshot, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetMetrics {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Latest Identify information of a connected peer
+    ///
+    /// None until the peer's first Identify arrives and after it disconnects.
+    pub async fn peer_info(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<Option<PeerIdentifyInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::GetPeerInfo {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Whether the peer announced `protocol` in Identify
+    ///
+    /// None until the peer's Identify has been received.
+    pub async fn supports_protocol(
+        &self,
+        peer_id: PeerId,
+        protocol: &str,
+    ) -> Result<Option<bool>, Box<dyn std::error::Error + Send + Sync>> {
+        let info = self.peer_info(peer_id).await?;
+        Ok(info.map(|info| info.protocols.iter().any(|p| p.as_ref() == protocol)))
+    }
+
+    /// Subscribe to incremental NetworkState changes
+    ///
+    /// Deltas start from the moment of subscription, use get_network_state for
+    /// the initial snapshot. A subscriber that falls behind skips the missed
+    /// deltas and should fetch a new snapshot.
+    pub async fn subscribe_network_state(
+        &self,
+    ) -> Result<BoxStream<'static, NetworkStateDelta>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::SubscribeNetworkState {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        let receiver = self.response(response_rx).await??;
+
+        Ok(futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(delta) => return Some((delta, receiver)),
+                    // Пропущенные изменения восстанавливаются через get_network_state
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed())
     }
 
     /// Shutdown the node
@@ -135,7 +635,21 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Refuse new dials and streams, returns open XStreams so they can be closed
+    ///
+    /// Used by Node::shutdown, the swarm loop keeps running until stopped.
+    pub async fn begin_shutdown(
+        &self,
+    ) -> Result<Vec<XStream>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::BeginShutdown {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Submit PoR verification result
@@ -149,6 +663,21 @@ impl Commander {
         self.send(command).await
     }
 
+    /// Reject PoR verification with a machine-readable reason
+    pub async fn reject_por_verification(
+        &self,
+        peer_id: PeerId,
+        code: xauth::definitions::RejectCode,
+        message: impl Into<String>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let command = XNetworkCommands::xauth(XAuthCommand::RejectPorVerification {
+            peer_id,
+            code,
+            message: message.into(),
+        });
+        self.send(command).await
+    }
+
 
     /// Start authentication for specific connection
     pub async fn start_auth_for_connection(
@@ -161,7 +690,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Open XStream to a peer
@@ -179,12 +708,185 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?.map_err(|e| {
+        self.response(response_rx)
+            .await?
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }
+
+    /// Open XStream to a peer, failing fast if Identify says it lacks XStream
+    ///
+    /// Peers whose Identify has not arrived yet are tried as usual.
+    pub async fn open_xstream_checked(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<XStream, Box<dyn std::error::Error + Send + Sync>> {
+        let supported = self
+            .supports_protocol(peer_id, xstream::consts::XSTREAM_PROTOCOL.as_ref())
+            .await?;
+        if supported == Some(false) {
+            return Err(Box::new(StreamOpenError::ProtocolUnsupported));
+        }
+        self.open_xstream(peer_id).await
+    }
+
+    /// Open XStream to a peer, write `data` and signal EOF
+    ///
+    /// The error tells which step failed. Whatever the peer writes back is
+    /// discarded in the background and the stream is closed once the peer
+    /// finishes writing, or after 30 seconds.
+    pub async fn open_stream_and_send(
+        &self,
+        peer_id: PeerId,
+        data: Vec<u8>,
+    ) -> Result<(), StreamError> {
+        let mut stream = self.open_and_send(peer_id, data).await?;
+        // Запись завершена, поток закрывается вместе с половиной чтения
+        tokio::spawn(async move {
+            let _ = stream.drain_and_close(ONE_SHOT_DRAIN_TIMEOUT).await;
+        });
+        Ok(())
+    }
+
+    /// Send `request` on a new XStream and read the full response
+    ///
+    /// An error written by the server with error_write is returned as `ReqRespError::Remote`.
+    pub async fn request_response(
+        &self,
+        peer_id: PeerId,
+        request: Vec<u8>,
+        read_timeout: std::time::Duration,
+    ) -> Result<Vec<u8>, ReqRespError> {
+        let mut stream = self
+            .open_and_send(peer_id, request)
+            .await
+            .map_err(ReqRespError::Send)?;
+
+        let result = match tokio::time::timeout(read_timeout, stream.read_to_end()).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(error)) => match error.as_xstream_error() {
+                Some(remote) => Err(ReqRespError::Remote(remote.data().to_vec())),
+                None => Err(ReqRespError::Read(error.to_io_error())),
+            },
+            Err(_) => Err(ReqRespError::Timeout),
+        };
+        let _ = stream.close().await;
+        result
+    }
+
+    /// Open XStream, write `data` and signal EOF, keeping the read half open
+    ///
+    /// The stream is closed if writing fails.
+    async fn open_and_send(&self, peer_id: PeerId, data: Vec<u8>) -> Result<XStream, StreamError> {
+        let mut stream = self
+            .open_xstream(peer_id)
+            .await
+            .map_err(|source| StreamError { step: StreamStep::Open, source })?;
+        let sent = async {
+            stream
+                .write_all(data)
+                .await
+                .map_err(|e| StreamError { step: StreamStep::Write, source: e.into() })?;
+            stream
+                .write_eof()
+                .await
+                .map_err(|e| StreamError { step: StreamStep::WriteEof, source: e.into() })
+        }
+        .await;
+        if let Err(error) = sent {
+            let _ = stream.close().await;
+            return Err(error);
+        }
+        Ok(stream)
+    }
+
+    /// Open XStream to a peer tagged with a purpose for per-tag metrics
+    pub async fn open_stream_tagged(
+        &self,
+        peer_id: PeerId,
+        tag: impl Into<String>,
+    ) -> Result<XStream, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xstream(XStreamCommand::OpenStreamTagged {
+            peer_id,
+            tag: tag.into(),
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx)
+            .await?
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }
+
+    /// Get stream metrics (count, bytes) aggregated by tag
+    pub async fn metrics_by_tag(
+        &self,
+    ) -> Result<HashMap<String, StreamTagMetrics>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xstream(XStreamCommand::GetMetricsByTag {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?.map_err(|e| {
             Box::new(std::io::Error::new(std::io::ErrorKind::Other, e))
                 as Box<dyn std::error::Error + Send + Sync>
         })
     }
 
+    /// Check whether the peer filter allows connections to a peer
+    pub async fn is_peer_allowed(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::peer_filter(PeerFilterCommand::IsPeerAllowed {
+            peer_id,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get the connection limits enforced by the node
+    pub async fn get_connection_limits(
+        &self,
+    ) -> Result<
+        crate::behaviours::connection_limits::ConnectionLimits,
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::connection_limits(ConnectionLimitsCommand::GetLimits {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Replace connection limits at runtime, existing connections are kept
+    pub async fn set_connection_limits(
+        &self,
+        limits: crate::behaviours::connection_limits::ConnectionLimits,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::connection_limits(ConnectionLimitsCommand::SetLimits {
+            limits,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get peers the node re-dials after their connection closes
+    pub async fn get_sticky_peers(
+        &self,
+    ) -> Result<Vec<(PeerId, Multiaddr)>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::reconnect(ReconnectCommand::GetStickyPeers {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
     // XRoutes commands
 
     /// Enable identify behaviour
@@ -194,7 +896,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Disable identify behaviour
@@ -204,7 +906,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Enable mDNS discovery
@@ -214,7 +916,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Disable mDNS discovery
@@ -224,7 +926,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Enable Kademlia DHT discovery
@@ -234,7 +936,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Disable Kademlia DHT discovery
@@ -244,7 +946,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get current status of XRoutes behaviours
@@ -254,7 +956,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        Ok(response_rx.await?)
+        Ok(self.response(response_rx).await?)
     }
 
     /// Bootstrap to a peer for Kademlia DHT
@@ -270,7 +972,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Find a peer through Kademlia DHT
@@ -284,7 +986,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get closest peers through Kademlia DHT
@@ -298,7 +1000,23 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Get closest peers to an arbitrary key through Kademlia DHT
+    ///
+    /// The local peer is never included; an empty routing table yields an empty list.
+    pub async fn get_closest_peers_to_key(
+        &self,
+        key: Vec<u8>,
+    ) -> Result<Vec<PeerId>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::GetClosestPeersToKey {
+            key,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Find peer addresses with automatic search and timeout
@@ -314,7 +1032,40 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Find a peer through Kademlia and dial all found addresses at once
+    ///
+    /// The addresses go into a single dial, so at most one connection is kept.
+    /// Fails if the search finds no addresses or the dial fails on every
+    /// address, the error lists each failed address.
+    pub async fn find_and_connect(
+        &self,
+        peer_id: PeerId,
+        timeout: std::time::Duration,
+    ) -> Result<libp2p::swarm::ConnectionId, Box<dyn std::error::Error + Send + Sync>> {
+        let started = std::time::Instant::now();
+        let addresses = self.find_peer_addresses(peer_id, timeout).await?;
+        if addresses.is_empty() {
+            return Err(format!("No addresses found for peer {}", peer_id).into());
+        }
+
+        // Dial получает оставшееся время, но не меньше секунды
+        let dial_timeout = timeout
+            .saturating_sub(started.elapsed())
+            .max(std::time::Duration::from_secs(1));
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::DialAndWait {
+            peer_id,
+            addresses,
+            timeout: dial_timeout,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx)
+            .await?
+            .map_err(|e| format!("Failed to connect to peer {}: {}", peer_id, e).into())
     }
 
     // mDNS cache commands
@@ -328,7 +1079,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Find a specific peer in mDNS cache
@@ -342,7 +1093,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get mDNS cache status
@@ -354,7 +1105,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Clear mDNS cache
@@ -366,7 +1117,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Enable mDNS with custom TTL
@@ -380,7 +1131,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Enable relay server
@@ -390,7 +1141,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Add a peer as AutoNAT server
@@ -406,21 +1157,42 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Set Kademlia mode (client, server, auto)
+    ///
+    /// Promotion to server fails without a confirmed external address,
+    /// emits NodeEvent::KadModeChanged when the mode changes.
     pub async fn set_kad_mode(
         &self,
         mode: crate::behaviours::xroutes::types::KadMode,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.send_kad_mode(mode, false).await
+    }
+
+    /// Set Kademlia mode without requiring a confirmed external address
+    pub async fn force_kad_mode(
+        &self,
+        mode: crate::behaviours::xroutes::types::KadMode,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.send_kad_mode(mode, true).await
+    }
+
+    /// Sends SetKadMode, `force` skips the external address check
+    async fn send_kad_mode(
+        &self,
+        mode: crate::behaviours::xroutes::types::KadMode,
+        force: bool,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let (response_tx, response_rx) = oneshot::channel();
-        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::SetKadMode {
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::SetKadMode {
             mode,
+            force,
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get current Kademlia mode
@@ -432,7 +1204,65 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Export Kademlia routing table for persisting across restarts
+    pub async fn export_routing_table(
+        &self,
+    ) -> Result<Vec<(PeerId, Vec<Multiaddr>)>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::ExportRoutingTable {
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Seed Kademlia routing table with exported entries, returns number of added addresses
+    pub async fn import_routing_table(
+        &self,
+        entries: Vec<(PeerId, Vec<Multiaddr>)>,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::ImportRoutingTable {
+            entries,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Store a Kademlia record locally and publish it to the DHT
+    ///
+    /// Returns once the record is in the local store; publication continues in the background.
+    pub async fn put_record(
+        &self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::PutRecord {
+            key,
+            value,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get a record value from the local Kademlia store
+    pub async fn get_local_record(
+        &self,
+        key: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::xroutes(crate::behaviours::xroutes::XRoutesCommand::GetLocalRecord {
+            key,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     // ConnectionTracker commands
@@ -448,7 +1278,39 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Get full connection info of connected peers
+    ///
+    /// A peer with several connections appears once per connection.
+    pub async fn connected_peers_detailed(
+        &self,
+    ) -> Result<Vec<crate::conntracker::ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::ConnectionTracker {
+            command: ConntrackerCommand::GetConnectedPeersDetailed {
+                response: response_tx,
+            },
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Get up to `limit` connections ordered by uptime, longest-lived first
+    pub async fn get_longest_lived_connections(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<crate::conntracker::ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::ConnectionTracker {
+            command: ConntrackerCommand::GetLongestLivedConnections {
+                limit,
+                response: response_tx,
+            },
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Get connections for a specific peer
@@ -464,7 +1326,7 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get information about a specific connection
@@ -480,7 +1342,23 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Get connections by the remote address they were dialed on or accepted from
+    pub async fn get_connections_by_address(
+        &self,
+        address: Multiaddr,
+    ) -> Result<Vec<crate::conntracker::ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::ConnectionTracker {
+            command: ConntrackerCommand::GetConnectionByAddress {
+                address,
+                response: response_tx,
+            },
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Get all connected peers
@@ -494,7 +1372,7 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get connection statistics
@@ -508,7 +1386,7 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Get listen addresses
@@ -522,7 +1400,18 @@ impl Commander {
             },
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Listen addresses from ConnectionTracker that pass the filter
+    ///
+    /// `AddrFilter::new().exclude_private()` leaves only publicly reachable addresses.
+    pub async fn listen_addresses_filtered(
+        &self,
+        filter: AddrFilter,
+    ) -> Result<Vec<Multiaddr>, Box<dyn std::error::Error + Send + Sync>> {
+        let addresses = self.get_listen_addresses().await?;
+        Ok(filter.apply(addresses))
     }
 
     /// Get external addresses from ConnectionTracker
@@ -534,7 +1423,7 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 
     /// Add external address to swarm
@@ -548,7 +1437,38 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
+    }
+
+    /// Remove external address from swarm
+    pub async fn remove_external_address(
+        &self,
+        address: Multiaddr,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::RemoveExternalAddress {
+            address,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
+    }
+
+    /// Replace the external address set of the swarm
+    ///
+    /// Addresses missing from `addresses` are removed; connected peers receive
+    /// the new set through an Identify push.
+    pub async fn set_external_addresses(
+        &self,
+        addresses: Vec<Multiaddr>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let command = XNetworkCommands::SwarmLevel(SwarmLevelCommand::SetExternalAddresses {
+            addresses,
+            response: response_tx,
+        });
+        self.send(command).await?;
+        self.response(response_rx).await?
     }
 
     /// Get all external addresses from swarm
@@ -560,6 +1480,6 @@ impl Commander {
             response: response_tx,
         });
         self.send(command).await?;
-        response_rx.await?
+        self.response(response_rx).await?
     }
 }
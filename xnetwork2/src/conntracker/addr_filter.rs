@@ -0,0 +1,90 @@
+//! Filter for listen addresses by transport and IP range
+
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+
+use crate::behaviours::peer_filter::filter::{is_private_ipv4, is_private_ipv6};
+
+/// Transport of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrTransport {
+    /// /udp/N/quic-v1
+    Quic,
+    /// /tcp/N
+    Tcp,
+    /// /memory/N
+    Memory,
+}
+
+impl AddrTransport {
+    /// Transport of the address, None if not recognized
+    pub fn of(addr: &Multiaddr) -> Option<Self> {
+        addr.iter().find_map(|protocol| match protocol {
+            Protocol::QuicV1 => Some(AddrTransport::Quic),
+            Protocol::Tcp(_) => Some(AddrTransport::Tcp),
+            Protocol::Memory(_) => Some(AddrTransport::Memory),
+            _ => None,
+        })
+    }
+}
+
+/// Selects addresses by transport and excludes loopback or private ranges
+///
+/// The default filter matches every address.
+#[derive(Debug, Clone, Default)]
+pub struct AddrFilter {
+    transports: Vec<AddrTransport>,
+    exclude_loopback: bool,
+    exclude_private: bool,
+}
+
+impl AddrFilter {
+    /// Filter matching every address
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only addresses of this transport; may be called several times to allow more
+    pub fn transport(mut self, transport: AddrTransport) -> Self {
+        self.transports.push(transport);
+        self
+    }
+
+    /// Exclude 127.0.0.0/8 and ::1
+    pub fn exclude_loopback(mut self) -> Self {
+        self.exclude_loopback = true;
+        self
+    }
+
+    /// Exclude private, loopback and link-local ranges, leaving publicly reachable addresses
+    pub fn exclude_private(mut self) -> Self {
+        self.exclude_private = true;
+        self
+    }
+
+    /// True if the address passes the filter
+    pub fn matches(&self, addr: &Multiaddr) -> bool {
+        if !self.transports.is_empty()
+            && !AddrTransport::of(addr).is_some_and(|transport| self.transports.contains(&transport))
+        {
+            return false;
+        }
+
+        addr.iter().all(|protocol| match protocol {
+            Protocol::Ip4(ip) => {
+                !(self.exclude_loopback && ip.is_loopback())
+                    && !(self.exclude_private && is_private_ipv4(&ip))
+            }
+            Protocol::Ip6(ip) => {
+                !(self.exclude_loopback && ip.is_loopback())
+                    && !(self.exclude_private && is_private_ipv6(&ip))
+            }
+            _ => true,
+        })
+    }
+
+    /// Keep only matching addresses
+    pub fn apply(&self, addrs: impl IntoIterator<Item = Multiaddr>) -> Vec<Multiaddr> {
+        addrs.into_iter().filter(|addr| self.matches(addr)).collect()
+    }
+}
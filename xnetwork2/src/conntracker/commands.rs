@@ -12,6 +12,11 @@ pub enum ConntrackerCommand {
     GetConnections {
         response: oneshot::Sender<Result<Vec<ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Get up to `limit` connections ordered by uptime, longest-lived first
+    GetLongestLivedConnections {
+        limit: usize,
+        response: oneshot::Sender<Result<Vec<ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Get connections for a specific peer
     GetPeerConnections {
         peer_id: PeerId,
@@ -22,6 +27,15 @@ pub enum ConntrackerCommand {
         connection_id: ConnectionId,
         response: oneshot::Sender<Result<ConnectionInfo, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Get connections by remote address (a trailing /p2p/<id> is ignored)
+    GetConnectionByAddress {
+        address: libp2p::Multiaddr,
+        response: oneshot::Sender<Result<Vec<ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get active connections of connected peers, grouped by peer
+    GetConnectedPeersDetailed {
+        response: oneshot::Sender<Result<Vec<ConnectionInfo>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Get all connected peers
     GetConnectedPeers {
         response: oneshot::Sender<Result<Vec<PeerId>, Box<dyn std::error::Error + Send + Sync>>>,
@@ -1,13 +1,14 @@
 //! Conntracker service for tracking peer connections and addresses
 
 use std::collections::{HashMap, HashSet};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use libp2p::{
     PeerId, Multiaddr,
     swarm::{FromSwarm, behaviour::ConnectionEstablished, behaviour::ConnectionClosed, behaviour::AddressChange, behaviour::NewListenAddr, behaviour::ExternalAddrConfirmed, behaviour::ExternalAddrExpired},
 };
 use libp2p::core::ConnectedPoint;
+use libp2p::multiaddr::Protocol;
 use libp2p::swarm::ConnectionId;
 
 /// Status of a connection
@@ -28,6 +29,17 @@ pub struct ConnectionInfo {
     pub endpoint: ConnectedPoint,
     pub established_at: Instant,
     pub status: ConnectionStatus,
+    /// Payload bytes received over XStreams on this connection, as of the last ConnectionTracker command
+    pub bytes_in: u64,
+    /// Payload bytes sent over XStreams on this connection, as of the last ConnectionTracker command
+    pub bytes_out: u64,
+}
+
+impl ConnectionInfo {
+    /// How long this connection has been alive
+    pub fn duration(&self) -> Duration {
+        self.established_at.elapsed()
+    }
 }
 
 /// All connections and addresses for a specific peer
@@ -88,6 +100,16 @@ impl PeerConnections {
     }
 }
 
+/// Traffic totals of a single connection
+///
+/// Sampled from the XStream counters each time a ConnectionTracker command is
+/// handled, not updated while data flows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionTraffic {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
 /// Statistics about connections
 #[derive(Debug, Clone)]
 pub struct ConnectionStats {
@@ -95,6 +117,12 @@ pub struct ConnectionStats {
     pub total_connections: usize,
     pub listen_addresses_count: usize,
     pub external_addresses_count: usize,
+    /// Payload bytes received over all connections
+    pub total_bytes_in: u64,
+    /// Payload bytes sent over all connections
+    pub total_bytes_out: u64,
+    /// Traffic totals per connection
+    pub connection_traffic: HashMap<ConnectionId, ConnectionTraffic>,
 }
 
 /// Conntracker service for tracking all peer connections and addresses
@@ -131,6 +159,18 @@ impl Conntracker {
         None
     }
 
+    /// Get connections whose remote address matches `address`
+    ///
+    /// A trailing `/p2p/<peer_id>` is ignored on both sides, so an address can be
+    /// looked up before the peer id is known.
+    pub fn get_connections_by_address(&self, address: &Multiaddr) -> Vec<&ConnectionInfo> {
+        let wanted = strip_p2p_suffix(address);
+        self.get_all_connections()
+            .into_iter()
+            .filter(|conn| strip_p2p_suffix(&conn.remote_addr) == wanted)
+            .collect()
+    }
+
     /// Get all connected peers (peers with at least one active connection)
     pub fn get_connected_peers(&self) -> Vec<PeerId> {
         self.peer_connections
@@ -140,6 +180,21 @@ impl Conntracker {
             .collect()
     }
 
+    /// Get active connections of connected peers, grouped by peer, oldest first
+    pub fn get_connected_connections(&self) -> Vec<&ConnectionInfo> {
+        let mut connections: Vec<&ConnectionInfo> = self
+            .get_all_connections()
+            .into_iter()
+            .filter(|conn| conn.status == ConnectionStatus::Active)
+            .collect();
+        connections.sort_by(|a, b| {
+            a.peer_id
+                .cmp(&b.peer_id)
+                .then(a.established_at.cmp(&b.established_at))
+        });
+        connections
+    }
+
     /// Get all connections (active and inactive)
     pub fn get_all_connections(&self) -> Vec<&ConnectionInfo> {
         self.peer_connections
@@ -148,6 +203,14 @@ impl Conntracker {
             .collect()
     }
 
+    /// Get up to `limit` connections ordered by uptime, longest-lived first
+    pub fn get_longest_lived_connections(&self, limit: usize) -> Vec<&ConnectionInfo> {
+        let mut connections = self.get_all_connections();
+        connections.sort_by_key(|conn| conn.established_at);
+        connections.truncate(limit);
+        connections
+    }
+
     /// Get listen addresses of the local node
     pub fn get_listen_addresses(&self) -> &[Multiaddr] {
         &self.listen_addresses
@@ -165,14 +228,52 @@ impl Conntracker {
             .map(|pc| pc.connection_count())
             .sum();
         
+        let connection_traffic: HashMap<ConnectionId, ConnectionTraffic> = self
+            .get_all_connections()
+            .into_iter()
+            .map(|conn| {
+                let traffic = ConnectionTraffic {
+                    bytes_in: conn.bytes_in,
+                    bytes_out: conn.bytes_out,
+                };
+                (conn.connection_id, traffic)
+            })
+            .collect();
+
         ConnectionStats {
             total_peers: self.peer_connections.len(),
             total_connections,
             listen_addresses_count: self.listen_addresses.len(),
             external_addresses_count: self.external_addresses.len(),
+            total_bytes_in: connection_traffic.values().map(|t| t.bytes_in).sum(),
+            total_bytes_out: connection_traffic.values().map(|t| t.bytes_out).sum(),
+            connection_traffic,
         }
     }
 
+    /// Update traffic totals of a connection, returns false if the connection is unknown
+    ///
+    /// The swarm handler calls it with fresh XStream totals before answering a ConnectionTracker command.
+    pub fn update_connection_traffic(
+        &mut self,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+        bytes_in: u64,
+        bytes_out: u64,
+    ) -> bool {
+        let Some(conn_info) = self
+            .peer_connections
+            .get_mut(peer_id)
+            .and_then(|pc| pc.connections.get_mut(connection_id))
+        else {
+            return false;
+        };
+
+        conn_info.bytes_in = bytes_in;
+        conn_info.bytes_out = bytes_out;
+        true
+    }
+
     /// Handle ConnectionEstablished event
     pub fn handle_connection_established(&mut self, event: &ConnectionEstablished) {
         let connection_info = ConnectionInfo {
@@ -189,6 +290,8 @@ impl Conntracker {
             endpoint: event.endpoint.clone(),
             established_at: Instant::now(),
             status: ConnectionStatus::Active,
+            bytes_in: 0,
+            bytes_out: 0,
         };
 
         // Get or create PeerConnections for this peer
@@ -225,13 +328,32 @@ impl Conntracker {
 
     /// Handle AddressChange event
     pub fn handle_address_change(&mut self, event: &AddressChange) {
-        // Update connection addresses when they change
-        if let Some(peer_connections) = self.peer_connections.get_mut(&event.peer_id) {
-            // Remove old address
-            peer_connections.remove_address(event.old.get_remote_address());
-            // Add new address
-            peer_connections.add_address(event.new.get_remote_address().clone());
-        }
+        self.update_connection_address(&event.peer_id, &event.connection_id, event.new);
+    }
+
+    /// Update the endpoint of a migrated connection
+    ///
+    /// Returns the old and new remote addresses, or None if the connection is unknown.
+    pub fn update_connection_address(
+        &mut self,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+        new_endpoint: &ConnectedPoint,
+    ) -> Option<(Multiaddr, Multiaddr)> {
+        let peer_connections = self.peer_connections.get_mut(peer_id)?;
+        let connection_info = peer_connections.connections.get_mut(connection_id)?;
+
+        let old_addr = connection_info.remote_addr.clone();
+        let new_addr = new_endpoint.get_remote_address().clone();
+        connection_info.remote_addr = new_addr.clone();
+        connection_info.endpoint = new_endpoint.clone();
+
+        // Remove old address
+        peer_connections.remove_address(&old_addr);
+        // Add new address
+        peer_connections.add_address(new_addr.clone());
+
+        Some((old_addr, new_addr))
     }
 
     /// Handle NewListenAddr event
@@ -280,6 +402,8 @@ impl Conntracker {
             endpoint: endpoint.clone(),
             established_at: std::time::Instant::now(),
             status: ConnectionStatus::Active,
+            bytes_in: 0,
+            bytes_out: 0,
         };
 
         // Get or create PeerConnections for this peer
@@ -303,7 +427,19 @@ impl Conntracker {
 
 }
 
+/// Remove a trailing `/p2p/<peer_id>` component from an address
+fn strip_p2p_suffix(address: &Multiaddr) -> Multiaddr {
+    let mut address = address.clone();
+    if let Some(Protocol::P2p(_)) = address.iter().last() {
+        address.pop();
+    }
+    address
+}
+
+pub mod addr_filter;
 pub mod commands;
 
+pub use addr_filter::{AddrFilter, AddrTransport};
+
 #[cfg(test)]
 mod test_basic;
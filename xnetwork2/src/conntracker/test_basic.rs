@@ -48,6 +48,8 @@ mod tests {
             endpoint: endpoint.clone(),
             established_at: std::time::Instant::now(),
             status: ConnectionStatus::Active,
+            bytes_in: 0,
+            bytes_out: 0,
         };
 
         assert_eq!(connection_info.connection_id, connection_id);
@@ -76,6 +78,8 @@ mod tests {
             },
             established_at: std::time::Instant::now(),
             status: ConnectionStatus::Active,
+            bytes_in: 0,
+            bytes_out: 0,
         };
 
         // Add connection
@@ -119,4 +123,122 @@ mod tests {
         assert_eq!(stats.listen_addresses_count, 0);
         assert_eq!(stats.external_addresses_count, 0);
     }
+
+    #[test]
+    fn test_update_connection_address_on_migration() {
+        let mut conntracker = Conntracker::new(PeerId::random());
+        let peer_id = PeerId::random();
+        let connection_id = ConnectionId::new_unchecked(7);
+        let old_addr: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+        let new_addr: Multiaddr = "/ip4/127.0.0.1/udp/4002/quic-v1".parse().unwrap();
+
+        let old_endpoint = ConnectedPoint::Dialer {
+            address: old_addr.clone(),
+            role_override: libp2p::core::Endpoint::Dialer,
+            port_use: PortUse::Reuse,
+        };
+        let new_endpoint = ConnectedPoint::Dialer {
+            address: new_addr.clone(),
+            role_override: libp2p::core::Endpoint::Dialer,
+            port_use: PortUse::Reuse,
+        };
+
+        conntracker.add_connection(connection_id, peer_id, old_endpoint);
+        conntracker
+            .peer_connections
+            .get_mut(&peer_id)
+            .unwrap()
+            .add_address(old_addr.clone());
+
+        let migrated = conntracker.update_connection_address(&peer_id, &connection_id, &new_endpoint);
+        assert_eq!(migrated, Some((old_addr.clone(), new_addr.clone())));
+
+        let info = conntracker.get_connection(&connection_id).unwrap();
+        assert_eq!(info.remote_addr, new_addr);
+        assert_eq!(info.endpoint, new_endpoint);
+
+        let peer_connections = conntracker.get_peer_connections(&peer_id).unwrap();
+        assert!(peer_connections.addresses.contains(&new_addr));
+        assert!(!peer_connections.addresses.contains(&old_addr));
+
+        // Unknown connection is not migrated
+        let unknown = ConnectionId::new_unchecked(8);
+        assert!(conntracker.update_connection_address(&peer_id, &unknown, &new_endpoint).is_none());
+    }
+
+    #[test]
+    fn test_longest_lived_connections_ordering() {
+        let mut conntracker = Conntracker::new(PeerId::random());
+        let endpoint = ConnectedPoint::Dialer {
+            address: "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap(),
+            role_override: libp2p::core::Endpoint::Dialer,
+            port_use: PortUse::Reuse,
+        };
+
+        let ids: Vec<ConnectionId> = (1..=3).map(ConnectionId::new_unchecked).collect();
+        for id in &ids {
+            conntracker.add_connection(*id, PeerId::random(), endpoint.clone());
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let longest = conntracker.get_longest_lived_connections(2);
+        let longest_ids: Vec<ConnectionId> = longest.iter().map(|c| c.connection_id).collect();
+        assert_eq!(longest_ids, vec![ids[0], ids[1]]);
+        assert!(longest[0].duration() >= longest[1].duration());
+
+        assert_eq!(conntracker.get_longest_lived_connections(10).len(), 3);
+        assert!(conntracker.get_longest_lived_connections(0).is_empty());
+    }
+
+    #[test]
+    fn test_connection_traffic_in_stats() {
+        let mut conntracker = Conntracker::new(PeerId::random());
+        let peer_id = PeerId::random();
+        let endpoint = ConnectedPoint::Dialer {
+            address: "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap(),
+            role_override: libp2p::core::Endpoint::Dialer,
+            port_use: PortUse::Reuse,
+        };
+        let first = ConnectionId::new_unchecked(1);
+        let second = ConnectionId::new_unchecked(2);
+        conntracker.add_connection(first, peer_id, endpoint.clone());
+        conntracker.add_connection(second, peer_id, endpoint);
+
+        assert!(conntracker.update_connection_traffic(&peer_id, &first, 100, 40));
+        assert!(conntracker.update_connection_traffic(&peer_id, &second, 5, 60));
+        assert!(!conntracker.update_connection_traffic(&peer_id, &ConnectionId::new_unchecked(3), 1, 1));
+
+        let info = conntracker.get_connection(&first).unwrap();
+        assert_eq!((info.bytes_in, info.bytes_out), (100, 40));
+
+        let stats = conntracker.get_connection_stats();
+        assert_eq!(stats.total_bytes_in, 105);
+        assert_eq!(stats.total_bytes_out, 100);
+        assert_eq!(stats.connection_traffic.len(), 2);
+        assert_eq!(stats.connection_traffic[&second], ConnectionTraffic { bytes_in: 5, bytes_out: 60 });
+    }
+
+    #[test]
+    fn test_get_connections_by_address_normalizes_p2p_suffix() {
+        let mut conntracker = Conntracker::new(PeerId::random());
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+        let with_p2p = address.clone().with(libp2p::multiaddr::Protocol::P2p(peer_id));
+        let endpoint = ConnectedPoint::Dialer {
+            address: with_p2p.clone(),
+            role_override: libp2p::core::Endpoint::Dialer,
+            port_use: PortUse::Reuse,
+        };
+        let connection_id = ConnectionId::new_unchecked(1);
+        conntracker.add_connection(connection_id, peer_id, endpoint);
+
+        for lookup in [&address, &with_p2p] {
+            let found = conntracker.get_connections_by_address(lookup);
+            assert_eq!(found.len(), 1, "lookup by {} failed", lookup);
+            assert_eq!(found[0].connection_id, connection_id);
+        }
+
+        let other: Multiaddr = "/ip4/127.0.0.1/udp/4002/quic-v1".parse().unwrap();
+        assert!(conntracker.get_connections_by_address(&other).is_empty());
+    }
 }
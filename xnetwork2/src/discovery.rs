@@ -0,0 +1,161 @@
+//! Агрегатор обнаружения пиров
+//!
+//! mDNS, Kademlia и Identify могут независимо находить одного и того же пира.
+//! Агрегатор собирает такие находки в окне времени и выпускает одно событие
+//! `NodeEvent::PeerDiscovered` со всеми адресами и источниками.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use libp2p::{Multiaddr, PeerId};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::node_events::{NodeEvent, NodeEventSender};
+
+/// Окно агрегации по умолчанию
+pub const DEFAULT_DISCOVERY_WINDOW: Duration = Duration::from_millis(500);
+
+/// Source that reported a discovered peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscoverySource {
+    Mdns,
+    Kademlia,
+    Identify,
+}
+
+/// Single discovery report from one source
+#[derive(Debug)]
+struct DiscoveryReport {
+    peer_id: PeerId,
+    source: DiscoverySource,
+    addresses: Vec<Multiaddr>,
+}
+
+/// Находки по пиру, собранные в текущем окне
+struct PendingDiscovery {
+    deadline: Instant,
+    addresses: Vec<Multiaddr>,
+    sources: Vec<DiscoverySource>,
+}
+
+impl PendingDiscovery {
+    fn merge(&mut self, source: DiscoverySource, addresses: Vec<Multiaddr>) {
+        if !self.sources.contains(&source) {
+            self.sources.push(source);
+        }
+        for address in addresses {
+            if !self.addresses.contains(&address) {
+                self.addresses.push(address);
+            }
+        }
+    }
+
+    fn into_event(self, peer_id: PeerId) -> NodeEvent {
+        NodeEvent::PeerDiscovered {
+            peer_id,
+            addresses: self.addresses,
+            sources: self.sources,
+        }
+    }
+}
+
+/// Deduplicating aggregator of discovery reports
+///
+/// The aggregation task is spawned on the first report and stops when the
+/// aggregator is dropped, flushing what it still holds.
+pub struct DiscoveryAggregator {
+    window: Duration,
+    reports: Option<mpsc::UnboundedSender<DiscoveryReport>>,
+}
+
+impl Default for DiscoveryAggregator {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISCOVERY_WINDOW)
+    }
+}
+
+impl DiscoveryAggregator {
+    /// Create an aggregator with the given window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            reports: None,
+        }
+    }
+
+    /// Aggregation window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Report a peer found by a source; never blocks
+    pub fn report(
+        &mut self,
+        event_sender: &NodeEventSender,
+        peer_id: PeerId,
+        source: DiscoverySource,
+        addresses: Vec<Multiaddr>,
+    ) {
+        let window = self.window;
+        let reports = self.reports.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            tokio::spawn(run_aggregation(window, rx, event_sender.clone()));
+            tx
+        });
+
+        let _ = reports.send(DiscoveryReport {
+            peer_id,
+            source,
+            addresses,
+        });
+    }
+}
+
+/// Задача агрегации: копит находки и выпускает события по истечении окна
+async fn run_aggregation(
+    window: Duration,
+    mut reports: mpsc::UnboundedReceiver<DiscoveryReport>,
+    event_sender: NodeEventSender,
+) {
+    let mut pending: HashMap<PeerId, PendingDiscovery> = HashMap::new();
+
+    loop {
+        let next_deadline = pending.values().map(|p| p.deadline).min();
+
+        tokio::select! {
+            report = reports.recv() => {
+                let Some(report) = report else {
+                    break;
+                };
+                pending
+                    .entry(report.peer_id)
+                    .or_insert_with(|| PendingDiscovery {
+                        deadline: Instant::now() + window,
+                        addresses: Vec::new(),
+                        sources: Vec::new(),
+                    })
+                    .merge(report.source, report.addresses);
+            }
+            _ = tokio::time::sleep_until(next_deadline.unwrap_or_else(Instant::now)), if next_deadline.is_some() => {
+                let now = Instant::now();
+                let expired: Vec<PeerId> = pending
+                    .iter()
+                    .filter(|(_, p)| p.deadline <= now)
+                    .map(|(peer_id, _)| *peer_id)
+                    .collect();
+                for peer_id in expired {
+                    if let Some(discovery) = pending.remove(&peer_id) {
+                        debug!("🔎 [DiscoveryAggregator] Peer {} discovered via {:?}", peer_id, discovery.sources);
+                        let _ = event_sender.send(discovery.into_event(peer_id));
+                    }
+                }
+            }
+        }
+    }
+
+    for (peer_id, discovery) in pending {
+        let _ = event_sender.send(discovery.into_event(peer_id));
+    }
+}
@@ -6,24 +6,37 @@
 #![allow(warnings)]
 
 pub mod behaviours;
+pub mod bootstrap;
 pub mod commander;
 pub mod conntracker;
+pub mod discovery;
 pub mod main_behaviour;
+pub mod metrics;
+pub mod nat;
 pub mod node;
 pub mod node_builder;
 pub mod node_events;
+pub mod rtt;
 pub mod swarm_commands;
 pub mod swarm_handler;
+pub mod telemetry;
 
 // Re-export main components for public API
 pub use behaviours::*;
-pub use commander::Commander;
+pub use bootstrap::{
+    BootstrapConfig, BootstrapConnect, BootstrapEvent, BootstrapHandle, BootstrapServer, ConfigDiff,
+    RetryPolicy,
+};
+pub use commander::{CommandError, Commander, ReqRespError, StreamError, StreamStep};
+pub use nat::NatStatus;
+pub use node_events::{EventDelivery, NodeEventSender, TaggedNodeEvent};
 pub use main_behaviour::{XNetworkBehaviour, XNetworkBehaviourHandlerDispatcher, XNetworkCommands};
-pub use node::Node;
+pub use node::{Node, ShutdownReport};
 pub use node_builder::{InboundDecisionPolicy, NodeBuilder, builder};
+pub use rtt::{PeerRtt, PingError};
 pub use swarm_commands::SwarmLevelCommand;
 pub use swarm_handler::XNetworkSwarmHandler;
 
 // Re-export commonly used types
-pub use command_swarm::{SwarmLoop, SwarmLoopBuilder, SwarmLoopStopper};
+pub use command_swarm::{CommandId, SwarmLoop, SwarmLoopBuilder, SwarmLoopStopper};
 pub use libp2p::{Multiaddr, PeerId};
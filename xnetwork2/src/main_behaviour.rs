@@ -1,6 +1,6 @@
 //! Main behaviour for XNetwork2 using command-swarm macro
 
-use crate::behaviours::{IdentifyHandler, PingHandler, XAuthHandler, XStreamHandler, XRoutesHandler, KeepAliveHandler};
+use crate::behaviours::{IdentifyHandler, PingHandler, XAuthHandler, XStreamHandler, XRoutesHandler, KeepAliveHandler, PeerFilterHandler, ConnectionLimitsHandler, ReconnectHandler};
 use crate::swarm_commands::SwarmLevelCommand;
 use crate::swarm_handler::XNetworkSwarmHandler;
 use command_swarm::{
@@ -11,11 +11,14 @@ use command_swarm::{
 make_command_swarm! {
     behaviour_name: XNetworkBehaviour,
     behaviours_handlers: {
+        peer_filter: PeerFilterHandler,
+        connection_limits: ConnectionLimitsHandler,
         ping: PingHandler,
         xauth: XAuthHandler,
         xstream: XStreamHandler,
         xroutes: XRoutesHandler,
-        keep_alive: KeepAliveHandler
+        keep_alive: KeepAliveHandler,
+        reconnect: ReconnectHandler
     },
     commands: {
         name: XNetworkCommands,
@@ -0,0 +1,77 @@
+//! Снимок счетчиков узла в текстовом формате Prometheus
+//!
+//! Модуль не поднимает HTTP сервер: приложение само отдает текст из
+//! Node::metrics_snapshot на своем эндпоинте /metrics.
+
+use std::fmt::Write;
+
+use libp2p::PeerId;
+
+use crate::rtt::PeerRtt;
+
+/// Counters collected by the swarm handler at one moment
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    /// Established connections, a peer may have several
+    pub connection_count: usize,
+    pub connected_peer_count: usize,
+    /// Open XStreams in both directions
+    pub open_stream_count: usize,
+    /// Mutual authentications since the node started
+    pub auth_successes: u64,
+    /// Failed inbound and outbound authentications since the node started
+    pub auth_failures: u64,
+    /// Peers in the Kademlia routing table, None without Kademlia
+    pub routing_table_size: Option<usize>,
+    /// Ping RTT of connected peers with at least one successful ping
+    pub peer_rtts: Vec<(PeerId, PeerRtt)>,
+}
+
+impl MetricsSnapshot {
+    /// Render the snapshot in Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        gauge(&mut out, "xnetwork_connections", "Established connections", self.connection_count as u64);
+        gauge(&mut out, "xnetwork_connected_peers", "Connected peers", self.connected_peer_count as u64);
+        gauge(&mut out, "xnetwork_open_streams", "Open XStreams", self.open_stream_count as u64);
+        counter(&mut out, "xnetwork_auth_success_total", "Mutual authentications", self.auth_successes);
+        counter(&mut out, "xnetwork_auth_failure_total", "Failed authentications", self.auth_failures);
+        if let Some(size) = self.routing_table_size {
+            gauge(
+                &mut out,
+                "xnetwork_kad_routing_table_size",
+                "Peers in the Kademlia routing table",
+                size as u64,
+            );
+        }
+
+        if !self.peer_rtts.is_empty() {
+            header(&mut out, "xnetwork_ping_rtt_seconds", "Last ping RTT per peer", "gauge");
+            for (peer_id, rtt) in &self.peer_rtts {
+                let seconds = rtt.last.as_secs_f64();
+                let _ = writeln!(out, "xnetwork_ping_rtt_seconds{{peer=\"{}\"}} {}", peer_id, seconds);
+            }
+            header(&mut out, "xnetwork_ping_rtt_avg_seconds", "Average ping RTT per peer", "gauge");
+            for (peer_id, rtt) in &self.peer_rtts {
+                let seconds = rtt.avg.as_secs_f64();
+                let _ = writeln!(out, "xnetwork_ping_rtt_avg_seconds{{peer=\"{}\"}} {}", peer_id, seconds);
+            }
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, help, "counter");
+    let _ = writeln!(out, "{} {}", name, value);
+}
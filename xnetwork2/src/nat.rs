@@ -0,0 +1,66 @@
+//! Статус NAT по результатам проб AutoNAT
+//!
+//! AutoNAT v2 проверяет достижимость отдельных адресов. Узел считается
+//! публичным, пока хотя бы один проверенный адрес достижим, и приватным,
+//! если все проверенные адреса недостижимы.
+
+use std::collections::HashMap;
+
+use libp2p::Multiaddr;
+
+/// Reachability of the node as seen by AutoNAT servers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NatStatus {
+    /// Reachable from outside on this address
+    Public(Multiaddr),
+    /// All probed addresses are unreachable, a relay is needed
+    Private,
+    /// No probe has completed yet
+    Unknown,
+}
+
+impl Default for NatStatus {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+/// Collects AutoNAT probe results into a NatStatus
+#[derive(Debug, Default)]
+pub struct NatStatusTracker {
+    /// Last probe result per tested address
+    reachable: HashMap<Multiaddr, bool>,
+    /// Most recently confirmed public address
+    last_public: Option<Multiaddr>,
+    status: NatStatus,
+}
+
+impl NatStatusTracker {
+    /// Current status
+    pub fn status(&self) -> &NatStatus {
+        &self.status
+    }
+
+    /// Record a probe result, returning the previous status if it changed
+    pub fn record(&mut self, address: Multiaddr, reachable: bool) -> Option<NatStatus> {
+        self.reachable.insert(address.clone(), reachable);
+        if reachable {
+            self.last_public = Some(address);
+        } else if self.last_public.as_ref() == Some(&address) {
+            self.last_public = self
+                .reachable
+                .iter()
+                .find(|(_, ok)| **ok)
+                .map(|(addr, _)| addr.clone());
+        }
+
+        let status = match &self.last_public {
+            Some(address) => NatStatus::Public(address.clone()),
+            None => NatStatus::Private,
+        };
+        if status == self.status {
+            return None;
+        }
+        Some(std::mem::replace(&mut self.status, status))
+    }
+}
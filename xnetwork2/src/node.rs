@@ -1,10 +1,15 @@
 //! Node creation and management for XNetwork2
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use command_swarm::{SwarmLoop, SwarmLoopBuilder, SwarmLoopStopper};
+use futures::stream::{BoxStream, StreamExt};
 use libp2p::{identity, quic, Multiaddr, PeerId};
 use tokio::sync::{broadcast, mpsc};
 
-use crate::node_events::NodeEvent;
+use crate::node_events::{FilteredEvent, NodeEvent, NodeEventSender, TaggedNodeEvent};
 
 use crate::behaviours::{IdentifyHandler, PingHandler, XAuthHandler, XStreamHandler};
 use crate::commander::Commander;
@@ -13,6 +18,20 @@ use crate::main_behaviour::{
 };
 use crate::swarm_handler::XNetworkSwarmHandler;
 
+/// Summary of a graceful shutdown
+///
+/// Counts reflect the progress made before the deadline; `timed_out` marks
+/// that some step was cut off and the loop was stopped anyway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Open XStreams whose EOF and close finished before the deadline
+    pub streams_closed: usize,
+    /// Peers disconnected by the disconnect step
+    pub peers_disconnected: usize,
+    /// The deadline was reached before all steps finished
+    pub timed_out: bool,
+}
+
 /// XNetwork2 Node
 pub struct Node {
     /// Commander for sending commands to the node
@@ -27,8 +46,8 @@ pub struct Node {
     /// Handle to the background swarm loop task (Some after start)
     pub swarm_loop_handle:
         Option<tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>>>,
-    /// Broadcast channel for sending NodeEvents to multiple subscribers
-    pub event_sender: broadcast::Sender<NodeEvent>,
+    /// Sender of NodeEvents to all subscribers
+    pub event_sender: NodeEventSender,
     /// Peer ID of this node (available immediately after creation)
     pub peer_id: libp2p::PeerId,
     /// Keypair used by this node (available immediately after creation)
@@ -72,6 +91,68 @@ impl Node {
         Ok(())
     }
 
+    /// Gracefully shut down the node within the deadline
+    ///
+    /// Refuses new dials and streams, closes open XStreams (EOF, then close),
+    /// disconnects peers and stops the swarm loop. Steps not finished before
+    /// the deadline are skipped and the loop is stopped anyway; the returned
+    /// report counts what was done and flags the timeout. Emits
+    /// `NodeEvent::ShuttingDown` at the start and `NodeEvent::Shutdown` at the end.
+    pub async fn shutdown(
+        &mut self,
+        deadline: Duration,
+    ) -> Result<ShutdownReport, Box<dyn std::error::Error + Send + Sync>> {
+        println!("🛑 Gracefully shutting down XNetwork2 node...");
+        let _ = self.event_sender.send(NodeEvent::ShuttingDown);
+        let deadline = tokio::time::Instant::now() + deadline;
+        let mut report = ShutdownReport::default();
+
+        if self.is_running() {
+            // Счетчики живут вне future, чтобы частичный прогресс пережил таймаут
+            let streams_closed = Arc::new(AtomicUsize::new(0));
+            let peers_disconnected = Arc::new(AtomicUsize::new(0));
+            let commander = self.commander.clone();
+            let graceful = {
+                let streams_closed = streams_closed.clone();
+                let peers_disconnected = peers_disconnected.clone();
+                async move {
+                    let streams = commander.begin_shutdown().await?;
+                    println!("🔒 Closing {} open streams...", streams.len());
+                    futures::future::join_all(streams.into_iter().map(|mut stream| {
+                        let streams_closed = streams_closed.clone();
+                        async move {
+                            if !stream.is_closed() {
+                                let _ = stream.write_eof().await;
+                                let _ = stream.close().await;
+                                streams_closed.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    }))
+                    .await;
+                    let disconnected = commander.disconnect_all().await?;
+                    peers_disconnected.store(disconnected, Ordering::Relaxed);
+                    Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+                }
+            };
+            match tokio::time::timeout_at(deadline, graceful).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => println!("⚠️ Graceful shutdown step failed: {}", e),
+                Err(_) => {
+                    println!("⚠️ Graceful shutdown deadline reached, stopping anyway");
+                    report.timed_out = true;
+                }
+            }
+            report.streams_closed = streams_closed.load(Ordering::Relaxed);
+            report.peers_disconnected = peers_disconnected.load(Ordering::Relaxed);
+        }
+
+        self.stopper.stop();
+        self.wait_for_shutdown().await?;
+        let _ = self.event_sender.send(NodeEvent::Shutdown);
+        println!("✅ XNetwork2 node shutdown completed: {:?}", report);
+        Ok(report)
+    }
+
     /// Force shutdown the node (immediate stop via stopper)
     pub async fn force_shutdown(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         println!("🛑 Force shutting down XNetwork2 node...");
@@ -134,6 +215,54 @@ impl Node {
         self.event_sender.subscribe()
     }
 
+    /// Subscribe to NodeEvents tagged with the id of the command that emitted them
+    ///
+    /// Only events emitted while a command is handled carry its id, e.g.
+    /// DialAddressRewritten of a Dial; the resulting ConnectionEstablished
+    /// arrives later as a swarm event and is tagged None.
+    pub fn subscribe_tagged(&self) -> broadcast::Receiver<TaggedNodeEvent> {
+        self.event_sender.subscribe_tagged()
+    }
+
+    /// Subscribe to NodeEvents without ever skipping one
+    ///
+    /// Requires `EventDelivery::Reliable` (see `NodeBuilder::with_event_delivery`).
+    /// Events queue up unbounded until the receiver reads them.
+    pub fn subscribe_reliable(
+        &self,
+    ) -> Result<mpsc::UnboundedReceiver<NodeEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.event_sender
+            .subscribe_reliable()
+            .ok_or_else(|| "Reliable event delivery is not enabled".into())
+    }
+
+    /// Subscribe only to NodeEvents matching the predicate
+    ///
+    /// Non-matching events are skipped inside the stream, so the consumer is
+    /// woken only for relevant ones. If the receiver falls behind, a
+    /// `FilteredEvent::Lagged` item reports how many events were missed.
+    pub fn subscribe_filtered<F>(&self, predicate: F) -> BoxStream<'static, FilteredEvent>
+    where
+        F: Fn(&NodeEvent) -> bool + Send + 'static,
+    {
+        let receiver = self.event_sender.subscribe();
+        futures::stream::unfold((receiver, predicate), |(mut receiver, predicate)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if predicate(&event) => {
+                        return Some((FilteredEvent::Event(event), (receiver, predicate)));
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        return Some((FilteredEvent::Lagged(skipped), (receiver, predicate)));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
     /// Get the Peer ID of this node
     ///
     /// Available immediately after node creation, no need to wait for startup.
@@ -155,6 +284,13 @@ impl Node {
         self.force_shutdown().await
     }
 
+    /// Counters of the node in Prometheus text exposition format
+    ///
+    /// No HTTP server is started; serve the returned text from the application.
+    pub async fn metrics_snapshot(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.commander.metrics().await?.render())
+    }
+
     // XRoutes convenience methods
 
     /// Enable identify behaviour
@@ -287,10 +423,33 @@ impl Node {
         self.commander.set_kad_mode(mode).await
     }
 
+    /// Set Kademlia mode without requiring a confirmed external address
+    pub async fn force_kad_mode(
+        &self,
+        mode: crate::behaviours::xroutes::types::KadMode,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.commander.force_kad_mode(mode).await
+    }
+
     /// Get current Kademlia mode
     pub async fn get_kad_mode(
         &self,
     ) -> Result<crate::behaviours::xroutes::types::KadMode, Box<dyn std::error::Error + Send + Sync>> {
         self.commander.get_kad_mode().await
     }
+
+    /// Export Kademlia routing table
+    pub async fn export_routing_table(
+        &self,
+    ) -> Result<Vec<(PeerId, Vec<Multiaddr>)>, Box<dyn std::error::Error + Send + Sync>> {
+        self.commander.export_routing_table().await
+    }
+
+    /// Import Kademlia routing table entries
+    pub async fn import_routing_table(
+        &self,
+        entries: Vec<(PeerId, Vec<Multiaddr>)>,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        self.commander.import_routing_table(entries).await
+    }
 }
@@ -2,9 +2,14 @@
 //!
 //! Поддерживает fluent интерфейс для настройки поведения узла,
 //! включая политику принятия решений для входящих XStream потоков.
+use std::collections::HashMap;
 use std::time::Duration;
-use libp2p::{identity, quic};
-use tokio::sync::broadcast;
+use libp2p::core::muxing::StreamMuxerBox;
+use libp2p::core::transport::timeout::{TransportTimeout, TransportTimeoutError};
+use libp2p::core::transport::{Boxed, MemoryTransport};
+use libp2p::core::upgrade::Version;
+use libp2p::{identity, noise, quic, yamux, PeerId, Transport};
+use crate::node_events::{EventDelivery, NodeEventSender};
 use xstream::events::IncomingConnectionApprovePolicy;
 
 /// Политика принятия решений для входящих потоков
@@ -27,6 +32,8 @@ pub struct NodeConfig {
     pub inbound_decision_policy: InboundDecisionPolicy,
     /// Размер буфера для каналов событий
     pub event_buffer_size: usize,
+    /// Режим доставки NodeEvent подписчикам
+    pub event_delivery: EventDelivery,
     /// Включить relay сервер
     pub enable_relay_server: bool,
     /// Включить DCUtR для hole punching
@@ -41,6 +48,36 @@ pub struct NodeConfig {
     pub enable_kad_server: bool,
     /// Включить клиентский режим Kademlia (только делает запросы)
     pub enable_kad_client: bool,
+    /// Окно объединения находок одного пира в событие PeerDiscovered
+    pub discovery_window: Duration,
+    /// Таймаут входящей PoR аутентификации
+    pub inbound_auth_timeout: Duration,
+    /// Таймаут исходящей PoR аутентификации
+    pub outbound_auth_timeout: Duration,
+    /// Метаданные, отправляемые вместе с PoR
+    pub auth_metadata: HashMap<String, String>,
+    /// Только сообщать результат валидатора метаданных, без автоматической отправки
+    pub manual_metadata_validation: bool,
+    /// Требовать от пира подпись свежего nonce ключом узла из его PoR
+    pub require_por_challenge: bool,
+    /// Максимум одновременных исходящих dial, остальные ждут в очереди
+    pub max_concurrent_dials: Option<usize>,
+    /// Запускать аутентификацию сразу после установки соединения
+    pub auto_auth: bool,
+    /// Использовать MemoryTransport (адреса /memory/N) вместо QUIC
+    pub memory_transport: bool,
+    /// Емкость канала команд; по умолчанию равна event_buffer_size
+    pub max_pending_commands: Option<usize>,
+    /// Максимум входящих потоков в секунду от одного пира
+    pub stream_rate_limit: Option<u32>,
+    /// Максимум одновременных исходящих потоков к одному пиру
+    pub max_outbound_streams_per_peer: Option<usize>,
+    /// Верхняя и нижняя отметки застрявших записей XStream
+    pub stream_backpressure: Option<(usize, usize)>,
+    /// Максимальное время установки соединения (handshake, security, muxer)
+    pub negotiation_timeout: Option<Duration>,
+    /// Время на асинхронное решение о входящем потоке, после него поток отклоняется
+    pub inbound_decision_timeout: Duration,
 }
 
 impl Default for NodeConfig {
@@ -48,6 +85,7 @@ impl Default for NodeConfig {
         Self {
             inbound_decision_policy: InboundDecisionPolicy::default(),
             event_buffer_size: 100,
+            event_delivery: EventDelivery::Lossy(100),
             enable_relay_server: false,
             enable_dcutr: false,
             enable_autonat_server: false,
@@ -55,14 +93,49 @@ impl Default for NodeConfig {
             enable_kademlia: false,
             enable_kad_server: false,
             enable_kad_client: false,
+            discovery_window: crate::discovery::DEFAULT_DISCOVERY_WINDOW,
+            inbound_auth_timeout: xauth::definitions::AUTH_TIMEOUT,
+            outbound_auth_timeout: xauth::definitions::AUTH_TIMEOUT,
+            auth_metadata: HashMap::new(),
+            manual_metadata_validation: false,
+            require_por_challenge: false,
+            max_concurrent_dials: None,
+            auto_auth: true,
+            memory_transport: false,
+            max_pending_commands: None,
+            stream_rate_limit: None,
+            max_outbound_streams_per_peer: None,
+            stream_backpressure: None,
+            negotiation_timeout: None,
+            inbound_decision_timeout: crate::behaviours::xstream::DEFAULT_INBOUND_DECISION_TIMEOUT,
         }
     }
 }
 
+/// Функция пользовательской настройки XRoutesConfig
+type XRoutesConfigFn = Box<
+    dyn FnOnce(crate::behaviours::xroutes::XRoutesConfig) -> crate::behaviours::xroutes::XRoutesConfig
+        + Send,
+>;
+
 /// Fluent builder для создания конфигурируемого Node
 pub struct NodeBuilder {
     config: NodeConfig,
     keypair: Option<identity::Keypair>,
+    xroutes_config_fn: Option<XRoutesConfigFn>,
+    peer_filter: Option<Box<dyn crate::behaviours::peer_filter::PeerFilter>>,
+    connection_limits: crate::behaviours::connection_limits::ConnectionLimits,
+    sticky_peers: Vec<(PeerId, libp2p::Multiaddr)>,
+    reconnect_backoff: Duration,
+    por: Option<xauth::por::por::ProofOfRepresentation>,
+    metadata_validator: Option<crate::behaviours::xauth::MetadataValidator>,
+    auto_auth_policy: Option<crate::behaviours::xauth::AutoAuthPolicy>,
+    inbound_stream_policy: Option<crate::behaviours::xstream::InboundStreamPolicy>,
+    async_inbound_decision: Option<crate::behaviours::xstream::AsyncInboundDecision>,
+    telemetry: Option<crate::telemetry::ConnectionTelemetry>,
+    kad_store: Option<crate::behaviours::xroutes::KadStore>,
+    dial_address_filter: Option<crate::behaviours::peer_filter::DialAddressFilter>,
+    address_rewriter: Option<crate::behaviours::peer_filter::AddressRewriter>,
 }
 
 impl NodeBuilder {
@@ -71,6 +144,20 @@ impl NodeBuilder {
         Self {
             config: NodeConfig::default(),
             keypair: None,
+            xroutes_config_fn: None,
+            peer_filter: None,
+            connection_limits: Default::default(),
+            sticky_peers: Vec::new(),
+            reconnect_backoff: Duration::from_secs(1),
+            por: None,
+            metadata_validator: None,
+            auto_auth_policy: None,
+            inbound_stream_policy: None,
+            async_inbound_decision: None,
+            telemetry: None,
+            kad_store: None,
+            dial_address_filter: None,
+            address_rewriter: None,
         }
     }
 
@@ -80,9 +167,173 @@ impl NodeBuilder {
         self
     }
 
+    /// Устанавливает окно, в котором находки одного пира из mDNS, Kademlia
+    /// и Identify объединяются в одно событие PeerDiscovered
+    pub fn with_discovery_window(mut self, window: Duration) -> Self {
+        self.config.discovery_window = window;
+        self
+    }
+
+    /// Устанавливает таймаут PoR аутентификации для обоих направлений
+    pub fn with_auth_timeout(self, timeout: Duration) -> Self {
+        self.with_auth_timeouts(timeout, timeout)
+    }
+
+    /// Устанавливает отдельные таймауты входящей и исходящей PoR аутентификации
+    pub fn with_auth_timeouts(mut self, inbound: Duration, outbound: Duration) -> Self {
+        self.config.inbound_auth_timeout = inbound;
+        self.config.outbound_auth_timeout = outbound;
+        self
+    }
+
+    /// Устанавливает метаданные, отправляемые удаленной стороне при аутентификации
+    pub fn with_auth_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.config.auth_metadata = metadata;
+        self
+    }
+
+    /// Устанавливает валидатор метаданных, вызываемый при каждом VerifyPorRequest
+    ///
+    /// Результат валидатора отправляется автоматически, если не включен
+    /// ручной режим через with_manual_metadata_validation
+    pub fn with_metadata_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&PeerId, &HashMap<String, String>) -> xauth::definitions::AuthResult
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.metadata_validator = Some(std::sync::Arc::new(validator));
+        self
+    }
+
+    /// Запускать ли аутентификацию автоматически после установки соединения
+    ///
+    /// Включено по умолчанию. Если выключено, приложение вызывает
+    /// start_auth_for_connection само
+    pub fn with_auto_auth(mut self, enabled: bool) -> Self {
+        self.config.auto_auth = enabled;
+        self
+    }
+
+    /// Автоматическая аутентификация только для пиров, одобренных policy
+    pub fn with_auto_auth_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&PeerId) -> bool + Send + Sync + 'static,
+    {
+        self.auto_auth_policy = Some(std::sync::Arc::new(policy));
+        self
+    }
+
+    /// Решает о входящих потоках до XStreamIncomingStreamRequest
+    ///
+    /// Accept и Reject отвечают сразу, Defer оставляет решение приложению.
+    pub fn with_inbound_stream_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&PeerId, libp2p::swarm::ConnectionId) -> crate::behaviours::xstream::InboundDecision
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.inbound_stream_policy = Some(std::sync::Arc::new(policy));
+        self
+    }
+
+    /// Асинхронное решение о входящих потоках, например с проверкой по базе
+    ///
+    /// Вызывается, если синхронная политика не задана или вернула Defer.
+    /// Решение, не готовое за with_inbound_decision_timeout, отклоняет поток.
+    pub fn with_async_inbound_decision<F>(mut self, decision: F) -> Self
+    where
+        F: Fn(
+                PeerId,
+                libp2p::swarm::ConnectionId,
+            ) -> futures::future::BoxFuture<'static, crate::behaviours::xstream::InboundDecision>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.async_inbound_decision = Some(std::sync::Arc::new(decision));
+        self
+    }
+
+    /// Устанавливает время на асинхронное решение о входящем потоке (по умолчанию 5с)
+    pub fn with_inbound_decision_timeout(mut self, timeout: Duration) -> Self {
+        self.config.inbound_decision_timeout = timeout;
+        self
+    }
+
+    /// Включает ручной режим: результат валидатора только передается в VerifyPorRequest
+    pub fn with_manual_metadata_validation(mut self) -> Self {
+        self.config.manual_metadata_validation = true;
+        self
+    }
+
+    /// Экспортирует события соединений и аутентификации в переданный tracing dispatch
+    ///
+    /// Подходит для подписчика со слоем OpenTelemetry; поля событий описаны в модуле telemetry
+    pub fn with_telemetry(mut self, dispatch: tracing::Dispatch) -> Self {
+        self.telemetry = Some(crate::telemetry::ConnectionTelemetry::new(dispatch));
+        self
+    }
+
+    /// Требует от удаленного пира подписать выданный нами nonce вместе с его PoR
+    ///
+    /// PoR передается приложению на проверку только после подписи ключом `por.peer_id`,
+    /// поэтому перехваченный PoR нельзя предъявить повторно (старые пиры отклоняются)
+    pub fn with_required_por_challenge(mut self) -> Self {
+        self.config.require_por_challenge = true;
+        self
+    }
+
     /// Устанавливает размер буфера для каналов событий
     pub fn with_event_buffer_size(mut self, size: usize) -> Self {
         self.config.event_buffer_size = size;
+        if let EventDelivery::Lossy(_) = self.config.event_delivery {
+            self.config.event_delivery = EventDelivery::Lossy(size);
+        }
+        self
+    }
+
+    /// Ограничивает число команд, ожидающих обработки в SwarmLoop
+    ///
+    /// При заполненной очереди `Commander::try_*` возвращают `CommandError::Backpressured`.
+    pub fn with_max_pending_commands(mut self, max: usize) -> Self {
+        self.config.max_pending_commands = Some(max);
+        self
+    }
+
+    /// Ограничивает частоту входящих потоков от одного пира (token bucket)
+    ///
+    /// Лишние потоки сбрасываются и сообщаются как XStreamError с причиной RateLimited.
+    pub fn with_stream_rate_limit(mut self, per_peer_streams_per_sec: u32) -> Self {
+        self.config.stream_rate_limit = Some(per_peer_streams_per_sec);
+        self
+    }
+
+    /// Ограничивает число одновременных исходящих потоков к одному пиру
+    ///
+    /// Открытие сверх лимита сразу завершается ошибкой StreamOpenError::TooManyStreams.
+    pub fn with_max_outbound_streams_per_peer(mut self, limit: usize) -> Self {
+        self.config.max_outbound_streams_per_peer = Some(limit);
+        self
+    }
+
+    /// Включает события XStreamBackpressure и XStreamDrained
+    ///
+    /// Учитываются записи, которые транспорт перестал принимать: XStreamBackpressure
+    /// приходит на `high_water` байтах, XStreamDrained - после спада до `low_water`.
+    pub fn with_stream_backpressure(mut self, high_water: usize, low_water: usize) -> Self {
+        self.config.stream_backpressure = Some((high_water, low_water));
+        self
+    }
+
+    /// Устанавливает режим доставки NodeEvent
+    ///
+    /// `Reliable` включает Node::subscribe_reliable без потерь событий;
+    /// обычная подписка при этом остается доступной.
+    pub fn with_event_delivery(mut self, delivery: EventDelivery) -> Self {
+        self.config.event_delivery = delivery;
         self
     }
 
@@ -92,6 +343,33 @@ impl NodeBuilder {
         self
     }
 
+    /// Устанавливает Ed25519 ключ из 32-байтного seed
+    ///
+    /// Один и тот же seed всегда дает один и тот же PeerId.
+    pub fn with_ed25519_seed(mut self, seed: [u8; 32]) -> Self {
+        let keypair = identity::Keypair::ed25519_from_bytes(seed)
+            .expect("32-byte seed is always a valid Ed25519 secret key");
+        self.keypair = Some(keypair);
+        self
+    }
+
+    /// Устанавливает ключ из protobuf-кодировки (`Keypair::to_protobuf_encoding`)
+    pub fn with_protobuf_key(
+        mut self,
+        bytes: &[u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let keypair = identity::Keypair::from_protobuf_encoding(bytes)
+            .map_err(|e| format!("Invalid protobuf-encoded key: {}", e))?;
+        self.keypair = Some(keypair);
+        Ok(self)
+    }
+
+    /// Устанавливает PoR, выданный владельцем, вместо самоподписанного
+    pub fn with_por(mut self, por: xauth::por::por::ProofOfRepresentation) -> Self {
+        self.por = Some(por);
+        self
+    }
+
     /// Устанавливает фиксированный приватный ключ из байтов (Ed25519)
     pub fn with_fixed_key(mut self, key_bytes: Vec<u8>) -> Self {
         use libp2p::identity::ed25519;
@@ -120,12 +398,96 @@ impl NodeBuilder {
     }
 
     /// Устанавливает конфигурацию XRoutes
+    ///
+    /// Функция применяется к конфигурации, собранной из остальных настроек builder'а
     pub fn with_xroutes_config<F>(mut self, config_fn: F) -> Self
     where
-        F: FnOnce(crate::behaviours::xroutes::XRoutesConfig) -> crate::behaviours::xroutes::XRoutesConfig,
+        F: FnOnce(crate::behaviours::xroutes::XRoutesConfig) -> crate::behaviours::xroutes::XRoutesConfig
+            + Send
+            + 'static,
     {
-        // Note: XRoutesConfig is currently used in XRoutesHandler, not in NodeBuilder
-        // This method is provided for future compatibility
+        self.xroutes_config_fn = Some(Box::new(config_fn));
+        self
+    }
+
+    /// Устанавливает хранилище записей Kademlia вместо MemoryStore
+    ///
+    /// Например, `FileRecordStore` сохраняет записи между перезапусками узла
+    pub fn with_kad_store<S>(mut self, store: S) -> Self
+    where
+        S: libp2p::kad::store::RecordStore + Send + 'static,
+    {
+        self.kad_store = Some(crate::behaviours::xroutes::KadStore::new(store));
+        self
+    }
+
+    /// Устанавливает фильтр пиров для входящих и исходящих соединений
+    ///
+    /// Заблокированные пиры отклоняются до запуска любых протоколов, включая аутентификацию
+    pub fn with_peer_filter<F>(mut self, filter: F) -> Self
+    where
+        F: crate::behaviours::peer_filter::PeerFilter,
+    {
+        self.peer_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Проверяет адреса перед dial, заблокированные завершаются DialError::AddressBlocked
+    ///
+    /// Фильтр возвращает true для разрешенных адресов, например `deny_private_ranges()`.
+    pub fn with_dial_address_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&libp2p::Multiaddr) -> bool + Send + Sync + 'static,
+    {
+        self.dial_address_filter = Some(std::sync::Arc::new(filter));
+        self
+    }
+
+    /// Переписывает каждый адрес непосредственно перед dial
+    ///
+    /// Например, заменяет DNS на IP или добавляет префикс relay. Фильтр
+    /// with_dial_address_filter проверяет исходный адрес; об измененном адресе
+    /// сообщает NodeEvent::DialAddressRewritten с исходным и новым адресом.
+    pub fn with_address_rewriter<F>(mut self, rewriter: F) -> Self
+    where
+        F: Fn(libp2p::Multiaddr) -> libp2p::Multiaddr + Send + Sync + 'static,
+    {
+        self.address_rewriter = Some(std::sync::Arc::new(rewriter));
+        self
+    }
+
+    /// Устанавливает ограничения на число установленных соединений
+    ///
+    /// Отклоненные соединения сообщаются через NodeEvent::ConnectionLimitReached
+    pub fn with_connection_limits(
+        mut self,
+        limits: crate::behaviours::connection_limits::ConnectionLimits,
+    ) -> Self {
+        self.connection_limits = limits;
+        self
+    }
+
+    /// Ограничивает число одновременных исходящих dial
+    ///
+    /// Лишние команды dial ждут в очереди, пока предыдущие не завершатся установкой
+    /// соединения или ошибкой
+    pub fn with_max_concurrent_dials(mut self, max_concurrent_dials: usize) -> Self {
+        self.config.max_concurrent_dials = Some(max_concurrent_dials.max(1));
+        self
+    }
+
+    /// Переподключаться к указанным пирам после закрытия последнего соединения
+    ///
+    /// Задержка удваивается с каждой неудачной попыткой, начиная с `backoff`.
+    /// Явный `disconnect` прекращает переподключение к пиру.
+    /// Каждая попытка сообщается через NodeEvent::ReconnectAttempt
+    pub fn with_auto_reconnect(
+        mut self,
+        peers: Vec<(PeerId, libp2p::Multiaddr)>,
+        backoff: Duration,
+    ) -> Self {
+        self.sticky_peers = peers;
+        self.reconnect_backoff = backoff;
         self
     }
 
@@ -180,6 +542,25 @@ impl NodeBuilder {
         self
     }
 
+    /// Ограничивает время установки соединения
+    ///
+    /// Полуоткрытое соединение, не завершившее handshake за `timeout`, сбрасывается
+    /// (включая соединения через relay);
+    /// исходящий dial завершается NodeEvent::DialFailed с DialError::NegotiationTimeout.
+    pub fn with_negotiation_timeout(mut self, timeout: Duration) -> Self {
+        self.config.negotiation_timeout = Some(timeout);
+        self
+    }
+
+    /// Строит узел поверх MemoryTransport вместо QUIC
+    ///
+    /// Узлы соединяются через адреса `/memory/N` внутри процесса, без сокетов ОС.
+    /// Предназначено для тестов.
+    pub fn with_memory_transport(mut self) -> Self {
+        self.config.memory_transport = true;
+        self
+    }
+
     /// Создает Node с текущей конфигурацией
     pub async fn build(
         self,
@@ -191,6 +572,13 @@ impl NodeBuilder {
             self.config
         );
 
+        // Нулевой таймаут провалил бы аутентификацию сразу после старта
+        if self.config.inbound_auth_timeout.is_zero() || self.config.outbound_auth_timeout.is_zero() {
+            return Err("Auth timeout must be greater than zero".into());
+        }
+        let inbound_auth_timeout = self.config.inbound_auth_timeout;
+        let outbound_auth_timeout = self.config.outbound_auth_timeout;
+
         // Создаем или используем существующий ключ
         let keypair = self
             .keypair
@@ -198,40 +586,46 @@ impl NodeBuilder {
         let peer_id = keypair.public().to_peer_id();
         println!("🔑 Generated/using keypair with PeerId: {}", peer_id);
         
-        // Создаем QUIC транспорт
-        let quic_config = quic::Config::new(&keypair);
-        let quic_transport = quic::tokio::Transport::new(quic_config);
-
-        // Определяем политику для XStream - всегда ручной контроль через события
-        let xstream_policy = IncomingConnectionApprovePolicy::ApproveViaEvent;
-
-        // Создаем swarm с XStream поведением с выбранной политикой
-        let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair.clone())
-            .with_tokio()
-            .with_other_transport(|_key| quic_transport)
-            .expect("Failed to create QUIC transport")
-            .with_relay_client(libp2p::noise::Config::new, libp2p::yamux::Config::default)
-            .expect("Failed to create relay client transport")
-            .with_behaviour(|key, relay_client_behaviour| {
-                let peer_id = key.public().to_peer_id();
-
-                let ping_config = libp2p::ping::Config::new()
-                    .with_interval(Duration::from_secs(1))
-                    ; // держать соединение активным
-                let ping_behaviour = libp2p::ping::Behaviour::new(ping_config);
-
-                // Безопасное создание POR
-                let por = xauth::por::por::ProofOfRepresentation::create(
-                    &key,
-                    peer_id,
-                    std::time::Duration::from_secs(3600), // 1 hour validity
-                ).expect("❌ CRITICAL SECURITY ERROR: Failed to create Proof of Representation - system security compromised");
-
-                let xauth_behaviour = xauth::behaviours::PorAuthBehaviour::new(por);
-
-                let xstream_behaviour = xstream::behaviour::XStreamNetworkBehaviour::new_with_policy(xstream_policy);
-
-        // Create XRoutes behaviour with NAT traversal configuration
+        // Создаем QUIC транспорт или MemoryTransport для тестов
+        let transport: Boxed<(PeerId, StreamMuxerBox)> = if self.config.memory_transport {
+            MemoryTransport::default()
+                .upgrade(Version::V1)
+                .authenticate(noise::Config::new(&keypair)?)
+                .multiplex(yamux::Config::default())
+                .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+                .boxed()
+        } else {
+            quic::tokio::Transport::new(quic::Config::new(&keypair))
+                .map(|(peer_id, connection), _| (peer_id, StreamMuxerBox::new(connection)))
+                .boxed()
+        };
+
+        // Relay client транспорт объединяем с базовым до таймаута,
+        // чтобы таймаут согласования покрывал и relayed соединения
+        let (relay_transport, relay_client_behaviour) = libp2p::relay::client::new(peer_id);
+        let transport: Boxed<(PeerId, StreamMuxerBox)> = relay_transport
+            .upgrade(Version::V1)
+            .authenticate(noise::Config::new(&keypair)?)
+            .multiplex(yamux::Config::default())
+            .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+            .or_transport(transport)
+            .map(|either, _| match either {
+                futures::future::Either::Left(output) | futures::future::Either::Right(output) => output,
+            })
+            .boxed();
+        let transport = match self.config.negotiation_timeout {
+            Some(timeout) => TransportTimeout::new(transport, timeout)
+                .map_err(|e| match e {
+                    TransportTimeoutError::Timeout => {
+                        crate::node_events::NegotiationTimedOut.into_io_error()
+                    }
+                    TransportTimeoutError::TimerError(e) | TransportTimeoutError::Other(e) => e,
+                })
+                .boxed(),
+            None => transport,
+        };
+
+        // Create XRoutes configuration with NAT traversal settings
         let mut xroutes_config = crate::behaviours::xroutes::XRoutesConfig::disabled()
             .with_relay_server(self.config.enable_relay_server)
             .with_dcutr(self.config.enable_dcutr)
@@ -248,22 +642,96 @@ impl NodeBuilder {
             // Legacy mode - enable Kademlia without specific mode
             xroutes_config = xroutes_config.with_kad(true);
         }
-        let xroutes_behaviour = crate::behaviours::xroutes::XRoutesBehaviour::new(
+
+        // Apply user-provided XRoutes customization
+        if let Some(config_fn) = self.xroutes_config_fn {
+            xroutes_config = config_fn(xroutes_config);
+        }
+        let handler_xroutes_config = xroutes_config.clone();
+        let peer_filter = self.peer_filter;
+        let kad_store = self
+            .kad_store
+            .unwrap_or_else(|| crate::behaviours::xroutes::KadStore::memory(peer_id));
+        let connection_limits = self.connection_limits;
+        let sticky_peers = self.sticky_peers;
+        let reconnect_backoff = self.reconnect_backoff;
+        let custom_por = self.por;
+        let auth_metadata = self.config.auth_metadata.clone();
+        let require_por_challenge = self.config.require_por_challenge;
+        let stream_rate_limit = self.config.stream_rate_limit;
+        let max_outbound_streams_per_peer = self.config.max_outbound_streams_per_peer;
+        let stream_backpressure = self.config.stream_backpressure;
+
+        // Определяем политику для XStream - всегда ручной контроль через события
+        let xstream_policy = IncomingConnectionApprovePolicy::ApproveViaEvent;
+
+        // Создаем swarm с XStream поведением с выбранной политикой
+        let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair.clone())
+            .with_tokio()
+            .with_other_transport(|_key| transport)
+            .expect("Failed to create transport")
+            .with_behaviour(|key| {
+                let peer_id = key.public().to_peer_id();
+
+                let ping_config = libp2p::ping::Config::new()
+                    .with_interval(Duration::from_secs(1))
+                    ; // держать соединение активным
+                let ping_behaviour = libp2p::ping::Behaviour::new(ping_config);
+
+                // Безопасное создание POR (если не задан пользователем)
+                let por = match custom_por {
+                    Some(por) => por,
+                    None => xauth::por::por::ProofOfRepresentation::create(
+                        &key,
+                        peer_id,
+                        std::time::Duration::from_secs(3600), // 1 hour validity
+                    ).expect("❌ CRITICAL SECURITY ERROR: Failed to create Proof of Representation - system security compromised"),
+                };
+
+                let xauth_behaviour = xauth::behaviours::PorAuthBehaviour::with_metadata(por, auth_metadata)
+                    .with_auth_timeouts(inbound_auth_timeout, outbound_auth_timeout)
+                    .with_challenge(key.clone(), require_por_challenge);
+
+                let mut xstream_behaviour = xstream::behaviour::XStreamNetworkBehaviour::new_with_policy(xstream_policy);
+                if let Some(limit) = stream_rate_limit {
+                    xstream_behaviour = xstream_behaviour.with_inbound_rate_limit(limit);
+                }
+                if let Some(limit) = max_outbound_streams_per_peer {
+                    xstream_behaviour = xstream_behaviour.with_max_outbound_streams_per_peer(limit);
+                }
+                if let Some((high_water, low_water)) = stream_backpressure {
+                    xstream_behaviour = xstream_behaviour.with_backpressure_thresholds(high_water, low_water);
+                }
+
+        let xroutes_behaviour = crate::behaviours::xroutes::XRoutesBehaviour::new_with_kad_store(
             keypair.public(),
             &xroutes_config,
             Some(relay_client_behaviour), // Pass the relay client behaviour as Some
+            kad_store,
         ).expect("Failed to create XRoutes behaviour");
 
                 // Create KeepAlive behaviour
                 let keep_alive_behaviour = crate::behaviours::keep_alive::KeepAliveBehaviour::new();
 
+                // Create PeerFilter behaviour (первым, чтобы отклонять до остальных протоколов)
+                let peer_filter_behaviour = crate::behaviours::peer_filter::PeerFilterBehaviour::new(peer_filter);
+
+                // Create ConnectionLimits behaviour
+                let connection_limits_behaviour = crate::behaviours::connection_limits::ConnectionLimitsBehaviour::new(connection_limits);
+
+                // Create Reconnect behaviour
+                let reconnect_behaviour = crate::behaviours::reconnect::ReconnectBehaviour::new(sticky_peers, reconnect_backoff);
+
                 // Create main behaviour
                 crate::main_behaviour::XNetworkBehaviour {
+                    peer_filter: peer_filter_behaviour,
+                    connection_limits: connection_limits_behaviour,
                     ping: ping_behaviour,
                     xauth: xauth_behaviour,
                     xstream: xstream_behaviour,
                     xroutes: xroutes_behaviour,
                     keep_alive: keep_alive_behaviour,
+                    reconnect: reconnect_behaviour,
                 }
             })
             .unwrap()
@@ -272,24 +740,43 @@ impl NodeBuilder {
         let peer_id = swarm.local_peer_id().clone();
         println!("🆕 XNetwork2 node created with PeerId: {}", peer_id);
 
-        // Create broadcast channel for NodeEvents
-        let (event_sender, _) = broadcast::channel(self.config.event_buffer_size);
+        // Create NodeEvent sender for the selected delivery mode
+        let event_sender = NodeEventSender::new(self.config.event_delivery, self.config.event_buffer_size);
 
         // Create handler dispatcher with event channel
         let behaviour_handler_dispatcher =
             crate::main_behaviour::XNetworkBehaviourHandlerDispatcher {
                 swarm_handler: crate::swarm_handler::XNetworkSwarmHandler::with_event_sender(
                     event_sender.clone(),
-                ),
+                )
+                .with_mdns_interface(handler_xroutes_config.mdns_interface)
+                .with_discovery_window(self.config.discovery_window)
+                .with_metadata_validator(
+                    self.metadata_validator,
+                    self.config.manual_metadata_validation,
+                )
+                .with_telemetry(self.telemetry)
+                .with_max_concurrent_dials(self.config.max_concurrent_dials)
+                .with_auto_auth(self.config.auto_auth, self.auto_auth_policy)
+                .with_inbound_stream_policy(self.inbound_stream_policy)
+                .with_async_inbound_decision(
+                    self.async_inbound_decision,
+                    self.config.inbound_decision_timeout,
+                )
+                .with_dial_address_filter(self.dial_address_filter)
+                .with_address_rewriter(self.address_rewriter),
                 //identify: crate::behaviours::IdentifyHandler::default(),
+                peer_filter: crate::behaviours::PeerFilterHandler::default(),
+                connection_limits: crate::behaviours::ConnectionLimitsHandler::default(),
                 ping: crate::behaviours::PingHandler::default(),
                 xauth: crate::behaviours::XAuthHandler::default(),
                 xstream: crate::behaviours::XStreamHandler::default(),
                 xroutes: crate::behaviours::XRoutesHandler::new(
                     keypair.public(),
-                    crate::behaviours::xroutes::XRoutesConfig::default(),
+                    handler_xroutes_config,
                 ),
                 keep_alive: crate::behaviours::KeepAliveHandler::default(),
+                reconnect: crate::behaviours::ReconnectHandler::default(),
             };
 
         // Create SwarmLoop using correct builder pattern
@@ -299,7 +786,11 @@ impl NodeBuilder {
             crate::main_behaviour::XNetworkCommands,
         > = command_swarm::SwarmLoopBuilder::new()
             .with_behaviour_handler(behaviour_handler_dispatcher)
-            .with_channel_size(self.config.event_buffer_size)
+            .with_channel_size(
+                self.config
+                    .max_pending_commands
+                    .unwrap_or(self.config.event_buffer_size),
+            )
             .with_swarm(swarm);
 
         let (command_tx, stopper, swarm_loop) = sl2_builder.build().unwrap();
@@ -2,13 +2,20 @@
 //!
 //! Cloneable events that are sent to developers through event channels
 
+use std::sync::{Arc, Mutex};
+
+use command_swarm::CommandId;
 use libp2p::{Multiaddr, PeerId, swarm::ConnectionId};
 use libp2p::core::transport::ListenerId;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, mpsc, oneshot};
 use xstream::events::{InboundUpgradeDecision, StreamOpenDecisionSender};
 use xstream::types::XStreamID;
 use xstream::xstream::XStream;
 
+use crate::behaviours::xroutes::types::KadMode;
+use crate::discovery::DiscoverySource;
+use crate::nat::NatStatus;
+
 /// Node events that are sent to developers
 #[derive(Debug, Clone)]
 pub enum NodeEvent {
@@ -33,6 +40,73 @@ pub enum NodeEvent {
         listener_id: ListenerId,
         address: Multiaddr 
     },
+    /// Connection refused because a connection limit was reached
+    ConnectionLimitReached {
+        /// Remote peer, None if refused before the peer was known
+        peer_id: Option<PeerId>,
+        /// Our role on the refused connection
+        endpoint: libp2p::core::Endpoint,
+        reason: String,
+    },
+    /// Outgoing dial attempt failed
+    DialFailed {
+        /// Peer we dialed, None for dials by address only
+        peer_id: Option<PeerId>,
+        /// Address that failed, None if no address was tried
+        address: Option<Multiaddr>,
+        kind: DialError,
+        error: String,
+    },
+    /// Address rewriter changed a dial address, the dial goes to `rewritten`
+    DialAddressRewritten {
+        peer_id: PeerId,
+        /// Address requested by the application
+        original: Multiaddr,
+        rewritten: Multiaddr,
+    },
+    /// Re-dial of a peer registered with auto-reconnect started
+    ReconnectAttempt {
+        peer_id: PeerId,
+        /// Attempt number since the connection was lost, starting at 1
+        attempt: u32,
+    },
+    /// Relay accepted our reservation request
+    RelayReservationAccepted {
+        relay_peer_id: PeerId,
+        /// True if this renewed an existing reservation
+        renewal: bool,
+    },
+    /// DCUtR upgraded a relayed connection to a direct one
+    HolePunchSucceeded {
+        peer_id: PeerId,
+        /// Remote address of the direct connection, if known
+        address: Option<Multiaddr>,
+    },
+    /// DCUtR failed to upgrade a relayed connection
+    HolePunchFailed {
+        peer_id: PeerId,
+        error: String,
+    },
+    /// Ping measured the round-trip time to a peer
+    PingRtt {
+        peer_id: PeerId,
+        rtt: std::time::Duration,
+    },
+    /// Reachability reported by AutoNAT probes changed
+    NatStatusChanged {
+        old: NatStatus,
+        new: NatStatus,
+    },
+    /// External address set replaced via Commander::set_external_addresses
+    ExternalAddressesChanged {
+        addresses: Vec<Multiaddr>,
+    },
+    /// Remote address of a connection changed (e.g. QUIC connection migration)
+    ConnectionMigrated {
+        peer_id: PeerId,
+        old_addr: Multiaddr,
+        new_addr: Multiaddr,
+    },
 
     // Аутентификация события
     /// Mutual authentication successfully completed
@@ -50,12 +124,29 @@ pub enum NodeEvent {
         peer_id: PeerId,
         connection_id: ConnectionId 
     },
+    /// Authentication did not complete within the configured timeout
+    PeerAuthTimeout {
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        direction: xauth::definitions::AuthDirection,
+    },
+    /// Remote peer rejected our authentication
+    PeerAuthRejected {
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        reason: String,
+        code: Option<xauth::definitions::RejectCode>,
+    },
     /// PoR verification requested
     VerifyPorRequest {
         peer_id: PeerId,
         connection_id: String,
         por: Vec<u8>,
         metadata: std::collections::HashMap<String, String>,
+        /// Result of local PoR validation, None if the PoR is valid
+        reject_code: Option<xauth::definitions::RejectCode>,
+        /// Result of the metadata validator, already submitted unless manual validation is enabled
+        metadata_validation: Option<xauth::definitions::AuthResult>,
     },
 
     // XStream события
@@ -77,6 +168,18 @@ pub enum NodeEvent {
         peer_id: PeerId,
         stream_id: XStreamID,
     },
+    /// Застрявшие записи в XStream достигли верхней отметки
+    XStreamBackpressure {
+        peer_id: PeerId,
+        stream_id: XStreamID,
+        buffered_bytes: usize,
+    },
+    /// Застрявшие записи в XStream опустились до нижней отметки
+    XStreamDrained {
+        peer_id: PeerId,
+        stream_id: XStreamID,
+        buffered_bytes: usize,
+    },
     /// Запрос на принятие решения о входящем потоке XStream
     XStreamIncomingStreamRequest {
         peer_id: PeerId,
@@ -112,6 +215,11 @@ pub enum NodeEvent {
     KademliaRoutingUpdated { 
         peer_id: PeerId 
     },
+    /// Kademlia mode switched at runtime
+    KadModeChanged {
+        previous: KadMode,
+        mode: KadMode,
+    },
 
     // mDNS события
     /// mDNS discovered a new peer in local network
@@ -127,6 +235,249 @@ pub enum NodeEvent {
     MdnsError { 
         error: String 
     },
+
+    // Агрегированное обнаружение
+    /// Peer found by one or more discovery sources within the aggregation window
+    PeerDiscovered {
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+        sources: Vec<DiscoverySource>,
+    },
+
+    // Жизненный цикл узла
+    /// Graceful shutdown started, new streams and dials are refused
+    ShuttingDown,
+    /// Graceful shutdown finished, the swarm loop is about to stop
+    Shutdown,
+}
+
+/// Message of the error reported when connection negotiation exceeds its timeout
+const NEGOTIATION_TIMEOUT_ERROR: &str = "Connection negotiation timed out";
+
+/// Error reported by the transport when connection negotiation exceeds its timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiationTimedOut;
+
+impl std::fmt::Display for NegotiationTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", NEGOTIATION_TIMEOUT_ERROR)
+    }
+}
+
+impl std::error::Error for NegotiationTimedOut {}
+
+impl NegotiationTimedOut {
+    /// io::Error returned by the transport for this timeout
+    pub fn into_io_error(self) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, NegotiationTimeoutPayload(self))
+    }
+
+    /// Returns true if `error` or one of its causes is a negotiation timeout
+    pub fn is_cause_of(error: &(dyn std::error::Error + 'static)) -> bool {
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if error.is::<NegotiationTimedOut>() {
+                return true;
+            }
+            current = match error.downcast_ref::<std::io::Error>() {
+                Some(io) => io.get_ref().map(|inner| inner as &(dyn std::error::Error + 'static)),
+                None => error.source(),
+            };
+        }
+        false
+    }
+}
+
+/// Payload of the io::Error carrying NegotiationTimedOut
+///
+/// io::Error and Either above the transport forward `source()` of their inner
+/// error rather than the error itself, so the payload exposes the timeout as its source.
+#[derive(Debug)]
+struct NegotiationTimeoutPayload(NegotiationTimedOut);
+
+impl std::fmt::Display for NegotiationTimeoutPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for NegotiationTimeoutPayload {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Classified reason of a failed dial
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialError {
+    /// Transport gave up waiting for the remote
+    Timeout,
+    /// Transport failed to reach the remote
+    TransportError(String),
+    /// Connection was denied locally or by the remote peer
+    Denied(String),
+    /// Dial was aborted or not attempted
+    Other(String),
+    /// Address rejected by the dial address filter, the transport was not used
+    AddressBlocked(Multiaddr),
+    /// Handshake did not finish within NodeBuilder::with_negotiation_timeout
+    NegotiationTimeout,
+}
+
+impl std::fmt::Display for DialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DialError::Timeout => write!(f, "Dial timed out"),
+            DialError::TransportError(e) => write!(f, "Transport error: {}", e),
+            DialError::Denied(e) => write!(f, "Dial denied: {}", e),
+            DialError::Other(e) => write!(f, "Dial failed: {}", e),
+            DialError::AddressBlocked(addr) => write!(f, "Address {} is blocked by the dial filter", addr),
+            DialError::NegotiationTimeout => write!(f, "{}", NEGOTIATION_TIMEOUT_ERROR),
+        }
+    }
+}
+
+impl std::error::Error for DialError {}
+
+impl DialError {
+    /// Classify a swarm dial error
+    pub fn from_swarm(error: &libp2p::swarm::DialError) -> Self {
+        use libp2p::swarm::DialError as SwarmDialError;
+
+        match error {
+            SwarmDialError::Transport(errors) => {
+                let negotiation_timed_out = errors.iter().any(|(_, e)| match e {
+                    libp2p::TransportError::Other(io) => NegotiationTimedOut::is_cause_of(io),
+                    _ => false,
+                });
+                if negotiation_timed_out {
+                    return DialError::NegotiationTimeout;
+                }
+                let timed_out = errors.iter().any(|(_, e)| match e {
+                    libp2p::TransportError::Other(io) => {
+                        io.kind() == std::io::ErrorKind::TimedOut
+                    }
+                    _ => false,
+                });
+                if timed_out {
+                    DialError::Timeout
+                } else {
+                    DialError::TransportError(error.to_string())
+                }
+            }
+            SwarmDialError::Denied { .. }
+            | SwarmDialError::WrongPeerId { .. }
+            | SwarmDialError::LocalPeerId { .. } => DialError::Denied(error.to_string()),
+            _ => DialError::Other(error.to_string()),
+        }
+    }
+
+    /// First address involved in the failed dial, if any
+    pub fn failed_address(error: &libp2p::swarm::DialError) -> Option<Multiaddr> {
+        use libp2p::swarm::DialError as SwarmDialError;
+
+        match error {
+            SwarmDialError::Transport(errors) => errors.first().map(|(addr, _)| addr.clone()),
+            SwarmDialError::WrongPeerId { address, .. } => Some(address.clone()),
+            SwarmDialError::LocalPeerId { address } => Some(address.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// How NodeEvents are delivered to subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDelivery {
+    /// Bounded broadcast channel: a slow subscriber skips events and sees Lagged
+    Lossy(usize),
+    /// Additionally an unbounded channel per reliable subscriber: no events are
+    /// lost, memory grows while a subscriber falls behind
+    Reliable,
+}
+
+/// NodeEvent together with the command whose handling emitted it
+#[derive(Debug, Clone)]
+pub struct TaggedNodeEvent {
+    /// Set for events emitted while the SwarmLoop handled this command,
+    /// None for events caused by swarm activity
+    pub command_id: Option<CommandId>,
+    /// The event itself
+    pub event: NodeEvent,
+}
+
+/// Sends NodeEvents to broadcast subscribers and, in reliable mode, to every
+/// reliable subscriber
+#[derive(Debug, Clone)]
+pub struct NodeEventSender {
+    broadcast: broadcast::Sender<NodeEvent>,
+    tagged: broadcast::Sender<TaggedNodeEvent>,
+    reliable: Option<Arc<Mutex<Vec<mpsc::UnboundedSender<NodeEvent>>>>>,
+}
+
+impl NodeEventSender {
+    /// Broadcast capacity is used by `subscribe` in both modes
+    pub fn new(delivery: EventDelivery, broadcast_capacity: usize) -> Self {
+        let (capacity, reliable) = match delivery {
+            EventDelivery::Lossy(capacity) => (capacity, None),
+            EventDelivery::Reliable => (broadcast_capacity, Some(Arc::new(Mutex::new(Vec::new())))),
+        };
+        Self {
+            broadcast: broadcast::channel(capacity.max(1)).0,
+            tagged: broadcast::channel(capacity.max(1)).0,
+            reliable,
+        }
+    }
+
+    /// Send an event to all subscribers, returns the number of broadcast receivers
+    pub fn send(&self, event: NodeEvent) -> Result<usize, broadcast::error::SendError<NodeEvent>> {
+        if let Some(reliable) = &self.reliable {
+            // Закрытые подписки удаляются при следующей отправке
+            reliable
+                .lock()
+                .unwrap()
+                .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+        if self.tagged.receiver_count() > 0 {
+            let _ = self.tagged.send(TaggedNodeEvent {
+                command_id: command_swarm::current_command_id(),
+                event: event.clone(),
+            });
+        }
+        self.broadcast.send(event)
+    }
+
+    /// Subscribe to the broadcast channel, may lag behind
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.broadcast.subscribe()
+    }
+
+    /// Subscribe to the broadcast channel with each event tagged by the command
+    /// that emitted it, may lag behind
+    pub fn subscribe_tagged(&self) -> broadcast::Receiver<TaggedNodeEvent> {
+        self.tagged.subscribe()
+    }
+
+    /// Subscribe without losing events, None unless delivery is Reliable
+    pub fn subscribe_reliable(&self) -> Option<mpsc::UnboundedReceiver<NodeEvent>> {
+        let reliable = self.reliable.as_ref()?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        reliable.lock().unwrap().push(tx);
+        Some(rx)
+    }
+
+    /// Returns true if reliable subscriptions are available
+    pub fn is_reliable(&self) -> bool {
+        self.reliable.is_some()
+    }
+}
+
+/// Item of a filtered event subscription
+#[derive(Debug, Clone)]
+pub enum FilteredEvent {
+    /// Event matching the subscription predicate
+    Event(NodeEvent),
+    /// Receiver fell behind, this many events were skipped
+    Lagged(u64),
 }
 
 impl NodeEvent {
@@ -137,14 +488,29 @@ impl NodeEvent {
             NodeEvent::ConnectionClosed { .. } => "ConnectionClosed",
             NodeEvent::NewListenAddr { .. } => "NewListenAddr",
             NodeEvent::ExpiredListenAddr { .. } => "ExpiredListenAddr",
+            NodeEvent::ConnectionLimitReached { .. } => "ConnectionLimitReached",
+            NodeEvent::DialFailed { .. } => "DialFailed",
+            NodeEvent::DialAddressRewritten { .. } => "DialAddressRewritten",
+            NodeEvent::ReconnectAttempt { .. } => "ReconnectAttempt",
+            NodeEvent::RelayReservationAccepted { .. } => "RelayReservationAccepted",
+            NodeEvent::HolePunchSucceeded { .. } => "HolePunchSucceeded",
+            NodeEvent::HolePunchFailed { .. } => "HolePunchFailed",
+            NodeEvent::PingRtt { .. } => "PingRtt",
+            NodeEvent::NatStatusChanged { .. } => "NatStatusChanged",
+            NodeEvent::ExternalAddressesChanged { .. } => "ExternalAddressesChanged",
+            NodeEvent::ConnectionMigrated { .. } => "ConnectionMigrated",
             NodeEvent::PeerMutualAuthSuccess { .. } => "PeerMutualAuthSuccess",
             NodeEvent::PeerOutboundAuthSuccess { .. } => "PeerOutboundAuthSuccess",
             NodeEvent::PeerInboundAuthSuccess { .. } => "PeerInboundAuthSuccess",
+            NodeEvent::PeerAuthTimeout { .. } => "PeerAuthTimeout",
+            NodeEvent::PeerAuthRejected { .. } => "PeerAuthRejected",
             NodeEvent::VerifyPorRequest { .. } => "VerifyPorRequest",
             NodeEvent::XStreamIncoming { .. } => "XStreamIncoming",
             NodeEvent::XStreamEstablished { .. } => "XStreamEstablished",
             NodeEvent::XStreamError { .. } => "XStreamError",
             NodeEvent::XStreamClosed { .. } => "XStreamClosed",
+            NodeEvent::XStreamBackpressure { .. } => "XStreamBackpressure",
+            NodeEvent::XStreamDrained { .. } => "XStreamDrained",
             NodeEvent::XStreamIncomingStreamRequest { .. } => "XStreamIncomingStreamRequest",
             NodeEvent::IdentifyReceived { .. } => "IdentifyReceived",
             NodeEvent::IdentifySent { .. } => "IdentifySent",
@@ -152,9 +518,13 @@ impl NodeEvent {
             NodeEvent::KademliaPeerDiscovered { .. } => "KademliaPeerDiscovered",
             NodeEvent::KademliaBootstrapCompleted { .. } => "KademliaBootstrapCompleted",
             NodeEvent::KademliaRoutingUpdated { .. } => "KademliaRoutingUpdated",
+            NodeEvent::KadModeChanged { .. } => "KadModeChanged",
             NodeEvent::MdnsPeerDiscovered { .. } => "MdnsPeerDiscovered",
             NodeEvent::MdnsPeerExpired { .. } => "MdnsPeerExpired",
             NodeEvent::MdnsError { .. } => "MdnsError",
+            NodeEvent::PeerDiscovered { .. } => "PeerDiscovered",
+            NodeEvent::ShuttingDown => "ShuttingDown",
+            NodeEvent::Shutdown => "Shutdown",
         }
     }
 
@@ -166,6 +536,17 @@ impl NodeEvent {
                 | NodeEvent::ConnectionClosed { .. }
                 | NodeEvent::NewListenAddr { .. }
                 | NodeEvent::ExpiredListenAddr { .. }
+                | NodeEvent::ConnectionLimitReached { .. }
+                | NodeEvent::DialFailed { .. }
+                | NodeEvent::DialAddressRewritten { .. }
+                | NodeEvent::ReconnectAttempt { .. }
+                | NodeEvent::RelayReservationAccepted { .. }
+                | NodeEvent::HolePunchSucceeded { .. }
+                | NodeEvent::HolePunchFailed { .. }
+                | NodeEvent::PingRtt { .. }
+                | NodeEvent::NatStatusChanged { .. }
+                | NodeEvent::ExternalAddressesChanged { .. }
+                | NodeEvent::ConnectionMigrated { .. }
         )
     }
 
@@ -176,6 +557,8 @@ impl NodeEvent {
             NodeEvent::PeerMutualAuthSuccess { .. }
                 | NodeEvent::PeerOutboundAuthSuccess { .. }
                 | NodeEvent::PeerInboundAuthSuccess { .. }
+                | NodeEvent::PeerAuthTimeout { .. }
+                | NodeEvent::PeerAuthRejected { .. }
                 | NodeEvent::VerifyPorRequest { .. }
         )
     }
@@ -188,6 +571,8 @@ impl NodeEvent {
                 | NodeEvent::XStreamEstablished { .. }
                 | NodeEvent::XStreamError { .. }
                 | NodeEvent::XStreamClosed { .. }
+                | NodeEvent::XStreamBackpressure { .. }
+                | NodeEvent::XStreamDrained { .. }
                 | NodeEvent::XStreamIncomingStreamRequest { .. }
         )
     }
@@ -0,0 +1,124 @@
+//! Время отклика пиров по результатам libp2p ping
+//!
+//! Для каждого пира хранится последнее измерение и среднее по последним
+//! RTT_WINDOW измерениям. Данные пира сбрасываются после закрытия его
+//! последнего соединения.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+use libp2p::PeerId;
+use tokio::sync::oneshot;
+
+/// Number of latest samples in the rolling average
+pub const RTT_WINDOW: usize = 10;
+
+/// Round-trip time measured by ping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerRtt {
+    /// Most recent sample
+    pub last: Duration,
+    /// Average of the latest RTT_WINDOW samples
+    pub avg: Duration,
+    /// Successful pings since the peer connected
+    pub sample_count: u64,
+}
+
+/// Error of an on-demand ping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PingError {
+    /// No ping result within the timeout
+    Timeout,
+    /// Peer is not connected or disconnected while waiting
+    Disconnected,
+    /// Ping failed or the command could not be delivered
+    Failed(String),
+}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PingError::Timeout => write!(f, "Ping timed out"),
+            PingError::Disconnected => write!(f, "Peer is not connected"),
+            PingError::Failed(e) => write!(f, "Ping failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PingError {}
+
+/// Waiter for the next ping result of a peer
+pub type PingWaiter = oneshot::Sender<Result<Duration, PingError>>;
+
+#[derive(Debug, Default)]
+struct PeerSamples {
+    window: VecDeque<Duration>,
+    sample_count: u64,
+}
+
+/// Collects ping results into PeerRtt per peer
+#[derive(Debug, Default)]
+pub struct RttTracker {
+    peers: HashMap<PeerId, PeerSamples>,
+    /// Commander::ping calls waiting for the next result of a peer
+    waiters: HashMap<PeerId, Vec<PingWaiter>>,
+}
+
+impl RttTracker {
+    /// Record a successful ping
+    pub fn record(&mut self, peer_id: PeerId, rtt: Duration) {
+        let samples = self.peers.entry(peer_id).or_default();
+        if samples.window.len() == RTT_WINDOW {
+            samples.window.pop_front();
+        }
+        samples.window.push_back(rtt);
+        samples.sample_count += 1;
+        self.resolve(&peer_id, Ok(rtt));
+    }
+
+    /// Record a failed ping
+    pub fn record_failure(&mut self, peer_id: PeerId, error: String) {
+        self.resolve(&peer_id, Err(PingError::Failed(error)));
+    }
+
+    /// Forget a peer, e.g. after its last connection closed
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+        self.resolve(peer_id, Err(PingError::Disconnected));
+    }
+
+    /// Wait for the next ping result of a connected peer
+    pub fn add_waiter(&mut self, peer_id: PeerId, waiter: PingWaiter) {
+        let waiters = self.waiters.entry(peer_id).or_default();
+        // Отмененные по таймауту ожидания не копятся
+        waiters.retain(|waiter| !waiter.is_closed());
+        waiters.push(waiter);
+    }
+
+    fn resolve(&mut self, peer_id: &PeerId, result: Result<Duration, PingError>) {
+        for waiter in self.waiters.remove(peer_id).unwrap_or_default() {
+            let _ = waiter.send(result.clone());
+        }
+    }
+
+    /// RTT of a peer, None before the first successful ping
+    pub fn get(&self, peer_id: &PeerId) -> Option<PeerRtt> {
+        let samples = self.peers.get(peer_id)?;
+        let last = *samples.window.back()?;
+        let avg = samples.window.iter().sum::<Duration>() / samples.window.len() as u32;
+        Some(PeerRtt {
+            last,
+            avg,
+            sample_count: samples.sample_count,
+        })
+    }
+
+    /// RTT of every peer with at least one successful ping
+    pub fn all(&self) -> Vec<(PeerId, PeerRtt)> {
+        self.peers
+            .keys()
+            .filter_map(|peer_id| self.get(peer_id).map(|rtt| (*peer_id, rtt)))
+            .collect()
+    }
+}
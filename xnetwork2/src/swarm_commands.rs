@@ -1,12 +1,15 @@
 //! Swarm-level commands for XNetwork2
 
-use libp2p::{Multiaddr, PeerId};
+use libp2p::{Multiaddr, PeerId, StreamProtocol};
 use libp2p::core::transport::ListenerId;
-use tokio::sync::oneshot;
+use libp2p::swarm::dial_opts::PeerCondition;
+use tokio::sync::{broadcast, oneshot};
 use std::time::Duration;
 use std::fmt;
 
+use crate::behaviours::xroutes::types::KadMode;
 use crate::conntracker::commands::ConntrackerCommand;
+use xstream::xstream::XStream;
 
 /// Swarm-level commands for XNetwork2 with response channels
 pub enum SwarmLevelCommand {
@@ -16,10 +19,17 @@ pub enum SwarmLevelCommand {
         addr: Multiaddr,
         response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
     },
-    /// Dial a peer and wait for connection established
+    /// Dial a peer with an explicit dial condition and candidate addresses
+    DialWithOpts {
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+        condition: PeerCondition,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Dial a peer on all addresses at once and wait for the connection
     DialAndWait {
         peer_id: PeerId,
-        addr: Multiaddr,
+        addresses: Vec<Multiaddr>,
         timeout: Duration,
         response: oneshot::Sender<Result<libp2p::swarm::ConnectionId, Box<dyn std::error::Error + Send + Sync>>>,
     },
@@ -28,26 +38,117 @@ pub enum SwarmLevelCommand {
         addr: Multiaddr,
         response: oneshot::Sender<Result<ListenerId, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Stop listening (returns false if the listener does not exist)
+    RemoveListener {
+        listener_id: ListenerId,
+        response: oneshot::Sender<Result<bool, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Listen on an address and wait for first listen address event
     ListenAndWait {
         addr: Multiaddr,
         timeout: Duration,
         response: oneshot::Sender<Result<Multiaddr, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Listen on an address and collect every bound address of the listener within `window`
+    ListenAndCollect {
+        addr: Multiaddr,
+        window: Duration,
+        response: oneshot::Sender<Result<Vec<Multiaddr>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Disconnect from a peer
     Disconnect {
         peer_id: PeerId,
         response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Close a single connection, keeping other connections to the same peer
+    DisconnectConnection {
+        connection_id: libp2p::swarm::ConnectionId,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Disconnect from every connected peer (returns number of peers disconnected)
+    DisconnectAll {
+        response: oneshot::Sender<Result<usize, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Reserve a slot on a relay and listen on the relayed address
+    RequestRelayReservation {
+        relay_peer: PeerId,
+        relay_addr: Multiaddr,
+        timeout: Duration,
+        response: oneshot::Sender<Result<ReservationInfo, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Stop listening via a relay, dropping the reservation
+    ReleaseRelayReservation {
+        relay_peer: PeerId,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Attempt a DCUtR upgrade of a relayed connection to a direct one
+    DirectConnect {
+        peer_id: PeerId,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get reachability derived from AutoNAT probes
+    GetNatStatus {
+        response: oneshot::Sender<Result<crate::nat::NatStatus, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get ping round-trip times of a peer
+    GetPeerRtt {
+        peer_id: PeerId,
+        response: oneshot::Sender<Result<Option<crate::rtt::PeerRtt>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Wait for the next ping result of a connected peer
+    Ping {
+        peer_id: PeerId,
+        response: crate::rtt::PingWaiter,
+    },
+    /// Get the number of dials in flight and waiting for the concurrency limit
+    GetDialStats {
+        response: oneshot::Sender<Result<DialStats, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get aggregated node status for health checks
+    GetStatus {
+        response: oneshot::Sender<Result<NodeStatus, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Get network state
     GetNetworkState {
         response: oneshot::Sender<Result<NetworkState, Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Check if mutual authentication with a connected peer succeeded
+    IsPeerAuthenticated {
+        peer_id: PeerId,
+        response: oneshot::Sender<Result<bool, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Get the number of open XStreams per peer
+    GetStreamCounts {
+        response: oneshot::Sender<
+            Result<std::collections::HashMap<PeerId, usize>, Box<dyn std::error::Error + Send + Sync>>,
+        >,
+    },
+    /// Collect connection, stream, auth, DHT and RTT counters
+    GetMetrics {
+        response: oneshot::Sender<
+            Result<crate::metrics::MetricsSnapshot, Box<dyn std::error::Error + Send + Sync>>,
+        >,
+    },
+    /// Get the latest Identify information received from a peer
+    GetPeerInfo {
+        peer_id: PeerId,
+        response: oneshot::Sender<Result<Option<PeerIdentifyInfo>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Subscribe to incremental NetworkState changes
+    SubscribeNetworkState {
+        response: oneshot::Sender<
+            Result<broadcast::Receiver<NetworkStateDelta>, Box<dyn std::error::Error + Send + Sync>>,
+        >,
+    },
     /// Shutdown the node
     Shutdown {
         stopper: command_swarm::SwarmLoopStopper,
         response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Refuse new dials and streams, returns open XStreams to close
+    BeginShutdown {
+        response: oneshot::Sender<Result<Vec<XStream>, Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Echo command for testing - returns the same message back
     Echo {
         message: String,
@@ -63,6 +164,22 @@ pub enum SwarmLevelCommand {
         address: Multiaddr,
         response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
     },
+    /// Remove external address from swarm
+    RemoveExternalAddress {
+        address: Multiaddr,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Replace the external address set and push it to connected peers via Identify
+    SetExternalAddresses {
+        addresses: Vec<Multiaddr>,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
+    /// Switch Kademlia mode, promotion to server requires a confirmed external address unless forced
+    SetKadMode {
+        mode: KadMode,
+        force: bool,
+        response: oneshot::Sender<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    },
     /// Get all external addresses
     GetExternalAddresses {
         response: oneshot::Sender<Result<Vec<Multiaddr>, Box<dyn std::error::Error + Send + Sync>>>,
@@ -82,30 +199,176 @@ pub struct NetworkState {
     pub authenticated_peers: Vec<PeerId>,
 }
 
+/// Incremental change of NetworkState
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkStateDelta {
+    /// First connection with the peer established
+    PeerAdded { peer_id: PeerId },
+    /// Last connection with the peer closed
+    PeerRemoved { peer_id: PeerId },
+    /// Listener reported a new listen address
+    ListenerAdded {
+        listener_id: ListenerId,
+        address: Multiaddr,
+    },
+    /// Listen address expired or its listener closed
+    ListenerRemoved {
+        listener_id: ListenerId,
+        address: Multiaddr,
+    },
+    /// Peer became authenticated or lost authentication on disconnect
+    AuthChanged { peer_id: PeerId, authenticated: bool },
+}
+
+/// Dial concurrency limiter state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialStats {
+    /// Issued dials not established or failed yet, tracked only with a limit
+    pub in_flight: usize,
+    /// Dials waiting for a free slot
+    pub queued: usize,
+}
+
+/// Aggregated node status for health checks
+///
+/// `None` fields belong to components that are disabled on this node.
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub peer_id: PeerId,
+    pub listening_addresses: Vec<Multiaddr>,
+    /// Established connections, a peer may have several
+    pub connection_count: usize,
+    pub connected_peer_count: usize,
+    pub authenticated_peer_count: usize,
+    /// Reachability from AutoNAT probes, None without the AutoNAT client
+    pub nat_status: Option<crate::nat::NatStatus>,
+    /// Peers in the Kademlia routing table, None without Kademlia
+    pub routing_table_size: Option<usize>,
+}
+
+/// Renewal interval of a relay reservation
+///
+/// libp2p does not report the reservation expiry to the client; it renews
+/// at 3/4 of the expiry, which is one hour on default relay servers.
+pub const RELAY_RESERVATION_RENEWAL_INTERVAL: Duration = Duration::from_secs(45 * 60);
+
+/// Accepted relay reservation
+#[derive(Debug, Clone)]
+pub struct ReservationInfo {
+    pub relay_peer_id: PeerId,
+    /// Address other peers can dial us on through the relay
+    pub relayed_addr: Multiaddr,
+    /// How often the reservation is renewed in the background
+    pub renewal_interval: Duration,
+}
+
+/// Identify information last received from a connected peer
+#[derive(Debug, Clone)]
+pub struct PeerIdentifyInfo {
+    /// Protocols the peer supports
+    pub protocols: Vec<StreamProtocol>,
+    pub agent_version: String,
+    pub protocol_version: String,
+    /// Our address as seen by the peer
+    pub observed_addr: Multiaddr,
+}
+
+impl From<&libp2p::identify::Info> for PeerIdentifyInfo {
+    fn from(info: &libp2p::identify::Info) -> Self {
+        Self {
+            protocols: info.protocols.clone(),
+            agent_version: info.agent_version.clone(),
+            protocol_version: info.protocol_version.clone(),
+            observed_addr: info.observed_addr.clone(),
+        }
+    }
+}
+
 impl fmt::Debug for SwarmLevelCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SwarmLevelCommand::Dial { peer_id, addr, .. } => {
                 write!(f, "Dial(peer_id: {}, addr: {})", peer_id, addr)
             }
-            SwarmLevelCommand::DialAndWait { peer_id, addr, timeout, .. } => {
-                write!(f, "DialAndWait(peer_id: {}, addr: {}, timeout: {:?})", peer_id, addr, timeout)
+            SwarmLevelCommand::DialWithOpts { peer_id, addresses, condition, .. } => {
+                write!(
+                    f,
+                    "DialWithOpts(peer_id: {}, addresses: {:?}, condition: {:?})",
+                    peer_id, addresses, condition
+                )
+            }
+            SwarmLevelCommand::DialAndWait { peer_id, addresses, timeout, .. } => {
+                write!(f, "DialAndWait(peer_id: {}, addresses: {:?}, timeout: {:?})", peer_id, addresses, timeout)
             }
             SwarmLevelCommand::ListenOn { addr, .. } => {
                 write!(f, "ListenOn(addr: {})", addr)
             }
+            SwarmLevelCommand::RemoveListener { listener_id, .. } => {
+                write!(f, "RemoveListener(listener_id: {:?})", listener_id)
+            }
             SwarmLevelCommand::ListenAndWait { addr, timeout, .. } => {
                 write!(f, "ListenAndWait(addr: {}, timeout: {:?})", addr, timeout)
             }
+            SwarmLevelCommand::ListenAndCollect { addr, window, .. } => {
+                write!(f, "ListenAndCollect(addr: {}, window: {:?})", addr, window)
+            }
             SwarmLevelCommand::Disconnect { peer_id, .. } => {
                 write!(f, "Disconnect(peer_id: {})", peer_id)
             }
+            SwarmLevelCommand::DisconnectConnection { connection_id, .. } => {
+                write!(f, "DisconnectConnection(connection_id: {:?})", connection_id)
+            }
+            SwarmLevelCommand::DisconnectAll { .. } => {
+                write!(f, "DisconnectAll")
+            }
+            SwarmLevelCommand::RequestRelayReservation { relay_peer, relay_addr, timeout, .. } => {
+                write!(f, "RequestRelayReservation(relay_peer: {}, relay_addr: {}, timeout: {:?})", relay_peer, relay_addr, timeout)
+            }
+            SwarmLevelCommand::ReleaseRelayReservation { relay_peer, .. } => {
+                write!(f, "ReleaseRelayReservation(relay_peer: {})", relay_peer)
+            }
+            SwarmLevelCommand::DirectConnect { peer_id, .. } => {
+                write!(f, "DirectConnect(peer_id: {})", peer_id)
+            }
+            SwarmLevelCommand::GetNatStatus { .. } => {
+                write!(f, "GetNatStatus")
+            }
+            SwarmLevelCommand::GetPeerRtt { peer_id, .. } => {
+                write!(f, "GetPeerRtt(peer_id: {})", peer_id)
+            }
+            SwarmLevelCommand::Ping { peer_id, .. } => {
+                write!(f, "Ping(peer_id: {})", peer_id)
+            }
+            SwarmLevelCommand::GetDialStats { .. } => {
+                write!(f, "GetDialStats")
+            }
+            SwarmLevelCommand::GetStatus { .. } => {
+                write!(f, "GetStatus")
+            }
+            SwarmLevelCommand::IsPeerAuthenticated { peer_id, .. } => {
+                write!(f, "IsPeerAuthenticated(peer_id: {})", peer_id)
+            }
+            SwarmLevelCommand::GetStreamCounts { .. } => {
+                write!(f, "GetStreamCounts")
+            }
+            SwarmLevelCommand::GetMetrics { .. } => {
+                write!(f, "GetMetrics")
+            }
+            SwarmLevelCommand::GetPeerInfo { peer_id, .. } => {
+                write!(f, "GetPeerInfo(peer_id: {})", peer_id)
+            }
+            SwarmLevelCommand::SubscribeNetworkState { .. } => {
+                write!(f, "SubscribeNetworkState")
+            }
             SwarmLevelCommand::GetNetworkState { .. } => {
                 write!(f, "GetNetworkState")
             }
             SwarmLevelCommand::Shutdown { .. } => {
                 write!(f, "Shutdown")
             }
+            SwarmLevelCommand::BeginShutdown { .. } => {
+                write!(f, "BeginShutdown")
+            }
             SwarmLevelCommand::Echo { message, .. } => {
                 write!(f, "Echo(message: '{}')", message)
             }
@@ -115,6 +378,15 @@ impl fmt::Debug for SwarmLevelCommand {
             SwarmLevelCommand::AddExternalAddress { address, .. } => {
                 write!(f, "AddExternalAddress(address: {})", address)
             }
+            SwarmLevelCommand::RemoveExternalAddress { address, .. } => {
+                write!(f, "RemoveExternalAddress(address: {})", address)
+            }
+            SwarmLevelCommand::SetExternalAddresses { addresses, .. } => {
+                write!(f, "SetExternalAddresses(addresses: {:?})", addresses)
+            }
+            SwarmLevelCommand::SetKadMode { mode, force, .. } => {
+                write!(f, "SetKadMode(mode: {}, force: {})", mode, force)
+            }
             SwarmLevelCommand::GetExternalAddresses { .. } => {
                 write!(f, "GetExternalAddresses")
             }
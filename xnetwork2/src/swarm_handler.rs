@@ -3,34 +3,58 @@
 use async_trait::async_trait;
 use command_swarm::{NetworkBehaviour, SwarmHandler};
 use libp2p::core::transport::ListenerId;
-use libp2p::swarm::{FromSwarm, NewExternalAddrCandidate};
+use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
+use libp2p::swarm::{ConnectionId, FromSwarm, NewExternalAddrCandidate};
 use libp2p::{Multiaddr, PeerId, Swarm};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, oneshot};
 use tracing::{debug, info};
 
+use crate::behaviours::peer_filter::{AddressRewriter, DialAddressFilter};
+use crate::behaviours::xauth::{AutoAuthPolicy, MetadataValidator};
+use crate::behaviours::xstream::{
+    AsyncInboundDecision, InboundDecision, InboundStreamPolicy, DEFAULT_INBOUND_DECISION_TIMEOUT,
+};
 use crate::behaviours::xroutes::PendingTaskManager;
+use crate::behaviours::xroutes::types::KadMode;
 use crate::conntracker::{Conntracker, ConnectionInfo, PeerConnections};
 use crate::conntracker::commands::ConntrackerCommand;
+use crate::discovery::{DiscoveryAggregator, DiscoverySource};
+use crate::behaviours::connection_limits::ConnectionLimitsEvent;
+use crate::behaviours::reconnect::ReconnectEvent;
+use crate::behaviours::keep_alive::behaviour::KeepAliveEvent;
 use crate::main_behaviour::{XNetworkBehaviour, XNetworkBehaviourEvent};
-use crate::node_events::NodeEvent;
-use crate::swarm_commands::{NetworkState, SwarmLevelCommand};
+use crate::nat::NatStatusTracker;
+use crate::rtt::RttTracker;
+use crate::node_events::{DialError, NodeEvent, NodeEventSender};
+use crate::swarm_commands::{
+    DialStats, NetworkState, NetworkStateDelta, NodeStatus, PeerIdentifyInfo, ReservationInfo,
+    SwarmLevelCommand,
+    RELAY_RESERVATION_RENEWAL_INTERVAL,
+};
+use crate::telemetry::ConnectionTelemetry;
 use xauth::events::PorAuthEvent;
 use xstream::events::XStreamEvent;
+use xstream::types::XStreamID;
+
+/// Buffered NetworkState deltas per subscriber before it lags
+const NETWORK_STATE_DELTA_CAPACITY: usize = 256;
 
 /// Key for dial_and_wait operations to handle multiple connections to same peer
-/// We use a combination of peer_id and connection attempt counter to handle multiple connections
+/// The connection id of the dial tells which connection completes which task
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct DialWaitKey {
     peer_id: PeerId,
-    attempt_id: u64, // Simple counter to distinguish multiple connection attempts to same peer
+    connection_id: ConnectionId,
 }
 
 /// Swarm handler for XNetwork2
 pub struct XNetworkSwarmHandler {
     /// Broadcast channel for sending NodeEvents to multiple subscribers
-    event_sender: Option<broadcast::Sender<NodeEvent>>,
+    event_sender: Option<NodeEventSender>,
     /// Track authenticated peers
     authenticated_peers: std::collections::HashSet<PeerId>,
+    /// Incremental NetworkState changes for subscribers
+    state_deltas: broadcast::Sender<NetworkStateDelta>,
     /// Pending tasks for listen_and_wait operations
     listen_wait_tasks:
         PendingTaskManager<ListenerId, Multiaddr, Box<dyn std::error::Error + Send + Sync>, ()>,
@@ -41,8 +65,67 @@ pub struct XNetworkSwarmHandler {
         Box<dyn std::error::Error + Send + Sync>,
         (),
     >,
+    /// Pending relay reservations, extra holds (accepted, relayed address)
+    relay_reservation_tasks: PendingTaskManager<
+        PeerId,
+        ReservationInfo,
+        Box<dyn std::error::Error + Send + Sync>,
+        (bool, Option<Multiaddr>),
+    >,
+    /// Circuit listeners by relay peer
+    relay_listeners: std::collections::HashMap<PeerId, ListenerId>,
+    /// Relay peers whose reservation was accepted
+    relay_reserved: std::collections::HashSet<PeerId>,
+    /// Reachability derived from AutoNAT client probes
+    nat_status: NatStatusTracker,
+    /// Round-trip times measured by ping
+    rtt: RttTracker,
     /// Connection tracker service
     conntracker: Conntracker,
+    /// mDNS interface filter for emitted discovery events
+    mdns_interface: Option<std::net::IpAddr>,
+    /// Aggregates discoveries from mDNS, Kademlia and Identify into PeerDiscovered
+    discovery: DiscoveryAggregator,
+    /// Validator for requester metadata in PoR verification requests
+    metadata_validator: Option<MetadataValidator>,
+    /// Only report validator results, the application submits the decision itself
+    manual_metadata_validation: bool,
+    /// Optional export of connection and auth events to a tracing/OTel sink
+    telemetry: Option<ConnectionTelemetry>,
+    /// Dials issued at once, None means unlimited
+    max_concurrent_dials: Option<usize>,
+    /// Outgoing connections that are not established or failed yet
+    dials_in_flight: std::collections::HashSet<ConnectionId>,
+    /// Dial commands waiting for a free slot, with the time they were received
+    queued_dials: std::collections::VecDeque<(std::time::Instant, SwarmLevelCommand)>,
+    /// Relays dialed before their circuit listener is opened, by the dial's connection
+    relay_dials: std::collections::HashMap<PeerId, (ConnectionId, Multiaddr)>,
+    /// Start authentication right after a connection is established
+    auto_auth: bool,
+    /// Peers allowed for automatic authentication, None allows all
+    auto_auth_policy: Option<AutoAuthPolicy>,
+    /// Connections where authentication was started automatically
+    auto_auth_connections: std::collections::HashSet<ConnectionId>,
+    /// Graceful shutdown started, new dials and inbound streams are refused
+    shutting_down: bool,
+    /// Decides about inbound streams before asking the application, None defers all
+    inbound_stream_policy: Option<InboundStreamPolicy>,
+    /// Async decision for inbound streams the sync policy deferred
+    async_inbound_decision: Option<AsyncInboundDecision>,
+    /// Time given to the async decision before the stream is rejected
+    inbound_decision_timeout: std::time::Duration,
+    /// Open XStreams per peer, used to spot stream leaks
+    open_streams: std::collections::HashMap<PeerId, std::collections::HashSet<XStreamID>>,
+    /// Latest Identify information per connected peer
+    identify_info: std::collections::HashMap<PeerId, PeerIdentifyInfo>,
+    /// Consulted before swarm.dial, blocked addresses fail with DialError::AddressBlocked
+    dial_address_filter: Option<DialAddressFilter>,
+    /// Applied to every address right before swarm.dial
+    address_rewriter: Option<AddressRewriter>,
+    /// Mutual authentications since start, exported by metrics
+    auth_successes: u64,
+    /// Failed authentications since start, exported by metrics
+    auth_failures: u64,
 }
 
 impl Default for XNetworkSwarmHandler {
@@ -50,25 +133,459 @@ impl Default for XNetworkSwarmHandler {
         Self {
             event_sender: None,
             authenticated_peers: std::collections::HashSet::new(),
+            state_deltas: broadcast::channel(NETWORK_STATE_DELTA_CAPACITY).0,
             listen_wait_tasks: PendingTaskManager::new(),
             dial_wait_tasks: PendingTaskManager::new(),
+            relay_reservation_tasks: PendingTaskManager::new(),
+            relay_listeners: std::collections::HashMap::new(),
+            relay_reserved: std::collections::HashSet::new(),
+            nat_status: NatStatusTracker::default(),
+            rtt: RttTracker::default(),
             conntracker: Conntracker::new(PeerId::random()), // Will be updated with actual peer_id later
+            mdns_interface: None,
+            discovery: DiscoveryAggregator::default(),
+            metadata_validator: None,
+            manual_metadata_validation: false,
+            telemetry: None,
+            max_concurrent_dials: None,
+            dials_in_flight: std::collections::HashSet::new(),
+            queued_dials: std::collections::VecDeque::new(),
+            relay_dials: std::collections::HashMap::new(),
+            auto_auth: false,
+            auto_auth_policy: None,
+            auto_auth_connections: std::collections::HashSet::new(),
+            shutting_down: false,
+            inbound_stream_policy: None,
+            async_inbound_decision: None,
+            inbound_decision_timeout: DEFAULT_INBOUND_DECISION_TIMEOUT,
+            open_streams: std::collections::HashMap::new(),
+            identify_info: std::collections::HashMap::new(),
+            dial_address_filter: None,
+            address_rewriter: None,
+            auth_successes: 0,
+            auth_failures: 0,
         }
     }
 }
 
 impl XNetworkSwarmHandler {
     /// Create a new SwarmHandler with event sender
-    pub fn with_event_sender(event_sender: broadcast::Sender<NodeEvent>) -> Self {
+    pub fn with_event_sender(event_sender: NodeEventSender) -> Self {
         Self {
             event_sender: Some(event_sender),
             authenticated_peers: std::collections::HashSet::new(),
+            state_deltas: broadcast::channel(NETWORK_STATE_DELTA_CAPACITY).0,
             listen_wait_tasks: PendingTaskManager::new(),
             dial_wait_tasks: PendingTaskManager::new(),
+            relay_reservation_tasks: PendingTaskManager::new(),
+            relay_listeners: std::collections::HashMap::new(),
+            relay_reserved: std::collections::HashSet::new(),
+            nat_status: NatStatusTracker::default(),
+            rtt: RttTracker::default(),
             conntracker: Conntracker::new(PeerId::random()), // Will be updated with actual peer_id later
+            mdns_interface: None,
+            discovery: DiscoveryAggregator::default(),
+            metadata_validator: None,
+            manual_metadata_validation: false,
+            telemetry: None,
+            max_concurrent_dials: None,
+            dials_in_flight: std::collections::HashSet::new(),
+            queued_dials: std::collections::VecDeque::new(),
+            relay_dials: std::collections::HashMap::new(),
+            auto_auth: false,
+            auto_auth_policy: None,
+            auto_auth_connections: std::collections::HashSet::new(),
+            shutting_down: false,
+            inbound_stream_policy: None,
+            async_inbound_decision: None,
+            inbound_decision_timeout: DEFAULT_INBOUND_DECISION_TIMEOUT,
+            open_streams: std::collections::HashMap::new(),
+            identify_info: std::collections::HashMap::new(),
+            dial_address_filter: None,
+            address_rewriter: None,
+            auth_successes: 0,
+            auth_failures: 0,
+        }
+    }
+
+    /// Restrict emitted mDNS discovery events to the given interface network
+    pub fn with_mdns_interface(mut self, mdns_interface: Option<std::net::IpAddr>) -> Self {
+        self.mdns_interface = mdns_interface;
+        self
+    }
+
+    /// Set the window used to merge discoveries of the same peer
+    pub fn with_discovery_window(mut self, window: std::time::Duration) -> Self {
+        self.discovery = DiscoveryAggregator::new(window);
+        self
+    }
+
+    /// Set the metadata validator run on every PoR verification request
+    pub fn with_metadata_validator(
+        mut self,
+        validator: Option<MetadataValidator>,
+        manual: bool,
+    ) -> Self {
+        self.metadata_validator = validator;
+        self.manual_metadata_validation = manual;
+        self
+    }
+
+    /// Export connection and auth events to the given telemetry sink
+    pub fn with_telemetry(mut self, telemetry: Option<ConnectionTelemetry>) -> Self {
+        self.telemetry = telemetry;
+        self
+    }
+
+    /// Limit the number of dials issued at once, excess dials are queued
+    pub fn with_max_concurrent_dials(mut self, max_concurrent_dials: Option<usize>) -> Self {
+        self.max_concurrent_dials = max_concurrent_dials;
+        self
+    }
+
+    /// Start authentication automatically on new connections
+    pub fn with_auto_auth(mut self, enabled: bool, policy: Option<AutoAuthPolicy>) -> Self {
+        self.auto_auth = enabled;
+        self.auto_auth_policy = policy;
+        self
+    }
+
+    /// Decide about inbound streams with the policy before asking the application
+    pub fn with_inbound_stream_policy(mut self, policy: Option<InboundStreamPolicy>) -> Self {
+        self.inbound_stream_policy = policy;
+        self
+    }
+
+    /// Decide asynchronously about inbound streams the sync policy deferred,
+    /// a decision not ready within `timeout` rejects the stream
+    pub fn with_async_inbound_decision(
+        mut self,
+        decision: Option<AsyncInboundDecision>,
+        timeout: std::time::Duration,
+    ) -> Self {
+        self.async_inbound_decision = decision;
+        self.inbound_decision_timeout = timeout;
+        self
+    }
+
+    /// Filter addresses before dialing, None allows all
+    pub fn with_dial_address_filter(mut self, filter: Option<DialAddressFilter>) -> Self {
+        self.dial_address_filter = filter;
+        self
+    }
+
+    /// Rewrite addresses right before dialing, None dials them as requested
+    pub fn with_address_rewriter(mut self, rewriter: Option<AddressRewriter>) -> Self {
+        self.address_rewriter = rewriter;
+        self
+    }
+
+    /// Apply the address rewriter, a changed address is reported with DialAddressRewritten
+    fn rewrite_dial_address(&self, peer_id: PeerId, addr: Multiaddr) -> Multiaddr {
+        let Some(rewriter) = &self.address_rewriter else {
+            return addr;
+        };
+        let rewritten = rewriter(addr.clone());
+        if rewritten != addr {
+            debug!("🔀 [SwarmHandler] Dial address {} rewritten to {}", addr, rewritten);
+            if let Some(event_sender) = &self.event_sender {
+                let _ = event_sender.send(NodeEvent::DialAddressRewritten {
+                    peer_id,
+                    original: addr,
+                    rewritten: rewritten.clone(),
+                });
+            }
+        }
+        rewritten
+    }
+
+    /// Rewrite the addresses of one dial and drop those the filter rejects
+    ///
+    /// The filter sees the rewritten address, the one actually dialed.
+    /// Fails with DialError::AddressBlocked only if no address is left.
+    fn resolve_dial_addresses(
+        &self,
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+    ) -> Result<Vec<Multiaddr>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut blocked = None;
+        let addresses: Vec<Multiaddr> = addresses
+            .into_iter()
+            .map(|addr| self.rewrite_dial_address(peer_id, addr))
+            .filter(|addr| match self.check_dial_address(addr) {
+                Ok(()) => true,
+                Err(e) => {
+                    blocked.get_or_insert(e);
+                    false
+                }
+            })
+            .collect();
+        match blocked {
+            Some(e) if addresses.is_empty() => Err(e),
+            _ => Ok(addresses),
+        }
+    }
+
+    /// Returns DialError::AddressBlocked if the filter rejects the address
+    fn check_dial_address(
+        &self,
+        addr: &Multiaddr,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match &self.dial_address_filter {
+            Some(filter) if !filter(addr) => {
+                debug!("🚫 [SwarmHandler] Dial to {} blocked by address filter", addr);
+                Err(Box::new(DialError::AddressBlocked(addr.clone())))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Start authentication for a new connection if auto-auth allows the peer
+    fn auto_start_auth(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        match event {
+            libp2p::swarm::SwarmEvent::ConnectionEstablished {
+                peer_id,
+                connection_id,
+                ..
+            } if self.auto_auth => {
+                if let Some(policy) = &self.auto_auth_policy {
+                    if !policy(peer_id) {
+                        debug!(
+                            "🔐 [SwarmHandler] Auto-auth skipped by policy for peer: {}",
+                            peer_id
+                        );
+                        return;
+                    }
+                }
+                match swarm.behaviour_mut().xauth.start_authentication(*connection_id) {
+                    Ok(()) => {
+                        self.auto_auth_connections.insert(*connection_id);
+                        info!(
+                            "🔐 [SwarmHandler] Authentication started automatically for connection: {:?}",
+                            connection_id
+                        );
+                    }
+                    Err(e) => {
+                        debug!(
+                            "❌ [SwarmHandler] Failed to start auto-auth for connection {:?}: {}",
+                            connection_id, e
+                        );
+                    }
+                }
+            }
+            libp2p::swarm::SwarmEvent::ConnectionClosed { connection_id, .. } => {
+                self.auto_auth_connections.remove(connection_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// True if a new dial has to wait for a free slot
+    fn dial_limit_reached(&self) -> bool {
+        self.max_concurrent_dials
+            .is_some_and(|max| self.dials_in_flight.len() >= max)
+    }
+
+    /// Single choke point for every dial the node issues
+    ///
+    /// Addresses are rewritten exactly once and filtered after rewriting, the
+    /// connection is tracked while the dial limit is enabled. Without a condition
+    /// the first address is dialed as is, otherwise the peer is dialed on all of them.
+    /// Returns the connection id of the dial and the addresses actually dialed.
+    fn issue_dial(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+        condition: Option<PeerCondition>,
+    ) -> Result<(ConnectionId, Vec<Multiaddr>), Box<dyn std::error::Error + Send + Sync>> {
+        let addresses = self.resolve_dial_addresses(peer_id, addresses)?;
+        let opts = match condition {
+            Some(condition) => DialOpts::peer_id(peer_id)
+                .addresses(addresses.clone())
+                .condition(condition)
+                .build(),
+            None => match addresses.first() {
+                Some(addr) => DialOpts::from(addr.clone()),
+                None => return Err("No address to dial".into()),
+            },
+        };
+        let connection_id = opts.connection_id();
+        swarm.dial(opts)?;
+        if self.max_concurrent_dials.is_some() {
+            self.dials_in_flight.insert(connection_id);
+        }
+        Ok((connection_id, addresses))
+    }
+
+    /// Queue a dial command until a slot frees up
+    ///
+    /// The timeout of a dial_and_wait or a relay reservation counts from the
+    /// moment the command was received, so their responses are failed by a
+    /// timer while the command still waits in the queue.
+    fn queue_dial(&mut self, command: SwarmLevelCommand) {
+        let command = match command {
+            SwarmLevelCommand::DialAndWait { peer_id, addresses, timeout, response } => {
+                SwarmLevelCommand::DialAndWait {
+                    peer_id,
+                    addresses,
+                    timeout,
+                    response: respond_within(timeout, response, || Box::new(DialError::Timeout)),
+                }
+            }
+            SwarmLevelCommand::RequestRelayReservation { relay_peer, relay_addr, timeout, response } => {
+                SwarmLevelCommand::RequestRelayReservation {
+                    relay_peer,
+                    relay_addr,
+                    timeout,
+                    response: respond_within(timeout, response, move || {
+                        format!("Relay reservation on {} timed out", relay_peer).into()
+                    }),
+                }
+            }
+            command => command,
+        };
+        self.queued_dials.push_back((std::time::Instant::now(), command));
+    }
+
+    /// Drop queued commands whose caller already got a timeout
+    fn expire_queued_dials(&mut self) {
+        self.queued_dials.retain(|(_, command)| match command {
+            SwarmLevelCommand::DialAndWait { response, .. } => !response.is_closed(),
+            SwarmLevelCommand::RequestRelayReservation { response, .. } => !response.is_closed(),
+            _ => true,
+        });
+    }
+
+    /// Free the slot of a resolved dial and issue queued dials
+    async fn drain_queued_dials(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        match event {
+            libp2p::swarm::SwarmEvent::ConnectionEstablished { connection_id, .. }
+            | libp2p::swarm::SwarmEvent::OutgoingConnectionError { connection_id, .. } => {
+                self.dials_in_flight.remove(connection_id);
+            }
+            _ => return,
+        }
+
+        while !self.dial_limit_reached() {
+            let Some((received, mut command)) = self.queued_dials.pop_front() else {
+                break;
+            };
+            // Only the time left since the command was received
+            match &mut command {
+                SwarmLevelCommand::DialAndWait { timeout, .. }
+                | SwarmLevelCommand::RequestRelayReservation { timeout, .. } => {
+                    *timeout = timeout.saturating_sub(received.elapsed());
+                }
+                _ => {}
+            }
+            self.handle_command(swarm, command).await;
         }
     }
 
+    /// Dial a sticky peer whose re-dial is due, the same way as a Dial command
+    async fn redial_sticky_peer(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        let libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Reconnect(
+            ReconnectEvent::Attempt { peer_id, address, .. },
+        )) = event
+        else {
+            return;
+        };
+        let (response, mut result) = oneshot::channel();
+        self.handle_command(
+            swarm,
+            SwarmLevelCommand::DialWithOpts {
+                peer_id: *peer_id,
+                addresses: vec![address.clone()],
+                condition: PeerCondition::Disconnected,
+                response,
+            },
+        )
+        .await;
+        // Отказ swarm.dial сообщается behaviour через DialFailure, остальные отказы - здесь
+        if let Ok(Err(e)) = result.try_recv() {
+            debug!("🔁 [SwarmHandler] Re-dial of {} not issued: {}", peer_id, e);
+            let blocked = matches!(e.downcast_ref::<DialError>(), Some(DialError::AddressBlocked(_)));
+            if !blocked && !self.shutting_down {
+                swarm.behaviour_mut().reconnect.dial_not_issued(peer_id);
+            }
+        }
+    }
+
+    /// Run the metadata validator for a PoR verification request
+    /// Результат отправляется автоматически, если не включен ручной режим
+    fn validate_por_metadata(
+        &self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) -> Option<xauth::definitions::AuthResult> {
+        let validator = self.metadata_validator.as_ref()?;
+        let libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xauth(
+            PorAuthEvent::VerifyPorRequest {
+                peer_id,
+                connection_id,
+                por,
+                metadata,
+                ..
+            },
+        )) = event
+        else {
+            return None;
+        };
+
+        // Метаданные не подтверждают PoR, который не прошел проверку или выдан другому узлу
+        let result = if let Some(code) = xauth::definitions::RejectCode::for_por(por) {
+            let reason = por.validate().err().unwrap_or_else(|| "Invalid POR".to_string());
+            xauth::definitions::AuthResult::reject(code, reason)
+        } else if por.peer_id != *peer_id {
+            xauth::definitions::AuthResult::reject(
+                xauth::definitions::RejectCode::InvalidSignature,
+                format!("POR issued for {} presented by {}", por.peer_id, peer_id),
+            )
+        } else {
+            validator(peer_id, metadata)
+        };
+        debug!(
+            "🔎 [SwarmHandler] Metadata validator result for peer {}: {:?}",
+            peer_id, result
+        );
+
+        if !self.manual_metadata_validation {
+            if let Err(e) = swarm
+                .behaviour_mut()
+                .xauth
+                .submit_por_verification_result(*connection_id, result.clone())
+            {
+                debug!(
+                    "❌ [SwarmHandler] Failed to submit metadata validation for peer {}: {}",
+                    peer_id, e
+                );
+            }
+        }
+
+        Some(result)
+    }
+
     /// Update Conntracker with actual local peer ID from swarm
     pub fn update_local_peer_id(&mut self, local_peer_id: PeerId) {
         // Create new Conntracker with correct local peer ID
@@ -80,10 +597,296 @@ impl XNetworkSwarmHandler {
         self.authenticated_peers.contains(peer_id)
     }
 
-    /// Add a peer to authenticated set
-    fn mark_peer_authenticated(&mut self, peer_id: PeerId) {
-        self.authenticated_peers.insert(peer_id);
-        println!("✅ [SwarmHandler] Peer {} marked as authenticated", peer_id);
+    /// Add a peer to authenticated set, returns false if it was already there
+    fn mark_peer_authenticated(&mut self, peer_id: PeerId) -> bool {
+        let added = self.authenticated_peers.insert(peer_id);
+        if added {
+            println!("✅ [SwarmHandler] Peer {} marked as authenticated", peer_id);
+        }
+        added
+    }
+
+    /// Track open XStreams per peer: counted once established, dropped on the first StreamClosed
+    fn update_stream_counts(
+        &mut self,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        match event {
+            libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xstream(
+                XStreamEvent::StreamEstablished { peer_id, stream_id },
+            )) => {
+                self.open_streams.entry(*peer_id).or_default().insert(*stream_id);
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xstream(
+                XStreamEvent::IncomingStream { stream },
+            )) => {
+                self.open_streams.entry(stream.peer_id).or_default().insert(stream.id);
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xstream(
+                XStreamEvent::StreamClosed { peer_id, stream_id, .. },
+            )) => {
+                if let Some(streams) = self.open_streams.get_mut(peer_id) {
+                    streams.remove(stream_id);
+                    if streams.is_empty() {
+                        self.open_streams.remove(peer_id);
+                    }
+                }
+            }
+            libp2p::swarm::SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established: 0,
+                ..
+            } => {
+                // Streams do not outlive the last connection
+                self.open_streams.remove(peer_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn update_identify_info(
+        &mut self,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        match event {
+            libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xroutes(
+                super::behaviours::xroutes::XRoutesBehaviourEvent::Identify(
+                    libp2p::identify::Event::Received { peer_id, info, .. },
+                ),
+            )) => {
+                self.identify_info.insert(*peer_id, PeerIdentifyInfo::from(info));
+            }
+            libp2p::swarm::SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established: 0,
+                ..
+            } => {
+                self.identify_info.remove(peer_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Latest Identify information received from the peer
+    pub fn peer_info(&self, peer_id: &PeerId) -> Option<PeerIdentifyInfo> {
+        self.identify_info.get(peer_id).cloned()
+    }
+
+    /// Number of open XStreams per peer, peers without streams are omitted
+    pub fn stream_counts(&self) -> std::collections::HashMap<PeerId, usize> {
+        self.open_streams
+            .iter()
+            .map(|(peer_id, streams)| (*peer_id, streams.len()))
+            .collect()
+    }
+
+    /// Derive NetworkState deltas from a swarm event and send them to subscribers
+    fn update_network_state(
+        &mut self,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        let mut deltas = Vec::new();
+        match event {
+            libp2p::swarm::SwarmEvent::ConnectionEstablished {
+                peer_id,
+                num_established,
+                ..
+            } if num_established.get() == 1 => {
+                deltas.push(NetworkStateDelta::PeerAdded { peer_id: *peer_id });
+            }
+            libp2p::swarm::SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established: 0,
+                ..
+            } => {
+                deltas.push(NetworkStateDelta::PeerRemoved { peer_id: *peer_id });
+                if self.authenticated_peers.remove(peer_id) {
+                    deltas.push(NetworkStateDelta::AuthChanged {
+                        peer_id: *peer_id,
+                        authenticated: false,
+                    });
+                }
+            }
+            libp2p::swarm::SwarmEvent::NewListenAddr {
+                listener_id,
+                address,
+                ..
+            } => {
+                deltas.push(NetworkStateDelta::ListenerAdded {
+                    listener_id: *listener_id,
+                    address: address.clone(),
+                });
+            }
+            libp2p::swarm::SwarmEvent::ExpiredListenAddr {
+                listener_id,
+                address,
+                ..
+            } => {
+                deltas.push(NetworkStateDelta::ListenerRemoved {
+                    listener_id: *listener_id,
+                    address: address.clone(),
+                });
+            }
+            // Closed or removed listener drops all its addresses without ExpiredListenAddr
+            libp2p::swarm::SwarmEvent::ListenerClosed {
+                listener_id,
+                addresses,
+                ..
+            } => {
+                deltas.extend(addresses.iter().map(|address| NetworkStateDelta::ListenerRemoved {
+                    listener_id: *listener_id,
+                    address: address.clone(),
+                }));
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xauth(
+                PorAuthEvent::MutualAuthSuccess { peer_id, .. },
+            )) => {
+                if self.mark_peer_authenticated(*peer_id) {
+                    deltas.push(NetworkStateDelta::AuthChanged {
+                        peer_id: *peer_id,
+                        authenticated: true,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        for delta in deltas {
+            // Ошибка означает только отсутствие подписчиков
+            let _ = self.state_deltas.send(delta);
+        }
+    }
+
+    /// Relay peer whose circuit listener has the given id
+    fn relay_peer_for_listener(&self, listener_id: &ListenerId) -> Option<PeerId> {
+        self.relay_listeners
+            .iter()
+            .find(|(_, id)| *id == listener_id)
+            .map(|(peer_id, _)| *peer_id)
+    }
+
+    /// Remove circuit listeners whose reservation request timed out
+    ///
+    /// The timeout fires inside the task manager, so a listener that is neither
+    /// pending nor reserved is one the caller already gave up on.
+    fn prune_timed_out_relay_listeners(&mut self, swarm: &mut Swarm<XNetworkBehaviour>) {
+        let pending = self.relay_reservation_tasks.get_pending_keys();
+        let timed_out: Vec<PeerId> = self
+            .relay_listeners
+            .keys()
+            .filter(|peer| !pending.contains(peer) && !self.relay_reserved.contains(peer))
+            .copied()
+            .collect();
+
+        for relay_peer in timed_out {
+            if let Some(listener_id) = self.relay_listeners.remove(&relay_peer) {
+                debug!(
+                    "⏱️ [SwarmHandler] Relay reservation on {} timed out, removing listener {:?}",
+                    relay_peer, listener_id
+                );
+                swarm.remove_listener(listener_id);
+            }
+        }
+    }
+
+    /// Open the circuit listener that requests a reservation on a connected relay
+    fn listen_on_relay(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        relay_peer: PeerId,
+        circuit_addr: Multiaddr,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let listener_id = swarm.listen_on(circuit_addr.clone())?;
+        info!(
+            "📡 [SwarmHandler] Requesting relay reservation via {} with listener_id: {:?}",
+            circuit_addr, listener_id
+        );
+        self.relay_listeners.insert(relay_peer, listener_id);
+        Ok(())
+    }
+
+    /// Request the reservation once the dial to the relay resolves
+    fn continue_relay_dial(
+        &mut self,
+        swarm: &mut Swarm<XNetworkBehaviour>,
+        event: &libp2p::swarm::SwarmEvent<
+            <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
+        >,
+    ) {
+        let (peer_id, connection_id, error) = match event {
+            libp2p::swarm::SwarmEvent::ConnectionEstablished { peer_id, connection_id, .. } => {
+                (*peer_id, *connection_id, None)
+            }
+            libp2p::swarm::SwarmEvent::OutgoingConnectionError {
+                peer_id: Some(peer_id),
+                connection_id,
+                error,
+            } => (*peer_id, *connection_id, Some(error)),
+            _ => return,
+        };
+        if self.relay_dials.get(&peer_id).is_none_or(|(id, _)| *id != connection_id) {
+            return;
+        }
+        let Some((_, circuit_addr)) = self.relay_dials.remove(&peer_id) else {
+            return;
+        };
+        // Запрос мог истечь, пока шел dial
+        if !self.relay_reservation_tasks.get_pending_keys().contains(&peer_id) {
+            return;
+        }
+
+        let result = match error {
+            Some(error) => Err(format!("Failed to dial relay {}: {}", peer_id, error).into()),
+            None => self.listen_on_relay(swarm, peer_id, circuit_addr),
+        };
+        if let Err(e) = result {
+            let _ = self.relay_reservation_tasks.set_task_error(&peer_id, e);
+        }
+    }
+
+    /// Record reservation progress, completing the request once the relay
+    /// accepted it and the relayed address is known
+    ///
+    /// Returns true when this call completed the request
+    fn update_relay_reservation(
+        &self,
+        relay_peer: PeerId,
+        accepted: Option<bool>,
+        address: Option<Multiaddr>,
+    ) -> bool {
+        let Some((was_accepted, known_address)) =
+            self.relay_reservation_tasks.get_task_extra(&relay_peer)
+        else {
+            return false;
+        };
+        let accepted = accepted.unwrap_or(was_accepted);
+        let address = address.or(known_address);
+
+        match (accepted, address) {
+            (true, Some(relayed_addr)) => matches!(
+                self.relay_reservation_tasks.set_task_result(
+                    &relay_peer,
+                    ReservationInfo {
+                        relay_peer_id: relay_peer,
+                        relayed_addr,
+                        renewal_interval: RELAY_RESERVATION_RENEWAL_INTERVAL,
+                    },
+                ),
+                Ok(true)
+            ),
+            (accepted, address) => {
+                let _ = self
+                    .relay_reservation_tasks
+                    .set_task_extra(&relay_peer, (accepted, address));
+                false
+            }
+        }
     }
 
     /// Transform SwarmEvent into NodeEvent and emit through broadcast channel
@@ -92,6 +895,7 @@ impl XNetworkSwarmHandler {
         event: &libp2p::swarm::SwarmEvent<
             <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
         >,
+        metadata_validation: Option<xauth::definitions::AuthResult>,
     ) {
         // If event sender is not set, do nothing
         let event_sender = match self.event_sender.as_ref() {
@@ -118,6 +922,12 @@ impl XNetworkSwarmHandler {
                     );
                 }
 
+                if let Some(relay_peer) = self.relay_peer_for_listener(listener_id) {
+                    if self.update_relay_reservation(relay_peer, None, Some(address.clone())) {
+                        self.relay_reserved.insert(relay_peer);
+                    }
+                }
+
                 let _ = event_sender.send(NodeEvent::NewListenAddr {
                     listener_id: listener_id.clone(),
                     address: address.clone(),
@@ -141,28 +951,20 @@ impl XNetworkSwarmHandler {
             } => {
                 println!("Conn established {:?}", peer_id);
 
-                // Try to complete pending dial_and_wait task if exists
-                // We need to find any DialWaitKey that matches this peer_id
-                // This is a simplified approach - we complete the first matching task for this peer
-                // In practice, we should track which specific dial attempt corresponds to which connection
-
-                // Try to find and complete any pending task for this peer
-                let mut completed = false;
-                for key in self.dial_wait_tasks.get_pending_keys() {
-                    if key.peer_id == *peer_id {
-                        if self
-                            .dial_wait_tasks
-                            .set_task_result(&key, *connection_id)
-                            .is_ok()
-                        {
-                            debug!(
-                                "✅ [SwarmHandler] Completed dial_and_wait task for peer: {} with connection_id: {:?}",
-                                peer_id, connection_id
-                            );
-                            completed = true;
-                            break;
-                        }
-                    }
+                // Complete the dial_and_wait task of the dial that produced this connection
+                let key = DialWaitKey {
+                    peer_id: *peer_id,
+                    connection_id: *connection_id,
+                };
+                let completed = matches!(
+                    self.dial_wait_tasks.set_task_result(&key, *connection_id),
+                    Ok(true)
+                );
+                if completed {
+                    debug!(
+                        "✅ [SwarmHandler] Completed dial_and_wait task for peer: {} with connection_id: {:?}",
+                        peer_id, connection_id
+                    );
                 }
 
                 if !completed {
@@ -172,13 +974,13 @@ impl XNetworkSwarmHandler {
                     );
                 }
 
-                // Start authentication for this connection
-                // Note: We can't call commander directly from here, but we can emit an event
-                // that the application can listen to and then call start_auth_for_connection
-                debug!(
-                    "🔐 [SwarmHandler] Connection established - authentication will need to be started manually for connection: {:?}",
-                    connection_id
-                );
+                // Authentication is started in auto_start_auth unless auto-auth is off
+                if !self.auto_auth {
+                    debug!(
+                        "🔐 [SwarmHandler] Connection established - authentication will need to be started manually for connection: {:?}",
+                        connection_id
+                    );
+                }
 
                 let _ = event_sender.send(NodeEvent::ConnectionEstablished {
                     peer_id: *peer_id,
@@ -188,17 +990,72 @@ impl XNetworkSwarmHandler {
             libp2p::swarm::SwarmEvent::ConnectionClosed {
                 peer_id,
                 connection_id,
+                num_established,
                 ..
             } => {
+                if *num_established == 0 {
+                    self.rtt.remove(peer_id);
+                }
                 let _ = event_sender.send(NodeEvent::ConnectionClosed {
                     peer_id: *peer_id,
                     connection_id: *connection_id,
                 });
             }
 
+            libp2p::swarm::SwarmEvent::ListenerClosed {
+                listener_id,
+                reason,
+                ..
+            } => {
+                if let Some(relay_peer) = self.relay_peer_for_listener(listener_id) {
+                    self.relay_listeners.remove(&relay_peer);
+                    self.relay_reserved.remove(&relay_peer);
+                    let error = match reason {
+                        Ok(()) => "Relay listener closed".to_string(),
+                        Err(e) => format!("Relay reservation failed: {}", e),
+                    };
+                    let _ = self.relay_reservation_tasks.set_task_error(&relay_peer, error.into());
+                }
+            }
+
+            libp2p::swarm::SwarmEvent::OutgoingConnectionError {
+                peer_id,
+                connection_id,
+                error,
+            } => {
+                debug!(
+                    "❌ [SwarmHandler] Outgoing connection to {:?} failed: {}",
+                    peer_id, error
+                );
+                // A dial_and_wait fails as soon as its dial fails, every address is listed in the error
+                if let Some(peer_id) = peer_id {
+                    let key = DialWaitKey {
+                        peer_id: *peer_id,
+                        connection_id: *connection_id,
+                    };
+                    let _ = self.dial_wait_tasks.set_task_error(&key, error.to_string().into());
+                }
+                let _ = event_sender.send(NodeEvent::DialFailed {
+                    peer_id: *peer_id,
+                    address: DialError::failed_address(error),
+                    kind: DialError::from_swarm(error),
+                    error: error.to_string(),
+                });
+            }
+
             // Behaviour events - we'll handle XAuth and XStream events specifically
             libp2p::swarm::SwarmEvent::Behaviour(behaviour_event) => {
                 match behaviour_event {
+                    XNetworkBehaviourEvent::Ping(libp2p::ping::Event { peer, result: Ok(rtt), .. }) => {
+                        self.rtt.record(*peer, *rtt);
+                        let _ = event_sender.send(NodeEvent::PingRtt {
+                            peer_id: *peer,
+                            rtt: *rtt,
+                        });
+                    }
+                    XNetworkBehaviourEvent::Ping(libp2p::ping::Event { peer, result: Err(error), .. }) => {
+                        self.rtt.record_failure(*peer, error.to_string());
+                    }
                     XNetworkBehaviourEvent::Xauth(por_auth_event) => {
                         match por_auth_event {
                             PorAuthEvent::VerifyPorRequest {
@@ -213,6 +1070,8 @@ impl XNetworkSwarmHandler {
                                     connection_id: format!("{:?}", connection_id),
                                     por: por.peer_id.to_bytes(),
                                     metadata: metadata.clone(),
+                                    reject_code: xauth::definitions::RejectCode::for_por(por),
+                                    metadata_validation,
                                 });
                             }
                             PorAuthEvent::MutualAuthSuccess {
@@ -245,6 +1104,32 @@ impl XNetworkSwarmHandler {
                                     connection_id: *connection_id,
                                 });
                             }
+                            PorAuthEvent::InboundAuthFailure {
+                                peer_id,
+                                connection_id,
+                                reason,
+                                code,
+                                ..
+                            } => {
+                                let _ = event_sender.send(NodeEvent::PeerAuthRejected {
+                                    peer_id: *peer_id,
+                                    connection_id: *connection_id,
+                                    reason: reason.clone(),
+                                    code: *code,
+                                });
+                            }
+                            PorAuthEvent::AuthTimeout {
+                                peer_id,
+                                connection_id,
+                                direction,
+                                ..
+                            } => {
+                                let _ = event_sender.send(NodeEvent::PeerAuthTimeout {
+                                    peer_id: *peer_id,
+                                    connection_id: *connection_id,
+                                    direction: direction.clone(),
+                                });
+                            }
                             // Skip authentication failures and other XAuth events
                             _ => {}
                         }
@@ -273,41 +1158,191 @@ impl XNetworkSwarmHandler {
                                     error: error.clone(),
                                 });
                             }
-                            XStreamEvent::StreamClosed { peer_id, stream_id } => {
+                            XStreamEvent::StreamRejected {
+                                peer_id,
+                                stream_id,
+                                reason,
+                            } => {
+                                let _ = event_sender.send(NodeEvent::XStreamError {
+                                    peer_id: *peer_id,
+                                    stream_id: Some(*stream_id),
+                                    error: format!("Stream rejected: {:?}", reason),
+                                });
+                            }
+                            XStreamEvent::StreamClosed { peer_id, stream_id, .. } => {
                                 let _ = event_sender.send(NodeEvent::XStreamClosed {
                                     peer_id: *peer_id,
                                     stream_id: *stream_id,
                                 });
                             }
+                            XStreamEvent::StreamBackpressure {
+                                peer_id,
+                                stream_id,
+                                buffered_bytes,
+                            } => {
+                                let _ = event_sender.send(NodeEvent::XStreamBackpressure {
+                                    peer_id: *peer_id,
+                                    stream_id: *stream_id,
+                                    buffered_bytes: *buffered_bytes,
+                                });
+                            }
+                            XStreamEvent::StreamDrained {
+                                peer_id,
+                                stream_id,
+                                buffered_bytes,
+                            } => {
+                                let _ = event_sender.send(NodeEvent::XStreamDrained {
+                                    peer_id: *peer_id,
+                                    stream_id: *stream_id,
+                                    buffered_bytes: *buffered_bytes,
+                                });
+                            }
                             XStreamEvent::IncomingStreamRequest {
                                 peer_id,
                                 connection_id,
                                 decision_sender,
                             } => {
-                                // Always forward incoming stream requests to application for decision making
-                                debug!(
-                                    "🔍 [SwarmHandler] Forwarding IncomingStreamRequest from peer: {}, connection: {:?}",
-                                    peer_id, connection_id
-                                );
-                                let _ =
-                                    event_sender.send(NodeEvent::XStreamIncomingStreamRequest {
-                                        peer_id: *peer_id,
-                                        connection_id: *connection_id,
-                                        decision_sender: decision_sender.clone(),
+                                let decision = if self.shutting_down {
+                                    // New streams are refused during graceful shutdown
+                                    InboundDecision::Reject("Node is shutting down".to_string())
+                                } else {
+                                    match &self.inbound_stream_policy {
+                                        Some(policy) => policy(peer_id, *connection_id),
+                                        None => InboundDecision::Defer,
+                                    }
+                                };
+                                if decision == InboundDecision::Accept {
+                                    let _ = decision_sender.approve();
+                                } else if let InboundDecision::Reject(reason) = decision {
+                                    debug!(
+                                        "🚫 [SwarmHandler] Rejected IncomingStreamRequest from peer: {}, reason: {}",
+                                        peer_id, reason
+                                    );
+                                    let _ = decision_sender.reject(reason);
+                                } else if let Some(async_decision) = &self.async_inbound_decision {
+                                    let future = async_decision(*peer_id, *connection_id);
+                                    let timeout = self.inbound_decision_timeout;
+                                    let event_sender = event_sender.clone();
+                                    let peer_id = *peer_id;
+                                    let connection_id = *connection_id;
+                                    let decision_sender = decision_sender.clone();
+                                    tokio::spawn(async move {
+                                        let decision = tokio::time::timeout(timeout, future)
+                                            .await
+                                            .unwrap_or_else(|_| {
+                                                InboundDecision::Reject(
+                                                    "Inbound decision timed out".to_string(),
+                                                )
+                                            });
+                                        match decision {
+                                            InboundDecision::Accept => {
+                                                let _ = decision_sender.approve();
+                                            }
+                                            InboundDecision::Reject(reason) => {
+                                                debug!(
+                                                    "🚫 [SwarmHandler] Async decision rejected stream from peer: {}, reason: {}",
+                                                    peer_id, reason
+                                                );
+                                                let _ = decision_sender.reject(reason);
+                                            }
+                                            InboundDecision::Defer => {
+                                                let _ = event_sender.send(
+                                                    NodeEvent::XStreamIncomingStreamRequest {
+                                                        peer_id,
+                                                        connection_id,
+                                                        decision_sender,
+                                                    },
+                                                );
+                                            }
+                                        }
                                     });
+                                } else {
+                                    // Deferred requests go to the application for decision making
+                                    debug!(
+                                        "🔍 [SwarmHandler] Forwarding IncomingStreamRequest from peer: {}, connection: {:?}",
+                                        peer_id, connection_id
+                                    );
+                                    let _ =
+                                        event_sender.send(NodeEvent::XStreamIncomingStreamRequest {
+                                            peer_id: *peer_id,
+                                            connection_id: *connection_id,
+                                            decision_sender: decision_sender.clone(),
+                                        });
+                                }
                             }
                         }
                     }
                     XNetworkBehaviourEvent::Xroutes(xroutes_event) => {
                         // Transform XRoutes events to NodeEvents
                         match xroutes_event {
+                            super::behaviours::xroutes::XRoutesBehaviourEvent::AutonatClient(
+                                autonat_event,
+                            ) => {
+                                debug!(
+                                    "🔍 [SwarmHandler] AutoNAT probe of {} via {}: {:?}",
+                                    autonat_event.tested_addr, autonat_event.server, autonat_event.result
+                                );
+                                if let Some(old) = self.nat_status.record(
+                                    autonat_event.tested_addr.clone(),
+                                    autonat_event.result.is_ok(),
+                                ) {
+                                    let _ = event_sender.send(NodeEvent::NatStatusChanged {
+                                        old,
+                                        new: self.nat_status.status().clone(),
+                                    });
+                                }
+                            }
+                            super::behaviours::xroutes::XRoutesBehaviourEvent::Dcutr(dcutr_event) => {
+                                let peer_id = dcutr_event.remote_peer_id;
+                                match &dcutr_event.result {
+                                    Ok(connection_id) => {
+                                        let address = self
+                                            .conntracker
+                                            .get_connection(connection_id)
+                                            .map(|info| info.remote_addr.clone());
+                                        let _ = event_sender.send(NodeEvent::HolePunchSucceeded {
+                                            peer_id,
+                                            address,
+                                        });
+                                    }
+                                    Err(error) => {
+                                        let _ = event_sender.send(NodeEvent::HolePunchFailed {
+                                            peer_id,
+                                            error: error.to_string(),
+                                        });
+                                    }
+                                }
+                            }
+                            super::behaviours::xroutes::XRoutesBehaviourEvent::RelayClient(
+                                libp2p::relay::client::Event::ReservationReqAccepted {
+                                    relay_peer_id,
+                                    renewal,
+                                    ..
+                                },
+                            ) => {
+                                if !renewal
+                                    && self.update_relay_reservation(*relay_peer_id, Some(true), None)
+                                {
+                                    self.relay_reserved.insert(*relay_peer_id);
+                                }
+                                let _ = event_sender.send(NodeEvent::RelayReservationAccepted {
+                                    relay_peer_id: *relay_peer_id,
+                                    renewal: *renewal,
+                                });
+                            }
                             super::behaviours::xroutes::XRoutesBehaviourEvent::Kad(kad_event) => {
                                 match kad_event {
-                                    libp2p::kad::Event::RoutingUpdated { peer, .. } => {
+                                    libp2p::kad::Event::RoutingUpdated { peer, addresses, .. } => {
                                         let _ =
                                             event_sender.send(NodeEvent::KademliaRoutingUpdated {
                                                 peer_id: *peer,
                                             });
+                                        self.discovery.report(
+                                            event_sender,
+                                            *peer,
+                                            DiscoverySource::Kademlia,
+                                            addresses.iter().cloned().collect(),
+                                        );
                                     }
                                     libp2p::kad::Event::OutboundQueryProgressed {
                                         result, ..
@@ -328,6 +1363,12 @@ impl XNetworkSwarmHandler {
                                                             addresses: peer_info.addrs.clone(),
                                                         },
                                                     );
+                                                    self.discovery.report(
+                                                        event_sender,
+                                                        peer_info.peer_id,
+                                                        DiscoverySource::Kademlia,
+                                                        peer_info.addrs.clone(),
+                                                    );
                                                 }
                                             }
                                             _ => {}
@@ -341,11 +1382,22 @@ impl XNetworkSwarmHandler {
                                     libp2p::mdns::Event::Discovered(list) => {
                                         // Transform mDNS discovered event to NodeEvent
                                         for (peer_id, address) in list {
+                                            if let Some(interface) = self.mdns_interface {
+                                                if !crate::behaviours::xroutes::is_address_on_interface(address, interface) {
+                                                    continue;
+                                                }
+                                            }
                                             let _ =
                                                 event_sender.send(NodeEvent::MdnsPeerDiscovered {
                                                     peer_id: *peer_id,
                                                     addresses: vec![address.clone()],
                                                 });
+                                            self.discovery.report(
+                                                event_sender,
+                                                *peer_id,
+                                                DiscoverySource::Mdns,
+                                                vec![address.clone()],
+                                            );
                                         }
                                     }
                                     libp2p::mdns::Event::Expired(list) => {
@@ -358,11 +1410,42 @@ impl XNetworkSwarmHandler {
                                     }
                                 }
                             }
+                            super::behaviours::xroutes::XRoutesBehaviourEvent::Identify(
+                                libp2p::identify::Event::Received { peer_id, info, .. },
+                            ) => {
+                                let _ = event_sender.send(NodeEvent::IdentifyReceived {
+                                    peer_id: *peer_id,
+                                    addresses: info.listen_addrs.clone(),
+                                });
+                                self.discovery.report(
+                                    event_sender,
+                                    *peer_id,
+                                    DiscoverySource::Identify,
+                                    info.listen_addrs.clone(),
+                                );
+                            }
                             _ => {
                                 debug!("📡 [SwarmHandler] XRoutes event: {:?}", xroutes_event);
                             }
                         }
                     }
+                    XNetworkBehaviourEvent::ConnectionLimits(ConnectionLimitsEvent::LimitReached {
+                        peer_id,
+                        endpoint,
+                        reason,
+                    }) => {
+                        let _ = event_sender.send(NodeEvent::ConnectionLimitReached {
+                            peer_id: *peer_id,
+                            endpoint: *endpoint,
+                            reason: reason.clone(),
+                        });
+                    }
+                    XNetworkBehaviourEvent::Reconnect(ReconnectEvent::Attempt { peer_id, attempt, .. }) => {
+                        let _ = event_sender.send(NodeEvent::ReconnectAttempt {
+                            peer_id: *peer_id,
+                            attempt: *attempt,
+                        });
+                    }
                     // Skip other behaviour events
                     _ => {
                         debug!("📡 [SwarmHandler] beh event: {:?}", behaviour_event);
@@ -376,11 +1459,32 @@ impl XNetworkSwarmHandler {
     }
 }
 
+/// Forward the result sent through the returned sender, failing with `timeout_error` after `timeout`
+fn respond_within<T: Send + 'static>(
+    timeout: std::time::Duration,
+    response: oneshot::Sender<Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    timeout_error: impl FnOnce() -> Box<dyn std::error::Error + Send + Sync> + Send + 'static,
+) -> oneshot::Sender<Result<T, Box<dyn std::error::Error + Send + Sync>>> {
+    let (sender, receiver) = oneshot::channel();
+    tokio::spawn(async move {
+        let result = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Queued dial dropped".into()),
+            Err(_) => Err(timeout_error()),
+        };
+        let _ = response.send(result);
+    });
+    sender
+}
+
 #[async_trait]
 impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
     type Command = SwarmLevelCommand;
 
     async fn handle_command(&mut self, swarm: &mut Swarm<XNetworkBehaviour>, cmd: Self::Command) {
+        self.prune_timed_out_relay_listeners(swarm);
+        self.expire_queued_dials();
+
         match cmd {
             SwarmLevelCommand::Dial {
                 peer_id,
@@ -391,13 +1495,61 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                     "🔄 [SwarmHandler] Processing Dial command - Peer: {:?}, Addr: {}",
                     peer_id, addr
                 );
-                let result = swarm
-                    .dial(addr.clone())
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>);
+                if self.shutting_down {
+                    let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                    return;
+                }
+                if self.dial_limit_reached() {
+                    debug!("⏳ [SwarmHandler] Dial limit reached, queueing dial to {:?}", peer_id);
+                    self.queue_dial(SwarmLevelCommand::Dial { peer_id, addr, response });
+                    return;
+                }
+                let result = self.issue_dial(swarm, peer_id, vec![addr], None);
+                if let Ok((_, addresses)) = &result {
+                    info!(
+                        "📡 [SwarmHandler] Dialing peer {:?} at address {:?}",
+                        peer_id, addresses
+                    );
+                } else {
+                    debug!(
+                        "❌ [SwarmHandler] Failed to dial peer {:?}: {:?}",
+                        peer_id, result
+                    );
+                }
+                let _ = response.send(result.map(|_| ()));
+            }
+            SwarmLevelCommand::DialWithOpts {
+                peer_id,
+                addresses,
+                condition,
+                response,
+            } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing DialWithOpts command - Peer: {:?}, Addresses: {:?}, Condition: {:?}",
+                    peer_id, addresses, condition
+                );
+                if self.shutting_down {
+                    let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                    return;
+                }
+                if self.dial_limit_reached() {
+                    debug!("⏳ [SwarmHandler] Dial limit reached, queueing dial to {:?}", peer_id);
+                    self.queue_dial(SwarmLevelCommand::DialWithOpts {
+                        peer_id,
+                        addresses,
+                        condition,
+                        response,
+                    });
+                    return;
+                }
+                // Заблокированные адреса отбрасываются, ошибка только если не осталось ни одного
+                let result = self
+                    .issue_dial(swarm, peer_id, addresses, Some(condition))
+                    .map(|_| ());
                 if result.is_ok() {
                     info!(
-                        "📡 [SwarmHandler] Dialing peer {:?} at address {}",
-                        peer_id, addr
+                        "📡 [SwarmHandler] Dialing peer {:?} with condition {:?}",
+                        peer_id, condition
                     );
                 } else {
                     debug!(
@@ -425,6 +1577,13 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                 }
                 let _ = response.send(result);
             }
+            SwarmLevelCommand::RemoveListener { listener_id, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing RemoveListener command - Listener: {:?}",
+                    listener_id
+                );
+                let _ = response.send(Ok(swarm.remove_listener(listener_id)));
+            }
             SwarmLevelCommand::ListenAndWait {
                 addr,
                 timeout,
@@ -454,15 +1613,321 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                 self.listen_wait_tasks
                     .add_pending_task(listener_id, timeout, response);
             }
+            SwarmLevelCommand::ListenAndCollect {
+                addr,
+                window,
+                response,
+            } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing ListenAndCollect command - Addr: {}, Window: {:?}",
+                    addr, window
+                );
+                let Some(event_sender) = self.event_sender.as_ref() else {
+                    let _ = response.send(Err("Event sender not configured".into()));
+                    return;
+                };
+
+                // Подписываемся до listen_on, чтобы не пропустить ни одного NewListenAddr
+                let mut events = event_sender.subscribe();
+                let listener_id = match swarm.listen_on(addr.clone()) {
+                    Ok(listener_id) => listener_id,
+                    Err(e) => {
+                        let _ = response.send(Err(Box::new(e)));
+                        return;
+                    }
+                };
+                let local_peer_id = *swarm.local_peer_id();
+
+                tokio::spawn(async move {
+                    let mut addresses = Vec::new();
+                    let deadline = tokio::time::sleep(window);
+                    tokio::pin!(deadline);
+                    loop {
+                        tokio::select! {
+                            _ = &mut deadline => break,
+                            event = events.recv() => match event {
+                                Ok(NodeEvent::NewListenAddr { listener_id: id, address }) if id == listener_id => {
+                                    addresses.push(address.with_p2p(local_peer_id).unwrap_or_else(|a| a));
+                                }
+                                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                                Err(broadcast::error::RecvError::Closed) => break,
+                            },
+                        }
+                    }
+                    info!(
+                        "📡 [SwarmHandler] Listener {:?} bound {} addresses",
+                        listener_id,
+                        addresses.len()
+                    );
+                    let _ = response.send(Ok(addresses));
+                });
+            }
             SwarmLevelCommand::Disconnect { peer_id, response } => {
                 debug!(
                     "🔄 [SwarmHandler] Processing Disconnect command - Peer: {:?}",
                     peer_id
                 );
+                // Явное отключение прекращает автоматическое переподключение
+                swarm.behaviour_mut().reconnect.remove_peer(&peer_id);
                 swarm.disconnect_peer_id(peer_id);
                 info!("📤 [SwarmHandler] Disconnected from peer {:?}", peer_id);
                 let _ = response.send(Ok(()));
             }
+            SwarmLevelCommand::DisconnectConnection { connection_id, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing DisconnectConnection command - Connection: {:?}",
+                    connection_id
+                );
+                if swarm.close_connection(connection_id) {
+                    info!("📤 [SwarmHandler] Closing connection {:?}", connection_id);
+                    let _ = response.send(Ok(()));
+                } else {
+                    let _ = response.send(Err(
+                        format!("Unknown connection id: {:?}", connection_id).into()
+                    ));
+                }
+            }
+            SwarmLevelCommand::DisconnectAll { response } => {
+                debug!("🔄 [SwarmHandler] Processing DisconnectAll command");
+                swarm.behaviour_mut().reconnect.clear();
+                let peers = swarm.connected_peers().cloned().collect::<Vec<_>>();
+                let mut disconnected = 0;
+                for peer_id in peers {
+                    if swarm.disconnect_peer_id(peer_id).is_ok() {
+                        disconnected += 1;
+                    }
+                }
+                info!("📤 [SwarmHandler] Disconnected from {} peers", disconnected);
+                let _ = response.send(Ok(disconnected));
+            }
+            SwarmLevelCommand::RequestRelayReservation {
+                relay_peer,
+                relay_addr,
+                timeout,
+                response,
+            } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing RequestRelayReservation command - Relay: {} at {}",
+                    relay_peer, relay_addr
+                );
+                if self.relay_listeners.contains_key(&relay_peer)
+                    || self.relay_dials.contains_key(&relay_peer)
+                {
+                    let _ = response.send(Err(
+                        format!("Reservation on relay {} already requested", relay_peer).into()
+                    ));
+                    return;
+                }
+
+                let mut circuit_addr = relay_addr.clone();
+                if !circuit_addr.iter().any(|p| matches!(p, libp2p::multiaddr::Protocol::P2p(_))) {
+                    circuit_addr.push(libp2p::multiaddr::Protocol::P2p(relay_peer));
+                }
+                circuit_addr.push(libp2p::multiaddr::Protocol::P2pCircuit);
+
+                if swarm.is_connected(&relay_peer) {
+                    if let Err(e) = self.listen_on_relay(swarm, relay_peer, circuit_addr) {
+                        let _ = response.send(Err(e));
+                        return;
+                    }
+                    self.relay_reservation_tasks.add_pending_task_with_extra(
+                        relay_peer,
+                        timeout,
+                        response,
+                        (false, None),
+                    );
+                    return;
+                }
+
+                // Иначе relay клиент сам дозвонился бы до relay в обход фильтра и лимита
+                if self.shutting_down {
+                    let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                    return;
+                }
+                if self.dial_limit_reached() {
+                    debug!("⏳ [SwarmHandler] Dial limit reached, queueing reservation on {}", relay_peer);
+                    self.queue_dial(SwarmLevelCommand::RequestRelayReservation {
+                        relay_peer,
+                        relay_addr,
+                        timeout,
+                        response,
+                    });
+                    return;
+                }
+                match self.issue_dial(swarm, relay_peer, vec![relay_addr], Some(PeerCondition::Always)) {
+                    Ok((connection_id, _)) => {
+                        debug!(
+                            "📡 [SwarmHandler] Dialing relay {} before requesting the reservation",
+                            relay_peer
+                        );
+                        self.relay_dials.insert(relay_peer, (connection_id, circuit_addr));
+                        self.relay_reservation_tasks.add_pending_task_with_extra(
+                            relay_peer,
+                            timeout,
+                            response,
+                            (false, None),
+                        );
+                    }
+                    Err(e) => {
+                        let _ = response.send(Err(e));
+                    }
+                }
+            }
+            SwarmLevelCommand::ReleaseRelayReservation { relay_peer, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing ReleaseRelayReservation command - Relay: {}",
+                    relay_peer
+                );
+                match self.relay_listeners.remove(&relay_peer) {
+                    Some(listener_id) => {
+                        self.relay_reserved.remove(&relay_peer);
+                        swarm.remove_listener(listener_id);
+                        info!("📤 [SwarmHandler] Released relay reservation on {}", relay_peer);
+                        let _ = response.send(Ok(()));
+                    }
+                    // Relay still being dialed, the pending request is cancelled
+                    None if self.relay_dials.remove(&relay_peer).is_some() => {
+                        let _ = self.relay_reservation_tasks.set_task_error(
+                            &relay_peer,
+                            format!("Reservation on relay {} released", relay_peer).into(),
+                        );
+                        let _ = response.send(Ok(()));
+                    }
+                    None => {
+                        let _ = response.send(Err(
+                            format!("No reservation on relay {}", relay_peer).into()
+                        ));
+                    }
+                }
+            }
+            SwarmLevelCommand::DirectConnect { peer_id, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing DirectConnect command - Peer: {}",
+                    peer_id
+                );
+                if self.shutting_down {
+                    let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                    return;
+                }
+                if self.dial_limit_reached() {
+                    debug!("⏳ [SwarmHandler] Dial limit reached, queueing direct connect to {}", peer_id);
+                    self.queue_dial(SwarmLevelCommand::DirectConnect { peer_id, response });
+                    return;
+                }
+                // DCUtR запускается на новом relayed соединении, поэтому дозваниваемся через тот же relay
+                let relayed_addr = self
+                    .conntracker
+                    .get_peer_connections(&peer_id)
+                    .and_then(|peer| {
+                        peer.get_connections().into_iter().find_map(|info| {
+                            info.remote_addr
+                                .iter()
+                                .any(|p| p == libp2p::multiaddr::Protocol::P2pCircuit)
+                                .then(|| info.remote_addr.clone())
+                        })
+                    });
+
+                let Some(relayed_addr) = relayed_addr else {
+                    let _ = response.send(Err(
+                        format!("No relayed connection to peer {}", peer_id).into()
+                    ));
+                    return;
+                };
+
+                match self.issue_dial(swarm, peer_id, vec![relayed_addr], Some(PeerCondition::Always)) {
+                    Ok((_, addresses)) => {
+                        info!(
+                            "🕳️ [SwarmHandler] Direct connection upgrade to {} requested via {:?}",
+                            peer_id, addresses
+                        );
+                        let _ = response.send(Ok(()));
+                    }
+                    Err(e) => {
+                        let _ = response.send(Err(e));
+                    }
+                }
+            }
+            SwarmLevelCommand::GetNatStatus { response } => {
+                let _ = response.send(Ok(self.nat_status.status().clone()));
+            }
+            SwarmLevelCommand::GetPeerRtt { peer_id, response } => {
+                let _ = response.send(Ok(self.rtt.get(&peer_id)));
+            }
+            SwarmLevelCommand::Ping { peer_id, response } => {
+                if !swarm.is_connected(&peer_id) {
+                    let _ = response.send(Err(crate::rtt::PingError::Disconnected));
+                    return;
+                }
+                self.rtt.add_waiter(peer_id, response);
+            }
+            SwarmLevelCommand::GetDialStats { response } => {
+                let _ = response.send(Ok(DialStats {
+                    in_flight: self.dials_in_flight.len(),
+                    queued: self.queued_dials.len(),
+                }));
+            }
+            SwarmLevelCommand::GetStatus { response } => {
+                debug!("🔄 [SwarmHandler] Processing GetStatus command");
+                let routing_table_size = swarm
+                    .behaviour_mut()
+                    .xroutes
+                    .kad
+                    .as_mut()
+                    .map(|kad| kad.kbuckets().map(|bucket| bucket.num_entries()).sum());
+                let behaviour = swarm.behaviour();
+                let connected_peers = self.conntracker.get_connected_peers();
+                let authenticated_peer_count = connected_peers
+                    .iter()
+                    .filter(|peer_id| behaviour.xauth.is_peer_authenticated(peer_id))
+                    .count();
+                let nat_status = behaviour
+                    .xroutes
+                    .autonat_client
+                    .is_enabled()
+                    .then(|| self.nat_status.status().clone());
+
+                let status = NodeStatus {
+                    peer_id: *swarm.local_peer_id(),
+                    listening_addresses: swarm.listeners().cloned().collect(),
+                    connection_count: self.conntracker.get_all_connections().len(),
+                    connected_peer_count: connected_peers.len(),
+                    authenticated_peer_count,
+                    nat_status,
+                    routing_table_size,
+                };
+                let _ = response.send(Ok(status));
+            }
+            SwarmLevelCommand::IsPeerAuthenticated { peer_id, response } => {
+                let _ = response.send(Ok(self.is_peer_authenticated(&peer_id)));
+            }
+            SwarmLevelCommand::GetStreamCounts { response } => {
+                let _ = response.send(Ok(self.stream_counts()));
+            }
+            SwarmLevelCommand::GetMetrics { response } => {
+                let routing_table_size = swarm
+                    .behaviour_mut()
+                    .xroutes
+                    .kad
+                    .as_mut()
+                    .map(|kad| kad.kbuckets().map(|bucket| bucket.num_entries()).sum());
+                let snapshot = crate::metrics::MetricsSnapshot {
+                    connection_count: self.conntracker.get_all_connections().len(),
+                    connected_peer_count: self.conntracker.get_connected_peers().len(),
+                    open_stream_count: self.open_streams.values().map(|streams| streams.len()).sum(),
+                    auth_successes: self.auth_successes,
+                    auth_failures: self.auth_failures,
+                    routing_table_size,
+                    peer_rtts: self.rtt.all(),
+                };
+                let _ = response.send(Ok(snapshot));
+            }
+            SwarmLevelCommand::GetPeerInfo { peer_id, response } => {
+                let _ = response.send(Ok(self.peer_info(&peer_id)));
+            }
+            SwarmLevelCommand::SubscribeNetworkState { response } => {
+                debug!("🔄 [SwarmHandler] Processing SubscribeNetworkState command");
+                let _ = response.send(Ok(self.state_deltas.subscribe()));
+            }
             SwarmLevelCommand::GetNetworkState { response } => {
                 debug!("🔄 [SwarmHandler] Processing GetNetworkState command");
                 let listeners = swarm.listeners().cloned().collect::<Vec<_>>();
@@ -473,12 +1938,14 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                     peer_id,
                     listening_addresses: listeners,
                     connected_peers,
-                    authenticated_peers: vec![], // TODO: Add authenticated peers tracking
+                    authenticated_peers: self.authenticated_peers.iter().cloned().collect(),
                 };
 
                 info!(
-                    "📊 [SwarmHandler] Network state - Listeners: {:?}, Connected peers: {:?}",
-                    network_state.listening_addresses, network_state.connected_peers
+                    "📊 [SwarmHandler] Network state - Listeners: {:?}, Connected peers: {:?}, Authenticated peers: {:?}",
+                    network_state.listening_addresses,
+                    network_state.connected_peers,
+                    network_state.authenticated_peers
                 );
 
                 let _ = response.send(Ok(network_state));
@@ -490,6 +1957,33 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                 stopper.stop();
                 let _ = response.send(Ok(()));
             }
+            SwarmLevelCommand::BeginShutdown { response } => {
+                info!("🛑 [SwarmHandler] Graceful shutdown started, refusing new dials and streams");
+                self.shutting_down = true;
+                // Queued dials would never be issued
+                for (_, command) in self.queued_dials.drain(..) {
+                    match command {
+                        SwarmLevelCommand::Dial { response, .. } => {
+                            let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                        }
+                        SwarmLevelCommand::DialWithOpts { response, .. } => {
+                            let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                        }
+                        SwarmLevelCommand::DialAndWait { response, .. } => {
+                            let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                        }
+                        SwarmLevelCommand::DirectConnect { response, .. } => {
+                            let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                        }
+                        SwarmLevelCommand::RequestRelayReservation { response, .. } => {
+                            let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                        }
+                        _ => {}
+                    }
+                }
+                let streams = swarm.behaviour_mut().xstream.begin_shutdown();
+                let _ = response.send(Ok(streams));
+            }
             SwarmLevelCommand::Echo { message, response } => {
                 debug!(
                     "🔄 [SwarmHandler] Processing Echo command - Message: '{}'",
@@ -500,44 +1994,53 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
             }
             SwarmLevelCommand::DialAndWait {
                 peer_id,
-                addr,
+                addresses,
                 timeout,
                 response,
             } => {
                 debug!(
-                    "🔄 [SwarmHandler] Processing DialAndWait command - Peer: {:?}, Addr: {}, Timeout: {:?}",
-                    peer_id, addr, timeout
+                    "🔄 [SwarmHandler] Processing DialAndWait command - Peer: {:?}, Addresses: {:?}, Timeout: {:?}",
+                    peer_id, addresses, timeout
                 );
-
-                // Generate a simple attempt_id based on current time
-                let attempt_id = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_nanos() as u64;
-
-                let key = DialWaitKey {
-                    peer_id,
-                    attempt_id,
-                };
-
-                // Start dialing
-                let result = swarm.dial(addr.clone());
-                if let Err(e) = result {
-                    let error = Box::new(e) as Box<dyn std::error::Error + Send + Sync>;
-                    debug!(
-                        "❌ [SwarmHandler] Failed to dial peer {}: {:?}",
-                        peer_id, error
-                    );
-                    let _ = response.send(Err(error));
+                if self.shutting_down {
+                    let _ = response.send(Err("Cannot dial: node is shutting down".into()));
+                    return;
+                }
+                if self.dial_limit_reached() {
+                    debug!("⏳ [SwarmHandler] Dial limit reached, queueing dial to {:?}", peer_id);
+                    self.queue_dial(SwarmLevelCommand::DialAndWait {
+                        peer_id,
+                        addresses,
+                        timeout,
+                        response,
+                    });
                     return;
                 }
 
+                // One dial over all addresses, the swarm keeps only the first connection
+                let dialed = self.issue_dial(swarm, peer_id, addresses, Some(PeerCondition::Always));
+                let (connection_id, addresses) = match dialed {
+                    Ok(dialed) => dialed,
+                    Err(error) => {
+                        debug!(
+                            "❌ [SwarmHandler] Failed to dial peer {}: {:?}",
+                            peer_id, error
+                        );
+                        let _ = response.send(Err(error));
+                        return;
+                    }
+                };
+
                 info!(
-                    "📡 [SwarmHandler] Dialing peer {} at address {}, waiting for connection",
-                    peer_id, addr
+                    "📡 [SwarmHandler] Dialing peer {} at address {:?}, waiting for connection",
+                    peer_id, addresses
                 );
 
                 // Add pending task to wait for ConnectionEstablished event
+                let key = DialWaitKey {
+                    peer_id,
+                    connection_id,
+                };
                 self.dial_wait_tasks
                     .add_pending_task(key, timeout, response);
             }
@@ -550,6 +2053,16 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                     connection_id
                 );
 
+                // Already started by auto-auth, a manual start is not an error
+                if self.auto_auth_connections.contains(&connection_id) {
+                    debug!(
+                        "🔐 [SwarmHandler] Authentication already started automatically for connection: {:?}",
+                        connection_id
+                    );
+                    let _ = response.send(Ok(()));
+                    return;
+                }
+
                 // Start actual authentication using the xauth behaviour
                 let result = swarm
                     .behaviour_mut()
@@ -598,6 +2111,98 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
 
                 let _ = response.send(Ok(()));
             }
+            SwarmLevelCommand::RemoveExternalAddress { address, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing RemoveExternalAddress command - Address: {}",
+                    address
+                );
+
+                swarm.remove_external_address(&address);
+
+                info!("🌐 [SwarmHandler] Removed external address: {}", address);
+
+                let _ = response.send(Ok(()));
+            }
+            SwarmLevelCommand::SetExternalAddresses { addresses, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing SetExternalAddresses command - Addresses: {:?}",
+                    addresses
+                );
+
+                let current: Vec<Multiaddr> = swarm.external_addresses().cloned().collect();
+                let mut changed = false;
+                for address in current.iter().filter(|address| !addresses.contains(address)) {
+                    swarm.remove_external_address(address);
+                    changed = true;
+                }
+                for address in addresses.iter().filter(|address| !current.contains(address)) {
+                    swarm.add_external_address(address.clone());
+                    changed = true;
+                }
+
+                if changed {
+                    // Connected peers learn the new set without waiting for the identify interval
+                    let peers: Vec<PeerId> = swarm.connected_peers().cloned().collect();
+                    if let Some(identify) = swarm.behaviour_mut().xroutes.identify.as_mut() {
+                        identify.push(peers);
+                    }
+
+                    info!("🌐 [SwarmHandler] External addresses set to {:?}", addresses);
+
+                    if let Some(event_sender) = &self.event_sender {
+                        let _ = event_sender.send(NodeEvent::ExternalAddressesChanged {
+                            addresses: swarm.external_addresses().cloned().collect(),
+                        });
+                    }
+                }
+
+                let _ = response.send(Ok(()));
+            }
+            SwarmLevelCommand::SetKadMode { mode, force, response } => {
+                debug!(
+                    "🔄 [SwarmHandler] Processing SetKadMode command - Mode: {}, force: {}",
+                    mode, force
+                );
+
+                // Сервер DHT без подтвержденного внешнего адреса недоступен для пиров
+                if mode == KadMode::Server && !force && swarm.external_addresses().next().is_none() {
+                    let _ = response.send(Err(
+                        "No confirmed external address, Kademlia server mode requires force".into(),
+                    ));
+                    return;
+                }
+
+                let previous = match swarm.behaviour().xroutes.get_kad_mode() {
+                    Ok(previous) => previous,
+                    Err(e) => {
+                        let _ = response.send(Err(e));
+                        return;
+                    }
+                };
+                let result = swarm
+                    .behaviour_mut()
+                    .xroutes
+                    .set_kad_mode(mode)
+                    .and_then(|_| swarm.behaviour().xroutes.get_kad_mode());
+
+                match result {
+                    Ok(current) => {
+                        if current != previous {
+                            info!("🌐 [SwarmHandler] Kademlia mode changed: {} -> {}", previous, current);
+                            if let Some(event_sender) = &self.event_sender {
+                                let _ = event_sender.send(NodeEvent::KadModeChanged {
+                                    previous,
+                                    mode: current,
+                                });
+                            }
+                        }
+                        let _ = response.send(Ok(()));
+                    }
+                    Err(e) => {
+                        let _ = response.send(Err(e));
+                    }
+                }
+            }
             SwarmLevelCommand::GetExternalAddresses { response } => {
                 debug!("🔄 [SwarmHandler] Processing GetExternalAddresses command");
 
@@ -618,6 +2223,14 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
             SwarmLevelCommand::ConnectionTracker { command } => {
                 debug!("🔄 [SwarmHandler] Processing ConnectionTracker command: {:?}", command);
                 
+                // Подтягиваем счетчики трафика из XStream перед ответом
+                for ((peer_id, connection_id), (bytes_in, bytes_out)) in
+                    swarm.behaviour().xstream.connection_byte_totals()
+                {
+                    self.conntracker
+                        .update_connection_traffic(&peer_id, &connection_id, bytes_in, bytes_out);
+                }
+
                 // Handle ConnectionTracker commands using local Conntracker
                 match command {
                     ConntrackerCommand::GetConnections { response } => {
@@ -625,6 +2238,15 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                         let connections_cloned: Vec<ConnectionInfo> = connections.into_iter().cloned().collect();
                         let _ = response.send(Ok(connections_cloned));
                     }
+                    ConntrackerCommand::GetLongestLivedConnections { limit, response } => {
+                        let connections: Vec<ConnectionInfo> = self
+                            .conntracker
+                            .get_longest_lived_connections(limit)
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        let _ = response.send(Ok(connections));
+                    }
                     ConntrackerCommand::GetPeerConnections { peer_id, response } => {
                         match self.conntracker.get_peer_connections(&peer_id) {
                             Some(peer_connections) => {
@@ -647,6 +2269,24 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                             }
                         }
                     }
+                    ConntrackerCommand::GetConnectionByAddress { address, response } => {
+                        let connections: Vec<ConnectionInfo> = self
+                            .conntracker
+                            .get_connections_by_address(&address)
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        let _ = response.send(Ok(connections));
+                    }
+                    ConntrackerCommand::GetConnectedPeersDetailed { response } => {
+                        let connections: Vec<ConnectionInfo> = self
+                            .conntracker
+                            .get_connected_connections()
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        let _ = response.send(Ok(connections));
+                    }
                     ConntrackerCommand::GetConnectedPeers { response } => {
                         let connected_peers = self.conntracker.get_connected_peers();
                         let _ = response.send(Ok(connected_peers));
@@ -675,8 +2315,33 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
             <XNetworkBehaviour as libp2p::swarm::NetworkBehaviour>::ToSwarm,
         >,
     ) {
+        // Validate PoR metadata before the request is announced
+        let metadata_validation = self.validate_por_metadata(swarm, event);
+
         // First, transform and emit the event through the channel
-        self.transform_and_emit_event(event);
+        self.transform_and_emit_event(event, metadata_validation);
+
+        self.update_network_state(event);
+
+        self.update_stream_counts(event);
+
+        self.update_identify_info(event);
+
+        self.auto_start_auth(swarm, event);
+
+        self.prune_timed_out_relay_listeners(swarm);
+
+        self.expire_queued_dials();
+
+        self.redial_sticky_peer(swarm, event).await;
+
+        self.continue_relay_dial(swarm, event);
+
+        self.drain_queued_dials(swarm, event).await;
+
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.record_swarm_event(event);
+        }
 
         // Then handle the event normally (logging, etc.)
         match event {
@@ -723,6 +2388,7 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                                     "🎉 [SwarmHandler] MUTUAL AUTH SUCCESS for peer: {}, connection: {:?}",
                                     peer_id, connection_id
                                 );
+                                self.auth_successes += 1;
                             }
                             PorAuthEvent::OutboundAuthSuccess {
                                 peer_id,
@@ -753,6 +2419,7 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                                     "❌ [SwarmHandler] OUTBOUND AUTH FAILURE for peer: {}, connection: {:?}",
                                     peer_id, connection_id
                                 );
+                                self.auth_failures += 1;
                             }
                             PorAuthEvent::InboundAuthFailure {
                                 peer_id,
@@ -763,6 +2430,7 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                                     "❌ [SwarmHandler] INBOUND AUTH FAILURE for peer: {}, connection: {:?}",
                                     peer_id, connection_id
                                 );
+                                self.auth_failures += 1;
                             }
                             _ => {}
                         }
@@ -815,8 +2483,36 @@ impl SwarmHandler<XNetworkBehaviour> for XNetworkSwarmHandler {
                             _ => {}
                         }
                     }
+                    XNetworkBehaviourEvent::PeerFilter(event) => {
+                        debug!("📡 [SwarmHandler] PeerFilter event: {:?}", event);
+                    }
+                    XNetworkBehaviourEvent::ConnectionLimits(event) => {
+                        debug!("📡 [SwarmHandler] ConnectionLimits event: {:?}", event);
+                    }
+                    XNetworkBehaviourEvent::Reconnect(event) => {
+                        debug!("📡 [SwarmHandler] Reconnect event: {:?}", event);
+                    }
                     XNetworkBehaviourEvent::KeepAlive(event) => {
                         debug!("📡 [SwarmHandler] KeepAlive event: {:?}", event);
+
+                        if let KeepAliveEvent::AddressChanged { peer_id, connection_id, new, .. } = event {
+                            // Update Conntracker with migrated connection address
+                            if let Some((old_addr, new_addr)) =
+                                self.conntracker.update_connection_address(peer_id, connection_id, new)
+                            {
+                                info!(
+                                    "🔀 [SwarmHandler] Connection {:?} to {} migrated: {} -> {}",
+                                    connection_id, peer_id, old_addr, new_addr
+                                );
+                                if let Some(sender) = self.event_sender.as_ref() {
+                                    let _ = sender.send(NodeEvent::ConnectionMigrated {
+                                        peer_id: *peer_id,
+                                        old_addr,
+                                        new_addr,
+                                    });
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -0,0 +1,222 @@
+//! Экспорт событий соединений и аутентификации в tracing/OpenTelemetry
+//!
+//! События пишутся в переданный `tracing::Dispatch` (например, подписчик с
+//! слоем tracing-opentelemetry) с target [`TELEMETRY_TARGET`] и стандартными
+//! полями: `peer_id`, `connection_id`, `direction`, `transport`.
+
+use libp2p::core::ConnectedPoint;
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::{ConnectionId, SwarmEvent};
+use libp2p::{Multiaddr, PeerId};
+use xauth::definitions::AuthDirection;
+use xauth::events::PorAuthEvent;
+
+use crate::main_behaviour::XNetworkBehaviourEvent;
+
+/// Target всех телеметрических событий
+pub const TELEMETRY_TARGET: &str = "xnetwork2::telemetry";
+
+/// Экспортер событий жизненного цикла соединений и аутентификации
+#[derive(Clone)]
+pub struct ConnectionTelemetry {
+    dispatch: tracing::Dispatch,
+}
+
+impl std::fmt::Debug for ConnectionTelemetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionTelemetry").finish_non_exhaustive()
+    }
+}
+
+impl ConnectionTelemetry {
+    /// Создает экспортер, пишущий в переданный dispatch
+    pub fn new(dispatch: tracing::Dispatch) -> Self {
+        Self { dispatch }
+    }
+
+    /// Экспортирует событие swarm, если оно относится к соединениям или аутентификации
+    pub fn record_swarm_event(&self, event: &SwarmEvent<XNetworkBehaviourEvent>) {
+        match event {
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                connection_id,
+                endpoint,
+                ..
+            } => self.emit(
+                "connection_established",
+                Some(peer_id),
+                connection_id,
+                endpoint_direction(endpoint),
+                endpoint.get_remote_address(),
+                None,
+            ),
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                connection_id,
+                endpoint,
+                cause,
+                ..
+            } => self.emit(
+                "connection_closed",
+                Some(peer_id),
+                connection_id,
+                endpoint_direction(endpoint),
+                endpoint.get_remote_address(),
+                cause.as_ref().map(|cause| cause.to_string()),
+            ),
+            SwarmEvent::OutgoingConnectionError {
+                peer_id,
+                connection_id,
+                error,
+            } => self.emit(
+                "connection_failed",
+                peer_id.as_ref(),
+                connection_id,
+                "outbound",
+                &Multiaddr::empty(),
+                Some(error.to_string()),
+            ),
+            SwarmEvent::IncomingConnectionError {
+                connection_id,
+                send_back_addr,
+                error,
+                ..
+            } => self.emit(
+                "connection_failed",
+                None,
+                connection_id,
+                "inbound",
+                send_back_addr,
+                Some(error.to_string()),
+            ),
+            SwarmEvent::Behaviour(XNetworkBehaviourEvent::Xauth(auth_event)) => {
+                self.record_auth_event(auth_event)
+            }
+            _ => {}
+        }
+    }
+
+    fn record_auth_event(&self, event: &PorAuthEvent) {
+        match event {
+            PorAuthEvent::MutualAuthSuccess {
+                peer_id,
+                connection_id,
+                address,
+                ..
+            } => self.emit("auth_success", Some(peer_id), connection_id, "both", address, None),
+            PorAuthEvent::OutboundAuthSuccess {
+                peer_id,
+                connection_id,
+                address,
+                ..
+            } => self.emit("auth_success", Some(peer_id), connection_id, "outbound", address, None),
+            PorAuthEvent::InboundAuthSuccess {
+                peer_id,
+                connection_id,
+                address,
+            } => self.emit("auth_success", Some(peer_id), connection_id, "inbound", address, None),
+            PorAuthEvent::OutboundAuthFailure {
+                peer_id,
+                connection_id,
+                address,
+                reason,
+                ..
+            } => self.emit(
+                "auth_failure",
+                Some(peer_id),
+                connection_id,
+                "outbound",
+                address,
+                Some(reason.clone()),
+            ),
+            PorAuthEvent::InboundAuthFailure {
+                peer_id,
+                connection_id,
+                address,
+                reason,
+                ..
+            } => self.emit(
+                "auth_failure",
+                Some(peer_id),
+                connection_id,
+                "inbound",
+                address,
+                Some(reason.clone()),
+            ),
+            PorAuthEvent::AuthTimeout {
+                peer_id,
+                connection_id,
+                address,
+                direction,
+            } => self.emit(
+                "auth_timeout",
+                Some(peer_id),
+                connection_id,
+                auth_direction(direction),
+                address,
+                None,
+            ),
+            PorAuthEvent::VerifyPorRequest { .. } => {}
+        }
+    }
+
+    fn emit(
+        &self,
+        event: &'static str,
+        peer_id: Option<&PeerId>,
+        connection_id: &ConnectionId,
+        direction: &'static str,
+        address: &Multiaddr,
+        reason: Option<String>,
+    ) {
+        let peer_id = peer_id.map(|peer_id| peer_id.to_string()).unwrap_or_default();
+        let transport = transport_name(address);
+
+        tracing::dispatcher::with_default(&self.dispatch, || {
+            tracing::info!(
+                target: TELEMETRY_TARGET,
+                event,
+                peer_id = %peer_id,
+                connection_id = %connection_id,
+                direction,
+                transport,
+                address = %address,
+                reason = reason.as_deref(),
+                "{}",
+                event
+            );
+        });
+    }
+}
+
+fn endpoint_direction(endpoint: &ConnectedPoint) -> &'static str {
+    if endpoint.is_dialer() {
+        "outbound"
+    } else {
+        "inbound"
+    }
+}
+
+fn auth_direction(direction: &AuthDirection) -> &'static str {
+    match direction {
+        AuthDirection::Inbound => "inbound",
+        AuthDirection::Outbound => "outbound",
+        AuthDirection::Both => "both",
+    }
+}
+
+/// Имя транспорта по адресу; relay-соединения помечаются как relay
+pub fn transport_name(address: &Multiaddr) -> &'static str {
+    let mut transport = "unknown";
+    for protocol in address.iter() {
+        match protocol {
+            Protocol::P2pCircuit => return "relay",
+            Protocol::QuicV1 | Protocol::Quic => transport = "quic",
+            Protocol::Ws(_) | Protocol::Wss(_) => transport = "websocket",
+            Protocol::Tcp(_) if transport == "unknown" => transport = "tcp",
+            Protocol::Memory(_) => transport = "memory",
+            _ => {}
+        }
+    }
+    transport
+}
@@ -0,0 +1,83 @@
+//! Тест переписывания адресов перед dial
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_listening_node, wait_for_event};
+
+/// Порт-заглушка, который rewriter заменяет реальным портом сервера
+const PLACEHOLDER_PORT: u16 = 1;
+
+fn with_port(addr: &Multiaddr, port: u16) -> Multiaddr {
+    addr.iter()
+        .map(|protocol| match protocol {
+            Protocol::Udp(_) => Protocol::Udp(port),
+            other => other,
+        })
+        .collect()
+}
+
+fn udp_port(addr: &Multiaddr) -> Option<u16> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Udp(port) => Some(port),
+        _ => None,
+    })
+}
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Dial на порт-заглушку доходит до сервера по переписанному адресу
+#[tokio::test]
+async fn test_address_rewriter_redirects_dial() {
+    let mut server = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let real_port = udp_port(&server_addr).expect("❌ Адрес сервера без UDP порта");
+
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_address_rewriter(move |addr| match udp_port(&addr) {
+                Some(PLACEHOLDER_PORT) => with_port(&addr, real_port),
+                _ => addr,
+            }),
+    )
+    .await;
+    let mut client_events = client.subscribe();
+
+    let placeholder = with_port(&server_addr, PLACEHOLDER_PORT);
+    client
+        .commander
+        .dial_and_wait(server_peer, placeholder.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Dial по переписанному адресу не дошел до сервера");
+
+    let event = wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::DialAddressRewritten { .. }),
+        Duration::from_secs(1),
+    )
+    .await
+    .expect("❌ DialAddressRewritten не получен");
+    match event {
+        NodeEvent::DialAddressRewritten { peer_id, original, rewritten } => {
+            assert_eq!(peer_id, server_peer, "❌ Неверный пир в событии");
+            assert_eq!(original, placeholder, "❌ Исходный адрес не сохранен");
+            assert_eq!(udp_port(&rewritten), Some(real_port), "❌ Адрес переписан неверно: {}", rewritten);
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
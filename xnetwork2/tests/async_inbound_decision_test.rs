@@ -0,0 +1,82 @@
+//! Тест асинхронного решения о входящих потоках
+
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use tokio::time::timeout;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::InboundDecision;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Подключает клиента к серверу и пробует открыть поток
+async fn open_stream_to(server_builder: NodeBuilder) -> (Result<(), String>, Duration) {
+    let mut server = start_node(server_builder.with_auto_auth(false)).await;
+    let mut client = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let started = Instant::now();
+    let result = timeout(Duration::from_secs(10), client.commander.open_xstream(server_peer))
+        .await
+        .expect("❌ Таймаут открытия потока")
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+    let elapsed = started.elapsed();
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+    (result, elapsed)
+}
+
+/// Решение, готовое после паузы, принимает поток
+#[tokio::test]
+async fn test_async_decision_accepts_after_delay() {
+    let (result, elapsed) = open_stream_to(
+        NodeBuilder::new()
+            .with_inbound_decision_timeout(Duration::from_secs(2))
+            .with_async_inbound_decision(|_peer_id, _connection_id| {
+                async {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    InboundDecision::Accept
+                }
+                .boxed()
+            }),
+    )
+    .await;
+
+    assert!(result.is_ok(), "❌ Поток отклонен: {:?}", result.err());
+    assert!(elapsed >= Duration::from_millis(300), "❌ Поток принят до готовности решения: {:?}", elapsed);
+}
+
+/// Решение, не готовое за таймаут, отклоняет поток
+#[tokio::test]
+async fn test_async_decision_timeout_rejects() {
+    let (result, elapsed) = open_stream_to(
+        NodeBuilder::new()
+            .with_inbound_decision_timeout(Duration::from_millis(500))
+            .with_async_inbound_decision(|_peer_id, _connection_id| {
+                async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    InboundDecision::Accept
+                }
+                .boxed()
+            }),
+    )
+    .await;
+
+    assert!(result.is_err(), "❌ Поток принят, хотя решение не успело");
+    assert!(elapsed < Duration::from_secs(5), "❌ Отказ пришел слишком поздно: {:?}", elapsed);
+}
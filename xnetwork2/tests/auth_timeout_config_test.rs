@@ -0,0 +1,103 @@
+//! Тест настраиваемого таймаута PoR аутентификации на уровне ноды
+
+use std::time::Duration;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_connection_with_auth, setup_listening_node, wait_for_event};
+
+/// Нулевой таймаут отклоняется при сборке ноды
+#[tokio::test]
+async fn test_zero_auth_timeout_rejected() {
+    let result = NodeBuilder::new().with_auth_timeout(Duration::ZERO).build().await;
+    assert!(result.is_err(), "❌ Нулевой таймаут аутентификации должен отклоняться");
+
+    let result = NodeBuilder::new()
+        .with_auth_timeouts(Duration::from_secs(5), Duration::ZERO)
+        .build()
+        .await;
+    assert!(result.is_err(), "❌ Нулевой исходящий таймаут должен отклоняться");
+}
+
+/// Короткий таймаут срабатывает, если пир молчит и не подтверждает PoR
+#[tokio::test]
+async fn test_short_auth_timeout_fires_against_silent_peer() {
+    let mut silent = NodeBuilder::new().with_auto_auth(false).build().await.expect("❌ Не удалось создать молчащую ноду");
+    silent.start().await.expect("❌ Не удалось запустить молчащую ноду");
+    let silent_addr = setup_listening_node(&mut silent).await.expect("❌ Молчащая нода не слушает");
+    let silent_peer = *silent.peer_id();
+
+    let mut node = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_auth_timeout(Duration::from_millis(300))
+        .build()
+        .await
+        .expect("❌ Не удалось создать ноду");
+    node.start().await.expect("❌ Не удалось запустить ноду");
+    let mut events = node.subscribe();
+
+    let connection_id = dial_and_wait_connection(&mut node, silent_peer, silent_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к молчащей ноде");
+
+    // Молчащая нода не запускает аутентификацию и не одобряет PoR
+    node.commander
+        .start_auth_for_connection(connection_id)
+        .await
+        .expect("❌ Не удалось запустить аутентификацию");
+
+    let event = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::PeerAuthTimeout { peer_id, .. } if *peer_id == silent_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Не получено событие PeerAuthTimeout");
+
+    match event {
+        NodeEvent::PeerAuthTimeout { connection_id: timed_out, .. } => {
+            assert_eq!(timed_out, connection_id, "❌ Таймаут должен относиться к нашему соединению");
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить ноду");
+    silent.force_shutdown().await.expect("❌ Не удалось остановить молчащую ноду");
+}
+
+/// Длинный таймаут не мешает аутентификации с отвечающим пиром
+#[tokio::test]
+async fn test_long_auth_timeout_allows_responsive_peer() {
+    let mut node_a = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_auth_timeout(Duration::from_secs(30))
+        .build()
+        .await
+        .expect("❌ Не удалось создать node_a");
+    let mut node_b = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_auth_timeouts(Duration::from_secs(30), Duration::from_secs(20))
+        .build()
+        .await
+        .expect("❌ Не удалось создать node_b");
+    node_a.start().await.expect("❌ Не удалось запустить node_a");
+    node_b.start().await.expect("❌ Не удалось запустить node_b");
+    let mut events_a = node_a.subscribe();
+
+    let addr_b = setup_listening_node(&mut node_b).await.expect("❌ node_b не слушает");
+    setup_connection_with_auth(&mut node_a, &mut node_b, addr_b, Duration::from_secs(10))
+        .await
+        .expect("❌ Аутентификация с отвечающим пиром должна пройти");
+
+    let timeout_event = wait_for_event(
+        &mut events_a,
+        |e| matches!(e, NodeEvent::PeerAuthTimeout { .. }),
+        Duration::from_millis(500),
+    )
+    .await;
+    assert!(timeout_event.is_err(), "❌ Таймаут не должен срабатывать для отвечающего пира");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить node_a");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить node_b");
+}
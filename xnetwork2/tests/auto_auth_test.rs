@@ -0,0 +1,83 @@
+//! Тест автоматического запуска аутентификации после установки соединения
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, spawn_por_task, wait_for_event};
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// С auto-auth по умолчанию узлы достигают взаимной аутентификации без StartAuthForConnection
+#[tokio::test]
+async fn test_auto_auth_reaches_mutual_auth() {
+    let mut server = start_node(NodeBuilder::new()).await;
+    let mut client = start_node(NodeBuilder::new()).await;
+    let server_peer = *server.peer_id();
+    let client_peer = *client.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    // Приложение только подтверждает PoR, аутентификацию не запускает
+    let por_client = spawn_por_task(&mut client, server_peer, Duration::from_secs(10));
+    let por_server = spawn_por_task(&mut server, client_peer, Duration::from_secs(10));
+    let mut client_events = client.subscribe();
+    let mut server_events = server.subscribe();
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::PeerMutualAuthSuccess { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Клиент не достиг взаимной аутентификации");
+    wait_for_event(
+        &mut server_events,
+        |e| matches!(e, NodeEvent::PeerMutualAuthSuccess { peer_id, .. } if *peer_id == client_peer),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Сервер не достиг взаимной аутентификации");
+
+    por_client.await.unwrap().expect("❌ PoR клиента не подтвержден");
+    por_server.await.unwrap().expect("❌ PoR сервера не подтвержден");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Policy, отклонившая пира, оставляет аутентификацию ручной
+#[tokio::test]
+async fn test_auto_auth_policy_skips_peer() {
+    let mut server = start_node(NodeBuilder::new().with_auto_auth_policy(|_peer_id| false)).await;
+    let mut client = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let mut client_events = client.subscribe();
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    // Сервер не отправил PoR, значит аутентификация не запускалась
+    let por_request = wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::VerifyPorRequest { .. }),
+        Duration::from_secs(2),
+    )
+    .await;
+    assert!(por_request.is_err(), "❌ Аутентификация запущена вопреки policy");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,109 @@
+//! Тесты автоматического переподключения к sticky пирам
+
+use std::time::{Duration, Instant};
+
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// После разрыва соединения узел переподключается не раньше backoff
+#[tokio::test]
+async fn test_reconnect_attempt_after_connection_killed() {
+    let backoff = Duration::from_millis(500);
+
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let mut node_b = NodeBuilder::new()
+        .with_auto_reconnect(vec![(peer_a, addr_a.clone())], backoff)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел B");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+    let peer_b = *node_b.peer_id();
+
+    let sticky = node_b.commander.get_sticky_peers().await.expect("❌ Команда не выполнилась");
+    assert_eq!(sticky, vec![(peer_a, addr_a.clone())]);
+
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Первое соединение должно установиться");
+
+    // Соединение рвет удаленная сторона, поэтому B продолжает считать A sticky
+    let mut events_b = node_b.subscribe();
+    node_a.commander.disconnect(peer_b).await.expect("❌ Не удалось разорвать соединение");
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ConnectionClosed { peer_id, .. } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Соединение не закрылось");
+    let closed_at = Instant::now();
+
+    let event = wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ReconnectAttempt { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Попытка переподключения не произошла");
+    assert!(closed_at.elapsed() >= backoff - Duration::from_millis(50));
+    match event {
+        NodeEvent::ReconnectAttempt { peer_id, attempt } => {
+            assert_eq!(peer_id, peer_a);
+            assert_eq!(attempt, 1);
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ConnectionEstablished { peer_id, .. } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Соединение не восстановилось");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
+
+/// Явный disconnect прекращает переподключение
+#[tokio::test]
+async fn test_explicit_disconnect_stops_reconnect() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let mut node_b = NodeBuilder::new()
+        .with_auto_reconnect(vec![(peer_a, addr_a.clone())], Duration::from_millis(200))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел B");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Соединение должно установиться");
+
+    let mut events_b = node_b.subscribe();
+    node_b.commander.disconnect(peer_a).await.expect("❌ Не удалось отключиться");
+
+    let attempt = wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ReconnectAttempt { .. }),
+        Duration::from_secs(1),
+    )
+    .await;
+    assert!(attempt.is_err(), "❌ После явного disconnect переподключения быть не должно");
+    assert!(node_b.commander.get_sticky_peers().await.unwrap().is_empty());
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
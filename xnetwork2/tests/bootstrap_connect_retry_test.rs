@@ -0,0 +1,109 @@
+//! Тест повторных подключений BootstrapConnect к bootstrap пиру
+
+use std::time::Duration;
+
+use libp2p::identity;
+use libp2p::multiaddr::Protocol;
+use xnetwork2::Node;
+use xnetwork2::bootstrap::{
+    BootstrapConfig, BootstrapConnect, BootstrapEvent, BootstrapServer, RetryPolicy,
+};
+
+/// Задержка растет, ограничена сверху и разбрасывается в пределах jitter
+#[test]
+fn test_retry_policy_backoff_capped_and_jittered() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        base_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(1),
+        jitter: 0.2,
+    };
+
+    for _ in 0..20 {
+        let first = policy.backoff(1);
+        assert!(first >= Duration::from_millis(80) && first <= Duration::from_millis(120));
+        let third = policy.backoff(3);
+        assert!(third >= Duration::from_millis(320) && third <= Duration::from_millis(480));
+        assert!(policy.backoff(30) <= policy.max_backoff, "❌ Задержка превысила максимум");
+    }
+
+    let exact = RetryPolicy { jitter: 0.0, ..policy };
+    assert_eq!(exact.backoff(2), Duration::from_millis(200));
+    assert_eq!(exact.backoff(10), Duration::from_secs(1));
+}
+
+/// Bootstrap пир появляется после двух неудачных попыток, подключение удается
+#[tokio::test]
+async fn test_bootstrap_peer_comes_online_after_two_failures() {
+    let server_key = identity::Keypair::generate_ed25519();
+    let server_peer_id = server_key.public().to_peer_id();
+    let port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let listen_addr: libp2p::Multiaddr =
+        format!("/ip4/127.0.0.1/udp/{}/quic-v1", port).parse().unwrap();
+
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let connect = BootstrapConnect::new(vec![(
+        server_peer_id,
+        listen_addr.clone().with(Protocol::P2p(server_peer_id)),
+    )])
+    .with_retry_policy(RetryPolicy {
+        max_attempts: 6,
+        base_backoff: Duration::from_millis(500),
+        max_backoff: Duration::from_secs(2),
+        jitter: 0.1,
+    })
+    .with_dial_timeout(Duration::from_secs(1));
+    let mut events = connect.subscribe();
+
+    let commander = client.commander.clone();
+    let connect_task = tokio::spawn(async move { connect.connect(&commander).await });
+
+    // Ждем две неудачные попытки, пока сервер не запущен
+    let mut failures = 0;
+    while failures < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(10), events.recv())
+            .await
+            .expect("❌ Нет событий BootstrapConnect")
+            .unwrap();
+        if let BootstrapEvent::ConnectFailed { peer_id, retry_in, .. } = event {
+            assert_eq!(peer_id, server_peer_id);
+            assert!(retry_in.is_some(), "❌ Попытки не должны быть исчерпаны");
+            failures += 1;
+        }
+    }
+
+    let server = BootstrapServer::start(
+        BootstrapConfig::default()
+            .with_listen_addr(listen_addr)
+            .with_keypair(server_key),
+    )
+    .await
+    .expect("❌ Не удалось запустить bootstrap сервер");
+
+    let connected = tokio::time::timeout(Duration::from_secs(15), connect_task)
+        .await
+        .expect("❌ BootstrapConnect не завершился")
+        .unwrap();
+    assert_eq!(connected, vec![server_peer_id], "❌ Подключение не установлено");
+
+    let mut attempt_connected = None;
+    while let Ok(event) = events.try_recv() {
+        if let BootstrapEvent::Connected { attempt, .. } = event {
+            attempt_connected = Some(attempt);
+        }
+    }
+    assert!(
+        attempt_connected.is_some_and(|attempt| attempt >= 3),
+        "❌ Подключение должно произойти после двух неудач: {:?}",
+        attempt_connected
+    );
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
@@ -0,0 +1,263 @@
+//! Тест bootstrap сервера, запущенного как библиотека
+
+use std::time::Duration;
+
+use xnetwork2::Node;
+use xnetwork2::behaviours::connection_limits::ConnectionLimits;
+use xnetwork2::bootstrap::{BootstrapConfig, BootstrapEvent, BootstrapServer};
+
+/// Клиент подключается к bootstrap серверу по адресу из handle
+#[tokio::test]
+async fn test_client_connects_via_bootstrap_handle_address() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap());
+    let handle = BootstrapServer::start(config)
+        .await
+        .expect("❌ Не удалось запустить bootstrap сервер");
+
+    assert_eq!(
+        handle.address().iter().last(),
+        Some(libp2p::multiaddr::Protocol::P2p(handle.peer_id())),
+        "❌ Адрес должен заканчиваться на /p2p/<peer_id>"
+    );
+    assert_eq!(handle.connected_peer_count().await.unwrap(), 0);
+
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    client
+        .commander
+        .dial_and_wait(handle.peer_id(), handle.address().clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Клиент не смог подключиться к bootstrap серверу");
+
+    // Сервер может увидеть соединение чуть позже клиента
+    let mut count = 0;
+    for _ in 0..20 {
+        count = handle.connected_peer_count().await.unwrap();
+        if count > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(count, 1, "❌ У bootstrap сервера должен быть один подключенный пир");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Перезагрузка конфигурации переносит сервер на новый адрес и меняет лимиты
+#[tokio::test]
+async fn test_reload_config_adds_listen_address() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap());
+    let mut handle = BootstrapServer::start(config.clone())
+        .await
+        .expect("❌ Не удалось запустить bootstrap сервер");
+    let mut events = handle.subscribe();
+    let old_address = handle.address().clone();
+
+    let limits = ConnectionLimits::unlimited().with_max_established_per_peer(Some(2));
+    // Явный свободный порт, чтобы новый адрес отличался от старого
+    let port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let new_config = config
+        .with_listen_addr(format!("/ip4/127.0.0.1/udp/{}/quic-v1", port).parse().unwrap())
+        .with_connection_limits(limits);
+    let diff = handle
+        .reload_config(new_config)
+        .await
+        .expect("❌ Не удалось перезагрузить конфигурацию");
+
+    assert_eq!(diff.added_listen_addrs.len(), 1, "❌ Должен появиться один новый адрес");
+    assert_eq!(diff.removed_listen_addrs.len(), 1, "❌ Старый адрес должен быть удален");
+    assert_eq!(diff.connection_limits, Some(limits));
+    assert_ne!(handle.address(), &old_address, "❌ Адрес handle не обновлен");
+
+    match events.try_recv() {
+        Ok(BootstrapEvent::Listening { address, .. }) => {
+            assert_eq!(address, diff.added_listen_addrs[0])
+        }
+        other => panic!("❌ Ожидалось событие Listening, получено {:?}", other),
+    }
+    match events.try_recv() {
+        Ok(BootstrapEvent::ConfigReloaded(event_diff)) => assert_eq!(event_diff, diff),
+        other => panic!("❌ Ожидалось событие ConfigReloaded, получено {:?}", other),
+    }
+
+    let listen_addresses = handle.commander().get_listen_addresses().await.unwrap();
+    assert!(
+        listen_addresses.contains(&diff.added_listen_addrs[0]),
+        "❌ Новый адрес не активен: {:?}",
+        listen_addresses
+    );
+    assert!(
+        !listen_addresses.contains(&diff.removed_listen_addrs[0]),
+        "❌ Старый адрес все еще активен: {:?}",
+        listen_addresses
+    );
+
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    client
+        .commander
+        .dial_and_wait(handle.peer_id(), handle.address().clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Клиент не смог подключиться по новому адресу");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Конфигурация, требующая перезапуска, отклоняется без изменений
+#[tokio::test]
+async fn test_reload_config_rejects_restart_only_changes() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap());
+    let mut handle = BootstrapServer::start(config.clone())
+        .await
+        .expect("❌ Не удалось запустить bootstrap сервер");
+    let mut events = handle.subscribe();
+    let address = handle.address().clone();
+
+    let result = handle
+        .reload_config(
+            config
+                .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+                .with_relay_server(),
+        )
+        .await;
+    assert!(result.is_err(), "❌ Смена relay режима должна быть отклонена");
+    assert_eq!(handle.address(), &address, "❌ Адрес изменился после ошибки");
+    assert!(events.try_recv().is_err(), "❌ Событие не должно отправляться при ошибке");
+
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Ошибка одного адреса не мешает подняться остальным
+#[tokio::test]
+async fn test_multiple_listen_addrs_with_one_invalid() {
+    let valid: libp2p::Multiaddr = "/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap();
+    // Адрес из TEST-NET-1, не назначен локальному интерфейсу
+    let invalid: libp2p::Multiaddr = "/ip4/192.0.2.1/udp/0/quic-v1".parse().unwrap();
+    let config = BootstrapConfig::default().with_listen_addrs(vec![invalid.clone(), valid.clone()]);
+    let handle = BootstrapServer::start(config)
+        .await
+        .expect("❌ Сервер должен запуститься с одним рабочим адресом");
+
+    let events = handle.startup_events();
+    assert_eq!(events.len(), 2, "❌ Должно быть одно событие на каждый адрес");
+    match &events[0] {
+        BootstrapEvent::BindFailed { requested, .. } => assert_eq!(requested, &invalid),
+        other => panic!("❌ Ожидалось BindFailed, получено {:?}", other),
+    }
+    let bound = match &events[1] {
+        BootstrapEvent::Listening { requested, address } => {
+            assert_eq!(requested, &valid);
+            address.clone()
+        }
+        other => panic!("❌ Ожидалось Listening, получено {:?}", other),
+    };
+
+    assert_eq!(handle.addresses().len(), 1, "❌ Должен быть один рабочий адрес");
+    assert!(handle.address().to_string().starts_with(&bound.to_string()));
+
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    client
+        .commander
+        .dial_and_wait(handle.peer_id(), handle.address().clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Клиент не смог подключиться к рабочему адресу");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Если не поднялся ни один адрес, сервер не запускается
+#[tokio::test]
+async fn test_all_listen_addrs_invalid_fails_start() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/192.0.2.1/udp/0/quic-v1".parse().unwrap());
+    assert!(
+        BootstrapServer::start(config).await.is_err(),
+        "❌ Сервер без рабочих адресов не должен запускаться"
+    );
+}
+
+/// Перезагрузка без рабочих адресов отклоняется, ошибка одного адреса не мешает остальным
+#[tokio::test]
+async fn test_reload_config_with_unbindable_addresses() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap());
+    let mut handle = BootstrapServer::start(config.clone())
+        .await
+        .expect("❌ Не удалось запустить bootstrap сервер");
+    let old_address = handle.address().clone();
+    let old_listen = handle.commander().get_listen_addresses().await.unwrap();
+
+    // Адрес из TEST-NET-1, не назначен локальному интерфейсу
+    let invalid: libp2p::Multiaddr = "/ip4/192.0.2.1/udp/0/quic-v1".parse().unwrap();
+    let result = handle
+        .reload_config(config.clone().with_listen_addr(invalid.clone()))
+        .await;
+    assert!(result.is_err(), "❌ Конфигурация без рабочих адресов должна быть отклонена");
+    assert_eq!(handle.address(), &old_address, "❌ Адрес изменился после ошибки");
+    assert_eq!(
+        handle.commander().get_listen_addresses().await.unwrap(),
+        old_listen,
+        "❌ Старые адреса не должны останавливаться при ошибке"
+    );
+
+    let mut events = handle.subscribe();
+    let diff = handle
+        .reload_config(config.with_listen_addrs(vec![
+            invalid.clone(),
+            "/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap(),
+        ]))
+        .await
+        .expect("❌ Один рабочий адрес достаточен для перезагрузки");
+    assert!(diff.added_listen_addrs.is_empty(), "❌ Адрес уже поднят и не должен добавляться");
+    match events.try_recv() {
+        Ok(BootstrapEvent::BindFailed { requested, .. }) => assert_eq!(requested, invalid),
+        other => panic!("❌ Ожидалось событие BindFailed, получено {:?}", other),
+    }
+
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Удаленный адрес прослушивания перестает объявляться как внешний
+#[tokio::test]
+async fn test_reload_config_removes_external_address() {
+    let config = BootstrapConfig::default()
+        .with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap());
+    let mut handle = BootstrapServer::start(config.clone())
+        .await
+        .expect("❌ Не удалось запустить bootstrap сервер");
+
+    let port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let diff = handle
+        .reload_config(config.with_listen_addr(format!("/ip4/127.0.0.1/udp/{}/quic-v1", port).parse().unwrap()))
+        .await
+        .expect("❌ Не удалось перезагрузить конфигурацию");
+
+    let external = handle.commander().get_swarm_external_addresses().await.unwrap();
+    assert!(
+        !external.contains(&diff.removed_listen_addrs[0]),
+        "❌ Удаленный адрес все еще внешний: {:?}",
+        external
+    );
+    assert!(
+        external.contains(&diff.added_listen_addrs[0]),
+        "❌ Новый адрес должен быть внешним: {:?}",
+        external
+    );
+
+    handle.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
@@ -0,0 +1,85 @@
+//! Тест поиска ближайших к произвольному ключу пиров через Kademlia
+
+use std::time::Duration;
+use libp2p::kad::KBucketKey;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+async fn start_kad_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_kad_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел с Kademlia");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Без пиров в таблице маршрутизации возвращается пустой список
+#[tokio::test]
+async fn test_closest_peers_to_key_empty_routing_table() {
+    let mut node = start_kad_node().await;
+
+    let peers = node
+        .commander
+        .get_closest_peers_to_key(b"shard-0".to_vec())
+        .await
+        .expect("❌ Запрос ближайших пиров не выполнен");
+    assert!(peers.is_empty(), "❌ Ожидался пустой список: {:?}", peers);
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Из трех узлов результат содержит ближайший к ключу удаленный узел
+#[tokio::test]
+async fn test_closest_peers_to_key_includes_nearest() {
+    let mut node_a = start_kad_node().await;
+    let mut node_b = start_kad_node().await;
+    let mut node_c = start_kad_node().await;
+
+    let mut events_a = node_a.subscribe();
+    for node in [&mut node_b, &mut node_c] {
+        let peer_id = *node.peer_id();
+        let addr = setup_listening_node(node).await.expect("❌ Узел не слушает");
+        dial_and_wait_connection(&mut node_a, peer_id, addr, Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось подключиться к узлу");
+        wait_for_event(
+            &mut events_a,
+            |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id: updated } if *updated == peer_id),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("❌ Узел должен попасть в таблицу маршрутизации узла A");
+    }
+
+    let key = b"shard-42".to_vec();
+    let target = KBucketKey::from(key.clone());
+    let nearest = [*node_b.peer_id(), *node_c.peer_id()]
+        .into_iter()
+        .min_by_key(|peer_id| target.distance(&KBucketKey::from(*peer_id)))
+        .unwrap();
+
+    let peers = tokio::time::timeout(
+        Duration::from_secs(10),
+        node_a.commander.get_closest_peers_to_key(key),
+    )
+    .await
+    .expect("❌ Таймаут поиска ближайших пиров")
+    .expect("❌ Запрос ближайших пиров не выполнен");
+    assert!(
+        peers.contains(&nearest),
+        "❌ Ближайший к ключу узел {} отсутствует в результате: {:?}",
+        nearest,
+        peers
+    );
+    assert!(!peers.contains(node_a.peer_id()), "❌ Локальный узел не должен входить в результат");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+    node_c.force_shutdown().await.expect("❌ Не удалось остановить узел C");
+}
@@ -0,0 +1,34 @@
+//! Самопроверка тестового кластера
+
+mod test_harness;
+use test_harness::Cluster;
+
+/// В кластере из трех узлов каждый видит двух соединенных пиров
+#[tokio::test]
+async fn test_cluster_of_three_is_fully_connected() {
+    let mut cluster = Cluster::new(3).await.expect("❌ Не удалось собрать кластер");
+    assert_eq!(cluster.len(), 3, "❌ Неверный размер кластера");
+
+    for i in 0..cluster.len() {
+        let mut peers = cluster
+            .commander(i)
+            .get_connected_peers()
+            .await
+            .expect("❌ Не удалось получить соединенных пиров");
+        peers.sort();
+        let mut expected: Vec<_> = (0..cluster.len()).filter(|&j| j != i).map(|j| cluster.peer_id(j)).collect();
+        expected.sort();
+        assert_eq!(peers, expected, "❌ Узел {} видит не всех пиров", i);
+    }
+
+    // События подписаны до соединения: установка соединения видна в потоке событий
+    let mut established = 0;
+    while let Ok(event) = cluster.events(0).try_recv() {
+        if event.name() == "ConnectionEstablished" {
+            established += 1;
+        }
+    }
+    assert_eq!(established, 2, "❌ Узел 0 должен увидеть два соединения");
+
+    cluster.shutdown().await;
+}
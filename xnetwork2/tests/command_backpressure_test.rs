@@ -0,0 +1,61 @@
+//! Тест неблокирующих try_* команд при заполненной очереди команд
+
+use tokio::sync::oneshot;
+use xnetwork2::main_behaviour::XNetworkCommands;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::{CommandError, SwarmLevelCommand};
+
+fn echo_command(message: &str) -> XNetworkCommands {
+    let (response_tx, _response_rx) = oneshot::channel();
+    XNetworkCommands::SwarmLevel(SwarmLevelCommand::Echo {
+        message: message.to_string(),
+        response: response_tx,
+    })
+}
+
+/// Узел не запущен, очередь не разбирается: после заполнения try_* отказывают
+#[tokio::test]
+async fn test_try_commands_report_backpressure() {
+    let mut node = NodeBuilder::new()
+        .with_max_pending_commands(2)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+
+    assert_eq!(node.commander.command_queue_depth(), 0, "❌ Очередь должна быть пустой");
+
+    node.commander.try_send(echo_command("first")).expect("❌ Первая команда должна поместиться");
+    node.commander.try_send(echo_command("second")).expect("❌ Вторая команда должна поместиться");
+    assert_eq!(node.commander.command_queue_depth(), 2, "❌ Глубина очереди должна равняться емкости");
+    let loop_depth = node.swarm_loop.as_ref().expect("❌ SwarmLoop еще не запущен").command_queue_depth();
+    assert_eq!(loop_depth, 2, "❌ SwarmLoop должен видеть две ожидающие команды");
+
+    assert_eq!(
+        node.commander.try_send(echo_command("third")),
+        Err(CommandError::Backpressured),
+        "❌ Переполненная очередь должна вернуть Backpressured"
+    );
+
+    let error = node
+        .commander
+        .try_echo("fourth".to_string())
+        .await
+        .expect_err("❌ try_echo должен отказать при заполненной очереди");
+    assert_eq!(
+        error.downcast_ref::<CommandError>(),
+        Some(&CommandError::Backpressured),
+        "❌ Ожидалась ошибка Backpressured: {}",
+        error
+    );
+
+    // После запуска очередь разбирается и команды снова принимаются
+    node.start().await.expect("❌ Не удалось запустить узел");
+    let echo = node
+        .commander
+        .echo("after".to_string())
+        .await
+        .expect("❌ Эхо после запуска не выполнено");
+    assert_eq!(echo, "after", "❌ Неверный ответ эхо");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,135 @@
+//! Тест привязки NodeEvent к id команды, при обработке которой оно отправлено
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use tokio::sync::broadcast;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::{NodeEvent, TaggedNodeEvent};
+
+mod utils;
+use utils::setup_listening_node;
+
+/// Порт-заглушка, который rewriter заменяет реальным портом сервера
+const PLACEHOLDER_PORT: u16 = 1;
+
+fn with_port(addr: &Multiaddr, port: u16) -> Multiaddr {
+    addr.iter()
+        .map(|protocol| match protocol {
+            Protocol::Udp(_) => Protocol::Udp(port),
+            other => other,
+        })
+        .collect()
+}
+
+fn udp_port(addr: &Multiaddr) -> Option<u16> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Udp(port) => Some(port),
+        _ => None,
+    })
+}
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+async fn wait_for_tagged<F>(
+    receiver: &mut broadcast::Receiver<TaggedNodeEvent>,
+    predicate: F,
+    timeout: Duration,
+) -> Option<TaggedNodeEvent>
+where
+    F: Fn(&NodeEvent) -> bool,
+{
+    tokio::time::timeout(timeout, async {
+        loop {
+            match receiver.recv().await {
+                Ok(tagged) if predicate(&tagged.event) => return Some(tagged),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Событие, отправленное при обработке Dial, несет id этой команды,
+/// а установленное позже соединение - нет
+#[tokio::test]
+async fn test_dial_events_carry_command_id() {
+    let mut server = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let real_port = udp_port(&server_addr).expect("❌ Адрес сервера без UDP порта");
+
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_address_rewriter(move |addr| match udp_port(&addr) {
+                Some(PLACEHOLDER_PORT) => with_port(&addr, real_port),
+                _ => addr,
+            }),
+    )
+    .await;
+    let mut client_events = client.subscribe_tagged();
+
+    client
+        .commander
+        .dial(server_peer, with_port(&server_addr, PLACEHOLDER_PORT))
+        .await
+        .expect("❌ Dial не выполнен");
+
+    let rewritten = wait_for_tagged(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::DialAddressRewritten { .. }),
+        Duration::from_secs(1),
+    )
+    .await
+    .expect("❌ DialAddressRewritten не получен");
+    let dial_command = rewritten
+        .command_id
+        .expect("❌ DialAddressRewritten отправлен при обработке Dial, но без id команды");
+
+    let established = wait_for_tagged(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::ConnectionEstablished { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ ConnectionEstablished не получен");
+    assert_eq!(
+        established.command_id, None,
+        "❌ ConnectionEstablished пришло из swarm, а не из команды {}",
+        dial_command
+    );
+
+    // Следующая команда получает новый id
+    client
+        .commander
+        .dial(server_peer, with_port(&server_addr, PLACEHOLDER_PORT))
+        .await
+        .expect("❌ Повторный dial не выполнен");
+    let rewritten_again = wait_for_tagged(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::DialAddressRewritten { .. }),
+        Duration::from_secs(1),
+    )
+    .await
+    .expect("❌ Повторный DialAddressRewritten не получен");
+    let next_command = rewritten_again.command_id.expect("❌ Повторное событие без id команды");
+    assert!(
+        next_command > dial_command,
+        "❌ Id команд должны расти: {} после {}",
+        next_command,
+        dial_command
+    );
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
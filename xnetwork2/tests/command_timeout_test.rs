@@ -0,0 +1,52 @@
+//! Тест таймаута ожидания ответа на команду Commander
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use xnetwork2::main_behaviour::XNetworkCommands;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::{CommandError, Commander};
+
+/// Обработчик принимает команды и держит их, не отвечая: вызов завершается ResponseTimeout
+#[tokio::test]
+async fn test_command_without_response_times_out() {
+    let node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    let (command_tx, mut command_rx) = mpsc::channel::<XNetworkCommands>(8);
+    let swallowing_loop = tokio::spawn(async move {
+        let mut swallowed = Vec::new();
+        while let Some(command) = command_rx.recv().await {
+            swallowed.push(command);
+        }
+    });
+
+    let commander = Commander::new(command_tx, node.stopper.clone())
+        .with_default_timeout(Duration::from_millis(300));
+    let started = Instant::now();
+    let error = timeout(Duration::from_secs(5), commander.echo("lost".to_string()))
+        .await
+        .expect("❌ Вызов завис вместо таймаута")
+        .expect_err("❌ Ответа не было, вызов должен завершиться ошибкой");
+    assert_eq!(
+        error.downcast_ref::<CommandError>(),
+        Some(&CommandError::ResponseTimeout),
+        "❌ Ожидалась ошибка ResponseTimeout: {}",
+        error
+    );
+    assert!(started.elapsed() >= Duration::from_millis(300), "❌ Таймаут сработал раньше срока");
+
+    swallowing_loop.abort();
+}
+
+/// Таймаут не мешает командам, на которые узел отвечает
+#[tokio::test]
+async fn test_default_timeout_passes_answered_commands() {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let commander = node.commander.clone().with_default_timeout(Duration::from_secs(5));
+    let echo = commander.echo("ping".to_string()).await.expect("❌ Эхо не выполнено");
+    assert_eq!(echo, "ping", "❌ Неверный ответ эхо");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
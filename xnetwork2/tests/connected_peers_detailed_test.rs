@@ -0,0 +1,55 @@
+//! Тест Commander::connected_peers_detailed
+
+use std::time::{Duration, Instant};
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+/// Каждое соединение с пиром возвращается с полной ConnectionInfo
+#[tokio::test]
+async fn test_connected_peers_detailed() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    let mut node_b = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел B");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let before_dial = Instant::now();
+    let first = dial_and_wait_connection(&mut node_b, peer_a, addr_a.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить первое соединение");
+
+    let connections = node_b
+        .commander
+        .connected_peers_detailed()
+        .await
+        .expect("❌ Не удалось получить соединения");
+    assert_eq!(connections.len(), 1, "❌ Ожидалось одно соединение: {:?}", connections);
+    let info = &connections[0];
+    assert_eq!(info.peer_id, peer_a, "❌ Неверный PeerId соединения");
+    assert_eq!(info.connection_id, first, "❌ Неверный ConnectionId");
+    assert!(info.endpoint.is_dialer(), "❌ Узел B должен быть инициатором соединения");
+    assert!(
+        info.established_at >= before_dial && info.established_at <= Instant::now(),
+        "❌ established_at должен соответствовать моменту соединения"
+    );
+
+    // Второе соединение к тому же пиру тоже попадает в список
+    let second = dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить второе соединение");
+    let connections = node_b
+        .commander
+        .connected_peers_detailed()
+        .await
+        .expect("❌ Не удалось получить соединения");
+    let ids: Vec<_> = connections.iter().map(|c| c.connection_id).collect();
+    assert_eq!(ids, vec![first, second], "❌ Ожидались оба соединения, старшее первым");
+    assert!(connections.iter().all(|c| c.peer_id == peer_a));
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
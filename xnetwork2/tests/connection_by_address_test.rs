@@ -0,0 +1,53 @@
+//! Тест поиска соединения в Conntracker по удаленному адресу
+
+use std::time::Duration;
+use libp2p::multiaddr::Protocol;
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+/// Соединение находится по адресу, на который был сделан dial, с суффиксом /p2p и без
+#[tokio::test]
+async fn test_lookup_connection_by_dialed_address() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    let mut node_b = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел B");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let connection_id = dial_and_wait_connection(&mut node_b, peer_a, addr_a.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение");
+
+    let found = node_b
+        .commander
+        .get_connections_by_address(addr_a.clone())
+        .await
+        .expect("❌ Не удалось выполнить поиск по адресу");
+    assert_eq!(found.len(), 1, "❌ По адресу должно находиться ровно одно соединение");
+    assert_eq!(found[0].connection_id, connection_id, "❌ Найдено не то соединение");
+    assert_eq!(found[0].peer_id, peer_a, "❌ Соединение должно вести к узлу A");
+
+    let with_p2p = addr_a.clone().with(Protocol::P2p(peer_a));
+    let found = node_b
+        .commander
+        .get_connections_by_address(with_p2p)
+        .await
+        .expect("❌ Не удалось выполнить поиск по адресу с /p2p");
+    assert_eq!(found.len(), 1, "❌ Суффикс /p2p должен игнорироваться");
+    assert_eq!(found[0].connection_id, connection_id);
+
+    let unknown: libp2p::Multiaddr = "/ip4/127.0.0.1/udp/1/quic-v1".parse().unwrap();
+    let found = node_b
+        .commander
+        .get_connections_by_address(unknown)
+        .await
+        .expect("❌ Не удалось выполнить поиск по неизвестному адресу");
+    assert!(found.is_empty(), "❌ Для неизвестного адреса соединений быть не должно");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
@@ -0,0 +1,74 @@
+//! Тесты ограничений на число соединений
+
+use std::time::Duration;
+
+use libp2p::core::Endpoint;
+use xnetwork2::behaviours::connection_limits::ConnectionLimits;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Создает и запускает узел с указанными ограничениями
+async fn start_node(limits: ConnectionLimits) -> Node {
+    let mut node = NodeBuilder::new()
+        .with_connection_limits(limits)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node
+}
+
+/// Второе соединение с тем же пиром отклоняется при max_established_per_peer = 1
+#[tokio::test]
+async fn test_second_dial_to_same_peer_refused() {
+    let mut node_a = start_node(ConnectionLimits::unlimited()).await;
+    let mut node_b =
+        start_node(ConnectionLimits::unlimited().with_max_established_per_peer(Some(1))).await;
+    let peer_a = *node_a.peer_id();
+
+    let limits = node_b.commander.get_connection_limits().await.expect("❌ Команда не выполнилась");
+    assert_eq!(limits.max_established_per_peer, Some(1));
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Первое соединение должно установиться");
+
+    let mut events_b = node_b.subscribe();
+    node_b
+        .commander
+        .dial(peer_a, addr_a)
+        .await
+        .expect("❌ Команда Dial должна быть принята");
+
+    let event = wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ConnectionLimitReached { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Ожидалось событие ConnectionLimitReached");
+
+    match event {
+        NodeEvent::ConnectionLimitReached { peer_id, endpoint, .. } => {
+            assert_eq!(peer_id, Some(peer_a), "❌ Событие должно относиться к узлу A");
+            assert_eq!(endpoint, Endpoint::Dialer, "❌ Отклонено должно быть исходящее соединение");
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    let connections = node_b
+        .commander
+        .get_peer_connections(peer_a)
+        .await
+        .expect("❌ Не удалось получить соединения");
+    assert_eq!(connections.connections.len(), 1, "❌ С узлом A должно быть одно соединение");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
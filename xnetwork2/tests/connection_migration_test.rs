@@ -0,0 +1,80 @@
+//! Тест пробрасывания события миграции соединения (AddressChange)
+
+use std::time::Duration;
+
+use libp2p::core::transport::PortUse;
+use libp2p::core::{ConnectedPoint, Endpoint};
+use libp2p::swarm::behaviour::AddressChange;
+use libp2p::swarm::{ConnectionId, FromSwarm, NetworkBehaviour, ToSwarm};
+use libp2p::{Multiaddr, PeerId};
+use xnetwork2::behaviours::keep_alive::KeepAliveBehaviour;
+use xnetwork2::behaviours::keep_alive::behaviour::KeepAliveEvent;
+use xnetwork2::conntracker::Conntracker;
+use xnetwork2::node_events::NodeEvent;
+
+/// AddressChange из swarm превращается в событие миграции с прежним и новым адресом
+#[tokio::test]
+async fn test_address_change_emits_migration_event() {
+    let peer_id = PeerId::random();
+    let connection_id = ConnectionId::new_unchecked(42);
+    let old_addr: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+    let new_addr: Multiaddr = "/ip4/127.0.0.1/udp/4002/quic-v1".parse().unwrap();
+
+    let old = ConnectedPoint::Dialer {
+        address: old_addr.clone(),
+        role_override: Endpoint::Dialer,
+        port_use: PortUse::Reuse,
+    };
+    let new = ConnectedPoint::Dialer {
+        address: new_addr.clone(),
+        role_override: Endpoint::Dialer,
+        port_use: PortUse::Reuse,
+    };
+
+    let mut conntracker = Conntracker::new(PeerId::random());
+    conntracker.add_connection(connection_id, peer_id, old.clone());
+
+    let mut behaviour = KeepAliveBehaviour::new();
+    behaviour.on_swarm_event(FromSwarm::AddressChange(AddressChange {
+        peer_id,
+        connection_id,
+        old: &old,
+        new: &new,
+    }));
+
+    let event = tokio::time::timeout(
+        Duration::from_secs(1),
+        std::future::poll_fn(|cx| behaviour.poll(cx)),
+    )
+    .await
+    .expect("❌ Поведение должно сразу выдать событие AddressChanged");
+
+    let ToSwarm::GenerateEvent(KeepAliveEvent::AddressChanged {
+        peer_id: event_peer,
+        connection_id: event_connection,
+        new: event_new,
+        ..
+    }) = event
+    else {
+        panic!("❌ Ожидалось событие AddressChanged, получено: {:?}", event);
+    };
+    assert_eq!(event_peer, peer_id, "❌ Неверный peer_id в событии");
+    assert_eq!(event_connection, connection_id, "❌ Неверный connection_id в событии");
+
+    // Так SwarmHandler строит NodeEvent::ConnectionMigrated
+    let (migrated_old, migrated_new) = conntracker
+        .update_connection_address(&event_peer, &event_connection, &event_new)
+        .expect("❌ Conntracker должен знать мигрировавшее соединение");
+    assert_eq!(migrated_old, old_addr, "❌ Старый адрес должен браться из conntracker");
+    assert_eq!(migrated_new, new_addr, "❌ Новый адрес должен совпадать с AddressChange");
+
+    assert!(NodeEvent::ConnectionMigrated {
+        peer_id,
+        old_addr: old_addr.clone(),
+        new_addr: new_addr.clone(),
+    }
+    .is_network_event());
+
+    let info = conntracker.get_connection(&connection_id).unwrap();
+    assert_eq!(info.remote_addr, new_addr, "❌ Conntracker должен хранить новый адрес");
+}
@@ -0,0 +1,147 @@
+//! Тест учета трафика соединений в Conntracker по счетчикам XStream
+
+use std::time::Duration;
+use tokio::time::timeout;
+use xnetwork2::Node;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+/// Известный объем данных в обе стороны отражается в статистике соединений
+#[tokio::test]
+async fn test_connection_stats_track_xstream_bytes() {
+    const REQUEST_LEN: usize = 4096;
+    const RESPONSE_LEN: usize = 1024;
+
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    // Сервер читает запрос до EOF и отвечает фиксированным объемом данных
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    let data = stream.read_to_end().await.expect("❌ Сервер не смог прочитать запрос");
+                    stream.write_all(vec![0xAB; RESPONSE_LEN]).await.expect("❌ Сервер не смог ответить");
+                    stream.write_eof().await.expect("❌ Сервер не смог закрыть запись");
+                    return data.len();
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    let server_peer = *server.peer_id();
+
+    let stream = client.commander.open_xstream(server_peer).await.expect("❌ Не удалось открыть XStream");
+    stream.write_all(vec![0xCD; REQUEST_LEN]).await.expect("❌ Не удалось отправить запрос");
+    stream.write_eof().await.expect("❌ Не удалось закрыть запись");
+
+    let response = timeout(Duration::from_secs(5), stream.read_to_end())
+        .await
+        .expect("❌ Таймаут чтения ответа")
+        .expect("❌ Не удалось прочитать ответ");
+    assert_eq!(response.len(), RESPONSE_LEN, "❌ Ответ неполный");
+
+    let received = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не завершил обмен вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    assert_eq!(received, REQUEST_LEN, "❌ Сервер получил неполный запрос");
+
+    // Счетчики считают полезную нагрузку, поэтому итоги совпадают точно
+    let stats = client.commander.get_connection_stats().await.expect("❌ Не удалось получить статистику");
+    assert_eq!(stats.total_bytes_out, REQUEST_LEN as u64, "❌ Неверный исходящий трафик");
+    assert_eq!(stats.total_bytes_in, RESPONSE_LEN as u64, "❌ Неверный входящий трафик");
+
+    let peer_connections = client
+        .commander
+        .get_peer_connections(server_peer)
+        .await
+        .expect("❌ Conntracker должен знать сервер");
+    let per_connection_out: u64 = peer_connections.connections.values().map(|c| c.bytes_out).sum();
+    let per_connection_in: u64 = peer_connections.connections.values().map(|c| c.bytes_in).sum();
+    assert_eq!(per_connection_out, REQUEST_LEN as u64, "❌ Сумма по соединениям должна совпадать с итогом");
+    assert_eq!(per_connection_in, RESPONSE_LEN as u64, "❌ Сумма по соединениям должна совпадать с итогом");
+    for (connection_id, traffic) in &stats.connection_traffic {
+        let info = &peer_connections.connections[connection_id];
+        assert_eq!((traffic.bytes_in, traffic.bytes_out), (info.bytes_in, info.bytes_out));
+    }
+
+    let server_stats = server.commander.get_connection_stats().await.expect("❌ Не удалось получить статистику сервера");
+    assert_eq!(server_stats.total_bytes_in, REQUEST_LEN as u64, "❌ Неверный входящий трафик сервера");
+    assert_eq!(server_stats.total_bytes_out, RESPONSE_LEN as u64, "❌ Неверный исходящий трафик сервера");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Байты закрытых потоков сохраняются в итогах соединения
+#[tokio::test]
+async fn test_connection_stats_keep_bytes_of_closed_streams() {
+    const STREAMS: usize = 20;
+    const MESSAGE_LEN: usize = 256;
+
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        let mut served = 0;
+        while served < STREAMS {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { mut stream }) => {
+                    stream.read_to_end().await.expect("❌ Сервер не смог прочитать запрос");
+                    let _ = stream.close().await;
+                    served += 1;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    let server_peer = *server.peer_id();
+
+    // Много коротких потоков по одному соединению, каждый закрывается после записи
+    for _ in 0..STREAMS {
+        let mut stream = client.commander.open_xstream(server_peer).await.expect("❌ Не удалось открыть XStream");
+        stream.write_all(vec![0x11; MESSAGE_LEN]).await.expect("❌ Не удалось отправить данные");
+        stream.write_eof().await.expect("❌ Не удалось закрыть запись");
+        let _ = stream.close().await;
+    }
+    timeout(Duration::from_secs(10), server_task)
+        .await
+        .expect("❌ Сервер не обработал все потоки вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let stats = client.commander.get_connection_stats().await.expect("❌ Не удалось получить статистику");
+    assert_eq!(stats.total_bytes_out, (STREAMS * MESSAGE_LEN) as u64, "❌ Байты закрытых потоков потеряны");
+    let server_stats = server.commander.get_connection_stats().await.expect("❌ Не удалось получить статистику сервера");
+    assert_eq!(server_stats.total_bytes_in, (STREAMS * MESSAGE_LEN) as u64, "❌ Байты закрытых потоков потеряны");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
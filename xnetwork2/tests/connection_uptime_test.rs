@@ -0,0 +1,56 @@
+//! Тест времени жизни соединений и запроса самых долгоживущих соединений
+
+use std::time::Duration;
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+/// Соединения, открытые с задержкой, возвращаются в порядке убывания времени жизни
+#[tokio::test]
+async fn test_longest_lived_connections_ordering() {
+    let mut server = NodeBuilder::new().build().await.expect("❌ Не удалось создать сервер");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+
+    let mut clients = Vec::new();
+    for i in 0..3 {
+        let mut client = NodeBuilder::new()
+            .build()
+            .await
+            .unwrap_or_else(|_| panic!("❌ Не удалось создать клиента {}", i));
+        client.start().await.expect("❌ Не удалось запустить клиента");
+        dial_and_wait_connection(&mut client, server_peer, server_addr.clone(), Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось подключиться к серверу");
+        clients.push(client);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    let client_peers: Vec<_> = clients.iter().map(|c| *c.peer_id()).collect();
+
+    // Все соединения видны с ненулевым временем жизни
+    let connections = server.commander.get_connections().await.expect("❌ Не удалось получить соединения");
+    assert_eq!(connections.len(), 3, "❌ Сервер должен видеть три соединения");
+    for conn in &connections {
+        assert!(conn.duration() > Duration::ZERO, "❌ Время жизни соединения должно быть больше нуля");
+    }
+
+    let longest = server
+        .commander
+        .get_longest_lived_connections(2)
+        .await
+        .expect("❌ Не удалось получить долгоживущие соединения");
+    assert_eq!(longest.len(), 2, "❌ Должно вернуться ровно два соединения");
+    assert_eq!(longest[0].peer_id, client_peers[0], "❌ Первым должно быть самое старое соединение");
+    assert_eq!(longest[1].peer_id, client_peers[1], "❌ Вторым должно быть следующее по возрасту соединение");
+    assert!(
+        longest[0].duration() >= longest[1].duration() + Duration::from_millis(100),
+        "❌ Порядок должен соответствовать времени установления"
+    );
+
+    for mut client in clients {
+        client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    }
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,32 @@
+//! Тест детерминированных ключей из utils::make_key_from_seed
+
+use xnetwork2::NodeBuilder;
+
+mod utils;
+use utils::make_key_from_seed;
+
+/// Один seed дает один PeerId, разные seed дают разные PeerId
+#[test]
+fn test_make_key_from_seed_is_deterministic() {
+    let first = make_key_from_seed(42).public().to_peer_id();
+    let second = make_key_from_seed(42).public().to_peer_id();
+    assert_eq!(first, second, "❌ Один seed должен давать один PeerId");
+
+    let other = make_key_from_seed(43).public().to_peer_id();
+    assert_ne!(first, other, "❌ Разные seed должны давать разные PeerId");
+
+    let zero = make_key_from_seed(0).public().to_peer_id();
+    assert_ne!(zero, first, "❌ Нулевой seed должен давать отдельный PeerId");
+}
+
+/// Узел, собранный с ключом из seed, получает ожидаемый PeerId
+#[tokio::test]
+async fn test_node_with_seeded_key_has_stable_peer_id() {
+    let expected = make_key_from_seed(7).public().to_peer_id();
+    let node = NodeBuilder::new()
+        .with_keypair(make_key_from_seed(7))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    assert_eq!(*node.peer_id(), expected, "❌ PeerId узла не совпадает с PeerId ключа");
+}
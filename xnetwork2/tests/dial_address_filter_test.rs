@@ -0,0 +1,73 @@
+//! Тест фильтра адресов перед dial
+
+use libp2p::{Multiaddr, PeerId};
+use xnetwork2::behaviours::peer_filter::deny_private_ranges;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::DialError;
+
+/// Dial на адрес из 10.0.0.0/8 отклоняется фильтром частных диапазонов
+#[tokio::test]
+async fn test_dial_to_private_range_is_blocked() {
+    let mut node = NodeBuilder::new()
+        .with_dial_address_filter(deny_private_ranges())
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let blocked: Multiaddr = "/ip4/10.1.2.3/udp/4001/quic-v1".parse().unwrap();
+    let error = node
+        .commander
+        .dial(PeerId::random(), blocked.clone())
+        .await
+        .expect_err("❌ Dial на частный адрес должен быть отклонен");
+    assert_eq!(
+        error.downcast_ref::<DialError>(),
+        Some(&DialError::AddressBlocked(blocked)),
+        "❌ Ожидалась ошибка AddressBlocked: {}",
+        error
+    );
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Фильтр проверяет адрес после переписывания, то есть тот, на который идет dial
+#[tokio::test]
+async fn test_filter_checks_rewritten_address() {
+    let private: Multiaddr = "/ip4/10.1.2.3/udp/4001/quic-v1".parse().unwrap();
+    let rewritten = private.clone();
+    let mut node = NodeBuilder::new()
+        .with_dial_address_filter(deny_private_ranges())
+        .with_address_rewriter(move |_| rewritten.clone())
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let public: Multiaddr = "/ip4/8.8.8.8/udp/4001/quic-v1".parse().unwrap();
+    let error = node
+        .commander
+        .dial(PeerId::random(), public)
+        .await
+        .expect_err("❌ Переписанный на частный адрес dial должен быть отклонен");
+    assert_eq!(
+        error.downcast_ref::<DialError>(),
+        Some(&DialError::AddressBlocked(private)),
+        "❌ Ожидалась ошибка AddressBlocked для переписанного адреса: {}",
+        error
+    );
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Встроенный фильтр пропускает публичные адреса и адреса без IP
+#[test]
+fn test_deny_private_ranges_allows_public_addresses() {
+    let filter = deny_private_ranges();
+    for addr in ["/ip4/10.0.0.1/tcp/1", "/ip4/192.168.1.1/tcp/1", "/ip4/127.0.0.1/tcp/1", "/ip4/100.64.0.1/tcp/1", "/ip4/100.127.255.254/tcp/1", "/ip6/fd00::1/tcp/1"] {
+        assert!(!filter(&addr.parse().unwrap()), "❌ Частный адрес {} не заблокирован", addr);
+    }
+    for addr in ["/ip4/8.8.8.8/tcp/1", "/ip4/100.128.0.1/tcp/1", "/ip6/2001:4860::8888/tcp/1", "/dns4/example.com/tcp/1", "/memory/1"] {
+        assert!(filter(&addr.parse().unwrap()), "❌ Публичный адрес {} заблокирован", addr);
+    }
+}
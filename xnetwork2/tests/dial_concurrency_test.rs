@@ -0,0 +1,105 @@
+//! Тест ограничения числа одновременных dial через NodeBuilder::with_max_concurrent_dials
+
+use std::time::Duration;
+use tokio::time::timeout;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::setup_listening_node;
+
+const MAX_DIALS: usize = 5;
+const TOTAL_DIALS: usize = 50;
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// 50 dial с лимитом 5: в полете никогда не больше 5, лишние ждут и завершаются позже
+#[tokio::test]
+async fn test_dials_limited_to_max_concurrent() {
+    let mut server = start_node(NodeBuilder::new()).await;
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+
+    let mut client = start_node(NodeBuilder::new().with_max_concurrent_dials(MAX_DIALS)).await;
+    let mut events = client.subscribe();
+
+    let mut dial_tasks = Vec::new();
+    for _ in 0..TOTAL_DIALS {
+        let commander = client.commander.clone();
+        let addr = server_addr.clone();
+        dial_tasks.push(tokio::spawn(async move { commander.dial(server_peer, addr).await }));
+    }
+
+    let mut resolved = 0;
+    let mut max_in_flight = 0;
+    timeout(Duration::from_secs(30), async {
+        while resolved < TOTAL_DIALS {
+            let stats = client.commander.dial_stats().await.expect("❌ Не удалось получить статистику");
+            assert!(
+                stats.in_flight <= MAX_DIALS,
+                "❌ В полете {} dial при лимите {}",
+                stats.in_flight,
+                MAX_DIALS
+            );
+            max_in_flight = max_in_flight.max(stats.in_flight);
+
+            while let Ok(event) = events.try_recv() {
+                if matches!(event, NodeEvent::ConnectionEstablished { .. } | NodeEvent::DialFailed { .. }) {
+                    resolved += 1;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+    })
+    .await
+    .expect("❌ Не все dial завершились");
+
+    for task in dial_tasks {
+        task.await.unwrap().expect("❌ Dial из очереди завершился ошибкой");
+    }
+    assert!(max_in_flight >= 1, "❌ Не наблюдалось ни одного dial в полете");
+
+    let stats = client.commander.dial_stats().await.unwrap();
+    assert_eq!(stats.in_flight, 0);
+    assert_eq!(stats.queued, 0);
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Таймаут dial_and_wait в очереди отсчитывается с момента получения команды
+#[tokio::test]
+async fn test_queued_dial_and_wait_times_out_from_request() {
+    let mut client = start_node(NodeBuilder::new().with_max_concurrent_dials(1)).await;
+
+    // Единственный слот занят dial на адрес, который не отвечает
+    let blackhole: libp2p::Multiaddr = "/ip4/192.0.2.1/udp/4001/quic-v1".parse().unwrap();
+    client
+        .commander
+        .dial(libp2p::PeerId::random(), blackhole.clone())
+        .await
+        .expect("❌ Dial не запущен");
+
+    let dial_timeout = Duration::from_millis(500);
+    let started = std::time::Instant::now();
+    let result = timeout(
+        Duration::from_secs(5),
+        client.commander.dial_and_wait(libp2p::PeerId::random(), blackhole, dial_timeout),
+    )
+    .await
+    .expect("❌ dial_and_wait из очереди не завершился");
+    assert!(result.is_err(), "❌ Dial на неотвечающий адрес должен завершиться ошибкой");
+    assert!(
+        started.elapsed() < dial_timeout + Duration::from_secs(1),
+        "❌ Таймаут отсчитан не с момента запроса: {:?}",
+        started.elapsed()
+    );
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+}
@@ -0,0 +1,41 @@
+//! Тест события DialFailed при неудачном исходящем соединении
+
+use std::time::Duration;
+
+use libp2p::PeerId;
+use xnetwork2::node::Node;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::wait_for_event;
+
+/// Dial на недостижимый адрес порождает DialFailed с непустой ошибкой
+#[tokio::test]
+async fn test_dial_unreachable_address_emits_dial_failed() {
+    let mut node = Node::new().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    let mut events = node.subscribe();
+
+    // На порту 1 никто не слушает
+    let target = PeerId::random();
+    let addr: libp2p::Multiaddr = "/ip4/127.0.0.1/tcp/1".parse().unwrap();
+    let _ = node.commander.dial(target, addr).await;
+
+    let event = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::DialFailed { .. }),
+        Duration::from_secs(15),
+    )
+    .await
+    .expect("❌ Событие DialFailed не получено");
+
+    match event {
+        NodeEvent::DialFailed { peer_id, error, .. } => {
+            assert_eq!(peer_id, Some(target));
+            assert!(!error.is_empty(), "❌ DialFailed должен содержать текст ошибки");
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,58 @@
+//! Тест набора номера с явным условием PeerCondition
+
+use std::time::Duration;
+use libp2p::swarm::dial_opts::PeerCondition;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// PeerCondition::Always открывает второе соединение к подключенному пиру,
+/// PeerCondition::Disconnected отказывает
+#[tokio::test]
+async fn test_dial_with_opts_forces_second_connection() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    let mut node_b = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел B");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let first = dial_and_wait_connection(&mut node_b, peer_a, addr_a.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить первое соединение");
+
+    let refused = node_b
+        .commander
+        .dial_with_opts(peer_a, vec![addr_a.clone()], PeerCondition::Disconnected)
+        .await;
+    assert!(refused.is_err(), "❌ Набор с Disconnected к подключенному пиру должен быть отклонен");
+
+    let mut events_b = node_b.subscribe();
+    node_b
+        .commander
+        .dial_with_opts(peer_a, vec![addr_a], PeerCondition::Always)
+        .await
+        .expect("❌ Набор с Always должен начаться");
+
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ConnectionEstablished { peer_id, connection_id } if *peer_id == peer_a && *connection_id != first),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Второе соединение не установлено");
+
+    let connections = node_b
+        .commander
+        .get_peer_connections(peer_a)
+        .await
+        .expect("❌ Conntracker должен знать узел A")
+        .connection_count();
+    assert_eq!(connections, 2, "❌ Conntracker должен видеть два соединения");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
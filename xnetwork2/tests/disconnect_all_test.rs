@@ -0,0 +1,69 @@
+//! Тест отключения от всех пиров через Commander::disconnect_all
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Без соединений disconnect_all возвращает ноль
+#[tokio::test]
+async fn test_disconnect_all_without_connections() {
+    let mut node = start_node().await;
+
+    let count = node.commander.disconnect_all().await.expect("❌ Команда не выполнилась");
+    assert_eq!(count, 0);
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Отключение от трех пиров порождает три ConnectionClosed
+#[tokio::test]
+async fn test_disconnect_all_three_peers() {
+    let mut hub = start_node().await;
+    let mut peers = Vec::new();
+    for _ in 0..3 {
+        let mut peer = start_node().await;
+        let addr = setup_listening_node(&mut peer).await.expect("❌ Пир не слушает");
+        let peer_id = *peer.peer_id();
+        dial_and_wait_connection(&mut hub, peer_id, addr, Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось подключиться к пиру");
+        peers.push(peer);
+    }
+
+    let mut events = hub.subscribe();
+    let count = hub.commander.disconnect_all().await.expect("❌ Команда не выполнилась");
+    assert_eq!(count, 3, "❌ Должно быть отключено три пира");
+
+    let mut closed = HashSet::new();
+    for _ in 0..3 {
+        let event = wait_for_event(
+            &mut events,
+            |e| matches!(e, NodeEvent::ConnectionClosed { .. }),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("❌ Событие ConnectionClosed не получено");
+        if let NodeEvent::ConnectionClosed { peer_id, .. } = event {
+            closed.insert(peer_id);
+        }
+    }
+    let expected: HashSet<_> = peers.iter().map(|p| *p.peer_id()).collect();
+    assert_eq!(closed, expected);
+
+    for mut peer in peers {
+        peer.force_shutdown().await.expect("❌ Не удалось остановить пира");
+    }
+    hub.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
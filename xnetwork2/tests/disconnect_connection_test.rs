@@ -0,0 +1,92 @@
+//! Тест закрытия отдельного соединения через Commander::disconnect_connection
+
+use std::time::Duration;
+use libp2p::swarm::ConnectionId;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Закрытие одного из двух соединений не затрагивает второе
+#[tokio::test]
+async fn test_disconnect_single_connection() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    let mut node_b = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел B");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    let first = dial_and_wait_connection(&mut node_b, peer_a, addr_a.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить первое соединение");
+    let second = dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить второе соединение");
+    assert_ne!(first, second, "❌ Должно быть два разных соединения");
+
+    let before = node_b
+        .commander
+        .get_peer_connections(peer_a)
+        .await
+        .expect("❌ Conntracker должен знать узел A")
+        .connection_count();
+    assert_eq!(before, 2, "❌ Conntracker должен видеть два соединения");
+
+    let mut events_b = node_b.subscribe();
+    node_b
+        .commander
+        .disconnect_connection(first)
+        .await
+        .expect("❌ Не удалось закрыть соединение");
+
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::ConnectionClosed { connection_id, .. } if *connection_id == first),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Должно прийти событие закрытия первого соединения");
+
+    let peer_connections = node_b
+        .commander
+        .get_peer_connections(peer_a)
+        .await
+        .expect("❌ Conntracker должен знать узел A");
+    assert_eq!(
+        peer_connections.connection_count(),
+        before - 1,
+        "❌ Количество соединений должно уменьшиться ровно на одно"
+    );
+    assert!(
+        peer_connections.connections.contains_key(&second),
+        "❌ Второе соединение должно остаться открытым"
+    );
+    assert!(
+        !peer_connections.connections.contains_key(&first),
+        "❌ Первое соединение должно быть закрыто"
+    );
+
+    let state = node_b.commander.get_network_state().await.expect("❌ Не удалось получить состояние сети");
+    assert!(state.connected_peers.contains(&peer_a), "❌ Узел A должен оставаться подключенным");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
+
+/// Неизвестный connection_id возвращает ошибку
+#[tokio::test]
+async fn test_disconnect_unknown_connection_fails() {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let result = node
+        .commander
+        .disconnect_connection(ConnectionId::new_unchecked(usize::MAX))
+        .await;
+    assert!(result.is_err(), "❌ Закрытие неизвестного соединения должно возвращать ошибку");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
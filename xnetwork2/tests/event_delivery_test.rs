@@ -0,0 +1,75 @@
+//! Тест режимов доставки NodeEvent: Lossy теряет события медленного подписчика, Reliable нет
+
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::{EventDelivery, Node};
+
+/// Число событий NewListenAddr, которые узел выпускает до чтения подписчиком
+const LISTENERS: usize = 20;
+
+async fn start_node(delivery: EventDelivery) -> Node {
+    let mut node = NodeBuilder::new()
+        .with_memory_transport()
+        .with_event_delivery(delivery)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Медленный подписчик: события копятся, пока узел открывает слушателей
+async fn open_listeners(node: &Node) {
+    for _ in 0..LISTENERS {
+        node.commander
+            .listen_and_wait("/memory/0".parse().unwrap(), Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось начать прослушивание");
+    }
+}
+
+/// Reliable подписка получает все события, даже если читает их после всплеска
+#[tokio::test]
+async fn test_reliable_delivery_loses_no_events() {
+    let mut node = start_node(EventDelivery::Reliable).await;
+    let mut events = node.subscribe_reliable().expect("❌ Reliable подписка недоступна");
+
+    open_listeners(&node).await;
+
+    let mut listen_addrs = 0;
+    while listen_addrs < LISTENERS {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("❌ События потеряны: таймаут ожидания")
+            .expect("❌ Канал событий закрыт");
+        if matches!(event, NodeEvent::NewListenAddr { .. }) {
+            listen_addrs += 1;
+        }
+        // Намеренно медленный подписчик
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(listen_addrs, LISTENERS, "❌ Получены не все NewListenAddr");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Lossy подписка с маленьким буфером сообщает о пропуске событий
+#[tokio::test]
+async fn test_lossy_delivery_reports_lag() {
+    let mut node = start_node(EventDelivery::Lossy(4)).await;
+    assert!(node.subscribe_reliable().is_err(), "❌ Reliable подписка не должна быть доступна в Lossy");
+    let mut events = node.subscribe();
+
+    open_listeners(&node).await;
+
+    match events.recv().await {
+        Err(RecvError::Lagged(skipped)) => {
+            assert!(skipped > 0, "❌ Должно быть пропущено хотя бы одно событие");
+        }
+        other => panic!("❌ Ожидался Lagged, получено {:?}", other),
+    }
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,98 @@
+//! Тест Commander::find_and_connect: поиск пира через Kademlia и подключение
+
+use std::time::Duration;
+
+use libp2p::PeerId;
+use xnetwork2::bootstrap::{BootstrapConfig, BootstrapServer};
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::setup_listening_node_with_kad;
+
+async fn start_kad_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_kad_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// A находит B через bootstrap узел и подключается без адреса B
+#[tokio::test]
+async fn test_find_and_connect_via_bootstrap() {
+    let bootstrap = BootstrapServer::start(
+        BootstrapConfig::default().with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()),
+    )
+    .await
+    .expect("❌ Не удалось запустить bootstrap сервер");
+
+    let mut node_a = start_kad_node().await;
+    let mut node_b = start_kad_node().await;
+    setup_listening_node_with_kad(&mut node_a).await.expect("❌ Node A не слушает");
+    setup_listening_node_with_kad(&mut node_b).await.expect("❌ Node B не слушает");
+
+    for node in [&node_a, &node_b] {
+        node.commander
+            .dial_and_wait(bootstrap.peer_id(), bootstrap.address().clone(), Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось подключиться к bootstrap узлу");
+        node.commander
+            .bootstrap_to_peer(bootstrap.peer_id(), vec![bootstrap.address().clone()])
+            .await
+            .expect("❌ Kademlia bootstrap не выполнен");
+    }
+
+    let peer_b = *node_b.peer_id();
+    let state = node_a.commander.get_network_state().await.unwrap();
+    assert!(!state.connected_peers.contains(&peer_b), "❌ A не должен быть подключен к B заранее");
+
+    node_a
+        .commander
+        .find_and_connect(peer_b, Duration::from_secs(10))
+        .await
+        .expect("❌ A не смог найти и подключиться к B");
+
+    let state = node_a.commander.get_network_state().await.unwrap();
+    assert!(state.connected_peers.contains(&peer_b), "❌ A не подключен к B");
+
+    // Все найденные адреса идут в один dial, лишних соединений не остается
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let connections = node_a.commander.get_peer_connections(peer_b).await.unwrap();
+    assert_eq!(connections.get_connections().len(), 1, "❌ К B должно быть ровно одно соединение");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить node A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить node B");
+    bootstrap.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
+
+/// Поиск неизвестного пира завершается ошибкой
+#[tokio::test]
+async fn test_find_and_connect_unknown_peer_fails() {
+    let bootstrap = BootstrapServer::start(
+        BootstrapConfig::default().with_listen_addr("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()),
+    )
+    .await
+    .expect("❌ Не удалось запустить bootstrap сервер");
+
+    let mut node = start_kad_node().await;
+    node.commander
+        .dial_and_wait(bootstrap.peer_id(), bootstrap.address().clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к bootstrap узлу");
+    node.commander
+        .bootstrap_to_peer(bootstrap.peer_id(), vec![bootstrap.address().clone()])
+        .await
+        .expect("❌ Kademlia bootstrap не выполнен");
+
+    let result = node
+        .commander
+        .find_and_connect(PeerId::random(), Duration::from_secs(3))
+        .await;
+    assert!(result.is_err(), "❌ Подключение к неизвестному пиру должно завершиться ошибкой");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+    bootstrap.shutdown().await.expect("❌ Не удалось остановить bootstrap сервер");
+}
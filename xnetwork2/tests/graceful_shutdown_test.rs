@@ -0,0 +1,135 @@
+//! Тест корректной остановки узла с открытым XStream
+
+use std::time::Duration;
+use tokio::time::timeout;
+use xnetwork2::Node;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+/// При shutdown пир видит чистое закрытие потока (EOF), а не сброс соединения
+#[tokio::test]
+async fn test_shutdown_closes_open_stream_cleanly() {
+    const PAYLOAD_LEN: usize = 2048;
+
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    // Сервер читает поток до EOF; сброс соединения дал бы ошибку чтения
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    return stream.read_to_end().await;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    let server_peer = *server.peer_id();
+
+    let stream = client.commander.open_xstream(server_peer).await.expect("❌ Не удалось открыть XStream");
+    stream.write_all(vec![0x5A; PAYLOAD_LEN]).await.expect("❌ Не удалось отправить данные");
+
+    let mut client_events = client.subscribe();
+    let report = client
+        .shutdown(Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось корректно остановить клиента");
+    assert!(!report.timed_out, "❌ Остановка уложилась в срок, но помечена как таймаут");
+    assert_eq!(report.streams_closed, 1, "❌ Неверное число закрытых потоков: {:?}", report);
+    assert_eq!(report.peers_disconnected, 1, "❌ Неверное число отключенных пиров: {:?}", report);
+
+    let received = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не дождался закрытия потока")
+        .expect("❌ Задача сервера завершилась с ошибкой")
+        .expect("❌ Поток оборван вместо чистого закрытия");
+    assert_eq!(received.len(), PAYLOAD_LEN, "❌ Сервер получил неполные данные");
+
+    // События жизненного цикла приходят в порядке ShuttingDown -> Shutdown
+    let mut lifecycle = Vec::new();
+    while let Ok(event) = client_events.try_recv() {
+        match event {
+            NodeEvent::ShuttingDown | NodeEvent::Shutdown => lifecycle.push(event.name()),
+            _ => continue,
+        }
+    }
+    assert_eq!(
+        lifecycle,
+        vec![NodeEvent::ShuttingDown.name(), NodeEvent::Shutdown.name()],
+        "❌ Неверная последовательность событий остановки"
+    );
+
+    // Цикл swarm остановлен
+    assert!(!client.is_running(), "❌ Клиент все еще работает после shutdown");
+
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Поток, занятый записью в непрочитываемый поток, не закрывается до дедлайна:
+/// отчет помечает таймаут и учитывает закрытый до него поток
+#[tokio::test]
+async fn test_shutdown_report_flags_timed_out_stream() {
+    // Больше окон управления потоком QUIC: запись блокируется, пока сервер не читает
+    const STUCK_PAYLOAD_LEN: usize = 64 * 1024 * 1024;
+
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    // Сервер принимает потоки и держит их, ничего не читая
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok(event) = server_events.recv().await {
+            match event {
+                NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. } => {
+                    let _ = decision_sender.approve();
+                }
+                NodeEvent::XStreamIncoming { stream } => held.push(stream),
+                _ => continue,
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    let server_peer = *server.peer_id();
+
+    let _idle = client.commander.open_xstream(server_peer).await.expect("❌ Не удалось открыть XStream");
+    let stuck = client.commander.open_xstream(server_peer).await.expect("❌ Не удалось открыть XStream");
+    // Запись держит блокировку записи, поэтому write_eof при остановке не завершится
+    let writer = tokio::spawn(async move { stuck.write_all(vec![0x5A; STUCK_PAYLOAD_LEN]).await });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!writer.is_finished(), "❌ Запись не заблокировалась, сценарий не воспроизведен");
+
+    let report = timeout(Duration::from_secs(10), client.shutdown(Duration::from_secs(1)))
+        .await
+        .expect("❌ shutdown не уложился в дедлайн")
+        .expect("❌ shutdown вернул ошибку");
+    assert!(report.timed_out, "❌ Таймаут остановки скрыт: {:?}", report);
+    assert_eq!(report.streams_closed, 1, "❌ Частичный прогресс не учтен: {:?}", report);
+    assert_eq!(report.peers_disconnected, 0, "❌ Шаг отключения не должен был выполниться: {:?}", report);
+    assert!(!client.is_running(), "❌ Клиент все еще работает после shutdown");
+
+    writer.abort();
+    server_task.abort();
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
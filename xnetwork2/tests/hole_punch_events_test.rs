@@ -0,0 +1,93 @@
+//! Тест событий DCUtR в топологии из трех узлов с relay
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_listening_node, wait_for_event};
+
+async fn start_dcutr_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_dcutr()
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+fn is_hole_punch_event(event: &NodeEvent) -> bool {
+    matches!(
+        event,
+        NodeEvent::HolePunchSucceeded { .. } | NodeEvent::HolePunchFailed { .. }
+    )
+}
+
+/// Попытка hole punching заканчивается событием успеха или ошибки, а не тишиной
+#[tokio::test]
+async fn test_hole_punch_outcome_is_reported() {
+    let mut relay = NodeBuilder::new()
+        .with_relay_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать relay узел");
+    relay.start().await.expect("❌ Не удалось запустить relay узел");
+    let relay_addr = setup_listening_node(&mut relay).await.expect("❌ Relay не слушает");
+    relay
+        .commander
+        .add_external_address(relay_addr.clone())
+        .await
+        .expect("❌ Не удалось добавить внешний адрес relay");
+    let relay_peer = *relay.peer_id();
+
+    // Узел B доступен только через relay
+    let mut node_b = start_dcutr_node().await;
+    setup_listening_node(&mut node_b).await.expect("❌ Узел B не слушает");
+    let reservation = node_b
+        .commander
+        .request_relay_reservation(relay_peer, relay_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Узел B не получил резервацию");
+    let peer_b = *node_b.peer_id();
+
+    let mut node_a = start_dcutr_node().await;
+    setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let mut events_a = node_a.subscribe();
+    let mut events_b = node_b.subscribe();
+
+    node_a
+        .commander
+        .dial_and_wait(peer_b, reservation.relayed_addr.clone(), Duration::from_secs(10))
+        .await
+        .expect("❌ Узел A не подключился к B через relay");
+    node_a
+        .commander
+        .direct_connect(peer_b)
+        .await
+        .expect("❌ Не удалось запросить прямое соединение");
+
+    let timeout = Duration::from_secs(20);
+    let event = tokio::select! {
+        event = wait_for_event(&mut events_a, is_hole_punch_event, timeout) => event,
+        event = wait_for_event(&mut events_b, is_hole_punch_event, timeout) => event,
+    }
+    .expect("❌ Результат hole punching не был сообщен");
+
+    match event {
+        NodeEvent::HolePunchSucceeded { peer_id, .. } => {
+            println!("✅ Hole punching успешен с {}", peer_id);
+        }
+        NodeEvent::HolePunchFailed { peer_id, error } => {
+            println!("⚠️ Hole punching с {} не удался: {}", peer_id, error);
+            assert!(!error.is_empty());
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+    relay.force_shutdown().await.expect("❌ Не удалось остановить relay узел");
+}
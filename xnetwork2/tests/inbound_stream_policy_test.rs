@@ -0,0 +1,130 @@
+//! Тест политики входящих потоков: решение принимается до XStreamIncomingStreamRequest
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use libp2p::PeerId;
+use tokio::time::timeout;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::InboundDecision;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_connection_with_auth, setup_listening_node, wait_for_event};
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Потоки от аутентифицированных пиров принимаются, от остальных отклоняются
+#[tokio::test]
+async fn test_inbound_stream_policy_requires_authentication() {
+    let authenticated: Arc<Mutex<HashSet<PeerId>>> = Arc::new(Mutex::new(HashSet::new()));
+    let policy_peers = authenticated.clone();
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(move |peer_id, _connection_id| {
+                if policy_peers.lock().unwrap().contains(peer_id) {
+                    InboundDecision::Accept
+                } else {
+                    InboundDecision::Reject("Peer is not authenticated".to_string())
+                }
+            }),
+    )
+    .await;
+    let mut trusted = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let mut stranger = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let trusted_peer = *trusted.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    // Приложение сервера не получает запросов: политика решает сама
+    let mut server_events = server.subscribe();
+    let mut auth_events = server.subscribe();
+
+    setup_connection_with_auth(&mut trusted, &mut server, server_addr.clone(), Duration::from_secs(10))
+        .await
+        .expect("❌ Не удалось аутентифицировать доверенного клиента");
+    wait_for_event(
+        &mut auth_events,
+        |e| matches!(e, NodeEvent::PeerMutualAuthSuccess { peer_id, .. } if *peer_id == trusted_peer),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Сервер не аутентифицировал доверенного клиента");
+    authenticated.lock().unwrap().insert(trusted_peer);
+
+    dial_and_wait_connection(&mut stranger, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить неаутентифицированного клиента");
+
+    let accepted = timeout(Duration::from_secs(5), trusted.commander.open_xstream(server_peer))
+        .await
+        .expect("❌ Таймаут открытия потока доверенным клиентом");
+    assert!(accepted.is_ok(), "❌ Поток аутентифицированного пира отклонен: {:?}", accepted.err());
+
+    let rejected = timeout(Duration::from_secs(5), stranger.commander.open_xstream(server_peer))
+        .await
+        .expect("❌ Таймаут открытия потока неаутентифицированным клиентом");
+    assert!(rejected.is_err(), "❌ Поток неаутентифицированного пира принят");
+
+    while let Ok(event) = server_events.try_recv() {
+        assert!(
+            !matches!(event, NodeEvent::XStreamIncomingStreamRequest { .. }),
+            "❌ Решение политики передано приложению"
+        );
+    }
+
+    trusted.force_shutdown().await.expect("❌ Не удалось остановить доверенного клиента");
+    stranger.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Defer сохраняет ручной режим: запрос приходит приложению
+#[tokio::test]
+async fn test_inbound_stream_policy_defer_keeps_manual_decision() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Defer),
+    )
+    .await;
+    let mut client = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                    return;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let stream = timeout(Duration::from_secs(5), client.commander.open_xstream(server_peer))
+        .await
+        .expect("❌ Таймаут открытия потока");
+    assert!(stream.is_ok(), "❌ Поток не открыт после ручного одобрения: {:?}", stream.err());
+    timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Приложение не получило XStreamIncomingStreamRequest")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,86 @@
+//! Тест сохранения записей Kademlia между перезапусками через FileRecordStore
+
+use libp2p::identity::Keypair;
+use libp2p::kad::store::RecordStore;
+use libp2p::kad::{Record, RecordKey};
+use xnetwork2::behaviours::xroutes::FileRecordStore;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+
+async fn start_node_with_store(keypair: &Keypair, dir: &std::path::Path) -> Node {
+    let store = FileRecordStore::open(keypair.public().to_peer_id(), dir)
+        .expect("❌ Не удалось открыть файловое хранилище");
+    let mut node = NodeBuilder::new()
+        .with_keypair(keypair.clone())
+        .with_kad_server()
+        .with_kad_store(store)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Запись переживает пересоздание узла с тем же каталогом хранилища
+#[tokio::test]
+async fn test_kad_record_survives_restart() {
+    let dir = std::env::temp_dir().join(format!("xnetwork2-kad-store-{}", libp2p::PeerId::random()));
+    let keypair = Keypair::generate_ed25519();
+    let key = b"config/version".to_vec();
+    let value = b"42".to_vec();
+
+    let mut node = start_node_with_store(&keypair, &dir).await;
+    node.commander
+        .put_record(key.clone(), value.clone())
+        .await
+        .expect("❌ Не удалось сохранить запись");
+    assert_eq!(
+        node.commander.get_local_record(key.clone()).await.expect("❌ Запрос записи не выполнен"),
+        Some(value.clone()),
+        "❌ Запись должна быть в локальном хранилище"
+    );
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+    drop(node);
+
+    let mut restarted = start_node_with_store(&keypair, &dir).await;
+    assert_eq!(
+        restarted.commander.get_local_record(key).await.expect("❌ Запрос записи не выполнен"),
+        Some(value),
+        "❌ Запись потеряна после перезапуска узла"
+    );
+    restarted.force_shutdown().await.expect("❌ Не удалось остановить узел");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Поврежденные файлы пропускаются при открытии, остальные записи загружаются
+#[test]
+fn test_file_store_skips_corrupt_records() {
+    let dir = std::env::temp_dir().join(format!("xnetwork2-kad-store-{}", libp2p::PeerId::random()));
+    let peer_id = libp2p::PeerId::random();
+
+    let mut store = FileRecordStore::open(peer_id, &dir).expect("❌ Не удалось открыть файловое хранилище");
+    store
+        .put(Record::new(RecordKey::new(&b"good"), b"value".to_vec()))
+        .expect("❌ Не удалось сохранить запись");
+    drop(store);
+
+    // Обрезанный файл и файл с огромной длиной поля
+    std::fs::write(dir.join("0001.record"), [0u8, 0, 0, 5, 1, 2]).unwrap();
+    std::fs::write(dir.join("0002.record"), [0xffu8, 0xff, 0xff, 0xff, 1]).unwrap();
+
+    let store = FileRecordStore::open(peer_id, &dir).expect("❌ Поврежденные файлы не должны мешать открытию");
+    assert_eq!(store.records().count(), 1, "❌ Должна загрузиться только целая запись");
+    assert_eq!(
+        store.get(&RecordKey::new(&b"good")).map(|record| record.value.clone()),
+        Some(b"value".to_vec())
+    );
+    let leftovers = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+        .count();
+    assert_eq!(leftovers, 0, "❌ Временные файлы не должны оставаться после записи");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
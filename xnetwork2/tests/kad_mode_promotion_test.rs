@@ -0,0 +1,113 @@
+//! Тест перевода Kademlia из клиентского режима в серверный во время работы
+
+use std::time::Duration;
+use xnetwork2::behaviours::xroutes::types::KadMode;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node
+}
+
+/// Клиент с подтвержденным внешним адресом становится сервером и попадает в таблицы пиров
+#[tokio::test]
+async fn test_promote_client_to_server() {
+    let mut node_a = start_node(NodeBuilder::new().with_kad_client()).await;
+    let mut node_b = start_node(NodeBuilder::new().with_kad_server()).await;
+    assert_eq!(node_a.get_kad_mode().await.unwrap(), KadMode::Client);
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+    let mut events_a = node_a.subscribe();
+
+    // Без подтвержденного внешнего адреса повышение отклоняется
+    assert!(
+        node_a.set_kad_mode(KadMode::Server).await.is_err(),
+        "❌ Повышение без внешнего адреса должно быть отклонено"
+    );
+    assert_eq!(
+        node_a.get_kad_mode().await.unwrap(),
+        KadMode::Client,
+        "❌ Отклоненное повышение не должно менять режим"
+    );
+
+    node_a
+        .commander
+        .add_external_address(addr_a.clone())
+        .await
+        .expect("❌ Не удалось добавить внешний адрес");
+    node_a
+        .set_kad_mode(KadMode::Server)
+        .await
+        .expect("❌ Повышение с внешним адресом должно пройти");
+    wait_for_event(
+        &mut events_a,
+        |e| {
+            matches!(
+                e,
+                NodeEvent::KadModeChanged { previous: KadMode::Client, mode: KadMode::Server }
+            )
+        },
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Событие KadModeChanged не пришло");
+    assert_eq!(node_a.get_kad_mode().await.unwrap(), KadMode::Server);
+
+    // Серверный узел A принимается пирами в таблицу маршрутизации
+    let mut events_b = node_b.subscribe();
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к узлу A");
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Узел A должен попасть в таблицу маршрутизации узла B");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
+
+/// Принудительное повышение не требует внешнего адреса, повтор режима не шлет событие
+#[tokio::test]
+async fn test_force_promotion_without_external_address() {
+    let mut node = start_node(NodeBuilder::new().with_kad_client()).await;
+    let mut events = node.subscribe();
+
+    node.force_kad_mode(KadMode::Server)
+        .await
+        .expect("❌ Принудительное повышение должно пройти");
+    assert_eq!(node.get_kad_mode().await.unwrap(), KadMode::Server);
+    wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::KadModeChanged { mode: KadMode::Server, .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Событие KadModeChanged не пришло");
+
+    node.force_kad_mode(KadMode::Server).await.unwrap();
+    let repeated = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::KadModeChanged { .. }),
+        Duration::from_millis(300),
+    )
+    .await;
+    assert!(repeated.is_err(), "❌ Режим не изменился, события быть не должно");
+
+    // Понижение до клиента проверки внешнего адреса не требует
+    node.set_kad_mode(KadMode::Client).await.expect("❌ Понижение должно пройти");
+    assert_eq!(node.get_kad_mode().await.unwrap(), KadMode::Client);
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
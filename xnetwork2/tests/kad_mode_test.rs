@@ -41,7 +41,7 @@ async fn test_set_kad_mode_server() {
     node.start().await.unwrap();
 
     // Set mode to server
-    let result = node.set_kad_mode(KadMode::Server).await;
+    let result = node.force_kad_mode(KadMode::Server).await;
     assert!(result.is_ok(), "Failed to set Kademlia mode to server: {:?}", result);
 
     // Verify mode is server
@@ -98,7 +98,7 @@ async fn test_kad_mode_switching() {
     let mode = node.get_kad_mode().await.unwrap();
     assert_eq!(mode, KadMode::Client, "Should be in Client mode");
 
-    let result = node.set_kad_mode(KadMode::Server).await;
+    let result = node.force_kad_mode(KadMode::Server).await;
     assert!(result.is_ok(), "Failed to set Kademlia mode to server: {:?}", result);
 
     let mode = node.get_kad_mode().await.unwrap();
@@ -149,7 +149,7 @@ async fn test_kad_mode_in_status() {
     node.start().await.unwrap();
 
     // Set mode to server
-    let result = node.set_kad_mode(KadMode::Server).await;
+    let result = node.force_kad_mode(KadMode::Server).await;
     assert!(result.is_ok(), "Failed to set Kademlia mode to server");
 
     // Check status includes Kademlia mode
@@ -180,7 +180,7 @@ async fn test_kad_mode_timeout() {
     // Set mode with timeout - should complete quickly
     let result = timeout(
         Duration::from_secs(5),
-        node.set_kad_mode(KadMode::Server)
+        node.force_kad_mode(KadMode::Server)
     ).await;
 
     assert!(result.is_ok(), "Set mode operation timed out");
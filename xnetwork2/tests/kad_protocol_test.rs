@@ -0,0 +1,103 @@
+//! Тесты изоляции DHT через пользовательское имя протокола Kademlia
+
+use std::time::Duration;
+use xnetwork2::behaviours::xroutes::XRoutesConfig;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Создает и запускает узел в серверном режиме Kademlia с указанным протоколом
+async fn start_kad_node(protocol: &'static str) -> Node {
+    let mut node = NodeBuilder::new()
+        .with_kad_server()
+        .with_xroutes_config(move |config| {
+            config
+                .with_kad_protocol(protocol.to_string())
+                .expect("❌ Имя протокола Kademlia должно быть валидным")
+        })
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел с пользовательским протоколом Kademlia");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node
+}
+
+/// Имя протокола без ведущего `/` должно отклоняться
+#[test]
+fn test_kad_protocol_requires_leading_slash() {
+    assert!(
+        XRoutesConfig::new().with_kad_protocol("netcom/kad/1.0.0".to_string()).is_err(),
+        "❌ Протокол без '/' должен быть отклонен"
+    );
+
+    let config = XRoutesConfig::new()
+        .with_kad_protocol("/netcom/kad/1.0.0".to_string())
+        .expect("❌ Валидный протокол должен приниматься");
+    assert_eq!(config.kad_protocol.as_ref(), "/netcom/kad/1.0.0");
+}
+
+/// Узлы с одинаковым протоколом находят друг друга через Kademlia
+#[tokio::test]
+async fn test_kad_matching_protocol_populates_routing_table() {
+    let mut node_a = start_kad_node("/netcom-a/kad/1.0.0").await;
+    let mut node_b = start_kad_node("/netcom-a/kad/1.0.0").await;
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let mut events_b = node_b.subscribe();
+    let peer_a = *node_a.peer_id();
+
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к узлу A");
+
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Узел A должен попасть в таблицу маршрутизации узла B");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
+
+/// Узлы с разными протоколами не заполняют таблицы маршрутизации друг друга
+#[tokio::test]
+async fn test_kad_distinct_protocols_are_isolated() {
+    let mut node_a = start_kad_node("/netcom-a/kad/1.0.0").await;
+    let mut node_c = start_kad_node("/netcom-c/kad/1.0.0").await;
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let mut events_a = node_a.subscribe();
+    let mut events_c = node_c.subscribe();
+    let peer_a = *node_a.peer_id();
+    let peer_c = *node_c.peer_id();
+
+    dial_and_wait_connection(&mut node_c, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Соединение должно устанавливаться независимо от протокола Kademlia");
+
+    let routed_on_c = wait_for_event(
+        &mut events_c,
+        |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id } if *peer_id == peer_a),
+        Duration::from_secs(3),
+    )
+    .await;
+    assert!(routed_on_c.is_err(), "❌ Узел A не должен попасть в таблицу узла C");
+
+    let routed_on_a = wait_for_event(
+        &mut events_a,
+        |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id } if *peer_id == peer_c),
+        Duration::from_secs(1),
+    )
+    .await;
+    assert!(routed_on_a.is_err(), "❌ Узел C не должен попасть в таблицу узла A");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_c.force_shutdown().await.expect("❌ Не удалось остановить узел C");
+}
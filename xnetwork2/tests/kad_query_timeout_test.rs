@@ -0,0 +1,83 @@
+//! Тест таймаута Kademlia запросов на изолированном узле
+
+use std::time::Duration;
+use libp2p::{Multiaddr, PeerId};
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+use xnetwork2::behaviours::xroutes::KadQueryError;
+use xnetwork2::node_builder::NodeBuilder;
+
+/// Поиск без пиров в таблице маршрутизации завершается, а не зависает
+#[tokio::test]
+async fn test_kad_lookup_on_isolated_node_returns() {
+    let query_timeout = Duration::from_secs(2);
+    let mut node = NodeBuilder::new()
+        .with_kad_client()
+        .with_xroutes_config(move |config| config.with_kad_query_timeout(query_timeout))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let target = PeerId::random();
+    let result = timeout(query_timeout * 3, node.commander.find_peer(target))
+        .await
+        .expect("❌ Поиск пира завис дольше таймаута запроса");
+    if let Ok(addresses) = result {
+        assert!(addresses.is_empty(), "❌ Изолированный узел не может найти адреса: {:?}", addresses);
+    }
+
+    // Таймаут команды больше таймаута запроса: ответ определяется Kademlia
+    let result = timeout(
+        query_timeout * 3,
+        node.commander.find_peer_addresses(target, Duration::from_secs(60)),
+    )
+    .await
+    .expect("❌ find_peer_addresses завис дольше таймаута запроса");
+    assert!(result.is_err(), "❌ Изолированный узел не должен найти адреса пира");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Запрос к пиру, который не отвечает, завершается KadQueryError::QueryTimeout
+#[tokio::test]
+async fn test_closest_peers_to_key_query_times_out() {
+    let query_timeout = Duration::from_secs(1);
+    let mut node = NodeBuilder::new()
+        .with_kad_client()
+        .with_xroutes_config(move |config| config.with_kad_query_timeout(query_timeout))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    // Сырой UDP сокет молчит: QUIC handshake с ним длится дольше таймаута запроса
+    let silent = UdpSocket::bind("127.0.0.1:0").await.expect("❌ Не удалось открыть UDP сокет");
+    let port = silent.local_addr().unwrap().port();
+    let silent_task = tokio::spawn(async move {
+        let mut buf = [0u8; 2048];
+        while silent.recv_from(&mut buf).await.is_ok() {}
+    });
+    let silent_peer = PeerId::random();
+    let addr: Multiaddr = format!("/ip4/127.0.0.1/udp/{}/quic-v1", port).parse().unwrap();
+    let imported = node
+        .commander
+        .import_routing_table(vec![(silent_peer, vec![addr])])
+        .await
+        .expect("❌ Не удалось заполнить таблицу маршрутизации");
+    assert_eq!(imported, 1, "❌ Молчащий пир должен попасть в таблицу маршрутизации");
+
+    let error = timeout(query_timeout * 4, node.commander.get_closest_peers_to_key(b"shard-0".to_vec()))
+        .await
+        .expect("❌ Запрос завис дольше таймаута запроса")
+        .expect_err("❌ Запрос к молчащему пиру должен завершиться таймаутом");
+    assert_eq!(
+        error.downcast_ref::<KadQueryError>(),
+        Some(&KadQueryError::QueryTimeout),
+        "❌ Неверная ошибка: {}",
+        error
+    );
+
+    silent_task.abort();
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,82 @@
+//! Тест фильтрации адресов прослушивания по транспорту и диапазону IP
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use xnetwork2::conntracker::{AddrFilter, AddrTransport};
+use xnetwork2::node_builder::NodeBuilder;
+
+fn is_loopback(addr: &Multiaddr) -> bool {
+    addr.iter().any(|protocol| match protocol {
+        Protocol::Ip4(ip) => ip.is_loopback(),
+        Protocol::Ip6(ip) => ip.is_loopback(),
+        _ => false,
+    })
+}
+
+/// Loopback исключается фильтром, без фильтра он присутствует
+#[tokio::test]
+async fn test_listen_addresses_filtered_excludes_loopback() {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    node.commander
+        .listen_and_wait("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap(), Duration::from_secs(5))
+        .await
+        .expect("❌ Узел не слушает loopback");
+    node.commander
+        .listen_and_wait("/ip4/0.0.0.0/udp/0/quic-v1".parse().unwrap(), Duration::from_secs(5))
+        .await
+        .expect("❌ Узел не слушает wildcard");
+
+    let all = node
+        .commander
+        .listen_addresses_filtered(AddrFilter::new())
+        .await
+        .expect("❌ Не удалось получить адреса прослушивания");
+    assert!(all.iter().any(is_loopback), "❌ Без фильтра loopback должен присутствовать: {:?}", all);
+
+    let quic = node
+        .commander
+        .listen_addresses_filtered(AddrFilter::new().transport(AddrTransport::Quic))
+        .await
+        .expect("❌ Не удалось получить QUIC адреса");
+    assert_eq!(quic.len(), all.len(), "❌ Все адреса узла должны быть QUIC");
+
+    let tcp = node
+        .commander
+        .listen_addresses_filtered(AddrFilter::new().transport(AddrTransport::Tcp))
+        .await
+        .expect("❌ Не удалось получить TCP адреса");
+    assert!(tcp.is_empty(), "❌ Узел не слушает TCP: {:?}", tcp);
+
+    let without_loopback = node
+        .commander
+        .listen_addresses_filtered(AddrFilter::new().exclude_loopback())
+        .await
+        .expect("❌ Не удалось получить адреса без loopback");
+    assert!(
+        !without_loopback.iter().any(is_loopback),
+        "❌ Фильтр пропустил loopback: {:?}",
+        without_loopback
+    );
+    assert!(without_loopback.len() < all.len(), "❌ Фильтр ничего не исключил");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// exclude_private оставляет только публичные адреса
+#[test]
+fn test_addr_filter_exclude_private() {
+    let filter = AddrFilter::new().exclude_private();
+    let public: Multiaddr = "/ip4/8.8.8.8/udp/4001/quic-v1".parse().unwrap();
+    let private: Multiaddr = "/ip4/192.168.0.10/udp/4001/quic-v1".parse().unwrap();
+    let loopback: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+
+    assert_eq!(
+        filter.apply(vec![public.clone(), private, loopback]),
+        vec![public],
+        "❌ Должен остаться только публичный адрес"
+    );
+}
@@ -0,0 +1,37 @@
+//! Тест сбора всех адресов слушателя через Commander::listen_and_collect
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use xnetwork2::Node;
+
+/// Wildcard адрес разворачивается в конкретные адреса с суффиксом /p2p
+#[tokio::test]
+async fn test_listen_and_collect_wildcard() {
+    let mut node = Node::new().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    let peer_id = *node.peer_id();
+
+    let addresses = node
+        .commander
+        .listen_and_collect("/ip4/0.0.0.0/udp/0/quic-v1".parse().unwrap(), Duration::from_millis(500))
+        .await
+        .expect("❌ Не удалось начать прослушивание");
+
+    assert!(!addresses.is_empty(), "❌ Должен быть хотя бы один адрес");
+    for address in &addresses {
+        assert!(
+            !address.iter().any(|p| p == Protocol::Ip4(std::net::Ipv4Addr::UNSPECIFIED)),
+            "❌ Адрес не конкретный: {}",
+            address
+        );
+        assert!(
+            !address.iter().any(|p| p == Protocol::Udp(0)),
+            "❌ Порт не назначен: {}",
+            address
+        );
+        assert_eq!(address.iter().last(), Some(Protocol::P2p(peer_id)), "❌ Нет суффикса /p2p: {}", address);
+    }
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
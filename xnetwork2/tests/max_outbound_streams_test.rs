@@ -0,0 +1,238 @@
+//! Тест лимита одновременных исходящих потоков к одному пиру
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::InboundDecision;
+use xstream::xstream_error::StreamOpenError;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+const LIMIT: usize = 3;
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Открытие сверх лимита завершается TooManyStreams, закрытие потока освобождает слот
+#[tokio::test]
+async fn test_outbound_stream_limit_per_peer() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept),
+    )
+    .await;
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_max_outbound_streams_per_peer(LIMIT),
+    )
+    .await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    // Сервер держит входящие потоки открытыми до конца теста
+    let mut server_events = server.subscribe();
+    let holder = tokio::spawn(async move {
+        let mut streams = Vec::new();
+        while let Ok(event) = server_events.recv().await {
+            if let NodeEvent::XStreamIncoming { stream } = event {
+                streams.push(stream);
+            }
+        }
+    });
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let mut streams = Vec::new();
+    for i in 0..LIMIT {
+        let stream = client
+            .commander
+            .open_xstream(server_peer)
+            .await
+            .unwrap_or_else(|e| panic!("❌ Поток {} в пределах лимита не открылся: {}", i, e));
+        streams.push(stream);
+    }
+
+    let error = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect_err("❌ Поток сверх лимита должен быть отклонен");
+    assert_eq!(
+        error.downcast_ref::<StreamOpenError>(),
+        Some(&StreamOpenError::TooManyStreams),
+        "❌ Неверная ошибка для потока сверх лимита"
+    );
+
+    // Закрытие одного потока освобождает слот
+    let mut client_events = client.subscribe();
+    let mut closed = streams.pop().unwrap();
+    let closed_id = closed.id;
+    closed.close().await.expect("❌ Не удалось закрыть поток");
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::XStreamClosed { stream_id, .. } if *stream_id == closed_id),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Событие закрытия потока не пришло");
+
+    let reopened = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect("❌ Поток должен открыться после освобождения слота");
+    assert!(!reopened.is_closed(), "❌ Новый поток не должен быть закрыт");
+    for stream in &streams {
+        assert!(!stream.is_closed(), "❌ Потоки в пределах лимита должны оставаться открытыми");
+    }
+
+    holder.abort();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Поток, отброшенный без close(), освобождает слот после drop последнего клона
+#[tokio::test]
+async fn test_dropped_outbound_stream_releases_slot() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept),
+    )
+    .await;
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_max_outbound_streams_per_peer(1),
+    )
+    .await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let mut server_events = server.subscribe();
+    let holder = tokio::spawn(async move {
+        let mut streams = Vec::new();
+        while let Ok(event) = server_events.recv().await {
+            if let NodeEvent::XStreamIncoming { stream } = event {
+                streams.push(stream);
+            }
+        }
+    });
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let stream = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect("❌ Первый поток должен открыться");
+    let clone = stream.clone();
+    drop(stream);
+
+    // Клон еще держит слот
+    let error = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect_err("❌ Слот занят, пока жив клон потока");
+    assert_eq!(error.downcast_ref::<StreamOpenError>(), Some(&StreamOpenError::TooManyStreams));
+    drop(clone);
+
+    let reopened = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match client.commander.open_xstream(server_peer).await {
+                Ok(stream) => return stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+            }
+        }
+    })
+    .await
+    .expect("❌ Слот не освободился после drop потока");
+    assert!(!reopened.is_closed(), "❌ Новый поток не должен быть закрыт");
+
+    holder.abort();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Закрытие входящего потока с тем же id не освобождает слот исходящего
+#[tokio::test]
+async fn test_inbound_close_keeps_outbound_slot() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept),
+    )
+    .await;
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept)
+            .with_max_outbound_streams_per_peer(1),
+    )
+    .await;
+    let server_peer = *server.peer_id();
+    let client_peer = *client.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let mut server_events = server.subscribe();
+    let holder = tokio::spawn(async move {
+        let mut streams = Vec::new();
+        while let Ok(event) = server_events.recv().await {
+            if let NodeEvent::XStreamIncoming { stream } = event {
+                streams.push(stream);
+            }
+        }
+    });
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let outbound = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect("❌ Исходящий поток должен открыться");
+
+    // Сервер открывает поток к клиенту и закрывает его, у клиента он входящий
+    let mut client_events = client.subscribe();
+    let mut inbound = server
+        .commander
+        .open_xstream(client_peer)
+        .await
+        .expect("❌ Сервер не смог открыть поток к клиенту");
+    let inbound_id = inbound.id;
+    inbound.close().await.expect("❌ Не удалось закрыть поток сервера");
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::XStreamClosed { stream_id, .. } if *stream_id == inbound_id),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Клиент не получил закрытие входящего потока");
+
+    let error = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect_err("❌ Слот исходящего потока должен оставаться занятым");
+    assert_eq!(error.downcast_ref::<StreamOpenError>(), Some(&StreamOpenError::TooManyStreams));
+    assert!(!outbound.is_closed(), "❌ Исходящий поток должен оставаться открытым");
+
+    holder.abort();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
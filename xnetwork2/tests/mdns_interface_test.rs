@@ -0,0 +1,102 @@
+//! Тесты выбора сетевого интерфейса для mDNS
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use libp2p::Multiaddr;
+use xnetwork2::behaviours::xroutes::{XRoutesConfig, is_address_on_interface};
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_listening_node, wait_for_event};
+
+/// Фильтр loopback-интерфейса пропускает только loopback-адреса
+#[test]
+fn test_loopback_interface_filter() {
+    let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let local: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+    let lan: Multiaddr = "/ip4/192.168.1.10/udp/4001/quic-v1".parse().unwrap();
+
+    assert!(is_address_on_interface(&local, loopback), "❌ Loopback-адрес должен проходить фильтр");
+    assert!(!is_address_on_interface(&lan, loopback), "❌ LAN-адрес не должен проходить loopback-фильтр");
+
+    let lan_iface = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+    assert!(is_address_on_interface(&lan, lan_iface), "❌ Адрес из той же сети должен проходить фильтр");
+    assert!(!is_address_on_interface(&local, lan_iface), "❌ Loopback-адрес не должен проходить LAN-фильтр");
+}
+
+/// Без выбранного интерфейса поведение не меняется
+#[test]
+fn test_default_mdns_interface_allows_all() {
+    let config = XRoutesConfig::new();
+    assert!(config.mdns_interface.is_none(), "❌ По умолчанию интерфейс не должен быть выбран");
+
+    let lan: Multiaddr = "/ip4/10.0.0.5/udp/4001/quic-v1".parse().unwrap();
+    assert!(config.mdns_address_allowed(&lan), "❌ Без фильтра должны проходить все адреса");
+
+    let config = config.with_mdns_interface(Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    assert!(!config.mdns_address_allowed(&lan), "❌ С loopback-фильтром LAN-адрес должен отбрасываться");
+}
+
+/// Узел с loopback-фильтром получает через mDNS только loopback-адреса
+#[tokio::test]
+async fn test_mdns_discovery_scoped_to_loopback() {
+    let loopback = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    let mut node1 = NodeBuilder::new()
+        .with_xroutes_config(move |config| config.with_mdns(true).with_mdns_interface(loopback))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел 1");
+    let mut node2 = NodeBuilder::new()
+        .with_xroutes_config(|config| config.with_mdns(true))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел 2");
+    let peer_id2 = *node2.peer_id();
+
+    node1.start().await.expect("❌ Не удалось запустить узел 1");
+    node2.start().await.expect("❌ Не удалось запустить узел 2");
+
+    let mut node1_events = node1.subscribe();
+
+    setup_listening_node(&mut node1).await.expect("❌ Узел 1 не слушает");
+    setup_listening_node(&mut node2).await.expect("❌ Узел 2 не слушает");
+
+    let event = wait_for_event(
+        &mut node1_events,
+        |e| matches!(e, NodeEvent::MdnsPeerDiscovered { peer_id, .. } if *peer_id == peer_id2),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Узел 1 должен обнаружить узел 2 через loopback");
+
+    if let NodeEvent::MdnsPeerDiscovered { addresses, .. } = event {
+        for addr in &addresses {
+            assert!(
+                is_address_on_interface(addr, IpAddr::V4(Ipv4Addr::LOCALHOST)),
+                "❌ Адрес {} вне выбранного интерфейса",
+                addr
+            );
+        }
+    }
+
+    let cached = node1
+        .commander
+        .get_mdns_peers()
+        .await
+        .expect("❌ Не удалось получить mDNS кеш");
+    for (_, addrs) in &cached {
+        for addr in addrs {
+            assert!(
+                is_address_on_interface(addr, IpAddr::V4(Ipv4Addr::LOCALHOST)),
+                "❌ В кеше адрес {} вне выбранного интерфейса",
+                addr
+            );
+        }
+    }
+
+    node1.force_shutdown().await.expect("❌ Не удалось остановить узел 1");
+    node2.force_shutdown().await.expect("❌ Не удалось остановить узел 2");
+}
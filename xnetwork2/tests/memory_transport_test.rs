@@ -0,0 +1,79 @@
+//! Тест узлов поверх MemoryTransport: соединение и эхо без сокетов ОС
+
+use std::time::Duration;
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use tokio::time::timeout;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::Node;
+
+mod utils;
+use utils::setup_connection_with_auth;
+
+async fn start_memory_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_memory_transport()
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Два узла соединяются по /memory/N и обмениваются эхо-запросом
+#[tokio::test]
+async fn test_memory_transport_echo() {
+    let mut server = start_memory_node().await;
+    let mut client = start_memory_node().await;
+
+    let server_addr = server
+        .commander
+        .listen_and_wait("/memory/0".parse().unwrap(), Duration::from_secs(5))
+        .await
+        .expect("❌ Сервер не слушает memory адрес");
+    assert!(
+        matches!(server_addr.iter().next(), Some(Protocol::Memory(port)) if port != 0),
+        "❌ Ожидался /memory/N адрес: {}",
+        server_addr
+    );
+    let server_addr: Multiaddr = server_addr
+        .iter()
+        .filter(|protocol| !matches!(protocol, Protocol::P2p(_)))
+        .collect();
+
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { mut stream }) => {
+                    let request = stream.read_to_end().await.expect("❌ Сервер не смог прочитать запрос");
+                    stream.write_all(request).await.expect("❌ Не удалось записать ответ");
+                    let _ = stream.close().await;
+                    return;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let request = b"ping over memory".to_vec();
+    let response = client
+        .commander
+        .request_response(*server.peer_id(), request.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Запрос не выполнен");
+    assert_eq!(response, request, "❌ Ответ не совпадает с запросом");
+
+    timeout(Duration::from_secs(5), server_task).await.expect("❌ Сервер не завершился").unwrap();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,237 @@
+//! Тест валидатора метаданных PoR аутентификации
+
+use std::collections::HashMap;
+use std::time::Duration;
+use xauth::definitions::{AuthResult, RejectCode};
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, spawn_connection_established_task, wait_for_event};
+
+/// Подключает клиента к серверу и запускает аутентификацию на обеих сторонах
+async fn connect_and_start_auth(client: &mut Node, server: &mut Node, server_addr: libp2p::Multiaddr) {
+    let connection_task = spawn_connection_established_task(server, *client.peer_id(), Duration::from_secs(5));
+    let client_connection = dial_and_wait_connection(client, *server.peer_id(), server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+    let server_connection = connection_task
+        .await
+        .expect("❌ Задача ожидания соединения завершилась с ошибкой (join)")
+        .expect("❌ Сервер не увидел соединение");
+
+    client.commander.start_auth_for_connection(client_connection).await
+        .expect("❌ Не удалось запустить аутентификацию на клиенте");
+    server.commander.start_auth_for_connection(server_connection).await
+        .expect("❌ Не удалось запустить аутентификацию на сервере");
+}
+
+/// Валидатор отклоняет пиров без обязательного ключа метаданных и принимает остальных
+#[tokio::test]
+async fn test_metadata_validator_rejects_missing_key() {
+    let mut server = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_metadata_validator(|_peer_id, metadata| {
+            if metadata.contains_key("role") {
+                AuthResult::Ok(HashMap::new())
+            } else {
+                AuthResult::reject(RejectCode::PolicyDenied, "missing required metadata key: role")
+            }
+        })
+        .build()
+        .await
+        .expect("❌ Не удалось создать сервер");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let mut server_events = server.subscribe();
+
+    // Клиент с обязательным ключом проходит проверку
+    let mut accepted = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_auth_metadata(HashMap::from([("role".to_string(), "worker".to_string())]))
+        .build()
+        .await
+        .expect("❌ Не удалось создать клиента с метаданными");
+    accepted.start().await.expect("❌ Не удалось запустить клиента с метаданными");
+    let mut accepted_events = accepted.subscribe();
+    connect_and_start_auth(&mut accepted, &mut server, server_addr.clone()).await;
+
+    wait_for_event(
+        &mut accepted_events,
+        |e| matches!(e, NodeEvent::PeerOutboundAuthSuccess { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Клиент с обязательным ключом должен пройти проверку автоматически");
+
+    let accepted_peer = *accepted.peer_id();
+    let request = wait_for_event(
+        &mut server_events,
+        |e| matches!(e, NodeEvent::VerifyPorRequest { peer_id, .. } if *peer_id == accepted_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Сервер должен сообщить о запросе верификации");
+    match request {
+        NodeEvent::VerifyPorRequest { metadata_validation, .. } => {
+            assert!(
+                matches!(metadata_validation, Some(AuthResult::Ok(_))),
+                "❌ Результат валидатора должен быть в событии: {:?}",
+                metadata_validation
+            );
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    // Клиент без обязательного ключа отклоняется с кодом PolicyDenied
+    let mut rejected = NodeBuilder::new().with_auto_auth(false).build().await.expect("❌ Не удалось создать клиента без метаданных");
+    rejected.start().await.expect("❌ Не удалось запустить клиента без метаданных");
+    let mut rejected_events = rejected.subscribe();
+    connect_and_start_auth(&mut rejected, &mut server, server_addr).await;
+
+    let event = wait_for_event(
+        &mut rejected_events,
+        |e| matches!(e, NodeEvent::PeerAuthRejected { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Клиент без обязательного ключа должен быть отклонен");
+    match event {
+        NodeEvent::PeerAuthRejected { peer_id, code, .. } => {
+            assert_eq!(peer_id, *server.peer_id(), "❌ Отказ должен прийти от сервера");
+            assert_eq!(code, Some(RejectCode::PolicyDenied), "❌ Неверный код отказа");
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    accepted.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    rejected.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// В ручном режиме результат валидатора только сообщается, решение принимает приложение
+#[tokio::test]
+async fn test_manual_metadata_validation_is_not_submitted() {
+    let mut server = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_metadata_validator(|_peer_id, _metadata| {
+            AuthResult::reject(RejectCode::PolicyDenied, "denied by validator")
+        })
+        .with_manual_metadata_validation()
+        .build()
+        .await
+        .expect("❌ Не удалось создать сервер");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let mut server_events = server.subscribe();
+
+    let mut client = NodeBuilder::new().with_auto_auth(false).build().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    let mut client_events = client.subscribe();
+    connect_and_start_auth(&mut client, &mut server, server_addr).await;
+
+    let client_peer = *client.peer_id();
+    let request = wait_for_event(
+        &mut server_events,
+        |e| matches!(e, NodeEvent::VerifyPorRequest { peer_id, .. } if *peer_id == client_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Сервер должен сообщить о запросе верификации");
+    match request {
+        NodeEvent::VerifyPorRequest { metadata_validation, .. } => {
+            assert!(
+                matches!(metadata_validation, Some(AuthResult::Reject { code: RejectCode::PolicyDenied, .. })),
+                "❌ Результат валидатора должен быть в событии: {:?}",
+                metadata_validation
+            );
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    // Приложение переопределяет решение валидатора
+    server.commander.submit_por_verification(client_peer, true).await
+        .expect("❌ Не удалось отправить решение");
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::PeerOutboundAuthSuccess { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Решение приложения должно применяться в ручном режиме");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Подключает клиента с заданным PoR к серверу с валидатором и возвращает код отказа
+async fn rejection_code_for_por(por: xauth::por::por::ProofOfRepresentation, keypair: libp2p::identity::Keypair) -> Option<RejectCode> {
+    let mut server = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_metadata_validator(|_peer_id, _metadata| AuthResult::Ok(HashMap::new()))
+        .build()
+        .await
+        .expect("❌ Не удалось создать сервер");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let mut client = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_keypair(keypair)
+        .with_por(por)
+        .build()
+        .await
+        .expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    let mut client_events = client.subscribe();
+    connect_and_start_auth(&mut client, &mut server, server_addr).await;
+
+    let event = wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::PeerAuthRejected { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Валидатор не должен принимать недействительный PoR");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+    match event {
+        NodeEvent::PeerAuthRejected { code, .. } => code,
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+}
+
+/// Автоматическая отправка не принимает просроченный PoR, даже если метаданные подходят
+#[tokio::test]
+async fn test_metadata_validator_rejects_expired_por() {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let keypair = libp2p::identity::Keypair::generate_ed25519();
+    let owner = libp2p::identity::Keypair::generate_ed25519();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let por = xauth::por::por::ProofOfRepresentation::create_with_times(
+        &owner,
+        keypair.public().to_peer_id(),
+        now - 7200,
+        now - 3600,
+    )
+    .expect("❌ Не удалось создать PoR");
+
+    let code = rejection_code_for_por(por, keypair).await;
+    assert_eq!(code, Some(RejectCode::Expired), "❌ Просроченный PoR должен отклоняться с кодом Expired");
+}
+
+/// PoR, выданный другому узлу, отклоняется до вызова валидатора
+#[tokio::test]
+async fn test_metadata_validator_rejects_por_of_other_peer() {
+    let keypair = libp2p::identity::Keypair::generate_ed25519();
+    let owner = libp2p::identity::Keypair::generate_ed25519();
+    let other_peer = libp2p::identity::Keypair::generate_ed25519().public().to_peer_id();
+    let por = xauth::por::por::ProofOfRepresentation::create(&owner, other_peer, Duration::from_secs(3600))
+        .expect("❌ Не удалось создать PoR");
+
+    let code = rejection_code_for_por(por, keypair).await;
+    assert_eq!(code, Some(RejectCode::InvalidSignature), "❌ Чужой PoR должен отклоняться с кодом InvalidSignature");
+}
@@ -0,0 +1,59 @@
+//! Тест экспорта счетчиков узла в формате Prometheus
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_auto_auth(false)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Значение метрики без меток из текста экспозиции
+fn metric_value(text: &str, name: &str) -> Option<f64> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+}
+
+/// После подключения двух узлов снимок содержит все метрики и одно соединение
+#[tokio::test]
+async fn test_metrics_snapshot_after_connect() {
+    let mut server = start_node().await;
+    let mut client = start_node().await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    let before = client.metrics_snapshot().await.expect("❌ Не удалось получить метрики");
+    assert_eq!(metric_value(&before, "xnetwork_connections"), Some(0.0), "❌ До подключения соединений нет:\n{}", before);
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let text = client.metrics_snapshot().await.expect("❌ Не удалось получить метрики");
+    for name in [
+        "xnetwork_connections",
+        "xnetwork_connected_peers",
+        "xnetwork_open_streams",
+        "xnetwork_auth_success_total",
+        "xnetwork_auth_failure_total",
+    ] {
+        assert!(text.contains(&format!("# TYPE {} ", name)), "❌ Нет метрики {}:\n{}", name, text);
+    }
+    assert_eq!(metric_value(&text, "xnetwork_connections"), Some(1.0), "❌ Неверное число соединений:\n{}", text);
+    assert_eq!(metric_value(&text, "xnetwork_connected_peers"), Some(1.0), "❌ Неверное число пиров:\n{}", text);
+    assert_eq!(metric_value(&text, "xnetwork_auth_failure_total"), Some(0.0), "❌ Неожиданные ошибки аутентификации");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
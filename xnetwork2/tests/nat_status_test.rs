@@ -0,0 +1,58 @@
+//! Тест определения статуса NAT через AutoNAT
+
+use std::time::Duration;
+
+use xnetwork2::nat::NatStatus;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Клиент AutoNAT переходит из Unknown в определенный статус после проб сервера
+#[tokio::test]
+async fn test_nat_status_transitions_from_unknown() {
+    let mut server = NodeBuilder::new()
+        .with_autonat_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать AutoNAT сервер");
+    server.start().await.expect("❌ Не удалось запустить AutoNAT сервер");
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+
+    let mut client = NodeBuilder::new()
+        .with_autonat_client()
+        .build()
+        .await
+        .expect("❌ Не удалось создать AutoNAT клиента");
+    client.start().await.expect("❌ Не удалось запустить AutoNAT клиента");
+    setup_listening_node(&mut client).await.expect("❌ Клиент не слушает");
+
+    assert_eq!(client.commander.nat_status().await.unwrap(), NatStatus::Unknown);
+
+    let mut events = client.subscribe();
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Клиент не подключился к серверу");
+
+    let event = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::NatStatusChanged { .. }),
+        Duration::from_secs(60),
+    )
+    .await
+    .expect("❌ Статус NAT не изменился");
+
+    match event {
+        NodeEvent::NatStatusChanged { old, new } => {
+            assert_eq!(old, NatStatus::Unknown);
+            assert_ne!(new, NatStatus::Unknown);
+            assert_eq!(client.commander.nat_status().await.unwrap(), new);
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,79 @@
+//! Тест таймаута установки соединения с пиром, не завершающим handshake
+
+use std::time::{Duration, Instant};
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use tokio::net::UdpSocket;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::{DialError, NegotiationTimedOut, NodeEvent};
+
+mod utils;
+use utils::wait_for_event;
+
+/// Сырой UDP сокет принимает пакеты QUIC, но не отвечает: dial обрывается по таймауту
+#[tokio::test]
+async fn test_negotiation_timeout_drops_half_open_dial() {
+    let mut node = NodeBuilder::new()
+        .with_negotiation_timeout(Duration::from_millis(500))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    let mut events = node.subscribe();
+
+    let silent = UdpSocket::bind("127.0.0.1:0").await.expect("❌ Не удалось открыть UDP сокет");
+    let port = silent.local_addr().unwrap().port();
+    let silent_task = tokio::spawn(async move {
+        let mut buf = [0u8; 2048];
+        while silent.recv_from(&mut buf).await.is_ok() {}
+    });
+
+    let target = PeerId::random();
+    let addr: Multiaddr = format!("/ip4/127.0.0.1/udp/{}/quic-v1", port).parse().unwrap();
+    let started = Instant::now();
+    node.commander
+        .dial(target, addr.with(Protocol::P2p(target)))
+        .await
+        .expect("❌ Dial должен начаться");
+
+    let event = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::DialFailed { .. }),
+        Duration::from_secs(4),
+    )
+    .await
+    .expect("❌ DialFailed не получен до штатного таймаута QUIC handshake");
+
+    match event {
+        NodeEvent::DialFailed { kind, error, .. } => {
+            assert_eq!(kind, DialError::NegotiationTimeout, "❌ Неверная причина: {}", error);
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+    assert!(
+        started.elapsed() < Duration::from_secs(3),
+        "❌ Таймаут сработал слишком поздно: {:?}",
+        started.elapsed()
+    );
+
+    silent_task.abort();
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Таймаут распознается по типу ошибки, а не по тексту
+#[test]
+fn test_negotiation_timeout_classified_by_type() {
+    let addr: Multiaddr = "/ip4/127.0.0.1/udp/1/quic-v1".parse().unwrap();
+    let dial_error = |io: std::io::Error| {
+        libp2p::swarm::DialError::Transport(vec![(addr.clone(), libp2p::TransportError::Other(io))])
+    };
+
+    // Обертки транспортов выше таймаута не скрывают его
+    let wrapped = std::io::Error::other(NegotiationTimedOut.into_io_error());
+    assert_eq!(DialError::from_swarm(&dial_error(wrapped)), DialError::NegotiationTimeout);
+
+    // Ошибка с тем же текстом, но другого типа - обычный таймаут
+    let lookalike = std::io::Error::new(std::io::ErrorKind::TimedOut, NegotiationTimedOut.to_string());
+    assert_eq!(DialError::from_swarm(&dial_error(lookalike)), DialError::Timeout);
+}
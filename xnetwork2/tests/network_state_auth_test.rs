@@ -0,0 +1,106 @@
+//! Тест списка аутентифицированных пиров в GetNetworkState
+
+use std::time::Duration;
+
+use xnetwork2::Node;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node, wait_for_event};
+
+/// Аутентифицированный пир есть в состоянии, после отключения его нет
+#[tokio::test]
+async fn test_authenticated_peer_listed_until_disconnect() {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    let server_peer = *server.peer_id();
+
+    let state = client.commander.get_network_state().await.unwrap();
+    assert!(state.authenticated_peers.is_empty(), "❌ До подключения список должен быть пуст");
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    // PoR задачи могут завершиться раньше события взаимной аутентификации
+    let mut events = client.subscribe();
+    let mut authenticated = false;
+    for _ in 0..50 {
+        let state = client.commander.get_network_state().await.unwrap();
+        if state.authenticated_peers.contains(&server_peer) {
+            authenticated = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(authenticated, "❌ Сервер должен быть в списке аутентифицированных пиров");
+
+    client.commander.disconnect(server_peer).await.expect("❌ Не удалось отключиться");
+    wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::ConnectionClosed { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Событие ConnectionClosed не получено");
+
+    let state = client.commander.get_network_state().await.unwrap();
+    assert!(
+        !state.authenticated_peers.contains(&server_peer),
+        "❌ Отключенный пир остался в списке аутентифицированных: {:?}",
+        state.authenticated_peers
+    );
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// is_peer_authenticated true после взаимной аутентификации и false после отключения
+#[tokio::test]
+async fn test_is_peer_authenticated_after_mutual_auth_and_disconnect() {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+    let server_peer = *server.peer_id();
+
+    assert!(!client.commander.is_peer_authenticated(server_peer).await.unwrap());
+
+    let mut events = client.subscribe();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::PeerMutualAuthSuccess { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Событие PeerMutualAuthSuccess не получено");
+
+    assert!(
+        client.commander.is_peer_authenticated(server_peer).await.unwrap(),
+        "❌ Пир должен быть аутентифицирован после MutualAuthSuccess"
+    );
+
+    client.commander.disconnect(server_peer).await.expect("❌ Не удалось отключиться");
+    wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::ConnectionClosed { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Событие ConnectionClosed не получено");
+
+    assert!(
+        !client.commander.is_peer_authenticated(server_peer).await.unwrap(),
+        "❌ Пир не должен считаться аутентифицированным после отключения"
+    );
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,109 @@
+//! Тест подписки на изменения NetworkState через Commander::subscribe_network_state
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::swarm_commands::NetworkStateDelta;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Подключение пира дает ровно одно изменение PeerAdded
+#[tokio::test]
+async fn test_peer_connection_emits_single_peer_added() {
+    let mut server = start_node().await;
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+    let mut client = start_node().await;
+
+    let mut deltas = client
+        .commander
+        .subscribe_network_state()
+        .await
+        .expect("❌ Не удалось подписаться на изменения");
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    // Собираем изменения, пока поток не затихнет
+    let mut received = Vec::new();
+    while let Ok(Some(delta)) = tokio::time::timeout(Duration::from_millis(500), deltas.next()).await {
+        received.push(delta);
+    }
+
+    let peer_added: Vec<_> = received
+        .iter()
+        .filter(|delta| matches!(delta, NetworkStateDelta::PeerAdded { .. }))
+        .collect();
+    assert_eq!(
+        peer_added,
+        vec![&NetworkStateDelta::PeerAdded { peer_id: server_peer }],
+        "❌ Ожидалось одно изменение PeerAdded: {:?}",
+        received
+    );
+
+    // Отключение клиента видно серверу как PeerRemoved
+    let mut server_deltas = server.commander.subscribe_network_state().await.unwrap();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    let mut removed = false;
+    while let Ok(Some(delta)) = tokio::time::timeout(Duration::from_secs(5), server_deltas.next()).await {
+        if matches!(delta, NetworkStateDelta::PeerRemoved { .. }) {
+            removed = true;
+            break;
+        }
+    }
+    assert!(removed, "❌ Сервер не получил PeerRemoved");
+
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Удаление слушателя дает ListenerRemoved для его адреса
+#[tokio::test]
+async fn test_removed_listener_emits_listener_removed() {
+    let mut node = start_node().await;
+    let mut deltas = node
+        .commander
+        .subscribe_network_state()
+        .await
+        .expect("❌ Не удалось подписаться на изменения");
+
+    let listener_id = node
+        .commander
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .await
+        .expect("❌ Не удалось начать слушать");
+    let mut address = None;
+    while let Ok(Some(delta)) = tokio::time::timeout(Duration::from_secs(5), deltas.next()).await {
+        if let NetworkStateDelta::ListenerAdded { listener_id: id, address: added } = delta {
+            if id == listener_id {
+                address = Some(added);
+                break;
+            }
+        }
+    }
+    let address = address.expect("❌ Не получено ListenerAdded");
+
+    assert!(
+        node.commander.remove_listener(listener_id).await.expect("❌ Не удалось удалить слушателя"),
+        "❌ Слушатель должен существовать"
+    );
+    let mut removed = false;
+    while let Ok(Some(delta)) = tokio::time::timeout(Duration::from_secs(5), deltas.next()).await {
+        if delta == (NetworkStateDelta::ListenerRemoved { listener_id, address: address.clone() }) {
+            removed = true;
+            break;
+        }
+    }
+    assert!(removed, "❌ Не получено ListenerRemoved для {}", address);
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,60 @@
+//! Тест агрегированного статуса узла через Commander::status
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Без AutoNAT и Kademlia соответствующие поля статуса отсутствуют
+#[tokio::test]
+async fn test_status_disabled_components() {
+    let mut node = start_node(NodeBuilder::new()).await;
+    let listen_addr = setup_listening_node(&mut node).await.expect("❌ Узел не слушает");
+
+    let status = node.commander.status().await.expect("❌ Команда не выполнилась");
+    assert_eq!(status.peer_id, *node.peer_id());
+    assert!(status.listening_addresses.contains(&listen_addr));
+    assert_eq!(status.connection_count, 0);
+    assert_eq!(status.connected_peer_count, 0);
+    assert_eq!(status.authenticated_peer_count, 0);
+    assert!(status.nat_status.is_none());
+    assert!(status.routing_table_size.is_none());
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Число соединений в статусе совпадает с числом установленных соединений
+#[tokio::test]
+async fn test_status_connection_count() {
+    let mut hub = start_node(NodeBuilder::new().with_kademlia().with_autonat_client()).await;
+    let mut peers = Vec::new();
+    for _ in 0..2 {
+        let mut peer = start_node(NodeBuilder::new()).await;
+        let addr = setup_listening_node(&mut peer).await.expect("❌ Пир не слушает");
+        let peer_id = *peer.peer_id();
+        dial_and_wait_connection(&mut hub, peer_id, addr, Duration::from_secs(5))
+            .await
+            .expect("❌ Не удалось подключиться к пиру");
+        peers.push(peer);
+    }
+
+    let status = hub.commander.status().await.expect("❌ Команда не выполнилась");
+    assert_eq!(status.connection_count, 2, "❌ Ожидалось два соединения");
+    assert_eq!(status.connected_peer_count, 2);
+    assert!(status.nat_status.is_some());
+    assert!(status.routing_table_size.is_some());
+
+    for mut peer in peers {
+        peer.force_shutdown().await.expect("❌ Не удалось остановить пира");
+    }
+    hub.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
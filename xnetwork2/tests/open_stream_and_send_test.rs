@@ -0,0 +1,156 @@
+//! Тест одноразовой отправки через Commander::open_stream_and_send
+
+use std::time::Duration;
+use tokio::time::timeout;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::{Node, StreamStep};
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node, wait_for_event};
+
+async fn connected_pair() -> (Node, Node) {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    (server, client)
+}
+
+/// Эхо-пир получает весь payload и EOF
+#[tokio::test]
+async fn test_open_stream_and_send_to_echo_peer() {
+    let (mut server, mut client) = connected_pair().await;
+
+    let mut server_events = server.subscribe();
+    let echo_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    let data = stream.read_to_end().await.expect("❌ Сервер не смог прочитать поток");
+                    let _ = stream.write_all(data.clone()).await;
+                    return data;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let payload = b"one-shot request".to_vec();
+    client
+        .commander
+        .open_stream_and_send(*server.peer_id(), payload.clone())
+        .await
+        .expect("❌ Отправка не удалась");
+
+    let received = timeout(Duration::from_secs(5), echo_task)
+        .await
+        .expect("❌ Эхо-пир не получил данные вовремя")
+        .unwrap();
+    assert_eq!(received, payload, "❌ Данные искажены");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Поток клиента закрывается, когда пир тоже завершает запись
+#[tokio::test]
+async fn test_open_stream_and_send_closes_stream_after_peer_finishes() {
+    let (mut server, mut client) = connected_pair().await;
+    let server_peer = *server.peer_id();
+
+    let mut server_events = server.subscribe();
+    let echo_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    let data = stream.read_to_end().await.expect("❌ Сервер не смог прочитать поток");
+                    stream.write_all(data).await.expect("❌ Сервер не смог ответить");
+                    stream.write_eof().await.expect("❌ Сервер не смог завершить запись");
+                    return stream;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let mut client_events = client.subscribe();
+    client
+        .commander
+        .open_stream_and_send(server_peer, b"request".to_vec())
+        .await
+        .expect("❌ Отправка не удалась");
+    let _server_stream = timeout(Duration::from_secs(5), echo_task)
+        .await
+        .expect("❌ Эхо-пир не ответил вовремя")
+        .unwrap();
+
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::XStreamClosed { peer_id, .. } if *peer_id == server_peer),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Поток клиента не закрыт после завершения записи обеими сторонами");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Пир отключается посреди записи, ошибка помечена шагом Write
+#[tokio::test]
+async fn test_open_stream_and_send_peer_disconnected_mid_send() {
+    let (mut server, mut client) = connected_pair().await;
+    let server_peer = *server.peer_id();
+
+    // Сервер принимает поток, но не читает, поэтому запись упирается в окно управления потоком
+    let mut server_events = server.subscribe();
+    let accept_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => return stream,
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let commander = client.commander.clone();
+    let send_task = tokio::spawn(async move {
+        commander.open_stream_and_send(server_peer, vec![0xAB; 64 * 1024 * 1024]).await
+    });
+
+    let _stalled_stream = timeout(Duration::from_secs(5), accept_task)
+        .await
+        .expect("❌ Сервер не получил поток")
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!send_task.is_finished(), "❌ Запись не должна завершиться без чтения");
+
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+
+    let error = timeout(Duration::from_secs(10), send_task)
+        .await
+        .expect("❌ Отправка не завершилась после отключения пира")
+        .unwrap()
+        .expect_err("❌ Ожидалась ошибка отправки");
+    assert_eq!(error.step, StreamStep::Write, "❌ Неверный шаг ошибки: {}", error);
+    assert!(error.to_string().starts_with("Stream write failed"));
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+}
@@ -0,0 +1,80 @@
+//! Тест агрегированного события обнаружения пира из нескольких источников
+
+use std::time::Duration;
+use xnetwork2::discovery::DiscoverySource;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Пир, найденный через mDNS и Identify, приходит одним событием с обоими источниками
+#[tokio::test]
+async fn test_peer_discovered_aggregates_mdns_and_identify() {
+    let window = Duration::from_secs(5);
+
+    let mut node1 = NodeBuilder::new()
+        .with_discovery_window(window)
+        .with_xroutes_config(|config| config.with_mdns(true))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел 1");
+    let mut node2 = NodeBuilder::new()
+        .with_xroutes_config(|config| config.with_mdns(true))
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел 2");
+    let peer_id2 = *node2.peer_id();
+
+    node1.start().await.expect("❌ Не удалось запустить узел 1");
+    node2.start().await.expect("❌ Не удалось запустить узел 2");
+
+    let mut node1_events = node1.subscribe();
+    let mut aggregated_events = node1.subscribe();
+
+    setup_listening_node(&mut node1).await.expect("❌ Узел 1 не слушает");
+    let addr2 = setup_listening_node(&mut node2).await.expect("❌ Узел 2 не слушает");
+
+    // Первый источник - mDNS
+    wait_for_event(
+        &mut node1_events,
+        |e| matches!(e, NodeEvent::MdnsPeerDiscovered { peer_id, .. } if *peer_id == peer_id2),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Узел 1 должен обнаружить узел 2 через mDNS");
+
+    // Второй источник - Identify после соединения
+    dial_and_wait_connection(&mut node1, peer_id2, addr2.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к узлу 2");
+
+    let event = wait_for_event(
+        &mut aggregated_events,
+        |e| matches!(e, NodeEvent::PeerDiscovered { peer_id, .. } if *peer_id == peer_id2),
+        window + Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ Должно прийти агрегированное событие PeerDiscovered");
+
+    let NodeEvent::PeerDiscovered { addresses, sources, .. } = event else {
+        unreachable!();
+    };
+    assert!(sources.contains(&DiscoverySource::Mdns), "❌ Среди источников должен быть mDNS: {:?}", sources);
+    assert!(sources.contains(&DiscoverySource::Identify), "❌ Среди источников должен быть Identify: {:?}", sources);
+    assert!(!addresses.is_empty(), "❌ Агрегированное событие должно содержать адреса");
+    let unique: std::collections::HashSet<_> = addresses.iter().collect();
+    assert_eq!(unique.len(), addresses.len(), "❌ Адреса не должны повторяться");
+
+    // Повторного события в пределах окна быть не должно
+    let duplicate = wait_for_event(
+        &mut aggregated_events,
+        |e| matches!(e, NodeEvent::PeerDiscovered { peer_id, .. } if *peer_id == peer_id2),
+        Duration::from_secs(1),
+    )
+    .await;
+    assert!(duplicate.is_err(), "❌ Пир должен быть объявлен ровно одним событием");
+
+    node1.force_shutdown().await.expect("❌ Не удалось остановить узел 1");
+    node2.force_shutdown().await.expect("❌ Не удалось остановить узел 2");
+}
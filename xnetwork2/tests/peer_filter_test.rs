@@ -0,0 +1,121 @@
+//! Тесты фильтра пиров (allowlist/denylist) на уровне соединений
+
+use std::time::Duration;
+
+use libp2p::{Multiaddr, PeerId, identity};
+use xnetwork2::behaviours::peer_filter::{AllowList, DenyList, PeerFilter};
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Создает и запускает узел с указанным ключом и опциональным denylist
+async fn start_node(keypair: identity::Keypair, deny: Option<DenyList>) -> Node {
+    let mut builder = NodeBuilder::new().with_keypair(keypair);
+    if let Some(deny) = deny {
+        builder = builder.with_peer_filter(deny);
+    }
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node
+}
+
+/// Проверка встроенных реализаций AllowList и DenyList
+#[test]
+fn test_allow_list_and_deny_list() {
+    let listed = PeerId::random();
+    let other = PeerId::random();
+    let addr: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+
+    let allow: AllowList = [listed].into_iter().collect();
+    assert!(allow.allow_inbound(&listed, &addr), "❌ Пир из allowlist должен пропускаться");
+    assert!(allow.allow_outbound(&listed), "❌ Пир из allowlist должен пропускаться");
+    assert!(!allow.allow_inbound(&other, &addr), "❌ Пир вне allowlist должен блокироваться");
+    assert!(!allow.allow_outbound(&other), "❌ Пир вне allowlist должен блокироваться");
+
+    let deny: DenyList = [listed].into_iter().collect();
+    assert!(!deny.allow_inbound(&listed, &addr), "❌ Пир из denylist должен блокироваться");
+    assert!(!deny.allow_outbound(&listed), "❌ Пир из denylist должен блокироваться");
+    assert!(deny.allow_inbound(&other, &addr), "❌ Пир вне denylist должен пропускаться");
+    assert!(deny.allow_outbound(&other), "❌ Пир вне denylist должен пропускаться");
+}
+
+/// Входящее соединение от пира из denylist отклоняется до аутентификации
+#[tokio::test]
+async fn test_denylisted_peer_dial_refused_before_auth() {
+    let key_b = identity::Keypair::generate_ed25519();
+    let peer_b = key_b.public().to_peer_id();
+
+    let mut node_a = start_node(
+        identity::Keypair::generate_ed25519(),
+        Some([peer_b].into_iter().collect()),
+    )
+    .await;
+    let mut node_b = start_node(key_b, None).await;
+    let peer_a = *node_a.peer_id();
+
+    assert!(
+        !node_a.commander.is_peer_allowed(peer_b).await.expect("❌ Команда не выполнилась"),
+        "❌ Узел B должен быть заблокирован на узле A"
+    );
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let mut events_a = node_a.subscribe();
+
+    node_b
+        .commander
+        .dial(peer_a, addr_a)
+        .await
+        .expect("❌ Команда Dial должна быть принята");
+
+    let unexpected = wait_for_event(
+        &mut events_a,
+        |e| match e {
+            NodeEvent::ConnectionEstablished { peer_id, .. } => *peer_id == peer_b,
+            NodeEvent::VerifyPorRequest { .. }
+            | NodeEvent::PeerMutualAuthSuccess { .. }
+            | NodeEvent::PeerOutboundAuthSuccess { .. }
+            | NodeEvent::PeerInboundAuthSuccess { .. } => true,
+            _ => false,
+        },
+        Duration::from_secs(3),
+    )
+    .await;
+    assert!(
+        unexpected.is_err(),
+        "❌ Узел A не должен устанавливать соединение или запускать аутентификацию: {:?}",
+        unexpected
+    );
+
+    let state = node_a.commander.get_network_state().await.expect("❌ Не удалось получить состояние сети");
+    assert!(
+        !state.connected_peers.contains(&peer_b),
+        "❌ Узел B не должен быть среди подключенных к узлу A"
+    );
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
+
+/// Пиры вне denylist подключаются как обычно
+#[tokio::test]
+async fn test_not_listed_peer_connects() {
+    let mut node_a = start_node(
+        identity::Keypair::generate_ed25519(),
+        Some([PeerId::random()].into_iter().collect()),
+    )
+    .await;
+    let mut node_b = start_node(identity::Keypair::generate_ed25519(), None).await;
+    let peer_a = *node_a.peer_id();
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Пир вне denylist должен подключаться");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
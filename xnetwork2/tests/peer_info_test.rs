@@ -0,0 +1,55 @@
+//! Тест кэша Identify информации о пирах
+
+use std::time::Duration;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xstream::consts::XSTREAM_PROTOCOL;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// После Identify узел знает протоколы пира, включая XStream
+#[tokio::test]
+async fn test_peer_info_contains_xstream_protocol() {
+    let mut node_a = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел A");
+    let mut node_b = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел B");
+    node_a.start().await.expect("❌ Не удалось запустить узел A");
+    node_b.start().await.expect("❌ Не удалось запустить узел B");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+
+    assert!(
+        node_b.commander.peer_info(peer_a).await.expect("❌ Запрос peer_info не выполнен").is_none(),
+        "❌ До соединения информации о пире быть не должно"
+    );
+
+    let mut events_b = node_b.subscribe();
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение");
+
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::IdentifyReceived { peer_id, .. } if *peer_id == peer_a),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ IdentifyReceived не получен");
+
+    let info = node_b
+        .commander
+        .peer_info(peer_a)
+        .await
+        .expect("❌ Запрос peer_info не выполнен")
+        .expect("❌ Информация Identify о пире отсутствует");
+    assert!(
+        info.protocols.contains(&XSTREAM_PROTOCOL),
+        "❌ Список протоколов пира не содержит XStream: {:?}",
+        info.protocols
+    );
+    assert!(!info.protocol_version.is_empty(), "❌ Пустая версия протокола");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+}
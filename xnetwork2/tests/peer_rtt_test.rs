@@ -0,0 +1,58 @@
+//! Тест учета времени отклика пиров через Commander::peer_rtt
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// После нескольких циклов ping у пира появляется RTT
+#[tokio::test]
+async fn test_peer_rtt_after_ping_cycles() {
+    let mut server = start_node().await;
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+    let mut client = start_node().await;
+
+    assert_eq!(client.commander.peer_rtt(server_peer).await.unwrap(), None);
+
+    let mut events = client.subscribe();
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    for _ in 0..3 {
+        let event = wait_for_event(
+            &mut events,
+            |e| matches!(e, NodeEvent::PingRtt { peer_id, .. } if *peer_id == server_peer),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("❌ Событие PingRtt не получено");
+        if let NodeEvent::PingRtt { rtt, .. } = event {
+            assert!(rtt > Duration::ZERO);
+        }
+    }
+
+    let rtt = client
+        .commander
+        .peer_rtt(server_peer)
+        .await
+        .expect("❌ Команда не выполнилась")
+        .expect("❌ RTT не появился после нескольких ping");
+    assert!(rtt.sample_count >= 3, "❌ Ожидалось не меньше трех измерений");
+    assert!(rtt.last > Duration::ZERO);
+    assert!(rtt.avg > Duration::ZERO);
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,46 @@
+//! Тесты постоянной идентичности узла из seed и protobuf ключа
+
+use libp2p::identity;
+use xnetwork2::node_builder::NodeBuilder;
+
+/// Два узла из одного seed имеют одинаковый PeerId
+#[tokio::test]
+async fn test_same_seed_gives_same_peer_id() {
+    let seed = [7u8; 32];
+    let node_a = NodeBuilder::new()
+        .with_ed25519_seed(seed)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел A");
+    let node_b = NodeBuilder::new()
+        .with_ed25519_seed(seed)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел B");
+
+    assert_eq!(node_a.peer_id(), node_b.peer_id());
+}
+
+/// Узел из protobuf ключа получает PeerId этого ключа
+#[tokio::test]
+async fn test_protobuf_key_round_trip() {
+    let keypair = identity::Keypair::generate_ed25519();
+    let bytes = keypair.to_protobuf_encoding().unwrap();
+
+    let node = NodeBuilder::new()
+        .with_protobuf_key(&bytes)
+        .expect("❌ Корректный ключ должен приниматься")
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+
+    assert_eq!(*node.peer_id(), keypair.public().to_peer_id());
+}
+
+/// Поврежденный protobuf ключ возвращает ошибку
+#[tokio::test]
+async fn test_bad_protobuf_key_errors() {
+    let result = NodeBuilder::new().with_protobuf_key(b"definitely not a key");
+    let error = result.err().expect("❌ Поврежденный ключ должен отклоняться");
+    assert!(error.to_string().contains("Invalid protobuf-encoded key"));
+}
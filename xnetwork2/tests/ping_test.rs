@@ -0,0 +1,82 @@
+//! Тест измерения RTT по запросу через Commander::ping
+
+use std::time::{Duration, Instant};
+
+use libp2p::PeerId;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::PingError;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Ping подключенного пира по loopback возвращает RTT меньше секунды
+#[tokio::test]
+async fn test_ping_connected_peer() {
+    let mut server = start_node().await;
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+    let mut client = start_node().await;
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    let rtt = client
+        .commander
+        .ping(server_peer, Duration::from_secs(5))
+        .await
+        .expect("❌ Ping подключенного пира не удался");
+    assert!(rtt > Duration::ZERO, "❌ RTT должен быть положительным");
+    assert!(rtt < Duration::from_secs(1), "❌ RTT по loopback слишком большой: {:?}", rtt);
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Ping неподключенного пира завершается ошибкой, не дожидаясь таймаута
+#[tokio::test]
+async fn test_ping_disconnected_peer_fails() {
+    let mut node = start_node().await;
+
+    let started = Instant::now();
+    let error = node
+        .commander
+        .ping(PeerId::random(), Duration::from_secs(2))
+        .await
+        .expect_err("❌ Ping неподключенного пира должен завершиться ошибкой");
+    assert_eq!(error, PingError::Disconnected, "❌ Ожидалась ошибка Disconnected: {}", error);
+    assert!(started.elapsed() < Duration::from_secs(2), "❌ Ошибка должна вернуться до таймаута");
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
+
+/// Слишком короткий таймаут возвращает PingError::Timeout
+#[tokio::test]
+async fn test_ping_times_out() {
+    let mut server = start_node().await;
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let server_peer = *server.peer_id();
+    let mut client = start_node().await;
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    // Очередной раунд ping идет раз в секунду, за 1 мс результата не будет
+    let error = client
+        .commander
+        .ping(server_peer, Duration::from_millis(1))
+        .await
+        .expect_err("❌ Ping должен завершиться таймаутом");
+    assert_eq!(error, PingError::Timeout, "❌ Ожидалась ошибка Timeout: {}", error);
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,90 @@
+//! Тест отклонения PoR с типизированной причиной
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use libp2p::identity;
+use xauth::definitions::RejectCode;
+use xauth::por::por::ProofOfRepresentation;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, spawn_connection_established_task, wait_for_event};
+
+/// Просроченный PoR отклоняется с кодом Expired, и код доходит до удалённой стороны
+#[tokio::test]
+async fn test_expired_por_yields_expired_reject_code() {
+    // Нода A представляет владельца по PoR, срок которого истёк час назад
+    let keypair_a = identity::Keypair::generate_ed25519();
+    let peer_a = keypair_a.public().to_peer_id();
+    let owner = identity::Keypair::generate_ed25519();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let expired_por = ProofOfRepresentation::create_with_times(&owner, peer_a, now - 7200, now - 3600)
+        .expect("❌ Не удалось создать PoR");
+
+    let mut node_a = NodeBuilder::new()
+        .with_auto_auth(false)
+        .with_keypair(keypair_a)
+        .with_por(expired_por)
+        .build()
+        .await
+        .expect("❌ Не удалось создать node_a");
+    let mut node_b = NodeBuilder::new().with_auto_auth(false).build().await.expect("❌ Не удалось создать node_b");
+    node_a.start().await.expect("❌ Не удалось запустить node_a");
+    node_b.start().await.expect("❌ Не удалось запустить node_b");
+    let mut events_a = node_a.subscribe();
+    let mut events_b = node_b.subscribe();
+
+    let addr_b = setup_listening_node(&mut node_b).await.expect("❌ node_b не слушает");
+    let connection_task_b = spawn_connection_established_task(&mut node_b, peer_a, Duration::from_secs(5));
+    let connection_a = dial_and_wait_connection(&mut node_a, *node_b.peer_id(), addr_b, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к node_b");
+    let connection_b = connection_task_b
+        .await
+        .expect("❌ Задача ожидания соединения завершилась с ошибкой (join)")
+        .expect("❌ node_b не увидел соединение");
+
+    node_a.commander.start_auth_for_connection(connection_a).await
+        .expect("❌ Не удалось запустить аутентификацию на node_a");
+    node_b.commander.start_auth_for_connection(connection_b).await
+        .expect("❌ Не удалось запустить аутентификацию на node_b");
+
+    // node_b видит просроченный PoR и отклоняет его с кодом из локальной проверки
+    let request = wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::VerifyPorRequest { peer_id, .. } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ node_b не получил VerifyPorRequest");
+    let reject_code = match request {
+        NodeEvent::VerifyPorRequest { reject_code, .. } => reject_code,
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    };
+    assert_eq!(reject_code, Some(RejectCode::Expired), "❌ Просроченный PoR должен классифицироваться как Expired");
+
+    node_b.commander
+        .reject_por_verification(peer_a, RejectCode::Expired, "POR has expired")
+        .await
+        .expect("❌ Не удалось отклонить PoR");
+
+    // node_a получает машиночитаемый код отказа
+    let rejected = wait_for_event(
+        &mut events_a,
+        |e| matches!(e, NodeEvent::PeerAuthRejected { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ node_a не получил PeerAuthRejected");
+    match rejected {
+        NodeEvent::PeerAuthRejected { peer_id, code, reason, .. } => {
+            assert_eq!(peer_id, *node_b.peer_id(), "❌ Отказ должен прийти от node_b");
+            assert_eq!(code, Some(RejectCode::Expired), "❌ Код отказа должен быть Expired");
+            assert_eq!(reason, "POR has expired", "❌ Сообщение отказа должно сохраниться");
+        }
+        other => panic!("❌ Неожиданное событие: {:?}", other),
+    }
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить node_a");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить node_b");
+}
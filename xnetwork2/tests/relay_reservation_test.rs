@@ -0,0 +1,62 @@
+//! Тест резервирования слота на relay сервере через Commander
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::swarm_commands::RELAY_RESERVATION_RENEWAL_INTERVAL;
+
+mod utils;
+use utils::setup_listening_node;
+
+/// Клиент получает резервацию и начинает слушать через relay
+#[tokio::test]
+async fn test_relay_reservation_acquired_and_released() {
+    let mut relay = NodeBuilder::new()
+        .with_relay_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать relay узел");
+    relay.start().await.expect("❌ Не удалось запустить relay узел");
+    let relay_addr = setup_listening_node(&mut relay).await.expect("❌ Relay не слушает");
+    let relay_peer = *relay.peer_id();
+    // Relay должен знать внешний адрес, чтобы выдавать резервации
+    relay
+        .commander
+        .add_external_address(relay_addr.clone())
+        .await
+        .expect("❌ Не удалось добавить внешний адрес relay");
+
+    let mut client = NodeBuilder::new().build().await.expect("❌ Не удалось создать клиента");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let info = client
+        .commander
+        .request_relay_reservation(relay_peer, relay_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Резервация не получена");
+
+    assert_eq!(info.relay_peer_id, relay_peer);
+    assert_eq!(info.renewal_interval, RELAY_RESERVATION_RENEWAL_INTERVAL);
+    assert!(
+        info.relayed_addr.iter().any(|p| p == Protocol::P2pCircuit),
+        "❌ Адрес должен идти через p2p-circuit: {}",
+        info.relayed_addr
+    );
+
+    let state = client.commander.get_network_state().await.expect("❌ Команда не выполнилась");
+    assert!(
+        state.listening_addresses.contains(&info.relayed_addr),
+        "❌ Клиент должен слушать relayed адрес"
+    );
+
+    client
+        .commander
+        .release_relay_reservation(relay_peer)
+        .await
+        .expect("❌ Не удалось освободить резервацию");
+    assert!(client.commander.release_relay_reservation(relay_peer).await.is_err());
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    relay.force_shutdown().await.expect("❌ Не удалось остановить relay узел");
+}
@@ -0,0 +1,88 @@
+//! Тест запроса-ответа через Commander::request_response
+
+use std::time::Duration;
+use tokio::time::timeout;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::{Node, ReqRespError};
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+async fn connected_pair() -> (Node, Node) {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+    (server, client)
+}
+
+/// Сервер читает запрос и отвечает эхом или ошибкой через error_write
+fn spawn_server(server: &Node, reject: bool) -> tokio::task::JoinHandle<()> {
+    let mut server_events = server.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { mut stream }) => {
+                    let request = stream.read_to_end().await.expect("❌ Сервер не смог прочитать запрос");
+                    if reject {
+                        stream.error_write(b"request rejected".to_vec()).await.expect("❌ Не удалось записать ошибку");
+                    } else {
+                        stream.write_all(request).await.expect("❌ Не удалось записать ответ");
+                        let _ = stream.close().await;
+                    }
+                    return;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    })
+}
+
+/// Эхо-пир возвращает ровно те же байты, что были в запросе
+#[tokio::test]
+async fn test_request_response_echo() {
+    let (mut server, mut client) = connected_pair().await;
+    let server_task = spawn_server(&server, false);
+
+    let request = b"ping over xstream".to_vec();
+    let response = client
+        .commander
+        .request_response(*server.peer_id(), request.clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Запрос не выполнен");
+    assert_eq!(response, request, "❌ Ответ не совпадает с запросом");
+
+    timeout(Duration::from_secs(5), server_task).await.expect("❌ Сервер не завершился").unwrap();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Ошибка сервера возвращается как ReqRespError::Remote
+#[tokio::test]
+async fn test_request_response_remote_error() {
+    let (mut server, mut client) = connected_pair().await;
+    let server_task = spawn_server(&server, true);
+
+    let error = client
+        .commander
+        .request_response(*server.peer_id(), b"bad request".to_vec(), Duration::from_secs(5))
+        .await
+        .expect_err("❌ Ожидалась ошибка сервера");
+    match error {
+        ReqRespError::Remote(data) => assert_eq!(data, b"request rejected".to_vec()),
+        other => panic!("❌ Неожиданная ошибка: {}", other),
+    }
+
+    timeout(Duration::from_secs(5), server_task).await.expect("❌ Сервер не завершился").unwrap();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
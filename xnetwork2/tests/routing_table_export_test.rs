@@ -0,0 +1,99 @@
+//! Тест экспорта и импорта таблицы маршрутизации Kademlia
+
+use std::time::Duration;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+/// Создает и запускает узел в серверном режиме Kademlia без mDNS
+async fn start_kad_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_kad_server()
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node
+}
+
+/// Экспорт с одного узла и импорт в новый узел позволяет сразу подключиться к пиру
+#[tokio::test]
+async fn test_export_import_routing_table() {
+    let mut node_a = start_kad_node().await;
+    let mut node_b = start_kad_node().await;
+
+    let status = node_a.get_xroutes_status().await.expect("❌ Не удалось получить статус");
+    assert!(!status.mdns_enabled, "❌ mDNS должен быть выключен в этом тесте");
+
+    let addr_a = setup_listening_node(&mut node_a).await.expect("❌ Узел A не слушает");
+    let peer_a = *node_a.peer_id();
+    let mut events_b = node_b.subscribe();
+
+    dial_and_wait_connection(&mut node_b, peer_a, addr_a, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к узлу A");
+    wait_for_event(
+        &mut events_b,
+        |e| matches!(e, NodeEvent::KademliaRoutingUpdated { peer_id } if *peer_id == peer_a),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ Узел A должен попасть в таблицу маршрутизации узла B");
+
+    // Экспорт с узла B
+    let exported = node_b.export_routing_table().await.expect("❌ Экспорт не удался");
+    let (_, exported_addrs) = exported
+        .iter()
+        .find(|(peer_id, _)| *peer_id == peer_a)
+        .cloned()
+        .expect("❌ Экспорт должен содержать узел A");
+    assert!(!exported_addrs.is_empty(), "❌ У экспортированной записи должны быть адреса");
+    for (_, addrs) in &exported {
+        assert!(!addrs.is_empty(), "❌ Экспорт не должен содержать записи без адресов");
+    }
+
+    // Импорт в новый узел C
+    let mut node_c = start_kad_node().await;
+    let before = node_c.export_routing_table().await.expect("❌ Экспорт не удался");
+    assert!(before.is_empty(), "❌ Таблица нового узла должна быть пустой");
+
+    let imported = node_c
+        .import_routing_table(exported.clone())
+        .await
+        .expect("❌ Импорт не удался");
+    assert!(imported > 0, "❌ Должен быть импортирован хотя бы один адрес");
+
+    let after = node_c.export_routing_table().await.expect("❌ Экспорт не удался");
+    let (_, seeded_addrs) = after
+        .iter()
+        .find(|(peer_id, _)| *peer_id == peer_a)
+        .cloned()
+        .expect("❌ Узел A должен появиться в таблице узла C сразу после импорта");
+
+    // Узел A доступен по адресу из импортированной записи без mDNS
+    dial_and_wait_connection(&mut node_c, peer_a, seeded_addrs[0].clone(), Duration::from_secs(5))
+        .await
+        .expect("❌ Узел C должен подключиться к узлу A по импортированному адресу");
+
+    node_a.force_shutdown().await.expect("❌ Не удалось остановить узел A");
+    node_b.force_shutdown().await.expect("❌ Не удалось остановить узел B");
+    node_c.force_shutdown().await.expect("❌ Не удалось остановить узел C");
+}
+
+/// Экспорт без включенного Kademlia возвращает ошибку
+#[tokio::test]
+async fn test_export_routing_table_requires_kad() {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    assert!(
+        node.export_routing_table().await.is_err(),
+        "❌ Экспорт без Kademlia должен возвращать ошибку"
+    );
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,56 @@
+//! Тест для команды set_external_addresses
+
+use std::time::Duration;
+
+use libp2p::Multiaddr;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::NodeBuilder;
+
+mod utils;
+use utils::wait_for_event;
+
+/// Набор внешних адресов заменяется целиком, лишние адреса удаляются
+#[tokio::test]
+async fn test_set_external_addresses_replaces_set() {
+    let mut node = NodeBuilder::new().build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    let stale: Multiaddr = "/ip4/9.9.9.9/tcp/9999".parse().unwrap();
+    let first: Multiaddr = "/ip4/1.2.3.4/tcp/1234".parse().unwrap();
+    let second: Multiaddr = "/ip4/5.6.7.8/udp/5678/quic-v1".parse().unwrap();
+
+    node.commander
+        .add_external_address(stale.clone())
+        .await
+        .expect("❌ Не удалось добавить внешний адрес");
+
+    let mut events = node.subscribe();
+    node.commander
+        .set_external_addresses(vec![first.clone(), second.clone()])
+        .await
+        .expect("❌ Не удалось установить внешние адреса");
+
+    let mut addresses = node
+        .commander
+        .get_swarm_external_addresses()
+        .await
+        .expect("❌ Не удалось получить внешние адреса");
+    addresses.sort();
+    let mut expected = vec![first.clone(), second.clone()];
+    expected.sort();
+    assert_eq!(addresses, expected, "❌ Набор внешних адресов не заменен");
+
+    let event = wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::ExternalAddressesChanged { .. }),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("❌ ExternalAddressesChanged не получен");
+    if let NodeEvent::ExternalAddressesChanged { addresses } = event {
+        assert!(!addresses.contains(&stale), "❌ Удаленный адрес остался в событии");
+        assert_eq!(addresses.len(), 2, "❌ Неверный набор адресов в событии: {:?}", addresses);
+    }
+
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,100 @@
+//! Тест событий XStreamBackpressure и XStreamDrained в NodeEvent
+
+use std::time::Duration;
+
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::InboundDecision;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node, wait_for_event};
+
+const HIGH_WATER: usize = 1024 * 1024;
+const LOW_WATER: usize = 64 * 1024;
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Непрочитанный поток дает XStreamBackpressure, начало чтения - XStreamDrained
+#[tokio::test]
+async fn test_stream_backpressure_events() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept),
+    )
+    .await;
+    let mut client = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_stream_backpressure(HIGH_WATER, LOW_WATER),
+    )
+    .await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    // Сервер начинает читать входящий поток только по сигналу теста
+    let (start_reading_tx, start_reading_rx) = tokio::sync::oneshot::channel::<()>();
+    let mut server_events = server.subscribe();
+    let reader = tokio::spawn(async move {
+        loop {
+            if let Ok(NodeEvent::XStreamIncoming { stream }) = server_events.recv().await {
+                let _ = start_reading_rx.await;
+                return stream.read_to_end().await.map(|data| data.len()).unwrap_or(0);
+            }
+        }
+    });
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let mut client_events = client.subscribe();
+    let stream = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect("❌ Поток не открылся");
+    let stream_id = stream.id;
+    let payload_len = 16 * HIGH_WATER;
+    let writer = stream.clone();
+    let write_task = tokio::spawn(async move {
+        writer.write_all(vec![7u8; payload_len]).await?;
+        writer.write_eof().await
+    });
+
+    let event = wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::XStreamBackpressure { stream_id: id, .. } if *id == stream_id),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ XStreamBackpressure не пришло при непрочитанном потоке");
+    if let NodeEvent::XStreamBackpressure { peer_id, buffered_bytes, .. } = event {
+        assert_eq!(peer_id, server_peer, "❌ Неверный пир в событии");
+        assert!(buffered_bytes >= HIGH_WATER, "❌ Событие до верхней отметки: {}", buffered_bytes);
+    }
+
+    start_reading_tx.send(()).unwrap();
+    wait_for_event(
+        &mut client_events,
+        |e| matches!(e, NodeEvent::XStreamDrained { stream_id: id, .. } if *id == stream_id),
+        Duration::from_secs(30),
+    )
+    .await
+    .expect("❌ XStreamDrained не пришло после начала чтения");
+
+    write_task.await.unwrap().expect("❌ Запись не удалась");
+    let received = tokio::time::timeout(Duration::from_secs(30), reader)
+        .await
+        .expect("❌ Сервер не дочитал поток")
+        .unwrap();
+    assert_eq!(received, payload_len, "❌ Сервер должен получить все данные");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
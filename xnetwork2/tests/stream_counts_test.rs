@@ -0,0 +1,82 @@
+//! Тест подсчета открытых XStream потоков по пирам
+
+use std::time::Duration;
+
+use libp2p::PeerId;
+use tokio::time::{sleep, timeout, Instant};
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+async fn start_node() -> Node {
+    let mut node = NodeBuilder::new()
+        .with_auto_auth(false)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// События потоков обрабатываются асинхронно, ждем нужного значения счетчика
+async fn wait_for_stream_count(node: &Node, peer_id: PeerId, expected: usize) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let counts = node.commander.stream_counts().await.expect("❌ Не удалось получить счетчики");
+        if counts.get(&peer_id) == Some(&expected) {
+            return;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "❌ Ожидалось {} потоков для {}: {:?}",
+            expected,
+            peer_id,
+            counts
+        );
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Открыты три потока, один закрыт: остается два
+#[tokio::test]
+async fn test_stream_counts_after_close() {
+    let mut server = start_node().await;
+    let mut client = start_node().await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    // Сервер одобряет все входящие потоки
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        while let Ok(event) = server_events.recv().await {
+            if let NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. } = event {
+                let _ = decision_sender.approve();
+            }
+        }
+    });
+
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(10))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let mut streams = Vec::new();
+    for _ in 0..3 {
+        let stream = timeout(Duration::from_secs(5), client.commander.open_xstream(server_peer))
+            .await
+            .expect("❌ Таймаут открытия потока")
+            .expect("❌ Не удалось открыть поток");
+        streams.push(stream);
+    }
+
+    wait_for_stream_count(&client, server_peer, 3).await;
+
+    streams[0].close().await.expect("❌ Не удалось закрыть поток");
+    wait_for_stream_count(&client, server_peer, 2).await;
+
+    server_task.abort();
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,85 @@
+//! Тест ограничения частоты входящих потоков от одного пира
+
+use std::time::{Duration, Instant};
+
+use tokio::time::timeout;
+use xnetwork2::node::Node;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::InboundDecision;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+const BURST: usize = 20;
+const LIMIT: u32 = 5;
+
+async fn start_node(builder: NodeBuilder) -> Node {
+    let mut node = builder.build().await.expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+    node
+}
+
+/// Из 20 одновременно открытых потоков приложению доходят не больше 5 в секунду,
+/// остальные отклоняются с RateLimited
+#[tokio::test]
+async fn test_stream_rate_limit_bounds_burst() {
+    let mut server = start_node(
+        NodeBuilder::new()
+            .with_auto_auth(false)
+            .with_stream_rate_limit(LIMIT)
+            .with_inbound_stream_policy(|_peer_id, _connection_id| InboundDecision::Accept),
+    )
+    .await;
+    let mut client = start_node(NodeBuilder::new().with_auto_auth(false)).await;
+    let server_peer = *server.peer_id();
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+
+    dial_and_wait_connection(&mut client, server_peer, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключить клиента");
+
+    let mut server_events = server.subscribe();
+    let started = Instant::now();
+    for _ in 0..BURST {
+        let commander = client.commander.clone();
+        tokio::spawn(async move {
+            let _ = commander.open_xstream(server_peer).await;
+        });
+    }
+
+    let mut surfaced = 0usize;
+    let mut rejected = 0usize;
+    let _ = timeout(Duration::from_secs(5), async {
+        while surfaced + rejected < BURST {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    surfaced += 1;
+                    drop(stream);
+                }
+                Ok(NodeEvent::XStreamError { error, .. }) if error.contains("RateLimited") => {
+                    rejected += 1;
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    })
+    .await;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    // Емкость bucket плюс пополнение за время теста
+    let bound = LIMIT as usize + (elapsed * LIMIT as f64).ceil() as usize;
+    assert!(surfaced > 0, "❌ Ни один поток не дошел до приложения");
+    assert!(
+        surfaced <= bound,
+        "❌ Доставлено {} потоков за {:.2}с, ожидалось не больше {}",
+        surfaced,
+        elapsed,
+        bound
+    );
+    assert!(rejected > 0, "❌ Лишние потоки не отклонены с RateLimited");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,145 @@
+//! Тест метрик XStream, сгруппированных по тегу назначения потока
+
+use std::time::Duration;
+use tokio::time::timeout;
+use xnetwork2::Node;
+use xnetwork2::node_events::NodeEvent;
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+/// Потоки с двумя тегами учитываются раздельно: количество и переданные байты
+#[tokio::test]
+async fn test_metrics_by_tag_counts_bytes_per_tag() {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    // Сервер принимает все входящие потоки и дочитывает их до EOF
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { stream }) => {
+                    let data = stream.read_to_end().await.expect("❌ Сервер не смог прочитать поток");
+                    received.push(data.len());
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+        received
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let server_peer = *server.peer_id();
+    let payloads = [("sync", vec![1u8; 1000]), ("sync", vec![2u8; 500]), ("rpc", vec![3u8; 64])];
+    for (tag, payload) in &payloads {
+        let stream = client
+            .commander
+            .open_stream_tagged(server_peer, *tag)
+            .await
+            .expect("❌ Не удалось открыть тегированный поток");
+        stream.write_all(payload.clone()).await.expect("❌ Не удалось записать данные");
+        stream.write_eof().await.expect("❌ Не удалось закрыть запись");
+    }
+
+    let received = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не получил все потоки вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    assert_eq!(received.iter().sum::<usize>(), 1564, "❌ Сервер должен получить все данные");
+
+    let metrics = client.commander.metrics_by_tag().await.expect("❌ Не удалось получить метрики");
+    assert_eq!(metrics.len(), 2, "❌ Должно быть ровно два тега");
+
+    let sync = metrics.get("sync").expect("❌ Нет метрик для тега sync");
+    assert_eq!(sync.stream_count, 2, "❌ Для sync должно быть два потока");
+    assert_eq!(sync.bytes_written, 1500, "❌ Неверное количество байт для sync");
+
+    let rpc = metrics.get("rpc").expect("❌ Нет метрик для тега rpc");
+    assert_eq!(rpc.stream_count, 1, "❌ Для rpc должен быть один поток");
+    assert_eq!(rpc.bytes_written, 64, "❌ Неверное количество байт для rpc");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
+
+/// Закрытые потоки учитываются в итогах тега после освобождения их счетчиков
+#[tokio::test]
+async fn test_metrics_by_tag_keep_totals_of_closed_streams() {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let mut server_events = server.subscribe();
+    let server_task = tokio::spawn(async move {
+        loop {
+            match server_events.recv().await {
+                Ok(NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. }) => {
+                    let _ = decision_sender.approve();
+                }
+                Ok(NodeEvent::XStreamIncoming { mut stream }) => {
+                    let data = stream.read_to_end().await.expect("❌ Сервер не смог прочитать поток");
+                    let _ = stream.close().await;
+                    return data.len();
+                }
+                Ok(_) => continue,
+                Err(e) => panic!("❌ Канал событий сервера закрыт: {:?}", e),
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let mut client_events = client.subscribe();
+    let mut stream = client
+        .commander
+        .open_stream_tagged(*server.peer_id(), "bulk")
+        .await
+        .expect("❌ Не удалось открыть тегированный поток");
+    let stream_id = stream.id;
+    stream.write_all(vec![7u8; 256]).await.expect("❌ Не удалось записать данные");
+    stream.write_eof().await.expect("❌ Не удалось закрыть запись");
+
+    let received = timeout(Duration::from_secs(5), server_task)
+        .await
+        .expect("❌ Сервер не получил поток вовремя")
+        .expect("❌ Задача сервера завершилась с ошибкой");
+    assert_eq!(received, 256, "❌ Сервер должен получить все данные");
+
+    stream.close().await.expect("❌ Не удалось закрыть поток");
+    drop(stream);
+    timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(NodeEvent::XStreamClosed { stream_id: closed, .. }) = client_events.recv().await {
+                if closed == stream_id {
+                    return;
+                }
+            }
+        }
+    })
+    .await
+    .expect("❌ Клиент не получил событие закрытия потока");
+
+    let metrics = client.commander.metrics_by_tag().await.expect("❌ Не удалось получить метрики");
+    let bulk = metrics.get("bulk").expect("❌ Нет метрик для тега bulk");
+    assert_eq!(bulk.stream_count, 1, "❌ Закрытый поток должен остаться в счетчике");
+    assert_eq!(bulk.bytes_written, 256, "❌ Байты закрытого потока должны остаться в итогах");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,60 @@
+//! Тест фильтрованной подписки на события узла
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use tokio::time::timeout;
+use xnetwork2::Node;
+use xnetwork2::node_events::{FilteredEvent, NodeEvent};
+
+mod utils;
+use utils::{setup_connection_with_auth, setup_listening_node};
+
+/// Подписка только на XStreamIncoming не получает события соединений
+#[tokio::test]
+async fn test_subscribe_filtered_skips_connection_events() {
+    let mut server = Node::new().await.expect("❌ Не удалось создать сервер");
+    let mut client = Node::new().await.expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    // Подписываемся до установки соединения, чтобы события соединения прошли через фильтр
+    let mut incoming = server.subscribe_filtered(|e| matches!(e, NodeEvent::XStreamIncoming { .. }));
+
+    // Сервер одобряет входящие потоки через обычную подписку
+    let mut server_events = server.subscribe();
+    tokio::spawn(async move {
+        while let Ok(event) = server_events.recv().await {
+            if let NodeEvent::XStreamIncomingStreamRequest { decision_sender, .. } = event {
+                let _ = decision_sender.approve();
+            }
+        }
+    });
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    setup_connection_with_auth(&mut client, &mut server, server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось установить соединение с аутентификацией");
+
+    let server_peer = *server.peer_id();
+    let _stream = client
+        .commander
+        .open_xstream(server_peer)
+        .await
+        .expect("❌ Не удалось открыть поток");
+
+    let item = timeout(Duration::from_secs(5), incoming.next())
+        .await
+        .expect("❌ Фильтрованная подписка не получила событие")
+        .expect("❌ Поток событий закрыт");
+
+    match item {
+        FilteredEvent::Event(NodeEvent::XStreamIncoming { stream }) => {
+            assert_eq!(stream.peer_id, *client.peer_id(), "❌ Поток должен прийти от клиента");
+        }
+        other => panic!("❌ Первым должно прийти XStreamIncoming, получено: {:?}", other),
+    }
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
@@ -0,0 +1,114 @@
+//! Тест проверки поддержки протокола пиром по кэшу Identify
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use libp2p::{identify, Multiaddr};
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xstream::xstream_error::StreamOpenError;
+
+mod utils;
+use utils::{dial_and_wait_connection, wait_for_event};
+
+const BARE_PROTOCOL_VERSION: &str = "/bare/1.0.0";
+
+/// Запускает libp2p swarm только с Identify, без XStream
+async fn spawn_bare_identify_peer() -> (libp2p::PeerId, Multiaddr, tokio::task::JoinHandle<()>) {
+    let mut swarm = libp2p::SwarmBuilder::with_new_identity()
+        .with_tokio()
+        .with_quic()
+        .with_behaviour(|key| {
+            identify::Behaviour::new(identify::Config::new(
+                BARE_PROTOCOL_VERSION.to_string(),
+                key.public(),
+            ))
+        })
+        .expect("❌ Не удалось создать identify поведение")
+        .with_swarm_config(|config| config.with_idle_connection_timeout(Duration::from_secs(60)))
+        .build();
+    let peer_id = *swarm.local_peer_id();
+
+    swarm
+        .listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+        .expect("❌ Не удалось начать прослушивание");
+    let addr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+            break address;
+        }
+    };
+
+    let task = tokio::spawn(async move {
+        loop {
+            swarm.select_next_some().await;
+        }
+    });
+    (peer_id, addr, task)
+}
+
+/// Пир без XStream: supports_protocol возвращает Some(false), открытие отклоняется сразу
+#[tokio::test]
+async fn test_supports_protocol_for_bare_identify_peer() {
+    let (bare_peer, bare_addr, bare_task) = spawn_bare_identify_peer().await;
+
+    let mut node = NodeBuilder::new()
+        .with_auto_auth(false)
+        .build()
+        .await
+        .expect("❌ Не удалось создать узел");
+    node.start().await.expect("❌ Не удалось запустить узел");
+
+    assert_eq!(
+        node.commander
+            .supports_protocol(bare_peer, "/xstream/")
+            .await
+            .expect("❌ Запрос supports_protocol не выполнен"),
+        None,
+        "❌ До Identify поддержка протокола неизвестна"
+    );
+
+    let mut events = node.subscribe();
+    dial_and_wait_connection(&mut node, bare_peer, bare_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось соединиться с identify пиром");
+    wait_for_event(
+        &mut events,
+        |e| matches!(e, NodeEvent::IdentifyReceived { peer_id, .. } if *peer_id == bare_peer),
+        Duration::from_secs(10),
+    )
+    .await
+    .expect("❌ IdentifyReceived не получен");
+
+    assert_eq!(
+        node.commander
+            .supports_protocol(bare_peer, "/xstream/")
+            .await
+            .expect("❌ Запрос supports_protocol не выполнен"),
+        Some(false),
+        "❌ Пир без XStream не должен поддерживать /xstream/"
+    );
+    assert_eq!(
+        node.commander
+            .supports_protocol(bare_peer, "/ipfs/id/1.0.0")
+            .await
+            .expect("❌ Запрос supports_protocol не выполнен"),
+        Some(true),
+        "❌ Пир должен поддерживать Identify"
+    );
+
+    let error = node
+        .commander
+        .open_xstream_checked(bare_peer)
+        .await
+        .expect_err("❌ Открытие потока к пиру без XStream должно быть отклонено");
+    assert_eq!(
+        error.downcast_ref::<StreamOpenError>(),
+        Some(&StreamOpenError::ProtocolUnsupported),
+        "❌ Ожидалась ошибка ProtocolUnsupported: {}",
+        error
+    );
+
+    bare_task.abort();
+    node.force_shutdown().await.expect("❌ Не удалось остановить узел");
+}
@@ -0,0 +1,111 @@
+//! Тест экспорта событий соединений в tracing/OpenTelemetry-совместимый приемник
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::telemetry::TELEMETRY_TARGET;
+
+mod utils;
+use utils::{dial_and_wait_connection, setup_listening_node};
+
+type RecordedEvents = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+/// Слой, сохраняющий поля всех телеметрических событий
+struct RecordingLayer {
+    events: RecordedEvents,
+}
+
+struct FieldRecorder(HashMap<String, String>);
+
+impl Visit for FieldRecorder {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for RecordingLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != TELEMETRY_TARGET {
+            return;
+        }
+        let mut recorder = FieldRecorder(HashMap::new());
+        event.record(&mut recorder);
+        self.events.lock().unwrap().push(recorder.0);
+    }
+}
+
+fn recording_dispatch() -> (tracing::Dispatch, RecordedEvents) {
+    let events: RecordedEvents = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = tracing_subscriber::registry().with(RecordingLayer { events: events.clone() });
+    (tracing::Dispatch::new(subscriber), events)
+}
+
+/// При установлении соединения экспортируются стандартные поля на обеих сторонах
+#[tokio::test]
+async fn test_connection_established_exports_standard_fields() {
+    let (server_dispatch, server_events) = recording_dispatch();
+    let (client_dispatch, client_events) = recording_dispatch();
+
+    let mut server = NodeBuilder::new()
+        .with_telemetry(server_dispatch)
+        .build()
+        .await
+        .expect("❌ Не удалось создать сервер");
+    let mut client = NodeBuilder::new()
+        .with_telemetry(client_dispatch)
+        .build()
+        .await
+        .expect("❌ Не удалось создать клиента");
+    server.start().await.expect("❌ Не удалось запустить сервер");
+    client.start().await.expect("❌ Не удалось запустить клиента");
+
+    let server_addr = setup_listening_node(&mut server).await.expect("❌ Сервер не слушает");
+    let connection_id = dial_and_wait_connection(&mut client, *server.peer_id(), server_addr, Duration::from_secs(5))
+        .await
+        .expect("❌ Не удалось подключиться к серверу");
+
+    // Событие сервера может прийти чуть позже клиентского
+    let find_established = |events: &RecordedEvents| {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|fields| fields.get("event").map(String::as_str) == Some("connection_established"))
+            .cloned()
+    };
+    let server_fields = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(fields) = find_established(&server_events) {
+                break fields;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("❌ Сервер не экспортировал connection_established");
+    let client_fields = find_established(&client_events).expect("❌ Клиент не экспортировал connection_established");
+
+    for fields in [&client_fields, &server_fields] {
+        for key in ["peer_id", "connection_id", "direction", "transport"] {
+            assert!(fields.contains_key(key), "❌ Поле {} отсутствует: {:?}", key, fields);
+        }
+        assert_eq!(fields["transport"], "quic", "❌ Транспорт должен определяться по адресу");
+    }
+
+    assert_eq!(client_fields["peer_id"], server.peer_id().to_string(), "❌ Клиент должен видеть PeerId сервера");
+    assert_eq!(client_fields["direction"], "outbound", "❌ Клиент набирает соединение");
+    assert_eq!(client_fields["connection_id"], connection_id.to_string(), "❌ Неверный connection_id");
+    assert_eq!(server_fields["peer_id"], client.peer_id().to_string(), "❌ Сервер должен видеть PeerId клиента");
+    assert_eq!(server_fields["direction"], "inbound", "❌ Сервер принимает соединение");
+
+    client.force_shutdown().await.expect("❌ Не удалось остановить клиента");
+    server.force_shutdown().await.expect("❌ Не удалось остановить сервер");
+}
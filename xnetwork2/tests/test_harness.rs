@@ -0,0 +1,120 @@
+//! Кластер из N соединенных узлов для интеграционных тестов
+//!
+//! Узлы работают поверх MemoryTransport, каждая пара соединена одним
+//! соединением и взаимно аутентифицирована.
+
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use xnetwork2::node_builder::NodeBuilder;
+use xnetwork2::node_events::NodeEvent;
+use xnetwork2::{Commander, Node};
+
+/// Время на соединение и аутентификацию всего кластера
+const CLUSTER_SETUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// N запущенных узлов, соединенных каждый с каждым
+pub struct Cluster {
+    nodes: Vec<Node>,
+    events: Vec<broadcast::Receiver<NodeEvent>>,
+}
+
+impl Cluster {
+    /// Строит n узлов, соединяет все пары и ждет взаимной аутентификации
+    pub async fn new(n: usize) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::with_builder(n, NodeBuilder::new).await
+    }
+
+    /// То же, что new, с собственной настройкой узлов; MemoryTransport включается всегда
+    pub async fn with_builder<F>(n: usize, builder: F) -> Result<Self, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Fn() -> NodeBuilder,
+    {
+        let mut nodes = Vec::with_capacity(n);
+        let mut events = Vec::with_capacity(n);
+        let mut addresses = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut node = builder().with_memory_transport().build().await?;
+            node.start().await?;
+            // Подписка до соединения, чтобы тесты видели все события
+            events.push(node.subscribe());
+            let addr = node
+                .commander
+                .listen_and_wait("/memory/0".parse()?, Duration::from_secs(5))
+                .await?;
+            addresses.push(addr.iter().filter(|p| !matches!(p, Protocol::P2p(_))).collect::<Multiaddr>());
+            nodes.push(node);
+        }
+
+        // Одно соединение на пару: узел с меньшим индексом набирает больший
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let peer_id = *nodes[j].peer_id();
+                nodes[i]
+                    .commander
+                    .dial_and_wait(peer_id, addresses[j].clone(), Duration::from_secs(5))
+                    .await?;
+            }
+        }
+
+        let cluster = Self { nodes, events };
+        cluster.wait_for_full_auth(CLUSTER_SETUP_TIMEOUT).await?;
+        Ok(cluster)
+    }
+
+    /// Ждет, пока каждый узел аутентифицирует всех остальных
+    async fn wait_for_full_auth(&self, timeout: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let deadline = Instant::now() + timeout;
+        for node in &self.nodes {
+            for other in &self.nodes {
+                if node.peer_id() == other.peer_id() {
+                    continue;
+                }
+                while !node.commander.is_peer_authenticated(*other.peer_id()).await? {
+                    if Instant::now() >= deadline {
+                        return Err(format!("{} did not authenticate {} in time", node.peer_id(), other.peer_id()).into());
+                    }
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Число узлов
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Узел с индексом i
+    pub fn node(&self, i: usize) -> &Node {
+        &self.nodes[i]
+    }
+
+    /// Commander узла с индексом i
+    pub fn commander(&self, i: usize) -> &Commander {
+        &self.nodes[i].commander
+    }
+
+    /// PeerId узла с индексом i
+    pub fn peer_id(&self, i: usize) -> PeerId {
+        *self.nodes[i].peer_id()
+    }
+
+    /// Приемник событий узла с индексом i, подписанный до соединения
+    pub fn events(&mut self, i: usize) -> &mut broadcast::Receiver<NodeEvent> {
+        &mut self.events[i]
+    }
+
+    /// Останавливает все узлы
+    pub async fn shutdown(mut self) {
+        for node in &mut self.nodes {
+            let _ = node.force_shutdown().await;
+        }
+    }
+}
@@ -588,3 +588,15 @@ pub fn spawn_auto_respond_por_task(
         Ok(())
     })
 }
+
+/// Детерминированный Ed25519 ключ из целочисленного seed (только для тестов)
+///
+/// Seed записывается в первые 8 байт 32-байтного секрета, остальные байты нулевые,
+/// поэтому один seed всегда дает один и тот же PeerId. Такой ключ не секретен.
+#[allow(dead_code)]
+pub fn make_key_from_seed(seed: u64) -> libp2p::identity::Keypair {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_be_bytes());
+    libp2p::identity::Keypair::ed25519_from_bytes(bytes)
+        .expect("❌ 32-байтный seed всегда является корректным Ed25519 ключом")
+}
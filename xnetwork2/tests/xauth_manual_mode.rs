@@ -18,9 +18,9 @@ async fn test_xauth_manual_mode() {
     let result = timeout(Duration::from_secs(15), async {
         // 1. СОЗДАНИЕ ДВУХ НОД (0-1 секунда)
         println!("🆕 Создаем две ноды...");
-        let mut node1 = Node::new().await
+        let mut node1 = Node::builder().await.with_auto_auth(false).build().await
             .expect("❌ Не удалось создать ноду 1 - критическая ошибка");
-        let mut node2 = Node::new().await
+        let mut node2 = Node::builder().await.with_auto_auth(false).build().await
             .expect("❌ Не удалось создать ноду 2 - критическая ошибка");
 
         println!("✅ Ноды созданы:");
@@ -47,9 +47,9 @@ async fn test_xauth_manual_mode() {
         println!("   Node1 адрес: {}", node1_addr);
         println!("   Node2 адрес: {}", node2_addr);
 
-        // 4. РЕЖИМ ПО УМОЛЧАНИЮ - РУЧНОЙ (4-5 секунд)
-        println!("🔄 Режим аутентификации по умолчанию - ручной...");
-        println!("✅ Ручной режим установлен по умолчанию для обеих нод");
+        // 4. РУЧНОЙ РЕЖИМ - АВТОМАТИЧЕСКАЯ АУТЕНТИФИКАЦИЯ ВЫКЛЮЧЕНА (4-5 секунд)
+        println!("🔄 Режим аутентификации - ручной (with_auto_auth(false))...");
+        println!("✅ Ручной режим установлен для обеих нод");
 
         // 5. ПОДКЛЮЧЕНИЕ И ПРОВЕРКА ОТСУТСТВИЯ АВТОМАТИЧЕСКОЙ АУТЕНТИФИКАЦИИ (5-8 секунд)
         println!("🔗 Подключаем ноду 1 к ноде 2...");
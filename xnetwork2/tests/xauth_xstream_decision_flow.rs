@@ -19,9 +19,9 @@ async fn test_xauth_xstream_decision_flow_in_5_seconds() {
     let result = timeout(Duration::from_secs(5), async {
         // 1. СОЗДАНИЕ ДВУХ НОД (0-1 секунда)
         println!("🆕 Создаем две ноды...");
-        let mut node1 = Node::new().await
+        let mut node1 = Node::builder().await.with_auto_auth(false).build().await
             .expect("❌ Не удалось создать первую ноду - критическая ошибка");
-        let mut node2 = Node::new().await
+        let mut node2 = Node::builder().await.with_auto_auth(false).build().await
             .expect("❌ Не удалось создать вторую ноду - критическая ошибка");
 
         println!("✅ Ноды созданы:");
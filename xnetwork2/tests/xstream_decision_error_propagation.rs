@@ -13,6 +13,7 @@ async fn test_xstream_decision_error_propagation() {
     // Создаем две ноды с ручной политикой принятия решений
     let mut node1 = Node::builder()
         .await
+        .with_auto_auth(false)
         .with_inbound_decision_policy(InboundDecisionPolicy::ManualApprove)
         .build()
         .await
@@ -20,6 +21,7 @@ async fn test_xstream_decision_error_propagation() {
 
     let mut node2 = Node::builder()
         .await
+        .with_auto_auth(false)
         .with_inbound_decision_policy(InboundDecisionPolicy::ManualApprove)
         .build()
         .await
@@ -123,6 +125,7 @@ async fn test_xstream_decision_approval_success() {
     // Создаем две ноды с ручной политикой принятия решений
     let mut node1 = Node::builder()
         .await
+        .with_auto_auth(false)
         .with_inbound_decision_policy(InboundDecisionPolicy::ManualApprove)
         .build()
         .await
@@ -130,6 +133,7 @@ async fn test_xstream_decision_approval_success() {
 
     let mut node2 = Node::builder()
         .await
+        .with_auto_auth(false)
         .with_inbound_decision_policy(InboundDecisionPolicy::ManualApprove)
         .build()
         .await